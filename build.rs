@@ -0,0 +1,30 @@
+//! Script de build : détecte la présence des fichiers du dashboard web
+//!
+//! Si le répertoire `web/` est complet, le cfg `embedded_dashboard` est activé
+//! et les fichiers sont embarqués dans le binaire. Sinon (archive source sans
+//! `web/`), le serveur web utilise un dashboard minimal intégré au code.
+
+use std::path::Path;
+
+/// Fichiers nécessaires au dashboard complet
+const DASHBOARD_FILES: &[&str] = &[
+    "web/index.html",
+    "web/favicon.ico",
+    "web/static/style.css",
+    "web/static/app.js",
+];
+
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(embedded_dashboard)");
+    println!("cargo:rerun-if-changed=web");
+
+    for file in DASHBOARD_FILES {
+        println!("cargo:rerun-if-changed={}", file);
+    }
+
+    if DASHBOARD_FILES.iter().all(|file| Path::new(file).is_file()) {
+        println!("cargo:rustc-cfg=embedded_dashboard");
+    } else {
+        println!("cargo:warning=web/ dashboard files not found, using the built-in minimal dashboard");
+    }
+}
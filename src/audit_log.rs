@@ -0,0 +1,138 @@
+/*!
+Journal d'audit des réponses NTP (`logging.audit_log_file`)
+
+Trace de conservation pour les environnements réglementés, distincte du log
+de debug des requêtes : une ligne JSON par réponse échantillonnée (une sur
+`logging.audit_sample_rate`), avec l'IP du client, les timestamps de la
+requête et de la réponse, le stratum et le reference ID servis. Le fichier
+est en ajout seul, avec rotation par taille (`RotatingFile`).
+
+```text
+{"time":"2026-10-16T08:00:00Z","client":"192.0.2.10","client_transmit":…,"receive":…,"transmit":…,"stratum":1,"refid":"PPS"}
+```
+
+Les timestamps sont les valeurs NTP 64 bits brutes (32.32), sans perte de
+précision.
+*/
+
+use crate::leap_seconds::ntp_seconds_to_iso;
+use crate::log_file::RotatingFile;
+use crate::packet::NtpPacket;
+use serde::Serialize;
+use std::io::Write;
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Ligne du journal d'audit
+#[derive(Debug, Serialize)]
+struct AuditEntry {
+    /// Instant d'émission de la réponse (UTC, à la seconde)
+    time: String,
+    client: IpAddr,
+
+    /// Transmit timestamp de la requête (T1)
+    client_transmit: u64,
+
+    /// Réception de la requête (T2) et émission de la réponse (T3)
+    receive: u64,
+    transmit: u64,
+
+    stratum: u8,
+    refid: String,
+}
+
+/// Journal d'audit échantillonné
+pub struct AuditLog {
+    file: Mutex<RotatingFile>,
+
+    /// Une réponse sur `sample_rate` est enregistrée
+    sample_rate: u64,
+    responses: AtomicU64,
+}
+
+impl AuditLog {
+    pub fn open(path: &Path, sample_rate: u64, max_bytes: u64, max_files: usize) -> std::io::Result<Self> {
+        Ok(AuditLog {
+            file: Mutex::new(RotatingFile::open(path, max_bytes, max_files)?),
+            sample_rate: sample_rate.max(1),
+            responses: AtomicU64::new(0),
+        })
+    }
+
+    /// Compte une réponse envoyée et l'enregistre si elle est échantillonnée
+    pub fn record(&self, client: IpAddr, request: &NtpPacket, response: &NtpPacket) -> std::io::Result<()> {
+        if !self.responses.fetch_add(1, Ordering::Relaxed).is_multiple_of(self.sample_rate) {
+            return Ok(());
+        }
+
+        // Reference ID ASCII (stratum 1) ou adresse/hash du serveur amont
+        let refid_bytes = response.reference_identifier.to_be_bytes();
+        let refid = if response.stratum <= 1 {
+            String::from_utf8_lossy(&refid_bytes).trim_end_matches('\0').to_string()
+        } else {
+            format!("{:08X}", response.reference_identifier)
+        };
+
+        let entry = AuditEntry {
+            time: ntp_seconds_to_iso(response.transmit_timestamp.seconds() as u64).unwrap_or_default(),
+            client,
+            client_transmit: request.transmit_timestamp.0,
+            receive: response.receive_timestamp.0,
+            transmit: response.transmit_timestamp.0,
+            stratum: response.stratum,
+            refid,
+        };
+
+        let mut line = serde_json::to_vec(&entry).map_err(std::io::Error::other)?;
+        line.push(b'\n');
+
+        // Une ligne = une écriture : la rotation ne coupe jamais une ligne
+        let mut file = self.file.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        file.write_all(&line)?;
+        file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet::NtpTimestamp;
+
+    #[test]
+    fn test_audit_sampling_one_in_n() {
+        let path = std::env::temp_dir().join(format!("pendulum-audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let log = AuditLog::open(&path, 10, 0, 1).unwrap();
+        let client: IpAddr = "192.0.2.10".parse().unwrap();
+
+        let mut request = NtpPacket::new_server_response();
+        request.transmit_timestamp = NtpTimestamp(0x1234_5678_0000_0001);
+        let mut response = NtpPacket::new_server_response();
+        response.stratum = 1;
+        response.reference_identifier = u32::from_be_bytes(*b"PPS\0");
+        response.receive_timestamp = NtpTimestamp(4_146_033_600u64 << 32);
+        response.transmit_timestamp = NtpTimestamp((4_146_033_600u64 << 32) + 1);
+
+        // Rafale de 1000 réponses, une sur 10 enregistrée
+        for _ in 0..1000 {
+            log.record(client, &request, &response).unwrap();
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 100);
+
+        let entry: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(entry["client"], "192.0.2.10");
+        assert_eq!(entry["client_transmit"], 0x1234_5678_0000_0001u64);
+        assert_eq!(entry["transmit"], (4_146_033_600u64 << 32) + 1);
+        assert_eq!(entry["stratum"], 1);
+        assert_eq!(entry["refid"], "PPS");
+        assert_eq!(entry["time"], "2031-05-20T12:00:00Z");
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,176 @@
+/*!
+Authentification par clé symétrique (RFC 5905 §7.3 et annexe A.2)
+
+Un client authentifié ajoute aux 48 octets de l'en-tête un code
+d'authentification (MAC) : l'identifiant de la clé partagée, puis le
+condensé de la clé suivie de l'en-tête, 16 octets en MD5 ou 20 en SHA-1 :
+
+```text
+| en-tête NTP (48) | key ID (4) | digest(clé || en-tête) (16 ou 20) |
+```
+
+Les clés sont déclarées dans `[[security.keys]]`, au format des fichiers de
+clés chrony/ntpd : texte ASCII, ou hexadécimal préfixé par `HEX:`.
+*/
+
+use crate::config::KeyConfig;
+use crate::packet::Mac;
+use sha1::Digest;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Algorithme de condensé d'une clé
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Md5,
+    Sha1,
+}
+
+impl DigestAlgorithm {
+    /// Algorithme depuis son nom de configuration ("md5" ou "sha1")
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "md5" => Some(DigestAlgorithm::Md5),
+            "sha1" => Some(DigestAlgorithm::Sha1),
+            _ => None,
+        }
+    }
+}
+
+/// Clé symétrique partagée avec des clients
+#[derive(Debug, Clone)]
+pub struct SymmetricKey {
+    algorithm: DigestAlgorithm,
+    secret: Vec<u8>,
+}
+
+impl SymmetricKey {
+    pub fn new(algorithm: DigestAlgorithm, secret: Vec<u8>) -> Self {
+        SymmetricKey { algorithm, secret }
+    }
+
+    /// Condensé de la clé suivie du message
+    pub fn digest(&self, message: &[u8]) -> Vec<u8> {
+        match self.algorithm {
+            DigestAlgorithm::Md5 => {
+                let mut context = md5::Context::new();
+                context.consume(&self.secret);
+                context.consume(message);
+                context.compute().0.to_vec()
+            }
+            DigestAlgorithm::Sha1 => {
+                let mut hasher = sha1::Sha1::new();
+                hasher.update(&self.secret);
+                hasher.update(message);
+                hasher.finalize().to_vec()
+            }
+        }
+    }
+
+    /// MAC d'un en-tête avec cette clé
+    pub fn sign(&self, key_id: u32, header: &[u8]) -> Mac {
+        Mac::new(key_id, &self.digest(header))
+    }
+
+    /// Vrai si le condensé correspond (comparaison en temps constant)
+    fn verify(&self, header: &[u8], digest: &[u8]) -> bool {
+        let expected = self.digest(header);
+        expected.len() == digest.len() && expected.iter().zip(digest).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+}
+
+/// Échec de vérification du MAC d'une requête
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AuthError {
+    #[error("unknown key ID {0}")]
+    UnknownKey(u32),
+
+    #[error("bad MAC for key ID {0}")]
+    BadDigest(u32),
+}
+
+/// Secret d'une clé : hexadécimal préfixé par `HEX:`, sinon texte ASCII
+pub fn parse_key_secret(text: &str) -> Result<Vec<u8>, String> {
+    let secret = match text.strip_prefix("HEX:") {
+        Some(hex) => {
+            if hex.is_empty() || !hex.len().is_multiple_of(2) {
+                return Err("HEX: key must have an even, non-zero number of digits".to_string());
+            }
+            (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(hex.get(i..i + 2).unwrap_or("?"), 16))
+                .collect::<Result<Vec<u8>, _>>()
+                .map_err(|_| "HEX: key contains a non-hexadecimal digit".to_string())?
+        }
+        None => text.as_bytes().to_vec(),
+    };
+
+    if secret.is_empty() {
+        return Err("key must not be empty".to_string());
+    }
+    Ok(secret)
+}
+
+/// Clés indexées par identifiant (entrées déjà validées au chargement de la configuration)
+pub fn keys_from_config(keys: &[KeyConfig]) -> HashMap<u32, SymmetricKey> {
+    keys.iter()
+        .filter_map(|key| {
+            let algorithm = DigestAlgorithm::parse(&key.algorithm)?;
+            let secret = parse_key_secret(&key.key).ok()?;
+            Some((key.id, SymmetricKey::new(algorithm, secret)))
+        })
+        .collect()
+}
+
+/// Vérifie le MAC d'un en-tête reçu et retourne la clé qui l'a produit
+pub fn verify_mac<'a>(
+    keys: &'a HashMap<u32, SymmetricKey>,
+    header: &[u8],
+    mac: &Mac,
+) -> Result<&'a SymmetricKey, AuthError> {
+    let key = keys.get(&mac.key_id).ok_or(AuthError::UnknownKey(mac.key_id))?;
+    if !key.verify(header, mac.digest()) {
+        return Err(AuthError::BadDigest(mac.key_id));
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digests_and_verification() {
+        // Vecteurs de référence : MD5("abc") et SHA-1("abc"), clé "a" + message "bc"
+        let md5 = SymmetricKey::new(DigestAlgorithm::Md5, b"a".to_vec());
+        let md5_hex: String = md5.digest(b"bc").iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(md5_hex, "900150983cd24fb0d6963f7d28e17f72");
+
+        let sha1 = SymmetricKey::new(DigestAlgorithm::Sha1, b"a".to_vec());
+        let sha1_hex: String = sha1.digest(b"bc").iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(sha1_hex, "a9993e364706816aba3e25717850c26c9cd0d89d");
+
+        let keys = HashMap::from([(1, md5), (2, sha1)]);
+        let header = [0x23u8; 48];
+        let mac = keys[&2].sign(2, &header);
+        assert!(verify_mac(&keys, &header, &mac).is_ok());
+
+        // En-tête modifié, clé inconnue, condensé d'une autre clé
+        let mut tampered = header;
+        tampered[47] ^= 1;
+        assert_eq!(verify_mac(&keys, &tampered, &mac).unwrap_err(), AuthError::BadDigest(2));
+        assert_eq!(
+            verify_mac(&keys, &header, &Mac::new(9, mac.digest())).unwrap_err(),
+            AuthError::UnknownKey(9)
+        );
+        assert_eq!(
+            verify_mac(&keys, &header, &Mac::new(1, mac.digest())).unwrap_err(),
+            AuthError::BadDigest(1)
+        );
+
+        assert_eq!(parse_key_secret("HEX:00ff10").unwrap(), vec![0x00, 0xFF, 0x10]);
+        assert_eq!(parse_key_secret("s3cret").unwrap(), b"s3cret".to_vec());
+        assert!(parse_key_secret("HEX:0g").is_err());
+        assert!(parse_key_secret("").is_err());
+    }
+}
@@ -1,8 +1,10 @@
-use crate::packet::NtpTimestamp;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-/// Différence entre l'epoch NTP (1900-01-01) et l'epoch Unix (1970-01-01) en secondes
-const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+use crate::config::SyncCriteria;
+use crate::packet::{NtpTimestamp, NTP_UNIX_OFFSET};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
 
 /// Trait pour les sources d'horloge
 pub trait ClockSource: Send + Sync {
@@ -17,6 +19,88 @@ pub trait ClockSource: Send + Sync {
 
     /// Retourne la précision estimée en log2 secondes (ex: -20 = ~1µs)
     fn precision(&self) -> i8;
+
+    /// Vrai si l'horloge dispose d'un temps absolu digne d'être servi
+    /// (faux tant qu'une horloge GPS sans RTC de confiance n'a reçu aucune trame)
+    fn has_absolute_time(&self) -> bool {
+        true
+    }
+
+    /// Précision temporelle estimée de la référence en nanosecondes
+    /// (fournie par le module GPS), `None` si inconnue
+    fn time_accuracy_ns(&self) -> Option<u64> {
+        None
+    }
+
+    /// État de discipline (offsets, fréquence) pour le rapport de suivi,
+    /// `None` si la source n'est disciplinée par aucune référence
+    fn discipline(&self) -> Option<DisciplineState> {
+        None
+    }
+
+    /// Nom de la source effectivement utilisée (horloges à sources multiples),
+    /// `None` pour une source unique
+    fn active_source(&self) -> Option<String> {
+        None
+    }
+
+    /// Instant de la dernière mise à jour de la référence (reference timestamp
+    /// des réponses) ; par défaut l'instant présent (référence continue)
+    fn last_update(&self) -> NtpTimestamp {
+        self.now()
+    }
+
+    /// Distribution des offsets PPS bruts (`clock.gps.pps_offset_histogram`),
+    /// `None` si la source ne la mesure pas
+    fn pps_offset_histogram(&self) -> Option<HistogramSnapshot> {
+        None
+    }
+
+    /// Écart référence/système mesuré lorsqu'il dépasse le seuil configuré
+    /// (`clock.max_gps_system_divergence_ms`, nanosecondes), `None` sans alarme
+    fn divergence_ns(&self) -> Option<u64> {
+        None
+    }
+
+    /// Vrai si la référence et l'horloge système divergent au-delà du seuil
+    fn divergence_alarm(&self) -> bool {
+        self.divergence_ns().is_some()
+    }
+}
+
+/// Croissance de l'erreur maximale du noyau entre deux mises à jour
+/// (`MAXFREQ` : 500 ppm, soit 500 µs par seconde)
+#[cfg(target_os = "linux")]
+const KERNEL_MAXERROR_GROWTH_US_PER_SECOND: u128 = 500;
+
+/// Erreur maximale au-delà de laquelle le noyau considère l'horloge
+/// non synchronisée (`NTP_PHASE_LIMIT` : 16 s)
+#[cfg(target_os = "linux")]
+const KERNEL_MAXERROR_LIMIT_US: libc::c_long = 16_000_000;
+
+/// Dernière synchronisation de l'horloge système déduite de l'état `adjtimex`
+///
+/// Le démon NTP de l'OS réinitialise `maxerror` à chaque mise à jour, puis le
+/// noyau l'augmente de 500 µs par seconde : l'erreur courante donne donc une
+/// borne de l'ancienneté de la dernière synchronisation. `None` si le noyau
+/// signale une horloge non synchronisée.
+#[cfg(target_os = "linux")]
+fn last_sync_from_timex(
+    now: NtpTimestamp,
+    state: libc::c_int,
+    status: libc::c_int,
+    maxerror_us: libc::c_long,
+) -> Option<NtpTimestamp> {
+    if state == libc::TIME_ERROR || status & libc::STA_UNSYNC != 0 {
+        return None;
+    }
+    if !(0..KERNEL_MAXERROR_LIMIT_US).contains(&maxerror_us) {
+        return None;
+    }
+
+    let elapsed_ns = maxerror_us as u128 * 1_000_000_000 / KERNEL_MAXERROR_GROWTH_US_PER_SECOND;
+    let elapsed = ((elapsed_ns << 32) / 1_000_000_000) as u64;
+    now.0.checked_sub(elapsed).map(NtpTimestamp)
 }
 
 /// Horloge système haute précision
@@ -92,6 +176,24 @@ impl SystemClock {
         Self::fallback_time()
     }
 
+    /// Dernière synchronisation de l'horloge système par l'OS (`adjtimex` en
+    /// lecture seule, sans privilège), `None` si inconnue ou non synchronisée
+    #[cfg(target_os = "linux")]
+    fn os_last_sync(now: NtpTimestamp) -> Option<NtpTimestamp> {
+        // SAFETY: timex mis à zéro (modes = 0 : lecture seule), valide pendant l'appel
+        let mut timex: libc::timex = unsafe { std::mem::MaybeUninit::zeroed().assume_init() };
+        let state = unsafe { libc::adjtimex(&mut timex) };
+        if state < 0 {
+            return None;
+        }
+        last_sync_from_timex(now, state, timex.status, timex.maxerror)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn os_last_sync(_now: NtpTimestamp) -> Option<NtpTimestamp> {
+        None
+    }
+
     #[allow(dead_code)]
     fn fallback_time() -> (u64, u32) {
         let duration = SystemTime::now()
@@ -142,6 +244,12 @@ impl ClockSource for SystemClock {
         #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
         return -20; // ~1µs par défaut
     }
+
+    fn last_update(&self) -> NtpTimestamp {
+        // Dernière synchronisation par le démon NTP de l'OS, sinon l'instant présent
+        let now = self.now();
+        Self::os_last_sync(now).unwrap_or(now)
+    }
 }
 
 /// Horloge synchronisée avec GPS/GNSS
@@ -155,11 +263,94 @@ pub struct GpsNmeaClock {
     /// Positif si l'horloge système est en avance sur GPS
     pps_offset: std::sync::Arc<std::sync::RwLock<Option<PpsOffset>>>,
 
+    /// Statistiques de discipline (offset RMS, fréquence) pour le rapport de suivi
+    discipline: Mutex<DisciplineTracker>,
+
     /// Horloge système comme fallback
     system_clock: SystemClock,
 
     /// Timeout après lequel on considère la sync GPS périmée (secondes)
     sync_timeout: u64,
+
+    /// Âge maximal d'un offset PPS avant de revenir à l'extrapolation NMEA
+    pps_offset_max_age: std::time::Duration,
+
+    /// Précision annoncée sans PPS (log2 secondes) : latence des trames NMEA
+    nmea_only_precision: i8,
+
+    /// Dernière qualité de fix GGA (0 = inconnue/invalide, 1 = GPS, 2 = DGPS/SBAS, 4/5 = RTK)
+    fix_quality: std::sync::atomic::AtomicU8,
+
+    /// Précision temporelle estimée par le module (ns) et instant de la mesure
+    time_accuracy: std::sync::RwLock<Option<(u64, std::time::Instant)>>,
+
+    /// Dernier HDOP annoncé par le module (trame GGA)
+    hdop: std::sync::RwLock<Option<f64>>,
+
+    /// Critères de validité de la synchronisation (satellites, HDOP, PPS)
+    sync_criteria: SyncCriteria,
+
+    /// Reference ID annoncé quand l'augmentation DGPS/SBAS est active (qualité 2)
+    dgps_refid: [u8; 4],
+
+    /// Horloge système (RTC) digne de confiance : si faux, le temps GPS est
+    /// calculé uniquement à partir de l'horloge monotone et des trames GPS
+    system_clock_trusted: bool,
+
+    /// Écart GPS/système maximal avant l'alarme de divergence (`None` = pas de contrôle)
+    max_divergence: Option<std::time::Duration>,
+
+    /// Ne plus fournir de temps absolu tant que l'alarme de divergence est levée
+    refuse_on_divergence: bool,
+
+    /// Écart GPS/système mesuré lorsqu'il dépasse le seuil (nanosecondes, 0 = pas d'alarme)
+    divergence_alarm_ns: std::sync::atomic::AtomicU64,
+
+    /// Distribution des offsets PPS bruts (`None` = non mesurée)
+    pps_offset_histogram: Option<PpsOffsetHistogram>,
+}
+
+/// Précision par défaut sans PPS : 2^-3 s = 125 ms, ordre de grandeur de la
+/// latence entre le début de la seconde GPS et la fin de sa trame NMEA
+pub const NMEA_ONLY_PRECISION: i8 = -3;
+
+/// Incertitude minimale du temps discipliné par PPS (ns, ≈ 2^-20 s) : gigue de
+/// la lecture du front sur la ligne CTS du port série
+pub const PPS_ACCURACY_FLOOR_NS: u64 = 953;
+
+/// Précision NTP (log2 secondes) correspondant à une incertitude en nanosecondes
+pub fn precision_from_ns(accuracy_ns: u64) -> i8 {
+    let seconds = accuracy_ns.max(1) as f64 * 1e-9;
+    (seconds.log2().ceil() as i8).clamp(-30, 0)
+}
+
+/// Convertit une incertitude en nanosecondes au format NTP court 16.16 (secondes),
+/// arrondie par excès pour ne jamais sous-estimer l'erreur
+pub fn ntp_short_from_ns(accuracy_ns: u64) -> u32 {
+    let units = (accuracy_ns as u128 * 65_536).div_ceil(1_000_000_000);
+    units.min(u32::MAX as u128) as u32
+}
+
+/// Dispersion annoncée hors mode dégradé (nanosecondes) : incertitude de la
+/// source si connue, élargie à l'écart GPS/système en cas de divergence
+pub fn root_dispersion_ns<C: ClockSource + ?Sized>(clock: &C) -> u64 {
+    let accuracy_ns = clock.time_accuracy_ns().unwrap_or(0);
+    accuracy_ns.max(clock.divergence_ns().unwrap_or(0))
+}
+
+/// Convertit une durée en microsecondes au format NTP court 16.16 (secondes),
+/// arrondie au plus proche (une unité vaut environ 15,3 µs)
+pub fn ntp_short_from_us(duration_us: u32) -> u32 {
+    ((duration_us as u64 * 65_536 + 500_000) / 1_000_000) as u32
+}
+
+/// Convertit un identifiant ASCII (4 caractères max) en reference ID NTP
+pub fn refid_from_str(s: &str) -> [u8; 4] {
+    let mut refid = [0u8; 4];
+    for (dst, src) in refid.iter_mut().zip(s.bytes()) {
+        *dst = src;
+    }
+    refid
 }
 
 #[derive(Clone)]
@@ -170,8 +361,160 @@ struct GpsSync {
     /// Moment système auquel cette sync a été reçue
     system_time: std::time::Instant,
 
-    /// Qualité du signal GPS (nombre de satellites)
-    quality: u8,
+    /// Nombre de satellites utilisés (trame GGA), `None` si inconnu
+    satellites: Option<u8>,
+}
+
+/// Résultat d'une mesure d'offset PPS
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PpsMeasurement {
+    /// Offset instantané de ce pulse (système - GPS, secondes)
+    pub raw_offset_seconds: f64,
+
+    /// Offset filtré (EWMA) après prise en compte de ce pulse
+    pub ewma_offset_seconds: f64,
+
+    /// Nombre de mesures PPS cumulées
+    pub sample_count: u32,
+}
+
+/// Bornes supérieures (secondes) des intervalles de l'histogramme des offsets
+/// PPS : pas fins autour de zéro jusqu'à ±1 ms, au-delà dans `+Inf`
+const PPS_OFFSET_BUCKETS: [f64; 20] = [
+    -1e-3, -5e-4, -2e-4, -1e-4, -5e-5, -2e-5, -1e-5, -5e-6, -2e-6, -1e-6, //
+    1e-6, 2e-6, 5e-6, 1e-5, 2e-5, 5e-5, 1e-4, 2e-4, 5e-4, 1e-3,
+];
+
+/// Histogramme des offsets PPS bruts, mis à jour sans verrou à chaque pulse
+///
+/// Un compteur par intervalle (non cumulé, le dernier pour `+Inf`) : une
+/// recherche dichotomique et deux incréments par mesure.
+#[derive(Debug, Default)]
+pub struct PpsOffsetHistogram {
+    buckets: [std::sync::atomic::AtomicU64; PPS_OFFSET_BUCKETS.len() + 1],
+
+    /// Somme des offsets (bits d'un f64)
+    sum: std::sync::atomic::AtomicU64,
+}
+
+/// Histogramme au format Prometheus : comptes cumulés par borne `le`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramSnapshot {
+    /// (borne supérieure, observations inférieures ou égales), `+Inf` compris
+    pub buckets: Vec<(f64, u64)>,
+    pub count: u64,
+    pub sum: f64,
+}
+
+impl PpsOffsetHistogram {
+    /// Ajoute un offset (secondes)
+    pub fn record(&self, offset_seconds: f64) {
+        use std::sync::atomic::Ordering;
+
+        let index = PPS_OFFSET_BUCKETS.partition_point(|&bound| bound < offset_seconds);
+        self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        let _ = self.sum.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+            Some((f64::from_bits(bits) + offset_seconds).to_bits())
+        });
+    }
+
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        use std::sync::atomic::Ordering;
+
+        let mut cumulative = 0;
+        let buckets: Vec<(f64, u64)> = PPS_OFFSET_BUCKETS
+            .iter()
+            .copied()
+            .chain(std::iter::once(f64::INFINITY))
+            .zip(&self.buckets)
+            .map(|(bound, count)| {
+                cumulative += count.load(Ordering::Relaxed);
+                (bound, cumulative)
+            })
+            .collect();
+
+        HistogramSnapshot {
+            buckets,
+            count: cumulative,
+            sum: f64::from_bits(self.sum.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// État de discipline de l'horloge, à la manière de `chronyc tracking`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DisciplineState {
+    /// Temps de référence de la dernière synchronisation
+    pub ref_time: Option<NtpTimestamp>,
+
+    /// Dernier offset mesuré (système - référence, secondes)
+    pub last_offset_seconds: f64,
+
+    /// Offset filtré (EWMA, secondes)
+    pub offset_seconds: f64,
+
+    /// Moyenne quadratique des offsets (EWMA des carrés, secondes)
+    pub rms_offset_seconds: f64,
+
+    /// Dérive de fréquence estimée de l'horloge système (ppm, positif si elle avance)
+    pub frequency_ppm: f64,
+
+    /// Écart entre la dernière dérive mesurée et la fréquence estimée (ppm)
+    pub residual_frequency_ppm: f64,
+
+    /// Dispersion de l'estimation de fréquence (EWMA des résidus absolus, ppm)
+    pub skew_ppm: f64,
+
+    /// Nombre de mesures prises en compte
+    pub sample_count: u32,
+}
+
+/// Coefficient des filtres EWMA de discipline (même pondération que l'offset PPS)
+const DISCIPLINE_EWMA_WEIGHT: f64 = 0.1;
+
+/// Accumulateur des statistiques de discipline, alimenté par les mesures PPS
+#[derive(Debug, Clone, Default)]
+struct DisciplineTracker {
+    state: DisciplineState,
+    mean_square_offset: f64,
+    last_measured_at: Option<std::time::Instant>,
+}
+
+impl DisciplineTracker {
+    /// Ajoute une mesure d'offset prise à l'instant monotone `at`
+    fn record(&mut self, offset: f64, at: std::time::Instant) {
+        let state = &mut self.state;
+        let alpha = DISCIPLINE_EWMA_WEIGHT;
+
+        if let Some(previous_at) = self.last_measured_at {
+            let interval = at.saturating_duration_since(previous_at).as_secs_f64();
+            if interval > 0.0 {
+                // Pente de l'offset entre deux mesures = dérive de fréquence
+                let drift_ppm = (offset - state.last_offset_seconds) / interval * 1e6;
+                state.frequency_ppm = if state.sample_count < 2 {
+                    drift_ppm
+                } else {
+                    state.frequency_ppm * (1.0 - alpha) + drift_ppm * alpha
+                };
+                state.residual_frequency_ppm = drift_ppm - state.frequency_ppm;
+                state.skew_ppm =
+                    state.skew_ppm * (1.0 - alpha) + state.residual_frequency_ppm.abs() * alpha;
+            }
+        }
+
+        if state.sample_count == 0 {
+            state.offset_seconds = offset;
+            self.mean_square_offset = offset * offset;
+        } else {
+            state.offset_seconds = state.offset_seconds * (1.0 - alpha) + offset * alpha;
+            self.mean_square_offset = self.mean_square_offset * (1.0 - alpha) + offset * offset * alpha;
+        }
+
+        state.rms_offset_seconds = self.mean_square_offset.sqrt();
+        state.last_offset_seconds = offset;
+        state.sample_count = state.sample_count.saturating_add(1);
+        self.last_measured_at = Some(at);
+    }
 }
 
 #[derive(Clone)]
@@ -185,6 +528,12 @@ struct PpsOffset {
 
     /// Nombre de mesures PPS utilisées pour calculer cet offset
     sample_count: u32,
+
+    /// Instant monotone du dernier pulse PPS
+    pulse_instant: std::time::Instant,
+
+    /// Temps GPS de la seconde entière marquée par ce pulse
+    pulse_timestamp: NtpTimestamp,
 }
 
 impl GpsNmeaClock {
@@ -192,18 +541,160 @@ impl GpsNmeaClock {
         GpsNmeaClock {
             last_sync: std::sync::Arc::new(std::sync::RwLock::new(None)),
             pps_offset: std::sync::Arc::new(std::sync::RwLock::new(None)),
+            discipline: Mutex::new(DisciplineTracker::default()),
             system_clock: SystemClock::new(),
             sync_timeout: sync_timeout_secs,
+            pps_offset_max_age: std::time::Duration::from_secs(5),
+            nmea_only_precision: NMEA_ONLY_PRECISION,
+            fix_quality: std::sync::atomic::AtomicU8::new(0),
+            time_accuracy: std::sync::RwLock::new(None),
+            hdop: std::sync::RwLock::new(None),
+            sync_criteria: SyncCriteria::default(),
+            dgps_refid: *b"SBAS",
+            system_clock_trusted: true,
+            max_divergence: None,
+            refuse_on_divergence: false,
+            divergence_alarm_ns: std::sync::atomic::AtomicU64::new(0),
+            pps_offset_histogram: None,
+        }
+    }
+
+    /// Mesure la distribution des offsets PPS bruts (exportée sur /metrics)
+    pub fn with_pps_offset_histogram(mut self, enabled: bool) -> Self {
+        self.pps_offset_histogram = enabled.then(PpsOffsetHistogram::default);
+        self
+    }
+
+    /// Écart maximal toléré entre le temps GPS et l'horloge système ; si
+    /// `refuse`, l'horloge ne fournit plus de temps absolu pendant l'alarme
+    pub fn with_divergence_limit(mut self, max: Option<std::time::Duration>, refuse: bool) -> Self {
+        self.max_divergence = max;
+        self.refuse_on_divergence = refuse;
+        self
+    }
+
+    /// Temps GPS courant si synchronisé, après réévaluation de l'alarme de
+    /// divergence (retombée sans synchronisation GPS)
+    fn refresh_divergence(&self) -> Option<NtpTimestamp> {
+        let gps_time = self.is_gps_synced().then(|| self.calculate_gps_time()).flatten();
+        match gps_time {
+            Some(gps_time) => self.check_divergence(gps_time),
+            None => self.divergence_alarm_ns.store(0, std::sync::atomic::Ordering::Relaxed),
+        }
+        gps_time
+    }
+
+    /// Compare le temps GPS servi à l'horloge système et lève ou retombe
+    /// l'alarme de divergence
+    fn check_divergence(&self, gps_time: NtpTimestamp) {
+        let Some(max) = self.max_divergence.filter(|_| self.system_clock_trusted) else {
+            return;
+        };
+
+        let divergence = self.system_clock.now().seconds_since(gps_time).abs();
+        let alarm_ns = if divergence > max.as_secs_f64() {
+            ((divergence * 1e9) as u64).max(1)
+        } else {
+            0
+        };
+
+        let previous = self.divergence_alarm_ns.swap(alarm_ns, std::sync::atomic::Ordering::Relaxed);
+        if alarm_ns > 0 && previous == 0 {
+            warn!(
+                "GPS and system clock diverge by {:.3} s (limit {:?}): bad GPS fix or wrong system clock?",
+                divergence, max
+            );
+        } else if alarm_ns == 0 && previous > 0 {
+            info!("GPS and system clock agree again (divergence {:.6} s)", divergence);
+        }
+    }
+
+    /// Indique si l'horloge système (RTC) est digne de confiance
+    /// (faux sur matériel sans RTC ou avec pile morte)
+    pub fn with_system_clock_trusted(mut self, trusted: bool) -> Self {
+        self.system_clock_trusted = trusted;
+        self
+    }
+
+    /// Âge maximal (secondes) au-delà duquel l'offset PPS n'est plus utilisé
+    pub fn with_pps_offset_max_age(mut self, secs: u64) -> Self {
+        self.pps_offset_max_age = std::time::Duration::from_secs(secs);
+        self
+    }
+
+    /// Précision annoncée tant que seules les trames NMEA datent l'horloge
+    pub fn with_nmea_only_precision(mut self, precision: i8) -> Self {
+        self.nmea_only_precision = precision;
+        self
+    }
+
+    /// Incertitude du temps daté par les seules trames NMEA (ns)
+    fn nmea_only_latency_ns(&self) -> u64 {
+        (1e9 * 2f64.powi(self.nmea_only_precision as i32)) as u64
+    }
+
+    /// Critères de validité de la synchronisation GPS (`[clock.gps.sync_criteria]`)
+    pub fn with_sync_criteria(mut self, criteria: SyncCriteria) -> Self {
+        self.sync_criteria = criteria;
+        self
+    }
+
+    /// Définit le reference ID annoncé en mode DGPS/SBAS (ex: "SBAS", "WAAS", "EGNO")
+    pub fn with_dgps_refid(mut self, refid: [u8; 4]) -> Self {
+        self.dgps_refid = refid;
+        self
+    }
+
+    /// Met à jour la qualité de fix GGA (appelé depuis le thread GPS)
+    pub fn set_fix_quality(&self, quality: u8) {
+        self.fix_quality.store(quality, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Met à jour le HDOP du dernier fix GGA (`None` si absent de la trame)
+    pub fn set_hdop(&self, hdop: Option<f64>) {
+        if let Ok(mut guard) = self.hdop.write() {
+            *guard = hdop;
+        }
+    }
+
+    /// Met à jour la précision temporelle estimée par le module (appelé depuis le thread GPS)
+    pub fn set_time_accuracy_ns(&self, accuracy_ns: u64) {
+        if let Ok(mut guard) = self.time_accuracy.write() {
+            *guard = Some((accuracy_ns, std::time::Instant::now()));
+        }
+    }
+
+    /// Reference ID correspondant à une qualité de fix GGA
+    fn reference_id_for_quality(&self, quality: u8) -> [u8; 4] {
+        match quality {
+            2 => self.dgps_refid,
+            4 | 5 => *b"RTK\0",
+            _ if self.pps_is_fresh() => *b"PPS\0",
+            _ => *b"GPS\0",
+        }
+    }
+
+    /// Vrai si un offset PPS récent (< `pps_offset_max_age`) est disponible
+    fn pps_is_fresh(&self) -> bool {
+        match self.pps_offset.read() {
+            Ok(guard) => guard
+                .as_ref()
+                .map(|pps| pps.measured_at.elapsed() < self.pps_offset_max_age)
+                .unwrap_or(false),
+            Err(_) => false,
         }
     }
 
     /// Met à jour la synchronisation GPS
     /// Cette méthode sera appelée depuis le thread qui lit le port série GPS
-    pub fn update_gps_time(&self, gps_timestamp: NtpTimestamp, satellite_count: u8) {
+    ///
+    /// `satellites` : satellites utilisés selon la dernière trame GGA (`None`
+    /// si le récepteur n'en a émis aucune)
+    pub fn update_gps_time(&self, gps_timestamp: NtpTimestamp, satellites: Option<u8>) {
         let sync = GpsSync {
             timestamp: gps_timestamp,
             system_time: std::time::Instant::now(),
-            quality: satellite_count,
+            satellites,
         };
 
         if let Ok(mut guard) = self.last_sync.write() {
@@ -217,7 +708,30 @@ impl GpsNmeaClock {
     /// # Arguments
     /// * `pps_instant` - Instant système du pulse PPS
     /// * `gps_second_boundary` - Timestamp GPS de la seconde entière (ex: 11:29:24.000000)
-    pub fn update_pps_offset(&self, pps_instant: std::time::Instant, gps_second_boundary: NtpTimestamp) {
+    ///
+    /// Retourne la mesure (offset instantané et filtré), ou None si aucun offset
+    /// n'est calculé (horloge système non fiable)
+    pub fn update_pps_offset(
+        &self,
+        pps_instant: std::time::Instant,
+        gps_second_boundary: NtpTimestamp,
+    ) -> Option<PpsMeasurement> {
+        // RTC non fiable : on ne mémorise que l'ancrage monotone du pulse,
+        // sans jamais comparer à l'horloge système
+        if !self.system_clock_trusted {
+            if let Ok(mut guard) = self.pps_offset.write() {
+                let sample_count = guard.as_ref().map(|pps| pps.sample_count + 1).unwrap_or(1);
+                *guard = Some(PpsOffset {
+                    offset_seconds: 0.0,
+                    measured_at: std::time::Instant::now(),
+                    sample_count,
+                    pulse_instant: pps_instant,
+                    pulse_timestamp: gps_second_boundary,
+                });
+            }
+            return None;
+        }
+
         // Convertir l'instant système en timestamp NTP pour comparaison
         let system_ntp = self.system_clock.now();
 
@@ -232,26 +746,47 @@ impl GpsNmeaClock {
         // Offset = système - GPS (positif si système en avance)
         let offset = system_at_pps_secs - gps_at_pps_secs;
 
-        if let Ok(mut guard) = self.pps_offset.write() {
-            if let Some(existing) = guard.as_mut() {
-                // Filtrage EWMA (Exponentially Weighted Moving Average) pour stabilité
-                // 90% ancien + 10% nouveau
-                existing.offset_seconds = existing.offset_seconds * 0.9 + offset * 0.1;
-                existing.measured_at = std::time::Instant::now();
-                existing.sample_count += 1;
-            } else {
-                // Première mesure
-                *guard = Some(PpsOffset {
-                    offset_seconds: offset,
-                    measured_at: std::time::Instant::now(),
-                    sample_count: 1,
-                });
-            }
+        if let Ok(mut discipline) = self.discipline.lock() {
+            discipline.record(offset, pps_instant);
+        }
+        if let Some(ref histogram) = self.pps_offset_histogram {
+            histogram.record(offset);
         }
+
+        let mut guard = self.pps_offset.write().ok()?;
+        if let Some(existing) = guard.as_mut() {
+            // Filtrage EWMA (Exponentially Weighted Moving Average) pour stabilité
+            // 90% ancien + 10% nouveau
+            existing.offset_seconds = existing.offset_seconds * 0.9 + offset * 0.1;
+            existing.measured_at = std::time::Instant::now();
+            existing.sample_count += 1;
+            existing.pulse_instant = pps_instant;
+            existing.pulse_timestamp = gps_second_boundary;
+        } else {
+            // Première mesure
+            *guard = Some(PpsOffset {
+                offset_seconds: offset,
+                measured_at: std::time::Instant::now(),
+                sample_count: 1,
+                pulse_instant: pps_instant,
+                pulse_timestamp: gps_second_boundary,
+            });
+        }
+
+        guard.as_ref().map(|pps| PpsMeasurement {
+            raw_offset_seconds: offset,
+            ewma_offset_seconds: pps.offset_seconds,
+            sample_count: pps.sample_count,
+        })
     }
 
     /// Retourne l'offset PPS actuel si disponible
+    /// (jamais calculé lorsque l'horloge système n'est pas de confiance)
     pub fn get_pps_offset(&self) -> Option<f64> {
+        if !self.system_clock_trusted {
+            return None;
+        }
+
         if let Ok(guard) = self.pps_offset.read() {
             guard.as_ref().map(|offset| offset.offset_seconds)
         } else {
@@ -259,15 +794,37 @@ impl GpsNmeaClock {
         }
     }
 
-    /// Vérifie si la synchronisation GPS est valide
+    /// Vérifie si la synchronisation GPS est valide : trame récente et
+    /// critères de `sync_criteria` satisfaits
     fn is_gps_synced(&self) -> bool {
-        if let Ok(guard) = self.last_sync.read() {
-            if let Some(sync) = guard.as_ref() {
-                let elapsed = sync.system_time.elapsed().as_secs();
-                return elapsed < self.sync_timeout && sync.quality >= 3;
+        let satellites = match self.last_sync.read() {
+            Ok(guard) => match guard.as_ref() {
+                Some(sync) if sync.system_time.elapsed().as_secs() < self.sync_timeout => sync.satellites,
+                _ => return false,
+            },
+            Err(_) => return false,
+        };
+
+        self.meets_sync_criteria(satellites)
+    }
+
+    /// Vrai si le fix satisfait les critères de synchronisation configurés
+    /// (un critère dont la donnée est inconnue n'est pas bloquant)
+    fn meets_sync_criteria(&self, satellites: Option<u8>) -> bool {
+        let criteria = &self.sync_criteria;
+
+        if satellites.is_some_and(|count| count < criteria.min_satellites) {
+            return false;
+        }
+
+        if let Some(max_hdop) = criteria.max_hdop {
+            let hdop = self.hdop.read().ok().and_then(|guard| *guard);
+            if hdop.is_some_and(|hdop| hdop > max_hdop) {
+                return false;
             }
         }
-        false
+
+        !criteria.require_pps || self.pps_is_fresh()
     }
 
     /// Calcule le temps GPS actuel avec correction PPS
@@ -280,8 +837,19 @@ impl GpsNmeaClock {
         // MÉTHODE 1 (préférée) : Utiliser l'offset PPS pour précision maximale
         if let Ok(pps_guard) = self.pps_offset.read() {
             if let Some(pps) = pps_guard.as_ref() {
-                // Vérifier que l'offset PPS est récent (< 5 secondes)
-                if pps.measured_at.elapsed().as_secs() < 5 {
+                // Vérifier que l'offset PPS est récent (< `pps_offset_max_age`)
+                let fresh = pps.measured_at.elapsed() < self.pps_offset_max_age;
+                if fresh && !self.system_clock_trusted {
+                    // RTC non fiable : seconde GPS du pulse + temps monotone écoulé depuis
+                    let elapsed = pps.pulse_instant.elapsed();
+                    let elapsed_ntp = NtpTimestamp::from_seconds_and_nanos(
+                        elapsed.as_secs(),
+                        elapsed.subsec_nanos(),
+                    );
+                    return Some(NtpTimestamp(pps.pulse_timestamp.0.wrapping_add(elapsed_ntp.0)));
+                }
+
+                if fresh {
                     // Obtenir le temps système actuel
                     let system_now = self.system_clock.now();
 
@@ -309,17 +877,14 @@ impl GpsNmeaClock {
             if let Some(sync) = guard.as_ref() {
                 let elapsed = sync.system_time.elapsed();
 
-                // Temps GPS + temps écoulé depuis la sync
-                let elapsed_secs = elapsed.as_secs();
-                let elapsed_nanos = elapsed.subsec_nanos();
-
-                let total_secs = sync.timestamp.seconds() as u64 + elapsed_secs;
-                let total_nanos = elapsed_nanos;
+                // Temps GPS (fraction RMC incluse) + temps écoulé depuis la sync,
+                // additionnés en virgule fixe 32.32 pour ne pas perdre la fraction
+                let elapsed_ntp = NtpTimestamp::from_seconds_and_nanos(
+                    elapsed.as_secs(),
+                    elapsed.subsec_nanos(),
+                );
 
-                return Some(NtpTimestamp::from_seconds_and_nanos(
-                    total_secs,
-                    total_nanos,
-                ));
+                return Some(NtpTimestamp(sync.timestamp.0.wrapping_add(elapsed_ntp.0)));
             }
         }
 
@@ -331,7 +896,15 @@ impl GpsNmeaClock {
 impl ClockSource for GpsNmeaClock {
     fn now(&self) -> NtpTimestamp {
         // Utiliser GPS si disponible, sinon fallback vers horloge système
-        if self.is_gps_synced() {
+        // (temps GPS servi même en cas de divergence : l'offset PPS, s'il est
+        // frais, l'emporte sur l'horloge système)
+        if let Some(gps_time) = self.refresh_divergence() {
+            return gps_time;
+        }
+
+        // RTC non fiable : continuer d'extrapoler depuis le dernier temps GPS connu
+        // plutôt que de retomber sur une horloge système erronée
+        if !self.system_clock_trusted {
             if let Some(gps_time) = self.calculate_gps_time() {
                 return gps_time;
             }
@@ -341,14 +914,66 @@ impl ClockSource for GpsNmeaClock {
         self.system_clock.now()
     }
 
+    fn has_absolute_time(&self) -> bool {
+        if self.system_clock_trusted {
+            // Alarme réévaluée à chaque appel : aucune réponse ne l'actualise pendant le refus
+            if self.refuse_on_divergence {
+                self.refresh_divergence();
+                return !self.divergence_alarm();
+            }
+            return true;
+        }
+
+        // Sans RTC de confiance, seule une trame GPS fournit le temps absolu
+        self.last_sync
+            .read()
+            .map(|guard| guard.is_some())
+            .unwrap_or(false)
+    }
+
     fn reference_id(&self) -> [u8; 4] {
         if self.is_gps_synced() {
-            *b"GPS\0" // Source GPS
+            // Source GPS, affinée selon la qualité du fix (DGPS/SBAS, RTK, PPS)
+            self.reference_id_for_quality(self.fix_quality.load(std::sync::atomic::Ordering::Relaxed))
         } else {
             *b"LOCL" // Horloge locale (pas synchronisé)
         }
     }
 
+    fn pps_offset_histogram(&self) -> Option<HistogramSnapshot> {
+        self.pps_offset_histogram.as_ref().map(PpsOffsetHistogram::snapshot)
+    }
+
+    fn last_update(&self) -> NtpTimestamp {
+        // NMEA seul : la référence est la dernière trame datée ; avec PPS, elle
+        // est disciplinée en continu
+        if self.is_gps_synced() && !self.pps_is_fresh() {
+            let last_fix = self
+                .last_sync
+                .read()
+                .ok()
+                .and_then(|guard| guard.as_ref().map(|sync| sync.timestamp));
+            if let Some(timestamp) = last_fix {
+                return timestamp;
+            }
+        }
+        self.now()
+    }
+
+    fn discipline(&self) -> Option<DisciplineState> {
+        let mut state = self.discipline.lock().ok()?.state;
+        state.ref_time = self
+            .last_sync
+            .read()
+            .ok()
+            .and_then(|guard| guard.as_ref().map(|sync| sync.timestamp));
+
+        if state.ref_time.is_none() && state.sample_count == 0 {
+            return None;
+        }
+        Some(state)
+    }
+
     fn stratum(&self) -> u8 {
         if self.is_gps_synced() {
             1 // Stratum 1 = source primaire (GPS)
@@ -359,11 +984,398 @@ impl ClockSource for GpsNmeaClock {
 
     fn precision(&self) -> i8 {
         if self.is_gps_synced() {
-            -20 // ~1µs avec GPS
+            // Incertitude bornée par le chemin PPS ou NMEA, sinon ~1µs avec PPS
+            let precision = self.time_accuracy_ns().map(precision_from_ns).unwrap_or(-20);
+            if self.pps_is_fresh() {
+                precision
+            } else {
+                // NMEA seul : la latence série (~100 ms) domine l'incertitude
+                precision.max(self.nmea_only_precision)
+            }
         } else {
             self.system_clock.precision()
         }
     }
+
+    fn time_accuracy_ns(&self) -> Option<u64> {
+        if !self.is_gps_synced() {
+            return None;
+        }
+
+        // Estimation périmée si le module a cessé de la fournir
+        let guard = self.time_accuracy.read().ok()?;
+        let estimate_ns = guard
+            .filter(|(_, measured_at)| measured_at.elapsed().as_secs() < self.sync_timeout)
+            .map(|(accuracy_ns, _)| accuracy_ns);
+
+        // L'estimation du module (quelques ns) ne couvre que la solution GPS : le
+        // temps servi hérite de la gigue du PPS lu sur CTS, ou de la latence NMEA
+        if self.pps_is_fresh() {
+            estimate_ns.map(|ns| ns.max(PPS_ACCURACY_FLOOR_NS))
+        } else {
+            Some(estimate_ns.unwrap_or(0).max(self.nmea_only_latency_ns()))
+        }
+    }
+
+    fn divergence_ns(&self) -> Option<u64> {
+        match self.divergence_alarm_ns.load(std::sync::atomic::Ordering::Relaxed) {
+            0 => None,
+            divergence_ns => Some(divergence_ns),
+        }
+    }
+}
+
+/// Échantillon d'offset entre une horloge et l'horloge système
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct OffsetSample {
+    /// Temps système au moment de la mesure (millisecondes Unix)
+    pub unix_timestamp_ms: u64,
+
+    /// Offset brut horloge - système (secondes, positif si l'horloge est en avance)
+    pub offset_seconds: f64,
+}
+
+/// Historique borné (buffer circulaire) des offsets mesurés
+pub struct OffsetHistory {
+    samples: Mutex<VecDeque<OffsetSample>>,
+    capacity: usize,
+}
+
+impl OffsetHistory {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        OffsetHistory {
+            samples: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    /// Ajoute un échantillon, en évinçant le plus ancien si le buffer est plein
+    pub fn push(&self, sample: OffsetSample) {
+        if let Ok(mut samples) = self.samples.lock() {
+            if samples.len() == self.capacity {
+                samples.pop_front();
+            }
+            samples.push_back(sample);
+        }
+    }
+
+    /// Copie des échantillons, du plus ancien au plus récent
+    pub fn snapshot(&self) -> Vec<OffsetSample> {
+        match self.samples.lock() {
+            Ok(samples) => samples.iter().copied().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+/// Décorateur d'horloge qui enregistre l'offset brut par rapport à l'horloge système
+///
+/// Le comportement temporel est inchangé : `now()` retourne exactement le temps
+/// de l'horloge interne, seule l'observabilité est ajoutée.
+pub struct InstrumentedClock<C: ClockSource + ?Sized> {
+    inner: Arc<C>,
+    system_clock: SystemClock,
+    history: Arc<OffsetHistory>,
+}
+
+impl<C: ClockSource + ?Sized> InstrumentedClock<C> {
+    pub fn new(inner: Arc<C>, history: Arc<OffsetHistory>) -> Self {
+        InstrumentedClock {
+            inner,
+            system_clock: SystemClock::new(),
+            history,
+        }
+    }
+}
+
+impl<C: ClockSource + ?Sized> ClockSource for InstrumentedClock<C> {
+    fn now(&self) -> NtpTimestamp {
+        let time = self.inner.now();
+        let system = self.system_clock.now();
+
+        let unix_seconds = (system.seconds() as u64).saturating_sub(NTP_UNIX_OFFSET);
+        let millis = (system.fraction() as u64 * 1000) >> 32;
+        self.history.push(OffsetSample {
+            unix_timestamp_ms: unix_seconds * 1000 + millis,
+            offset_seconds: time.seconds_since(system),
+        });
+
+        time
+    }
+
+    fn reference_id(&self) -> [u8; 4] {
+        self.inner.reference_id()
+    }
+
+    fn stratum(&self) -> u8 {
+        self.inner.stratum()
+    }
+
+    fn precision(&self) -> i8 {
+        self.inner.precision()
+    }
+
+    fn has_absolute_time(&self) -> bool {
+        self.inner.has_absolute_time()
+    }
+
+    fn discipline(&self) -> Option<DisciplineState> {
+        self.inner.discipline()
+    }
+
+    fn pps_offset_histogram(&self) -> Option<HistogramSnapshot> {
+        self.inner.pps_offset_histogram()
+    }
+
+    fn time_accuracy_ns(&self) -> Option<u64> {
+        self.inner.time_accuracy_ns()
+    }
+
+    fn active_source(&self) -> Option<String> {
+        self.inner.active_source()
+    }
+
+    fn divergence_ns(&self) -> Option<u64> {
+        self.inner.divergence_ns()
+    }
+
+    fn last_update(&self) -> NtpTimestamp {
+        self.inner.last_update()
+    }
+}
+
+/// Source de `PriorityClock`, identifiée par son nom de configuration
+pub struct PrioritySource {
+    pub name: String,
+    pub clock: Arc<dyn ClockSource>,
+}
+
+/// Sélection courante de `PriorityClock`
+#[derive(Debug)]
+struct PrioritySelection {
+    /// Index de la source active
+    active: usize,
+
+    /// Source de priorité supérieure redevenue disponible, et depuis quand
+    candidate: Option<(usize, Instant)>,
+}
+
+/// Horloge à sources multiples, par ordre de priorité
+///
+/// Chaque lecture utilise la première source disponible (temps absolu et
+/// stratum < 16) ; sans source disponible, la dernière de la liste sert de
+/// repli. Une source perdue est quittée immédiatement, mais une source de
+/// priorité supérieure n'est reprise qu'après être restée disponible pendant
+/// `holdoff` (hystérésis contre les basculements répétés).
+pub struct PriorityClock {
+    sources: Vec<PrioritySource>,
+    holdoff: Duration,
+    selection: Mutex<PrioritySelection>,
+}
+
+impl PriorityClock {
+    pub fn new(sources: Vec<PrioritySource>, holdoff: Duration) -> Self {
+        assert!(!sources.is_empty(), "PriorityClock requires at least one source");
+
+        let clock = PriorityClock {
+            sources,
+            holdoff,
+            selection: Mutex::new(PrioritySelection { active: 0, candidate: None }),
+        };
+
+        // Sélection initiale sans hystérésis
+        let best = clock.best_available();
+        if let Ok(mut selection) = clock.selection.lock() {
+            selection.active = best;
+        }
+        clock
+    }
+
+    fn is_available(source: &PrioritySource) -> bool {
+        source.clock.has_absolute_time() && source.clock.stratum() < 16
+    }
+
+    /// Index de la première source disponible (la dernière à défaut)
+    fn best_available(&self) -> usize {
+        self.sources
+            .iter()
+            .position(Self::is_available)
+            .unwrap_or(self.sources.len() - 1)
+    }
+
+    /// Réévalue la source active à l'instant donné
+    fn select_at(&self, now: Instant) -> &PrioritySource {
+        let best = self.best_available();
+        let Ok(mut selection) = self.selection.lock() else {
+            return &self.sources[best];
+        };
+
+        if best == selection.active {
+            selection.candidate = None;
+        } else if !Self::is_available(&self.sources[selection.active]) || best > selection.active {
+            // Source active perdue : basculement immédiat
+            self.switch(&mut selection, best);
+        } else {
+            // Source prioritaire revenue : attendre qu'elle reste stable
+            match selection.candidate {
+                Some((index, since)) if index == best => {
+                    if now.saturating_duration_since(since) >= self.holdoff {
+                        self.switch(&mut selection, best);
+                    }
+                }
+                _ => selection.candidate = Some((best, now)),
+            }
+        }
+
+        &self.sources[selection.active]
+    }
+
+    fn switch(&self, selection: &mut PrioritySelection, index: usize) {
+        info!(
+            "Clock source switched: {} -> {}",
+            self.sources[selection.active].name, self.sources[index].name
+        );
+        selection.active = index;
+        selection.candidate = None;
+    }
+
+    fn select(&self) -> &PrioritySource {
+        self.select_at(Instant::now())
+    }
+
+    /// Source active, sans réévaluation
+    fn current(&self) -> &PrioritySource {
+        let index = self.selection.lock().map(|selection| selection.active).unwrap_or(0);
+        &self.sources[index]
+    }
+}
+
+impl ClockSource for PriorityClock {
+    fn now(&self) -> NtpTimestamp {
+        self.select().clock.now()
+    }
+
+    fn reference_id(&self) -> [u8; 4] {
+        self.current().clock.reference_id()
+    }
+
+    fn stratum(&self) -> u8 {
+        self.select().clock.stratum()
+    }
+
+    fn precision(&self) -> i8 {
+        self.current().clock.precision()
+    }
+
+    fn has_absolute_time(&self) -> bool {
+        self.current().clock.has_absolute_time()
+    }
+
+    fn discipline(&self) -> Option<DisciplineState> {
+        self.current().clock.discipline()
+    }
+
+    fn pps_offset_histogram(&self) -> Option<HistogramSnapshot> {
+        self.current().clock.pps_offset_histogram()
+    }
+
+    fn time_accuracy_ns(&self) -> Option<u64> {
+        self.current().clock.time_accuracy_ns()
+    }
+
+    fn active_source(&self) -> Option<String> {
+        Some(self.current().name.clone())
+    }
+
+    fn divergence_ns(&self) -> Option<u64> {
+        self.current().clock.divergence_ns()
+    }
+
+    fn last_update(&self) -> NtpTimestamp {
+        self.current().clock.last_update()
+    }
+}
+
+/// Horloge réglée manuellement sur une référence externe (banc de calibration hors réseau)
+///
+/// Le temps absolu est fixé par `PUT /api/clock/set` à un instant monotone capturé,
+/// puis extrapolé par l'oscillateur local (`Instant`), insensible aux sauts de
+/// l'horloge système. Non synchronisée (stratum 16) tant qu'aucune référence n'a
+/// été fixée.
+pub struct ManualClock {
+    /// Temps de référence et instant monotone auquel il a été fixé
+    reference: Mutex<Option<(NtpTimestamp, Instant)>>,
+
+    /// Stratum annoncé une fois la référence fixée (`clock.manual_stratum`)
+    stratum: u8,
+}
+
+impl ManualClock {
+    pub fn new(stratum: u8) -> Self {
+        ManualClock {
+            reference: Mutex::new(None),
+            stratum,
+        }
+    }
+
+    /// Fixe le temps de référence à l'instant présent
+    pub fn set_reference(&self, time: NtpTimestamp) {
+        self.set_reference_at(time, Instant::now());
+    }
+
+    /// Fixe le temps de référence à un instant monotone donné
+    pub fn set_reference_at(&self, time: NtpTimestamp, at: Instant) {
+        if let Ok(mut reference) = self.reference.lock() {
+            *reference = Some((time, at));
+        }
+    }
+
+    fn reference(&self) -> Option<(NtpTimestamp, Instant)> {
+        self.reference.lock().ok().and_then(|reference| *reference)
+    }
+}
+
+impl ClockSource for ManualClock {
+    fn now(&self) -> NtpTimestamp {
+        match self.reference() {
+            Some((time, at)) => {
+                // Addition en virgule fixe 32.32 (fraction de la référence conservée)
+                let elapsed = at.elapsed();
+                let elapsed_ntp = NtpTimestamp::from_seconds_and_nanos(elapsed.as_secs(), elapsed.subsec_nanos());
+                NtpTimestamp(time.0.wrapping_add(elapsed_ntp.0))
+            }
+            // Pas encore de référence : heure système pour l'affichage (non servie)
+            None => SystemClock::new().now(),
+        }
+    }
+
+    fn reference_id(&self) -> [u8; 4] {
+        *b"LOCL"
+    }
+
+    fn stratum(&self) -> u8 {
+        if self.reference().is_some() {
+            self.stratum
+        } else {
+            16
+        }
+    }
+
+    fn precision(&self) -> i8 {
+        SystemClock::new().precision()
+    }
+
+    fn has_absolute_time(&self) -> bool {
+        self.reference().is_some()
+    }
+
+    fn last_update(&self) -> NtpTimestamp {
+        match self.reference() {
+            Some((time, _)) => time,
+            None => self.now(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -381,6 +1393,80 @@ mod tests {
         assert!(ts2.seconds() >= ts1.seconds());
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_last_sync_from_timex() {
+        let now = NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0);
+
+        // maxerror de 16 ms : 16 000 µs / 500 µs/s = 32 s depuis la dernière mise à jour
+        assert_eq!(
+            last_sync_from_timex(now, libc::TIME_OK, 0, 16_000),
+            Some(NtpTimestamp::from_seconds_and_nanos(3_899_999_968, 0))
+        );
+
+        // Fraction de seconde : 250 µs → 0,5 s
+        assert_eq!(
+            last_sync_from_timex(now, libc::TIME_OK, libc::STA_PLL, 250),
+            Some(NtpTimestamp::from_seconds_and_nanos(3_899_999_999, 500_000_000))
+        );
+
+        // Horloge non synchronisée selon le noyau : pas de date de synchronisation
+        assert_eq!(last_sync_from_timex(now, libc::TIME_ERROR, 0, 16_000), None);
+        assert_eq!(last_sync_from_timex(now, libc::TIME_OK, libc::STA_UNSYNC, 16_000), None);
+        assert_eq!(last_sync_from_timex(now, libc::TIME_OK, 0, 16_000_000), None);
+
+        // SystemClock : jamais dans le futur
+        let clock = SystemClock::new();
+        let reference = clock.last_update();
+        assert!(reference.0 <= clock.now().0);
+    }
+
+    #[test]
+    fn test_sync_criteria_gate_synced_state() {
+        let gps_time = NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0);
+
+        // Défaut : au moins 3 satellites, pas de limite HDOP ni d'exigence PPS
+        let clock = GpsNmeaClock::new(30);
+        clock.update_gps_time(gps_time, Some(2));
+        assert_eq!(clock.stratum(), 16);
+        clock.update_gps_time(gps_time, Some(3));
+        assert_eq!(clock.stratum(), 1);
+
+        // Satellites : seuil configurable, nombre inconnu (pas de GGA) non bloquant
+        let clock = GpsNmeaClock::new(30).with_sync_criteria(SyncCriteria {
+            min_satellites: 6,
+            ..SyncCriteria::default()
+        });
+        clock.update_gps_time(gps_time, Some(5));
+        assert_eq!(clock.stratum(), 16);
+        clock.update_gps_time(gps_time, Some(6));
+        assert_eq!(clock.stratum(), 1);
+        clock.update_gps_time(gps_time, None);
+        assert_eq!(clock.stratum(), 1);
+
+        // HDOP : fix rejeté au-delà du maximum, HDOP inconnu non bloquant
+        let clock = GpsNmeaClock::new(30).with_sync_criteria(SyncCriteria {
+            max_hdop: Some(2.0),
+            ..SyncCriteria::default()
+        });
+        clock.update_gps_time(gps_time, Some(8));
+        assert_eq!(clock.stratum(), 1);
+        clock.set_hdop(Some(4.5));
+        assert_eq!(clock.stratum(), 16);
+        clock.set_hdop(Some(1.1));
+        assert_eq!(clock.stratum(), 1);
+
+        // PPS exigé : non synchronisé tant qu'aucun offset PPS récent n'est disponible
+        let clock = GpsNmeaClock::new(30).with_sync_criteria(SyncCriteria {
+            require_pps: true,
+            ..SyncCriteria::default()
+        });
+        clock.update_gps_time(gps_time, Some(8));
+        assert_eq!(clock.stratum(), 16);
+        clock.update_pps_offset(Instant::now(), gps_time);
+        assert_eq!(clock.stratum(), 1);
+    }
+
     #[test]
     fn test_gps_clock_fallback() {
         let clock = GpsNmeaClock::new(10);
@@ -396,10 +1482,381 @@ mod tests {
 
         // Simuler une sync GPS
         let gps_time = NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0);
-        clock.update_gps_time(gps_time, 8);
+        clock.update_gps_time(gps_time, Some(8));
 
         // Doit être en stratum 1
         assert_eq!(clock.stratum(), 1);
         assert_eq!(&clock.reference_id(), b"GPS\0");
     }
+
+    #[test]
+    fn test_gps_system_divergence_alarm() {
+        let clock = GpsNmeaClock::new(10).with_divergence_limit(Some(Duration::from_secs(1)), true);
+        let system_now = SystemClock::new().now();
+
+        // GPS 100 s en avance sur l'horloge système : temps GPS servi, alarme levée
+        let gps_time = NtpTimestamp::from_seconds_and_nanos(system_now.seconds() as u64 + 100, 0);
+        clock.update_gps_time(gps_time, Some(8));
+        let served = clock.now();
+        assert!(served.seconds_since(gps_time) >= 0.0 && served.seconds_since(gps_time) < 1.0);
+        assert!(clock.divergence_alarm());
+
+        // Écart exposé à part (root dispersion), précision inchangée, refus de servir
+        let divergence_ns = clock.divergence_ns().unwrap();
+        assert!((99_000_000_000..101_000_000_000).contains(&divergence_ns), "{}", divergence_ns);
+        assert_eq!(clock.time_accuracy_ns(), Some(125_000_000));
+        assert_eq!(clock.precision(), NMEA_ONLY_PRECISION);
+        assert!(!clock.has_absolute_time());
+
+        // Sources de nouveau d'accord : alarme retombée, service repris
+        clock.update_gps_time(SystemClock::new().now(), Some(8));
+        assert!(clock.has_absolute_time());
+        assert!(!clock.divergence_alarm());
+        assert_eq!(clock.time_accuracy_ns(), Some(125_000_000));
+
+        // Sans refus : même divergence, temps GPS servi
+        let clock = GpsNmeaClock::new(10).with_divergence_limit(Some(Duration::from_secs(1)), false);
+        clock.update_gps_time(gps_time, Some(8));
+        assert!(clock.has_absolute_time());
+        clock.now();
+        assert!(clock.divergence_alarm());
+    }
+
+    #[test]
+    fn test_nmea_only_keeps_fraction() {
+        let clock = GpsNmeaClock::new(10);
+
+        // Trame RMC avec 0.25 s de fraction, sans PPS
+        let gps_time = NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 250_000_000);
+        clock.update_gps_time(gps_time, Some(8));
+
+        let now = clock.now();
+        let nanos = ((now.fraction() as u64 * 1_000_000_000) >> 32) as u32;
+
+        assert_eq!(now.seconds(), 3_900_000_000);
+        // Fraction conservée, plus le (faible) temps écoulé depuis la sync
+        assert!((250_000_000..350_000_000).contains(&nanos), "nanos = {}", nanos);
+    }
+
+    #[test]
+    fn test_offset_history_bounded() {
+        let history = Arc::new(OffsetHistory::new(4));
+        let clock = InstrumentedClock::new(Arc::new(SystemClock::new()), Arc::clone(&history));
+
+        clock.now();
+        assert_eq!(history.snapshot().len(), 1);
+
+        for _ in 0..10 {
+            clock.now();
+        }
+
+        // Le buffer reste borné à sa capacité
+        let samples = history.snapshot();
+        assert_eq!(samples.len(), 4);
+
+        // Horloge interne = horloge système : offset quasi nul
+        assert!(samples.iter().all(|s| s.offset_seconds.abs() < 0.01));
+        assert!(samples.windows(2).all(|w| w[0].unix_timestamp_ms <= w[1].unix_timestamp_ms));
+    }
+
+    #[test]
+    fn test_reference_id_from_fix_quality() {
+        let clock = GpsNmeaClock::new(10).with_dgps_refid(refid_from_str("WAAS"));
+        clock.update_gps_time(NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0), Some(8));
+
+        let expected: [(u8, &[u8; 4]); 6] = [
+            (0, b"GPS\0"),
+            (1, b"GPS\0"),
+            (2, b"WAAS"),
+            (4, b"RTK\0"),
+            (5, b"RTK\0"),
+            (6, b"GPS\0"),
+        ];
+        for (quality, refid) in expected {
+            clock.set_fix_quality(quality);
+            assert_eq!(&clock.reference_id(), refid, "quality {}", quality);
+        }
+
+        // Avec un PPS récent, un fix GPS standard est annoncé "PPS"
+        clock.update_pps_offset(
+            std::time::Instant::now(),
+            NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 0),
+        );
+        clock.set_fix_quality(1);
+        assert_eq!(&clock.reference_id(), b"PPS\0");
+        clock.set_fix_quality(2);
+        assert_eq!(&clock.reference_id(), b"WAAS");
+    }
+
+    #[test]
+    fn test_untrusted_rtc_pps_path() {
+        let clock = GpsNmeaClock::new(10).with_system_clock_trusted(false);
+
+        // Aucune trame GPS : pas de temps absolu à servir
+        assert!(!clock.has_absolute_time());
+
+        // Temps GPS volontairement éloigné de l'horloge système (RTC erronée)
+        let gps_second = NtpTimestamp::from_seconds_and_nanos(4_000_000_000, 0);
+        clock.update_gps_time(gps_second, Some(8));
+        assert!(clock.has_absolute_time());
+
+        let pulse = std::time::Instant::now() - std::time::Duration::from_millis(200);
+        clock.update_pps_offset(pulse, gps_second);
+
+        // Aucun offset par rapport à l'horloge système n'est calculé
+        assert_eq!(clock.get_pps_offset(), None);
+
+        // Temps = seconde GPS du pulse + temps monotone écoulé (~200 ms)
+        let elapsed = clock.now().seconds_since(gps_second);
+        assert!((0.2..0.3).contains(&elapsed), "elapsed = {}", elapsed);
+    }
+
+    #[test]
+    fn test_pps_offset_histogram_buckets() {
+        let histogram = PpsOffsetHistogram::default();
+        // Pulses normaux autour de zéro, plus un pulse décalé d'une demi-seconde
+        for offset in [0.5e-6, -0.5e-6, 3e-6, -1.5e-5, 1e-6, 0.5] {
+            histogram.record(offset);
+        }
+
+        let snapshot = histogram.snapshot();
+        let cumulative = |le: f64| snapshot.buckets.iter().find(|(bound, _)| *bound == le).unwrap().1;
+        assert_eq!(cumulative(-2e-5), 0);
+        assert_eq!(cumulative(-1e-5), 1);
+        assert_eq!(cumulative(-1e-6), 1);
+        assert_eq!(cumulative(1e-6), 4); // Borne supérieure incluse (le)
+        assert_eq!(cumulative(2e-6), 4);
+        assert_eq!(cumulative(5e-6), 5);
+        assert_eq!(cumulative(1e-3), 5);
+        assert_eq!(snapshot.buckets.last(), Some(&(f64::INFINITY, 6)));
+        assert_eq!(snapshot.count, 6);
+        assert!((snapshot.sum - 0.499_989).abs() < 1e-12);
+
+        // Désactivé par défaut sur l'horloge GPS
+        assert_eq!(GpsNmeaClock::new(30).pps_offset_histogram(), None);
+        let clock = GpsNmeaClock::new(30).with_pps_offset_histogram(true);
+        assert_eq!(clock.pps_offset_histogram().unwrap().count, 0);
+    }
+
+    #[test]
+    fn test_stale_pps_offset_ignored() {
+        let gps_time = NtpTimestamp::from_seconds_and_nanos(4_000_000_000, 0);
+        let pulse_second = NtpTimestamp::from_seconds_and_nanos(4_000_000_100, 0);
+
+        for (max_age, expected) in [(2, gps_time), (5, pulse_second)] {
+            let clock = GpsNmeaClock::new(10)
+                .with_system_clock_trusted(false)
+                .with_pps_offset_max_age(max_age);
+            clock.update_gps_time(gps_time, Some(8));
+            clock.update_pps_offset(std::time::Instant::now(), pulse_second);
+
+            // Offset mesuré il y a 3 s : ignoré avec 2 s d'âge maximal, utilisé avec 5 s
+            if let Some(pps) = clock.pps_offset.write().unwrap().as_mut() {
+                pps.measured_at -= std::time::Duration::from_secs(3);
+            }
+
+            let elapsed = clock.now().seconds_since(expected);
+            assert!((0.0..1.0).contains(&elapsed), "max_age = {}, elapsed = {}", max_age, elapsed);
+        }
+    }
+
+    #[test]
+    fn test_untrusted_rtc_keeps_gps_time_when_stale() {
+        // Timeout de sync à 0 : la sync GPS est immédiatement périmée
+        let clock = GpsNmeaClock::new(0).with_system_clock_trusted(false);
+        let gps_time = NtpTimestamp::from_seconds_and_nanos(4_000_000_000, 0);
+        clock.update_gps_time(gps_time, Some(8));
+
+        // Stratum 16, mais le temps reste extrapolé depuis le GPS et non depuis la RTC
+        assert_eq!(clock.stratum(), 16);
+        let elapsed = clock.now().seconds_since(gps_time);
+        assert!((0.0..1.0).contains(&elapsed), "elapsed = {}", elapsed);
+    }
+
+    /// Source de test au stratum réglable, datée d'une seconde fixe
+    struct TestSource {
+        stratum: std::sync::atomic::AtomicU8,
+        seconds: u64,
+    }
+
+    impl ClockSource for TestSource {
+        fn now(&self) -> NtpTimestamp {
+            NtpTimestamp::from_seconds_and_nanos(self.seconds, 0)
+        }
+
+        fn reference_id(&self) -> [u8; 4] {
+            *b"TEST"
+        }
+
+        fn stratum(&self) -> u8 {
+            self.stratum.load(std::sync::atomic::Ordering::Relaxed)
+        }
+
+        fn precision(&self) -> i8 {
+            -20
+        }
+    }
+
+    #[test]
+    fn test_priority_clock_fallback_order() {
+        let source = |stratum, seconds| {
+            Arc::new(TestSource {
+                stratum: std::sync::atomic::AtomicU8::new(stratum),
+                seconds,
+            })
+        };
+        let gps = source(1, 100);
+        let ntp = source(16, 200);
+        let system = source(16, 300);
+        let clock = PriorityClock::new(
+            vec![
+                PrioritySource { name: "gps".to_string(), clock: gps.clone() },
+                PrioritySource { name: "ntp".to_string(), clock: ntp.clone() },
+                PrioritySource { name: "system".to_string(), clock: system },
+            ],
+            Duration::from_secs(10),
+        );
+        let start = Instant::now();
+        let active = |offset_secs| clock.select_at(start + Duration::from_secs(offset_secs)).name.as_str();
+        let set = |source: &TestSource, stratum| source.stratum.store(stratum, std::sync::atomic::Ordering::Relaxed);
+
+        assert_eq!(active(0), "gps");
+        assert_eq!(clock.active_source().as_deref(), Some("gps"));
+        assert_eq!(clock.now().seconds(), 100);
+
+        // Aucune source disponible : repli sur la dernière de la liste
+        set(&gps, 16);
+        assert_eq!(active(1), "system");
+
+        // Source de priorité inférieure disponible : basculement immédiat
+        set(&ntp, 2);
+        assert_eq!(active(2), "ntp");
+
+        // Retour du GPS : repris seulement après 10 s de disponibilité continue
+        set(&gps, 1);
+        assert_eq!(active(3), "ntp");
+        assert_eq!(active(12), "ntp");
+
+        // Disparition passagère : l'hystérésis repart de zéro
+        set(&gps, 16);
+        assert_eq!(active(13), "ntp");
+        set(&gps, 1);
+        assert_eq!(active(14), "ntp");
+        assert_eq!(active(23), "ntp");
+        assert_eq!(active(24), "gps");
+
+        // Perte de la source active : basculement immédiat vers la suivante
+        set(&gps, 16);
+        assert_eq!(active(25), "ntp");
+    }
+
+    #[test]
+    fn test_refid_from_str() {
+        assert_eq!(&refid_from_str("SBAS"), b"SBAS");
+        assert_eq!(&refid_from_str("GPS"), b"GPS\0");
+        assert_eq!(&refid_from_str("TOOLONG"), b"TOOL");
+    }
+
+    #[test]
+    fn test_time_accuracy_drives_precision() {
+        assert_eq!(precision_from_ns(1_000), -19);
+        assert_eq!(precision_from_ns(30), -24);
+        assert_eq!(precision_from_ns(0), -29);
+        assert_eq!(ntp_short_from_ns(1_000_000_000), 65_536);
+        assert_eq!(ntp_short_from_ns(30), 1);
+
+        let clock = GpsNmeaClock::new(10);
+        clock.set_time_accuracy_ns(30);
+        // Non synchronisé : estimation ignorée
+        assert_eq!(clock.time_accuracy_ns(), None);
+
+        // NMEA seul : l'estimation du module est masquée par la latence des trames
+        clock.update_gps_time(NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0), Some(8));
+        assert_eq!(clock.time_accuracy_ns(), Some(125_000_000));
+        assert_eq!(clock.precision(), NMEA_ONLY_PRECISION);
+
+        // PPS : estimation bornée par la gigue du PPS (~1 µs), sauf si plus large
+        clock.update_pps_offset(
+            std::time::Instant::now(),
+            NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 0),
+        );
+        assert_eq!(clock.time_accuracy_ns(), Some(PPS_ACCURACY_FLOOR_NS));
+        assert_eq!(clock.precision(), -20);
+        clock.set_time_accuracy_ns(5_000);
+        assert_eq!(clock.time_accuracy_ns(), Some(5_000));
+        assert_eq!(clock.precision(), -17);
+    }
+
+    #[test]
+    fn test_nmea_only_precision() {
+        let fix = NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0);
+        let clock = GpsNmeaClock::new(10);
+        clock.update_gps_time(fix, Some(8));
+
+        // NMEA seul : précision grossière, référence à la dernière trame
+        assert_eq!(clock.precision(), NMEA_ONLY_PRECISION);
+        assert_eq!(clock.last_update(), fix);
+
+        // PPS récent : précision fine, référence continue
+        clock.update_pps_offset(
+            std::time::Instant::now(),
+            NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 0),
+        );
+        assert_eq!(clock.precision(), -20);
+        assert!(clock.last_update().0 > fix.0);
+
+        let clock = GpsNmeaClock::new(10).with_nmea_only_precision(-7);
+        clock.update_gps_time(fix, Some(8));
+        assert_eq!(clock.precision(), -7);
+    }
+
+    #[test]
+    fn test_discipline_tracks_frequency() {
+        // Horloge système qui avance de 10 µs par seconde : 10 ppm
+        let start = std::time::Instant::now();
+        let mut tracker = DisciplineTracker::default();
+        for i in 0..20u64 {
+            let at = start + std::time::Duration::from_secs(i);
+            tracker.record(0.000_5 + i as f64 * 0.000_010, at);
+        }
+
+        let state = tracker.state;
+        assert_eq!(state.sample_count, 20);
+        assert!((state.last_offset_seconds - 0.000_69).abs() < 1e-12);
+        assert!((state.frequency_ppm - 10.0).abs() < 1e-6);
+        assert!(state.residual_frequency_ppm.abs() < 1e-6);
+        assert!(state.skew_ppm < 1e-6);
+        assert!(state.rms_offset_seconds >= state.offset_seconds);
+
+        // Aucune discipline sans GPS
+        assert_eq!(GpsNmeaClock::new(10).discipline(), None);
+        assert_eq!(SystemClock::new().discipline(), None);
+    }
+
+    #[test]
+    fn test_manual_clock_advances_from_reference() {
+        let clock = ManualClock::new(1);
+        assert_eq!(clock.stratum(), 16);
+        assert!(!clock.has_absolute_time());
+
+        // Référence fixée 200 ms dans le passé (monotone)
+        let reference = NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 250_000_000);
+        let at = Instant::now() - Duration::from_millis(200);
+        clock.set_reference_at(reference, at);
+        assert_eq!(clock.stratum(), 1);
+        assert!(clock.has_absolute_time());
+        assert_eq!(clock.last_update(), reference);
+
+        let first = clock.now();
+        let elapsed = first.seconds_since(reference);
+        assert!((0.2..0.5).contains(&elapsed), "elapsed = {}", elapsed);
+
+        // Progression monotone depuis la référence
+        let mut previous = first;
+        for _ in 0..1000 {
+            let now = clock.now();
+            assert!(now.0 >= previous.0);
+            previous = now;
+        }
+    }
 }
@@ -2,10 +2,28 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 use anyhow::{Context, Result};
+use tracing::warn;
+
+/// Version courante du format du fichier de configuration (`config_version`)
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Options déplacées ou renommées : ancien chemin, nouveau chemin
+const MOVED_KEYS: &[(&str, &str)] = &[
+    ("clock.gps.min_satellites", "clock.gps.sync_criteria.min_satellites"),
+];
 
 /// Configuration du serveur NTP
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Config {
+    /// Version du format du fichier (absente = fichier antérieur au versionnage)
+    #[serde(default)]
+    pub config_version: u32,
+
+    /// Refuser de démarrer si le fichier contient une option inconnue
+    /// (faute de frappe, option renommée) au lieu d'un simple avertissement
+    #[serde(default = "default_false")]
+    pub strict_config: bool,
+
     /// Configuration du serveur
     pub server: ServerConfig,
 
@@ -28,6 +46,26 @@ pub struct ServerConfig {
     /// Adresse d'écoute (ex: "0.0.0.0:123")
     pub bind_address: String,
 
+    /// Ports d'écoute supplémentaires sur la même adresse IP (ex: [10123])
+    /// Utile pour tester sans root ou cohabiter avec un autre démon NTP
+    #[serde(default)]
+    pub additional_ports: Vec<u16>,
+
+    /// Politiques de réponse propres à un port d'écoute (ex: port exposé en DMZ
+    /// annoncé stratum 2 avec un reference ID générique)
+    #[serde(default)]
+    pub listen_policies: Vec<ListenPolicy>,
+
+    /// EXPÉRIMENTAL : écoute aussi en TCP sur `bind_address` (NTP-over-TCP),
+    /// chaque paquet étant préfixé de sa longueur sur 2 octets big-endian
+    #[serde(default = "default_false")]
+    pub tcp_enabled: bool,
+
+    /// Connexions TCP simultanées au-delà desquelles les nouvelles sont
+    /// fermées dès l'acceptation (0 = illimité) : une connexion = un thread
+    #[serde(default = "default_max_tcp_connections")]
+    pub max_tcp_connections: usize,
+
     /// Stratum du serveur (1-15, 1 = source primaire)
     /// Si clock_source = "gps", ce sera automatiquement 1 quand synchronisé
     #[serde(default = "default_stratum")]
@@ -40,16 +78,188 @@ pub struct ServerConfig {
     /// Intervalle de polling recommandé (en log2 secondes)
     #[serde(default = "default_poll")]
     pub poll_interval: i8,
+
+    /// Champ poll des réponses : "fixed" (annonce `poll_interval`, recommandation du serveur)
+    /// ou "echo" (recopie le poll de la requête, attendu par certains clients)
+    #[serde(default = "default_poll_mode")]
+    pub poll_mode: String,
+
+    /// Plage plausible du champ poll des requêtes (log2 secondes) : les valeurs
+    /// hors plage (-128, 127...) sont comptées comme anomalies et bornées avant
+    /// d'être recopiées en mode "echo"
+    #[serde(default = "default_poll_sane_min")]
+    pub poll_sane_min: i8,
+
+    #[serde(default = "default_poll_sane_max")]
+    pub poll_sane_max: i8,
+
+    /// Réponses lorsque l'horloge n'est pas synchronisée : "alarm" (leap indicator 3,
+    /// conforme RFC, les clients ignorent le serveur) ou "nowarning_degraded"
+    /// (leap indicator 0, stratum 10 et dispersion élevée : temps approximatif
+    /// mais utilisable, pour les réseaux fermés)
+    #[serde(default = "default_unsynced_behavior")]
+    pub unsynced_behavior: String,
+
+    /// Stratum minimum annoncé aux clients (1-15, absent = stratum de l'horloge)
+    /// Permet d'annoncer par exemple stratum 2 même si le GPS donne stratum 1
+    #[serde(default)]
+    pub advertised_stratum_min: Option<u8>,
+
+    /// Root delay fixe annoncé dans les réponses (microsecondes, 0 = aucun).
+    /// Réglage expert de compensation manuelle d'un chemin réseau asymétrique
+    /// connu : fausse le calcul de distance des clients s'il est mal choisi
+    #[serde(default)]
+    pub static_root_delay_us: u32,
+
+    /// Détecter les retransmissions (même IP et même transmit timestamp) :
+    /// elles sont répondues mais comptées dans `duplicate_requests` et non
+    /// dans `requests_total`, pour un débit de requêtes plus fidèle
+    #[serde(default = "default_false")]
+    pub detect_duplicate_requests: bool,
+
+    /// Réponse minimale : originate et receive timestamps à zéro, seul le transmit
+    /// timestamp est fourni (réseaux isolés où les clients n'ont besoin que de l'heure).
+    /// Non conforme SNTP : les clients ne peuvent plus calculer l'offset
+    #[serde(default = "default_false")]
+    pub minimal_reply: bool,
+
+    /// Réponse de même longueur que la requête : l'espace des champs d'extension
+    /// ou du MAC envoyés par le client est reproduit (rempli de zéros en
+    /// l'absence d'authentification). Jamais plus long que la requête
+    #[serde(default = "default_false")]
+    pub pad_response_to_request: bool,
+
+    /// Expérimental : ajouter aux réponses NTPv4 un champ d'extension (type
+    /// 0xF5E1, hors registre IANA) portant la borne d'erreur mesurée du serveur
+    /// en nanosecondes, lorsque la source d'horloge la fournit et que la requête
+    /// est au moins aussi longue que la réponse (76 octets, pas d'amplification).
+    /// Remplace le remplissage de `pad_response_to_request` pour ces réponses
+    #[serde(default = "default_false")]
+    pub emit_uncertainty_ext: bool,
+
+    /// Réponse construite une fois par seconde (ou à chaque changement de
+    /// stratum de l'horloge) puis recopiée : seuls version, mode, poll et les
+    /// timestamps originate, receive et transmit sont remplis par requête.
+    /// Reference timestamp et root dispersion ont au plus une seconde de retard
+    #[serde(default = "default_false")]
+    pub response_template: bool,
+
+    /// Mode anycast : aucun état par client (rate limiting par IP, overrides et
+    /// détection des retransmissions désactivés), seule la limite globale
+    /// `security.global_max_requests_per_second` s'applique. Tous les nœuds
+    /// répondent ainsi de la même façon, au prix de l'absence de limite par IP
+    #[serde(default = "default_false")]
+    pub anycast_mode: bool,
+
+    /// Horodatage d'émission par le noyau (Linux `SO_TIMESTAMPING`) : mesure la
+    /// latence entre l'envoi et le passage du datagramme au pilote, exposée
+    /// dans `ntp.tx_software_latency_ns` (horodatages lus sans attente)
+    #[serde(default = "default_false")]
+    pub tx_timestamping: bool,
+
+    /// Bit don't-fragment sur les réponses UDP (Linux `IP_PMTUDISC_DO`) :
+    /// une réponse trop grande échoue à l'envoi au lieu d'être fragmentée
+    #[serde(default = "default_false")]
+    pub dont_fragment: bool,
+
+    /// Erreurs ICMP des clients (port inaccessible...) lues dans la file
+    /// d'erreurs du socket (Linux `IP_RECVERR`), journalisées avec l'adresse
+    /// du client et comptées dans `ntp.icmp_errors`
+    #[serde(default = "default_false")]
+    pub log_icmp_errors: bool,
+
+    /// Répondre aux pairs symétriques actifs (mode 1) par des paquets
+    /// symétriques passifs (mode 2), avec une association par pair
+    #[serde(default = "default_false")]
+    pub allow_symmetric: bool,
+
+    /// Pairs symétriques autorisés (adresses ou CIDR, vide = tous)
+    #[serde(default)]
+    pub symmetric_peers: Vec<String>,
+
+    /// Fichier d'état du total des requêtes servies (`requests_total` conservé
+    /// entre redémarrages ; absent = compteur remis à zéro à chaque démarrage)
+    #[serde(default)]
+    pub requests_count_file: Option<String>,
+
+    /// Période de réécriture du fichier d'état (secondes, plus une écriture à l'arrêt)
+    #[serde(default = "default_requests_count_flush_secs")]
+    pub requests_count_flush_secs: u64,
+
+    /// Constante de temps de la moyenne glissante de `requests_per_second_smoothed` (secondes)
+    #[serde(default = "default_requests_rate_window_secs")]
+    pub requests_rate_window_secs: u64,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ClockConfig {
-    /// Source d'horloge: "system" ou "gps"
+    /// Source d'horloge: "system", "gps", "manual" (temps fixé par
+    /// `PUT /api/clock/set`, pour un banc de calibration hors réseau) ou "phc"
+    /// (temps système, incertitude mesurée contre l'horloge PTP de la carte réseau)
     #[serde(default = "default_clock_source")]
     pub source: String,
 
+    /// Horloge matérielle PTP de la source "phc" (Linux)
+    #[serde(default = "default_phc_device")]
+    pub phc_device: String,
+
+    /// Lectures PHC/système par mesure d'offset (1-25, ioctl `PTP_SYS_OFFSET_EXTENDED`)
+    #[serde(default = "default_phc_samples")]
+    pub phc_samples: usize,
+
+    /// Écart TAI - UTC du PHC (secondes) : 37 sous ptp4l (PHC en TAI), 0 si le
+    /// PHC compte en UTC
+    #[serde(default = "default_phc_utc_offset_secs")]
+    pub phc_utc_offset_secs: i64,
+
+    /// Stratum annoncé par la source "manual" une fois la référence fixée
+    #[serde(default = "default_manual_stratum")]
+    pub manual_stratum: u8,
+
     /// Configuration GPS (utilisé si source = "gps")
     pub gps: Option<GpsConfig>,
+
+    /// Enregistrer l'offset brut horloge/système à chaque lecture
+    /// (exposé sur /api/offset_history, pour le débogage de la discipline)
+    #[serde(default = "default_false")]
+    pub record_offset_history: bool,
+
+    /// Nombre maximum d'échantillons conservés dans l'historique d'offset
+    #[serde(default = "default_offset_history_size")]
+    pub offset_history_size: usize,
+
+    /// Horloge système (RTC) digne de confiance au démarrage
+    /// Mettre à false sur matériel sans RTC : le serveur ne répond qu'une fois
+    /// le temps absolu fourni par le GPS (source "gps" obligatoire)
+    #[serde(default = "default_true")]
+    pub system_clock_trusted: bool,
+
+    /// Fichier `leap-seconds.list` (format IANA, ex: /usr/share/zoneinfo/leap-seconds.list) :
+    /// décalage GPS - UTC courant exposé dans les statistiques et annonce des
+    /// secondes intercalaires dans le leap indicator des réponses
+    #[serde(default)]
+    pub leap_seconds_file: Option<String>,
+
+    /// Sources d'horloge par ordre de priorité (ex: ["gps", "system"]) : la
+    /// première disponible est utilisée. Vide = `source` seule
+    #[serde(default)]
+    pub sources: Vec<String>,
+
+    /// Durée pendant laquelle une source de priorité supérieure doit rester
+    /// disponible avant d'être reprise (secondes, hystérésis)
+    #[serde(default = "default_source_switch_holdoff_secs")]
+    pub source_switch_holdoff_secs: u64,
+
+    /// Écart maximal entre le temps GPS et l'horloge système (millisecondes,
+    /// absent = pas de contrôle). Au-delà, le temps GPS reste servi mais
+    /// `clock.divergence_alarm` est levé et la dispersion annoncée couvre l'écart
+    #[serde(default)]
+    pub max_gps_system_divergence_ms: Option<u64>,
+
+    /// Ne plus répondre tant que l'écart GPS/système dépasse
+    /// `max_gps_system_divergence_ms` (au lieu de servir le temps GPS)
+    #[serde(default = "default_false")]
+    pub refuse_on_divergence: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -65,15 +275,25 @@ pub struct GpsConfig {
     #[serde(default = "default_baud_rate")]
     pub baud_rate: u32,
 
+    /// Détection automatique du baud rate au démarrage : chaque vitesse de
+    /// `auto_baud_rates` est essayée jusqu'à recevoir des trames NMEA valides
+    /// (`baud_rate` est alors ignoré)
+    #[serde(default = "default_false")]
+    pub auto_baud: bool,
+
+    /// Vitesses essayées, dans l'ordre, en détection automatique
+    #[serde(default = "default_auto_baud_rates")]
+    pub auto_baud_rates: Vec<u32>,
+
     /// Timeout de synchronisation GPS en secondes
     /// Si aucune donnée GPS valide n'est reçue pendant ce délai,
     /// le serveur passe en mode non-synchronisé
     #[serde(default = "default_gps_timeout")]
     pub sync_timeout: u64,
 
-    /// Nombre minimum de satellites requis
-    #[serde(default = "default_min_satellites")]
-    pub min_satellites: u8,
+    /// Critères de validité de la synchronisation GPS (`[clock.gps.sync_criteria]`)
+    #[serde(default)]
+    pub sync_criteria: SyncCriteria,
 
     /// Activer la détection PPS via CTS (Pulse Per Second)
     /// Le signal PPS est détecté via la ligne CTS du port série
@@ -83,6 +303,123 @@ pub struct GpsConfig {
     /// Pin GPIO pour PPS (Linux/Raspberry Pi uniquement, ex: 18 pour GPIO18)
     /// Optionnel : utilisé uniquement pour PPS kernel Linux avancé
     pub pps_gpio_pin: Option<u32>,
+
+    /// Reference ID annoncé quand le fix GGA est augmenté DGPS/SBAS (qualité 2)
+    /// Ex: "SBAS", "WAAS", "EGNO", "DGPS" (4 caractères ASCII maximum)
+    #[serde(default = "default_dgps_refid")]
+    pub dgps_refid: String,
+
+    /// Délai sans progression (données reçues ou pulse PPS) au-delà duquel
+    /// le watchdog relance le thread de lecture GPS (secondes, 0 = désactivé)
+    #[serde(default = "default_watchdog_timeout_secs")]
+    pub watchdog_timeout_secs: u64,
+
+    /// Fichier CSV recevant une ligne par pulse PPS (instant monotone, intervalle,
+    /// offset instantané et EWMA), pour l'analyse (déviation d'Allan). Volume élevé
+    #[serde(default)]
+    pub pps_log_file: Option<String>,
+
+    /// État imposé à la ligne RTS à l'ouverture du port (absent = haut si le PPS
+    /// via CTS est activé, sinon ligne laissée telle quelle). Certains modules
+    /// utilisent RTS/DTR comme ligne de reset ou d'alimentation
+    #[serde(default)]
+    pub rts: Option<bool>,
+
+    /// État imposé à la ligne DTR à l'ouverture du port (même règle que `rts`)
+    #[serde(default)]
+    pub dtr: Option<bool>,
+
+    /// Contrôle de flux du port série : "none", "software" (XON/XOFF) ou "hardware"
+    /// (RTS/CTS, incompatible avec le PPS via CTS)
+    #[serde(default = "default_flow_control")]
+    pub flow_control: String,
+
+    /// Intervalle minimal entre deux mises à jour de `last_rx_ms` dans les
+    /// statistiques partagées (millisecondes), pour limiter les prises du verrou
+    /// en écriture par la boucle de lecture
+    #[serde(default = "default_rx_stats_interval_ms")]
+    pub rx_stats_interval_ms: u64,
+
+    /// Longueur maximale d'une trame NMEA (octets, au moins les 82 de la norme) :
+    /// une trame plus longue signale un délimiteur manquant et est écartée
+    #[serde(default = "default_max_sentence_length")]
+    pub max_sentence_length: usize,
+
+    /// Âge maximal d'un offset PPS (secondes) : au-delà, le serveur cesse de
+    /// l'utiliser et revient à l'extrapolation depuis les trames NMEA
+    #[serde(default = "default_pps_offset_max_age_secs")]
+    pub pps_offset_max_age_secs: u64,
+
+    /// Précision annoncée sans PPS (log2 secondes, -3 ≈ 125 ms) : les trames
+    /// NMEA seules ne datent la seconde qu'à la latence série près
+    #[serde(default = "default_nmea_only_precision")]
+    pub nmea_only_precision: i8,
+
+    /// Histogramme des offsets PPS bruts exporté sur /metrics
+    /// (`pendulum_pps_offset_seconds`)
+    #[serde(default = "default_false")]
+    pub pps_offset_histogram: bool,
+
+    /// Délai après l'ouverture du port pour recevoir une première trame NMEA
+    /// au checksum valide (secondes, 0 = pas de vérification). Sans trame, le
+    /// port est refermé (mauvais périphérique ?) et la reconnexion reprend
+    #[serde(default = "default_nmea_validation_timeout_secs")]
+    pub nmea_validation_timeout_secs: u64,
+
+    /// Constellations devant contribuer au fix (`GPS`, `GLONASS`, `Galileo`,
+    /// `BeiDou`) : tant que l'une d'elles n'a aucun satellite suivi (trames
+    /// GSV), le nombre de satellites retenu pour la synchronisation est 0
+    #[serde(default)]
+    pub require_constellations: Vec<String>,
+
+    /// Constellations dont les satellites ne sont pas comptés pour la
+    /// synchronisation (ex. `["GLONASS"]`)
+    #[serde(default)]
+    pub exclude_constellations: Vec<String>,
+
+    /// Trame datant explicitement les pulses PPS : "none" (le pulse marque la
+    /// seconde suivant la dernière trame RMC), "zda" (convention NMEA : `$--ZDA`
+    /// émise après le pulse qu'elle date, le suivant marque la seconde d'après)
+    /// ou "zda_ahead" (module de timing configuré pour annoncer par `$--ZDA`
+    /// l'heure UTC du pulse à venir). Seule ZDA est prise en charge : les trames
+    /// propriétaires comme `$PSTI,00` (Skytraq) ne sont pas lues
+    #[serde(default = "default_pps_time_sentence")]
+    pub pps_time_sentence: String,
+
+    /// Talker dont les trames RMC, GLL et ZDA datent l'horloge : "auto" (`$GP`
+    /// et `$GN`, la dernière reçue l'emporte) ou un talker précis ("GN" pour la
+    /// solution combinée, "GP" pour la solution GPS seule...). Les trames des
+    /// autres talkers restent utilisées pour les satellites
+    #[serde(default = "default_time_source_talker")]
+    pub time_source_talker: String,
+}
+
+/// Critères de validité de la synchronisation GPS : une trame RMC valide ne
+/// suffit pas si le fix est jugé peu fiable (peu de satellites, HDOP élevé)
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct SyncCriteria {
+    /// Nombre minimum de satellites utilisés (trame GGA ; non vérifié tant
+    /// que le récepteur n'a émis aucune trame GGA)
+    #[serde(default = "default_min_satellites")]
+    pub min_satellites: u8,
+
+    /// HDOP maximal (trame GGA), absent = pas de limite
+    #[serde(default)]
+    pub max_hdop: Option<f64>,
+
+    /// Exiger un offset PPS récent (< `pps_offset_max_age_secs`)
+    #[serde(default = "default_false")]
+    pub require_pps: bool,
+}
+
+impl Default for SyncCriteria {
+    fn default() -> Self {
+        SyncCriteria {
+            min_satellites: default_min_satellites(),
+            max_hdop: None,
+            require_pps: false,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -95,6 +432,13 @@ pub struct SecurityConfig {
     #[serde(default = "default_max_requests_per_second")]
     pub max_requests_per_second: u32,
 
+    /// Espacement minimal entre deux réponses à une même IP (millisecondes,
+    /// 0 = aucun) : les requêtes plus rapprochées sont ignorées. Sans effet si
+    /// le rate limiting est désactivé, en mode anycast, et pour les réseaux de
+    /// `rate_limit_overrides`
+    #[serde(default)]
+    pub min_client_interval_ms: u64,
+
     /// Liste blanche d'adresses IP (vide = toutes autorisées)
     #[serde(default)]
     pub ip_whitelist: Vec<String>,
@@ -102,6 +446,89 @@ pub struct SecurityConfig {
     /// Liste noire d'adresses IP
     #[serde(default)]
     pub ip_blacklist: Vec<String>,
+
+    /// Limites de requêtes par seconde spécifiques à certains réseaux
+    /// (ex: passerelle NAT partagée par de nombreux clients)
+    #[serde(default)]
+    pub rate_limit_overrides: Vec<RateLimitOverride>,
+
+    /// Débit total maximal de requêtes par seconde, tous clients confondus
+    /// (utilisé uniquement en mode anycast)
+    #[serde(default = "default_global_max_requests_per_second")]
+    pub global_max_requests_per_second: u32,
+
+    /// Nombre de bits de fraction conservés dans les timestamps receive/transmit
+    /// (arrondis au plus proche, bits de poids faible à zéro) pour ne pas révéler
+    /// la résolution exacte de l'horloge. Absent = pleine résolution (32 bits)
+    #[serde(default)]
+    pub timestamp_precision_bits: Option<u8>,
+
+    /// Répondre aux requêtes de version 0 (clients anciens ou bogués) comme à
+    /// des requêtes de version 4, au lieu de les rejeter
+    #[serde(default = "default_false")]
+    pub accept_version_zero: bool,
+
+    /// Ignorer les requêtes client (mode 3) émises depuis le port 123 : un client
+    /// légitime utilise un port éphémère, le port 123 trahit une adresse usurpée
+    /// pour faire se répondre deux serveurs en boucle. Désactivé par défaut
+    /// (certains clients anciens émettent depuis le port 123)
+    #[serde(default = "default_false")]
+    pub reject_source_port_123: bool,
+
+    /// Répondre en mode serveur (4) aux paquets symétriques actifs (mode 1)
+    /// d'une adresse qui n'est pas un pair configuré (clients mal configurés),
+    /// au lieu de les écarter ou d'ouvrir une association. Désactivé par défaut
+    #[serde(default = "default_false")]
+    pub answer_symmetric_as_server: bool,
+
+    /// Clés symétriques de l'authentification NTP (RFC 5905) : le MAC des
+    /// requêtes qui en portent un est vérifié, la réponse est signée avec la
+    /// même clé. Vide = pas d'authentification
+    #[serde(default)]
+    pub keys: Vec<KeyConfig>,
+}
+
+/// Clé symétrique partagée avec des clients NTP
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct KeyConfig {
+    /// Identifiant de la clé (key ID du MAC, 1 à 65534 par convention ntpd)
+    pub id: u32,
+
+    /// Algorithme de condensé : "md5" ou "sha1"
+    pub algorithm: String,
+
+    /// Secret : texte ASCII, ou hexadécimal préfixé par `HEX:`
+    pub key: String,
+}
+
+/// Politique de réponse d'un port d'écoute (`bind_address` ou `additional_ports`)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ListenPolicy {
+    /// Port d'écoute concerné
+    pub port: u16,
+
+    /// Plancher de stratum annoncé sur ce port (remplace `advertised_stratum_min`)
+    #[serde(default)]
+    pub advertised_stratum_min: Option<u8>,
+
+    /// Reference ID annoncé sur ce port (4 caractères ASCII maximum)
+    #[serde(default)]
+    pub reference_id: Option<String>,
+
+    /// Nombre maximum de requêtes par seconde par IP sur ce port
+    /// (remplace `security.max_requests_per_second`, overrides par réseau ignorés)
+    #[serde(default)]
+    pub max_requests_per_second: Option<u32>,
+}
+
+/// Limite de rate limiting spécifique à un réseau
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RateLimitOverride {
+    /// Réseau en notation CIDR (ex: "203.0.113.0/24") ou adresse seule
+    pub cidr: String,
+
+    /// Nombre maximum de requêtes par seconde par IP de ce réseau
+    pub max_requests_per_second: u32,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -116,6 +543,47 @@ pub struct LoggingConfig {
 
     /// Fichier de log (vide = stdout uniquement)
     pub log_file: Option<String>,
+
+    /// Taille maximale du fichier de log avant rotation en `<fichier>.1` (octets, 0 = illimitée)
+    #[serde(default = "default_log_max_file_bytes")]
+    pub max_file_bytes: u64,
+
+    /// Nombre de fichiers de log de rotation conservés (`<fichier>.1` à `<fichier>.N`)
+    #[serde(default = "default_log_max_files")]
+    pub max_files: usize,
+
+    /// Fichier CSV d'historique SNR des satellites (diagnostic d'antenne, vide = désactivé)
+    #[serde(default)]
+    pub snr_csv_file: Option<String>,
+
+    /// Intervalle entre deux relevés SNR (secondes)
+    #[serde(default = "default_snr_csv_interval_secs")]
+    pub snr_csv_interval_secs: u64,
+
+    /// Taille maximale du fichier CSV avant rotation en `<fichier>.1` (octets, 0 = illimitée)
+    #[serde(default = "default_snr_csv_max_bytes")]
+    pub snr_csv_max_bytes: u64,
+
+    /// Journal d'audit des réponses NTP (JSON par ligne, vide = désactivé)
+    #[serde(default)]
+    pub audit_log_file: Option<String>,
+
+    /// Une réponse sur N est enregistrée dans le journal d'audit
+    #[serde(default = "default_audit_sample_rate")]
+    pub audit_sample_rate: u64,
+
+    /// Taille maximale du journal d'audit avant rotation (octets, 0 = illimitée)
+    #[serde(default = "default_log_max_file_bytes")]
+    pub audit_max_file_bytes: u64,
+
+    /// Nombre de fichiers de rotation du journal d'audit conservés
+    #[serde(default = "default_audit_max_files")]
+    pub audit_max_files: usize,
+
+    /// Intervalle de la ligne de santé de l'horloge dans les logs (stratum,
+    /// refid, satellites, PPS, requêtes/s), en secondes (0 = désactivée)
+    #[serde(default)]
+    pub health_interval_secs: u64,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -127,52 +595,206 @@ pub struct WebServerConfig {
     /// Adresse d'écoute du serveur web
     #[serde(default = "default_web_bind_address")]
     pub bind_address: String,
+
+    /// Fuseau horaire d'affichage du dashboard et de /api/time (nom IANA, ex: "Europe/Paris",
+    /// ou décalage fixe, ex: "+02:00"). N'affecte jamais le protocole NTP, toujours en UTC
+    #[serde(default)]
+    pub display_timezone: Option<String>,
+
+    /// Jeton d'administration : requis (en-tête `Authorization: Bearer <jeton>`)
+    /// pour les points d'accès qui modifient l'état du serveur (ex: GET et PUT /api/ratelimit).
+    /// Absent = ces points d'accès sont désactivés
+    #[serde(default)]
+    pub admin_token: Option<String>,
+
+    /// Démarrage du serveur web : "immediate" ou "after_sync" (attend la première
+    /// synchronisation de l'horloge, au plus `start_delay_timeout_secs`)
+    #[serde(default = "default_web_start_delay")]
+    pub start_delay: String,
+
+    /// Attente maximale de la synchronisation en mode "after_sync" (secondes)
+    #[serde(default = "default_web_start_delay_timeout")]
+    pub start_delay_timeout_secs: u64,
+
+    /// Mesure de la contention du verrou des statistiques partagées (acquisitions
+    /// en écriture contendues et attente cumulée, exposées sur /metrics).
+    /// Ajoute un `try_write` à chaque écriture
+    #[serde(default = "default_false")]
+    pub stats_lock_metrics: bool,
+
+    /// Trames temps-réel conservées pour les clients WebSocket lents (une trame
+    /// toutes les 50 ms) : au-delà, le client saute à la trame la plus récente
+    #[serde(default = "default_ws_channel_capacity")]
+    pub ws_channel_capacity: usize,
+
+    /// Retards consécutifs (trames perdues) avant de déconnecter un client
+    /// WebSocket trop lent (0 = jamais)
+    #[serde(default = "default_ws_max_consecutive_lags")]
+    pub ws_max_consecutive_lags: u32,
+
+    /// Connexions WebSocket simultanées au-delà desquelles les nouvelles sont
+    /// refusées en 503 (0 = illimité)
+    #[serde(default = "default_max_ws_connections")]
+    pub max_ws_connections: usize,
+
+    /// Ajoute un objet `server_health` (requêtes/s, rejets/s, clients actifs,
+    /// latence de traitement) à /api/time et aux trames WebSocket
+    #[serde(default = "default_false")]
+    pub realtime_server_health: bool,
+
+    /// Masque d'élévation de /api/skyplot (degrés) : les satellites plus bas
+    /// sont signalés sous le masque (obstacles, placement de l'antenne)
+    #[serde(default = "default_skyplot_elevation_mask_deg")]
+    pub skyplot_elevation_mask_deg: u8,
 }
 
 // Fonctions par défaut pour serde
 fn default_stratum() -> u8 { 2 }
 fn default_precision() -> i8 { -20 }
 fn default_poll() -> i8 { 6 }
+fn default_poll_mode() -> String { "fixed".to_string() }
+fn default_poll_sane_min() -> i8 { -6 }
+fn default_poll_sane_max() -> i8 { 17 }
+fn default_unsynced_behavior() -> String { "alarm".to_string() }
 fn default_clock_source() -> String { "system".to_string() }
 fn default_gps_enabled() -> bool { true }
 fn default_baud_rate() -> u32 { 9600 }
+fn default_auto_baud_rates() -> Vec<u32> { vec![9600, 4800, 19200, 38400, 57600, 115200] }
 fn default_gps_timeout() -> u64 { 30 }
-fn default_min_satellites() -> u8 { 4 }
+fn default_min_satellites() -> u8 { 3 }
 fn default_pps_enabled() -> bool { true }
+fn default_dgps_refid() -> String { "SBAS".to_string() }
+fn default_watchdog_timeout_secs() -> u64 { 30 }
+fn default_flow_control() -> String { "none".to_string() }
+fn default_pps_time_sentence() -> String { "none".to_string() }
+fn default_time_source_talker() -> String { "auto".to_string() }
+fn default_rx_stats_interval_ms() -> u64 { 250 }
+fn default_max_sentence_length() -> usize { 256 }
+fn default_pps_offset_max_age_secs() -> u64 { 5 }
+fn default_nmea_only_precision() -> i8 { -3 }
+fn default_nmea_validation_timeout_secs() -> u64 { 15 }
+fn default_offset_history_size() -> usize { 3600 }
+fn default_requests_count_flush_secs() -> u64 { 60 }
+fn default_requests_rate_window_secs() -> u64 { 5 }
+fn default_max_tcp_connections() -> usize { 64 }
+fn default_source_switch_holdoff_secs() -> u64 { 10 }
+fn default_manual_stratum() -> u8 { 10 }
+fn default_phc_device() -> String { "/dev/ptp0".to_string() }
+fn default_phc_samples() -> usize { 5 }
+fn default_phc_utc_offset_secs() -> i64 { 37 }
 fn default_true() -> bool { true }
 fn default_false() -> bool { false }
 fn default_max_requests_per_second() -> u32 { 100 }
+fn default_global_max_requests_per_second() -> u32 { 10_000 }
 fn default_log_level() -> String { "info".to_string() }
+fn default_snr_csv_interval_secs() -> u64 { 10 }
+fn default_snr_csv_max_bytes() -> u64 { 10_000_000 }
+fn default_audit_sample_rate() -> u64 { 100 }
+fn default_audit_max_files() -> usize { 10 }
+fn default_log_max_file_bytes() -> u64 { 10_000_000 }
+fn default_log_max_files() -> usize { 5 }
 fn default_web_port() -> u16 { 8080 }
 fn default_web_bind_address() -> String { "0.0.0.0".to_string() }
+fn default_web_start_delay() -> String { "immediate".to_string() }
+fn default_web_start_delay_timeout() -> u64 { 300 }
+fn default_ws_channel_capacity() -> usize { 16 }
+fn default_ws_max_consecutive_lags() -> u32 { 5 }
+fn default_max_ws_connections() -> usize { 100 }
+fn default_skyplot_elevation_mask_deg() -> u8 { 10 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
+            config_version: CONFIG_VERSION,
+            strict_config: false,
             server: ServerConfig {
                 bind_address: "0.0.0.0:123".to_string(),
+                additional_ports: vec![],
+                listen_policies: Vec::new(),
+                tcp_enabled: false,
+                max_tcp_connections: default_max_tcp_connections(),
                 stratum: 2,
                 precision: -20,
                 poll_interval: 6,
+                poll_mode: "fixed".to_string(),
+                poll_sane_min: default_poll_sane_min(),
+                poll_sane_max: default_poll_sane_max(),
+                unsynced_behavior: default_unsynced_behavior(),
+                advertised_stratum_min: None,
+                static_root_delay_us: 0,
+                detect_duplicate_requests: false,
+                minimal_reply: false,
+                pad_response_to_request: false,
+                emit_uncertainty_ext: false,
+                response_template: false,
+                tx_timestamping: false,
+                dont_fragment: false,
+                log_icmp_errors: false,
+                allow_symmetric: false,
+                symmetric_peers: vec![],
+                requests_count_file: None,
+                requests_count_flush_secs: default_requests_count_flush_secs(),
+                requests_rate_window_secs: default_requests_rate_window_secs(),
+                anycast_mode: false,
             },
             clock: ClockConfig {
                 source: "system".to_string(),
                 gps: None,
+                record_offset_history: false,
+                offset_history_size: 3600,
+                system_clock_trusted: true,
+                leap_seconds_file: None,
+                sources: Vec::new(),
+                source_switch_holdoff_secs: default_source_switch_holdoff_secs(),
+                manual_stratum: default_manual_stratum(),
+                phc_device: default_phc_device(),
+                phc_samples: default_phc_samples(),
+                phc_utc_offset_secs: default_phc_utc_offset_secs(),
+                max_gps_system_divergence_ms: None,
+                refuse_on_divergence: false,
             },
             security: SecurityConfig {
                 enable_rate_limiting: true,
                 max_requests_per_second: 100,
+                min_client_interval_ms: 0,
+                global_max_requests_per_second: default_global_max_requests_per_second(),
                 ip_whitelist: vec![],
                 ip_blacklist: vec![],
+                rate_limit_overrides: vec![],
+                keys: Vec::new(),
+                timestamp_precision_bits: None,
+                accept_version_zero: false,
+                reject_source_port_123: false,
+                answer_symmetric_as_server: false,
             },
             logging: LoggingConfig {
                 level: "info".to_string(),
                 log_requests: false,
                 log_file: None,
+                max_file_bytes: default_log_max_file_bytes(),
+                max_files: default_log_max_files(),
+                snr_csv_file: None,
+                snr_csv_interval_secs: 10,
+                snr_csv_max_bytes: 10_000_000,
+                audit_log_file: None,
+                audit_sample_rate: default_audit_sample_rate(),
+                audit_max_file_bytes: default_log_max_file_bytes(),
+                audit_max_files: default_audit_max_files(),
+                health_interval_secs: 0,
             },
             webserver: WebServerConfig {
                 port: 8080,
                 bind_address: "0.0.0.0".to_string(),
+                display_timezone: None,
+                admin_token: None,
+                start_delay: default_web_start_delay(),
+                start_delay_timeout_secs: default_web_start_delay_timeout(),
+                stats_lock_metrics: false,
+                ws_channel_capacity: default_ws_channel_capacity(),
+                ws_max_consecutive_lags: default_ws_max_consecutive_lags(),
+                max_ws_connections: default_max_ws_connections(),
+                realtime_server_health: false,
+                skyplot_elevation_mask_deg: default_skyplot_elevation_mask_deg(),
             },
         }
     }
@@ -183,7 +805,49 @@ impl Default for WebServerConfig {
         WebServerConfig {
             port: 8080,
             bind_address: "0.0.0.0".to_string(),
+            display_timezone: None,
+            admin_token: None,
+            start_delay: default_web_start_delay(),
+            start_delay_timeout_secs: default_web_start_delay_timeout(),
+            stats_lock_metrics: false,
+            ws_channel_capacity: default_ws_channel_capacity(),
+            ws_max_consecutive_lags: default_ws_max_consecutive_lags(),
+            max_ws_connections: default_max_ws_connections(),
+            realtime_server_health: false,
+            skyplot_elevation_mask_deg: default_skyplot_elevation_mask_deg(),
+        }
+    }
+}
+
+/// Constellations reconnues dans `require_constellations` / `exclude_constellations`
+/// (noms des satellites des trames GSV)
+const GNSS_CONSTELLATIONS: [&str; 4] = ["GPS", "GLONASS", "Galileo", "BeiDou"];
+
+/// Longueur maximale d'une trame selon la norme NMEA 0183 (délimiteurs compris)
+const NMEA_MAX_SENTENCE_LENGTH: usize = 82;
+
+/// Root delay fixe maximal (`server.static_root_delay_us`) : une seconde
+const MAX_STATIC_ROOT_DELAY_US: u32 = 1_000_000;
+
+/// Parcourt le fichier en parallèle de la configuration resérialisée et
+/// collecte les clés sans équivalent (tables et tableaux de tables inclus)
+fn collect_unknown_keys(input: &toml::Value, known: &toml::Value, path: &str, unknown: &mut Vec<String>) {
+    match (input, known) {
+        (toml::Value::Table(input), toml::Value::Table(known)) => {
+            for (key, value) in input {
+                let key_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                match known.get(key) {
+                    Some(known_value) => collect_unknown_keys(value, known_value, &key_path, unknown),
+                    None => unknown.push(key_path),
+                }
+            }
+        }
+        (toml::Value::Array(input), toml::Value::Array(known)) => {
+            for (index, (value, known_value)) in input.iter().zip(known).enumerate() {
+                collect_unknown_keys(value, known_value, &format!("{}[{}]", path, index), unknown);
+            }
         }
+        _ => {}
     }
 }
 
@@ -196,10 +860,61 @@ impl Config {
         let config: Config = toml::from_str(&content)
             .context("Failed to parse config file")?;
 
+        config.check_format(&content)?;
         config.validate()?;
         Ok(config)
     }
 
+    /// Vérifie la version du format et signale les options inconnues
+    /// (erreur en mode `strict_config`, avertissement sinon)
+    fn check_format(&self, content: &str) -> Result<()> {
+        if self.config_version > CONFIG_VERSION {
+            anyhow::bail!(
+                "Config file format version {} is newer than supported version {}: upgrade pendulum",
+                self.config_version,
+                CONFIG_VERSION
+            );
+        }
+        if self.config_version < CONFIG_VERSION {
+            warn!(
+                "Config file has no config_version (format before version {}): missing options use their defaults, \
+                 review renamed options below then add config_version = {}",
+                CONFIG_VERSION, CONFIG_VERSION
+            );
+        }
+
+        let unknown: Vec<String> = self
+            .unknown_keys(content)?
+            .into_iter()
+            .map(|key| match MOVED_KEYS.iter().find(|(old, _)| *old == key) {
+                Some((_, new)) => format!("{} (moved to {})", key, new),
+                None => key,
+            })
+            .collect();
+        if unknown.is_empty() {
+            return Ok(());
+        }
+
+        if self.strict_config {
+            anyhow::bail!("Unknown config options: {}", unknown.join(", "));
+        }
+        for key in &unknown {
+            warn!("Unknown config option ignored: {}", key);
+        }
+        Ok(())
+    }
+
+    /// Chemins des options du fichier qui ne correspondent à aucun champ :
+    /// clés absentes de la configuration désérialisée puis resérialisée
+    fn unknown_keys(&self, content: &str) -> Result<Vec<String>> {
+        let input: toml::Value = toml::from_str(content).context("Failed to parse config file")?;
+        let known = toml::Value::try_from(self).context("Failed to serialize config")?;
+
+        let mut unknown = Vec::new();
+        collect_unknown_keys(&input, &known, "", &mut unknown);
+        Ok(unknown)
+    }
+
     /// Sauvegarde la configuration dans un fichier TOML
     pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let content = toml::to_string_pretty(self)
@@ -218,9 +933,89 @@ impl Config {
             anyhow::bail!("Invalid stratum: must be between 1 and 15");
         }
 
+        // Validation du plancher de stratum annoncé
+        if let Some(floor) = self.server.advertised_stratum_min {
+            if !(1..=15).contains(&floor) {
+                anyhow::bail!("Invalid advertised_stratum_min: must be between 1 and 15");
+            }
+        }
+
+        if self.server.requests_count_flush_secs == 0 {
+            anyhow::bail!("Invalid server.requests_count_flush_secs: must be at least 1");
+        }
+
+        if self.server.requests_rate_window_secs == 0 {
+            anyhow::bail!("Invalid server.requests_rate_window_secs: must be at least 1");
+        }
+
+        // Root delay fixe : au-delà d'une seconde, les clients écartent le serveur
+        if self.server.static_root_delay_us > MAX_STATIC_ROOT_DELAY_US {
+            anyhow::bail!(
+                "Invalid server.static_root_delay_us: must be at most {} (1 s)",
+                MAX_STATIC_ROOT_DELAY_US
+            );
+        }
+
+        // Politiques par port : ports d'écoute existants, valeurs valides
+        let bind_port = self
+            .server
+            .bind_address
+            .rsplit_once(':')
+            .and_then(|(_, port)| port.parse::<u16>().ok());
+        for (index, policy) in self.server.listen_policies.iter().enumerate() {
+            if bind_port != Some(policy.port) && !self.server.additional_ports.contains(&policy.port) {
+                anyhow::bail!("Invalid listen policy: port {} is not a listening port", policy.port);
+            }
+            if self.server.listen_policies[..index].iter().any(|other| other.port == policy.port) {
+                anyhow::bail!("Duplicate listen policy for port {}", policy.port);
+            }
+            if policy.advertised_stratum_min.is_some_and(|floor| !(1..=15).contains(&floor)) {
+                anyhow::bail!("Invalid listen policy for port {}: advertised_stratum_min must be between 1 and 15", policy.port);
+            }
+            if let Some(ref refid) = policy.reference_id {
+                if refid.is_empty() || refid.len() > 4 || !refid.is_ascii() {
+                    anyhow::bail!("Invalid listen policy for port {}: reference_id must be 1 to 4 ASCII characters", policy.port);
+                }
+            }
+            if policy.max_requests_per_second == Some(0) {
+                anyhow::bail!("Invalid listen policy for port {}: max_requests_per_second must be greater than 0", policy.port);
+            }
+        }
+
         // Validation de la source d'horloge
-        if self.clock.source != "system" && self.clock.source != "gps" {
-            anyhow::bail!("Invalid clock source: must be 'system' or 'gps'");
+        if self.clock.max_gps_system_divergence_ms == Some(0) {
+            anyhow::bail!("Invalid max_gps_system_divergence_ms: must be greater than 0");
+        }
+
+        if self.clock.max_gps_system_divergence_ms.is_some() && !self.clock.system_clock_trusted {
+            anyhow::bail!("max_gps_system_divergence_ms compares against the system clock and requires system_clock_trusted = true");
+        }
+
+        if self.clock.refuse_on_divergence && self.clock.max_gps_system_divergence_ms.is_none() {
+            anyhow::bail!("refuse_on_divergence requires max_gps_system_divergence_ms");
+        }
+
+        if !matches!(self.clock.source.as_str(), "system" | "gps" | "manual" | "phc") {
+            anyhow::bail!("Invalid clock source: must be 'system', 'gps', 'manual' or 'phc'");
+        }
+
+        if !(1..=crate::phc::PTP_MAX_SAMPLES).contains(&self.clock.phc_samples) {
+            anyhow::bail!(
+                "Invalid clock.phc_samples: must be between 1 and {}",
+                crate::phc::PTP_MAX_SAMPLES
+            );
+        }
+
+        if !(0..=100).contains(&self.clock.phc_utc_offset_secs) {
+            anyhow::bail!("Invalid clock.phc_utc_offset_secs: must be between 0 and 100");
+        }
+
+        if self.clock.source == "manual" && !self.clock.sources.is_empty() {
+            anyhow::bail!("Clock source 'manual' cannot be combined with a sources priority list");
+        }
+
+        if !(1..=15).contains(&self.clock.manual_stratum) {
+            anyhow::bail!("Invalid manual_stratum: must be between 1 and 15");
         }
 
         // Si source GPS, vérifier la config GPS
@@ -228,6 +1023,166 @@ impl Config {
             anyhow::bail!("GPS clock source selected but no GPS configuration provided");
         }
 
+        // Liste de priorité : sources connues, sans doublon
+        for (index, source) in self.clock.sources.iter().enumerate() {
+            match source.as_str() {
+                "system" | "phc" => {}
+                "gps" if self.clock.gps.is_none() => {
+                    anyhow::bail!("GPS clock source listed in sources but no GPS configuration provided");
+                }
+                "gps" => {}
+                _ => anyhow::bail!("Invalid clock source in sources: '{}' (must be 'gps', 'phc' or 'system')", source),
+            }
+            if self.clock.sources[..index].contains(source) {
+                anyhow::bail!("Duplicate clock source in sources: '{}'", source);
+            }
+        }
+
+        if self.server.poll_mode != "fixed" && self.server.poll_mode != "echo" {
+            anyhow::bail!("Invalid poll_mode: must be 'fixed' or 'echo'");
+        }
+
+        if self.server.poll_sane_min > self.server.poll_sane_max {
+            anyhow::bail!("Invalid poll_sane_min: must not exceed poll_sane_max");
+        }
+
+        if !matches!(self.server.unsynced_behavior.as_str(), "alarm" | "nowarning_degraded") {
+            anyhow::bail!("Invalid unsynced_behavior: must be 'alarm' or 'nowarning_degraded'");
+        }
+
+        if !self.clock.system_clock_trusted && self.clock.source != "gps" {
+            anyhow::bail!("system_clock_trusted = false requires the GPS clock source");
+        }
+
+        if !matches!(self.webserver.start_delay.as_str(), "immediate" | "after_sync") {
+            anyhow::bail!("Invalid webserver.start_delay: must be 'immediate' or 'after_sync'");
+        }
+
+        if self.webserver.skyplot_elevation_mask_deg > 90 {
+            anyhow::bail!("Invalid webserver.skyplot_elevation_mask_deg: must be between 0 and 90");
+        }
+
+        if self.webserver.ws_channel_capacity == 0 {
+            anyhow::bail!("Invalid webserver.ws_channel_capacity: must be greater than 0");
+        }
+
+        if self.webserver.admin_token.as_deref().is_some_and(str::is_empty) {
+            anyhow::bail!("Invalid webserver.admin_token: must not be empty");
+        }
+
+        if let Some(ref timezone) = self.webserver.display_timezone {
+            crate::web_server::DisplayTimezone::parse(timezone)?;
+        }
+
+        for peer in &self.server.symmetric_peers {
+            peer.parse::<crate::security::IpNetwork>()
+                .map_err(|e| anyhow::anyhow!("Invalid symmetric_peers entry: {}", e))?;
+        }
+
+        let mut key_ids = std::collections::HashSet::new();
+        for key in &self.security.keys {
+            if key.id == 0 {
+                anyhow::bail!("Invalid security.keys entry: key ID must be at least 1");
+            }
+            if !key_ids.insert(key.id) {
+                anyhow::bail!("Invalid security.keys entry: duplicate key ID {}", key.id);
+            }
+            if crate::auth::DigestAlgorithm::parse(&key.algorithm).is_none() {
+                anyhow::bail!("Invalid security.keys entry {}: algorithm must be 'md5' or 'sha1'", key.id);
+            }
+            crate::auth::parse_key_secret(&key.key)
+                .map_err(|e| anyhow::anyhow!("Invalid security.keys entry {}: {}", key.id, e))?;
+        }
+
+        for rate_override in &self.security.rate_limit_overrides {
+            rate_override
+                .cidr
+                .parse::<crate::security::IpNetwork>()
+                .map_err(|e| anyhow::anyhow!("Invalid rate_limit_overrides entry: {}", e))?;
+        }
+
+        if self.server.anycast_mode && self.security.global_max_requests_per_second == 0 {
+            anyhow::bail!("anycast_mode requires global_max_requests_per_second greater than 0");
+        }
+
+        if let Some(bits) = self.security.timestamp_precision_bits {
+            if !(1..=32).contains(&bits) {
+                anyhow::bail!("timestamp_precision_bits must be between 1 and 32");
+            }
+        }
+
+        if self.logging.snr_csv_file.is_some() && self.logging.snr_csv_interval_secs == 0 {
+            anyhow::bail!("snr_csv_interval_secs must be greater than 0");
+        }
+
+        if self.logging.audit_sample_rate == 0 {
+            anyhow::bail!("audit_sample_rate must be greater than 0 (1 = every response)");
+        }
+
+        if let Some(ref gps) = self.clock.gps {
+            if gps.auto_baud && gps.auto_baud_rates.is_empty() {
+                anyhow::bail!("auto_baud requires at least one rate in auto_baud_rates");
+            }
+
+            if gps.dgps_refid.is_empty() || gps.dgps_refid.len() > 4 || !gps.dgps_refid.is_ascii() {
+                anyhow::bail!("Invalid dgps_refid: must be 1 to 4 ASCII characters");
+            }
+
+            match gps.flow_control.as_str() {
+                "none" | "software" => {}
+                "hardware" if gps.pps_enabled => {
+                    anyhow::bail!("flow_control = 'hardware' uses CTS and cannot be combined with pps_enabled");
+                }
+                "hardware" => {}
+                _ => anyhow::bail!("Invalid flow_control: must be 'none', 'software' or 'hardware'"),
+            }
+
+            if !["none", "zda", "zda_ahead"].contains(&gps.pps_time_sentence.as_str()) {
+                anyhow::bail!("Invalid pps_time_sentence: must be 'none', 'zda' or 'zda_ahead'");
+            }
+
+            let talker = gps.time_source_talker.as_str();
+            if talker != "auto" && !(talker.len() == 2 && talker.bytes().all(|b| b.is_ascii_uppercase())) {
+                anyhow::bail!("Invalid time_source_talker: must be 'auto' or a two-letter talker ID such as 'GN' or 'GP'");
+            }
+
+            if gps.pps_offset_max_age_secs <= 1 {
+                anyhow::bail!("Invalid pps_offset_max_age_secs: must be greater than 1");
+            }
+
+            if !(-30..=0).contains(&gps.nmea_only_precision) {
+                anyhow::bail!("Invalid nmea_only_precision: must be between -30 and 0");
+            }
+
+            if gps.max_sentence_length < NMEA_MAX_SENTENCE_LENGTH {
+                anyhow::bail!(
+                    "Invalid max_sentence_length: must be at least {} (NMEA 0183 limit)",
+                    NMEA_MAX_SENTENCE_LENGTH
+                );
+            }
+
+            if gps.sync_criteria.max_hdop.is_some_and(|hdop| hdop.is_nan() || hdop <= 0.0) {
+                anyhow::bail!("Invalid sync_criteria.max_hdop: must be greater than 0");
+            }
+
+            for name in gps.require_constellations.iter().chain(&gps.exclude_constellations) {
+                if !GNSS_CONSTELLATIONS.iter().any(|known| known.eq_ignore_ascii_case(name)) {
+                    anyhow::bail!(
+                        "Invalid constellation '{}': must be one of {}",
+                        name,
+                        GNSS_CONSTELLATIONS.join(", ")
+                    );
+                }
+            }
+            if let Some(name) = gps
+                .require_constellations
+                .iter()
+                .find(|name| gps.exclude_constellations.iter().any(|excluded| excluded.eq_ignore_ascii_case(name)))
+            {
+                anyhow::bail!("Constellation '{}' is both required and excluded", name);
+            }
+        }
+
         Ok(())
     }
 
@@ -244,11 +1199,37 @@ impl Config {
         let (default_port, default_log) = ("/dev/ttyUSB0".to_string(), Some("/var/log/pendulum.log".to_string()));
 
         let example_config = Config {
+            config_version: CONFIG_VERSION,
+            strict_config: false,
             server: ServerConfig {
                 bind_address: "0.0.0.0:123".to_string(),
+                additional_ports: vec![],
+                listen_policies: Vec::new(),
+                tcp_enabled: false,
+                max_tcp_connections: default_max_tcp_connections(),
                 stratum: 1,
                 precision: -20,
                 poll_interval: 6,
+                poll_mode: "fixed".to_string(),
+                poll_sane_min: default_poll_sane_min(),
+                poll_sane_max: default_poll_sane_max(),
+                unsynced_behavior: default_unsynced_behavior(),
+                advertised_stratum_min: None,
+                static_root_delay_us: 0,
+                detect_duplicate_requests: false,
+                minimal_reply: false,
+                pad_response_to_request: false,
+                emit_uncertainty_ext: false,
+                response_template: false,
+                tx_timestamping: false,
+                dont_fragment: false,
+                log_icmp_errors: false,
+                allow_symmetric: false,
+                symmetric_peers: vec![],
+                requests_count_file: None,
+                requests_count_flush_secs: default_requests_count_flush_secs(),
+                requests_rate_window_secs: default_requests_rate_window_secs(),
+                anycast_mode: false,
             },
             clock: ClockConfig {
                 source: "gps".to_string(),
@@ -256,26 +1237,84 @@ impl Config {
                     enabled: true,
                     serial_port: default_port,
                     baud_rate: 9600,
+                    auto_baud: false,
+                    auto_baud_rates: default_auto_baud_rates(),
                     sync_timeout: 30,
-                    min_satellites: 4,
+                    sync_criteria: SyncCriteria::default(),
                     pps_enabled: true,
                     pps_gpio_pin: Some(18),
+                    dgps_refid: "SBAS".to_string(),
+                    watchdog_timeout_secs: 30,
+                    pps_log_file: None,
+                    rts: None,
+                    dtr: None,
+                    flow_control: default_flow_control(),
+                    rx_stats_interval_ms: default_rx_stats_interval_ms(),
+                    max_sentence_length: default_max_sentence_length(),
+                    pps_offset_max_age_secs: default_pps_offset_max_age_secs(),
+                    nmea_only_precision: default_nmea_only_precision(),
+                    pps_offset_histogram: false,
+                    nmea_validation_timeout_secs: default_nmea_validation_timeout_secs(),
+                    require_constellations: Vec::new(),
+                    exclude_constellations: Vec::new(),
+                    pps_time_sentence: "none".to_string(),
+                    time_source_talker: "auto".to_string(),
                 }),
+                record_offset_history: false,
+                offset_history_size: 3600,
+                system_clock_trusted: true,
+                leap_seconds_file: None,
+                sources: Vec::new(),
+                source_switch_holdoff_secs: default_source_switch_holdoff_secs(),
+                manual_stratum: default_manual_stratum(),
+                phc_device: default_phc_device(),
+                phc_samples: default_phc_samples(),
+                phc_utc_offset_secs: default_phc_utc_offset_secs(),
+                max_gps_system_divergence_ms: None,
+                refuse_on_divergence: false,
             },
             security: SecurityConfig {
                 enable_rate_limiting: true,
                 max_requests_per_second: 100,
+                min_client_interval_ms: 0,
+                global_max_requests_per_second: default_global_max_requests_per_second(),
                 ip_whitelist: vec![],
                 ip_blacklist: vec![],
+                rate_limit_overrides: vec![],
+                keys: Vec::new(),
+                timestamp_precision_bits: None,
+                accept_version_zero: false,
+                reject_source_port_123: false,
+                answer_symmetric_as_server: false,
             },
             logging: LoggingConfig {
                 level: "info".to_string(),
                 log_requests: true,
                 log_file: default_log,
+                max_file_bytes: default_log_max_file_bytes(),
+                max_files: default_log_max_files(),
+                snr_csv_file: None,
+                snr_csv_interval_secs: 10,
+                snr_csv_max_bytes: 10_000_000,
+                audit_log_file: None,
+                audit_sample_rate: default_audit_sample_rate(),
+                audit_max_file_bytes: default_log_max_file_bytes(),
+                audit_max_files: default_audit_max_files(),
+                health_interval_secs: 0,
             },
             webserver: WebServerConfig {
                 port: 8080,
                 bind_address: "0.0.0.0".to_string(),
+                display_timezone: None,
+                admin_token: None,
+                start_delay: default_web_start_delay(),
+                start_delay_timeout_secs: default_web_start_delay_timeout(),
+                stats_lock_metrics: false,
+                ws_channel_capacity: default_ws_channel_capacity(),
+                ws_max_consecutive_lags: default_ws_max_consecutive_lags(),
+                max_ws_connections: default_max_ws_connections(),
+                realtime_server_health: false,
+                skyplot_elevation_mask_deg: default_skyplot_elevation_mask_deg(),
             },
         };
 
@@ -309,4 +1348,107 @@ mod tests {
         config.server.stratum = 1;
         assert!(config.validate().is_ok());
     }
+
+    #[test]
+    fn test_advertised_stratum_min_validation() {
+        let mut config = Config::default();
+
+        config.server.advertised_stratum_min = Some(0);
+        assert!(config.validate().is_err());
+
+        config.server.advertised_stratum_min = Some(16);
+        assert!(config.validate().is_err());
+
+        config.server.advertised_stratum_min = Some(2);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_listen_policy_validation() {
+        let mut config = Config::default();
+        config.server.bind_address = "0.0.0.0:123".to_string();
+        config.server.additional_ports = vec![10123];
+
+        let policy = |port| ListenPolicy {
+            port,
+            advertised_stratum_min: Some(2),
+            reference_id: Some("DMZ".to_string()),
+            max_requests_per_second: None,
+        };
+
+        config.server.listen_policies = vec![policy(123), policy(10123)];
+        assert!(config.validate().is_ok());
+
+        // Port sur lequel le serveur n'écoute pas
+        config.server.listen_policies = vec![policy(124)];
+        assert!(config.validate().is_err());
+
+        // Doublon
+        config.server.listen_policies = vec![policy(123), policy(123)];
+        assert!(config.validate().is_err());
+
+        config.server.listen_policies = vec![ListenPolicy { reference_id: Some("TOOLONG".to_string()), ..policy(123) }];
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_unknown_config_keys_detected() {
+        let content = r#"
+            config_version = 1
+
+            [server]
+            bind_address = "0.0.0.0:123"
+            stratum = 1
+            precision = -20
+            poll_interval = 6
+            minimal_replly = true
+
+            [[server.listen_policies]]
+            port = 123
+            referance_id = "DMZ"
+
+            [clock]
+            source = "gps"
+
+            [clock.gps]
+            serial_port = "/dev/ttyUSB0"
+            min_satellites = 4
+
+            [security]
+            enable_rate_limiting = true
+            max_requests_per_second = 100
+            ip_whitelist = []
+            ip_blacklist = []
+
+            [logging]
+            level = "info"
+            log_requests = false
+        "#;
+
+        let config: Config = toml::from_str(content).unwrap();
+        assert_eq!(
+            config.unknown_keys(content).unwrap(),
+            vec![
+                "clock.gps.min_satellites",
+                "server.listen_policies[0].referance_id",
+                "server.minimal_replly",
+            ]
+        );
+
+        // Par défaut : avertissement seulement ; mode strict : refus avec l'option déplacée indiquée
+        assert!(config.check_format(content).is_ok());
+        let strict = content.replace("config_version = 1", "config_version = 1\nstrict_config = true");
+        let config: Config = toml::from_str(&strict).unwrap();
+        let error = config.check_format(&strict).unwrap_err().to_string();
+        assert!(error.contains("clock.gps.min_satellites (moved to clock.gps.sync_criteria.min_satellites)"));
+
+        // Fichier sans faute : aucune clé inconnue
+        let clean = Config::default();
+        let content = toml::to_string_pretty(&clean).unwrap();
+        assert!(clean.unknown_keys(&content).unwrap().is_empty());
+
+        // Format plus récent que la version supportée : refusé
+        let newer: Config = toml::from_str(&content.replace("config_version = 1", "config_version = 2")).unwrap();
+        assert!(newer.check_format(&content).is_err());
+    }
 }
@@ -0,0 +1,159 @@
+/*!
+Rechargement de la configuration à chaud (`POST /api/config/reload`)
+
+Relit et valide le fichier de configuration, applique les options modifiables
+en cours d'exécution et retourne la liste des champs modifiés :
+
+```json
+{
+  "applied": [{ "field": "security.max_requests_per_second", "old": 100, "new": 20 }],
+  "restart_required": [{ "field": "clock.source", "old": "system", "new": "gps" }]
+}
+```
+
+Les autres options (adresses d'écoute, source d'horloge...) ne prennent effet
+qu'au redémarrage : elles restent signalées à chaque rechargement tant que le
+serveur tourne avec l'ancienne valeur. Les secrets (jeton d'administration,
+clés symétriques) sont masqués par `***`.
+*/
+
+use crate::config::Config;
+use crate::security::RateLimiter;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+/// Champ modifié entre deux versions de la configuration
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigChange {
+    /// Chemin pointé de l'option (`section.clé`)
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// Résultat d'un rechargement
+#[derive(Debug, Default, Serialize)]
+pub struct ReloadReport {
+    /// Modifications appliquées immédiatement
+    pub applied: Vec<ConfigChange>,
+
+    /// Modifications prises en compte au prochain redémarrage seulement
+    pub restart_required: Vec<ConfigChange>,
+}
+
+/// Options secrètes, masquées dans les changements retournés et journalisés
+const SECRET_FIELDS: &[&str] = &["security.keys", "webserver.admin_token"];
+
+/// Champs modifiés entre deux configurations (tableaux comparés en bloc)
+pub fn diff(old: &Config, new: &Config) -> Vec<ConfigChange> {
+    let mut changes = Vec::new();
+    let old = serde_json::to_value(old).unwrap_or_default();
+    let new = serde_json::to_value(new).unwrap_or_default();
+    diff_values("", &old, &new, &mut changes);
+    for change in changes.iter_mut().filter(|change| SECRET_FIELDS.contains(&change.field.as_str())) {
+        redact(&mut change.old);
+        redact(&mut change.new);
+    }
+    changes
+}
+
+/// Masque un secret (réponse HTTP et journaux) : valeur présente, ou champ
+/// `key` de chaque clé symétrique
+fn redact(value: &mut Value) {
+    match value {
+        Value::Null => {}
+        Value::Array(entries) => {
+            for entry in entries {
+                if let Some(key) = entry.get_mut("key") {
+                    *key = Value::String("***".to_string());
+                }
+            }
+        }
+        _ => *value = Value::String("***".to_string()),
+    }
+}
+
+fn diff_values(prefix: &str, old: &Value, new: &Value, changes: &mut Vec<ConfigChange>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            let mut keys: Vec<&String> = old_map.keys().chain(new_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                let old_value = old_map.get(key).unwrap_or(&Value::Null);
+                let new_value = new_map.get(key).unwrap_or(&Value::Null);
+                diff_values(&path, old_value, new_value, changes);
+            }
+        }
+        _ if old != new => changes.push(ConfigChange {
+            field: prefix.to_string(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Rechargeur du fichier de configuration
+pub struct ConfigReloader {
+    path: PathBuf,
+
+    /// Configuration en vigueur (valeurs de démarrage et options rechargées)
+    current: Mutex<Config>,
+
+    /// Rate limiter du serveur NTP (absent si le rate limiting est désactivé)
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl ConfigReloader {
+    pub fn new(path: PathBuf, config: Config, rate_limiter: Option<Arc<RateLimiter>>) -> Self {
+        ConfigReloader {
+            path,
+            current: Mutex::new(config),
+            rate_limiter,
+        }
+    }
+
+    /// Relit le fichier, applique les options modifiables et décrit les changements
+    ///
+    /// Un fichier illisible ou invalide est refusé sans rien modifier.
+    pub fn reload(&self) -> anyhow::Result<ReloadReport> {
+        let new = Config::from_file(&self.path)?;
+        let mut current = self.current.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        // Limite modifiée entre-temps par PUT /api/ratelimit : comparer à la valeur en vigueur
+        if let Some(ref limiter) = self.rate_limiter {
+            current.security.max_requests_per_second = limiter.max_requests_per_second();
+        }
+
+        let mut report = ReloadReport::default();
+        for change in diff(&current, &new) {
+            if self.apply(&mut current, &new, &change.field) {
+                info!("Config reload applied {}: {} -> {}", change.field, change.old, change.new);
+                report.applied.push(change);
+            } else {
+                report.restart_required.push(change);
+            }
+        }
+        Ok(report)
+    }
+
+    /// Applique une option modifiée si elle peut l'être en cours d'exécution
+    fn apply(&self, current: &mut Config, new: &Config, field: &str) -> bool {
+        match field {
+            "security.max_requests_per_second" => match self.rate_limiter {
+                Some(ref limiter) => {
+                    limiter.set_max_requests_per_second(new.security.max_requests_per_second);
+                    current.security.max_requests_per_second = new.security.max_requests_per_second;
+                    true
+                }
+                None => false,
+            },
+            _ => false,
+        }
+    }
+}
@@ -19,6 +19,12 @@ Les trames NMEA utilisées :
 - **$GPRMC** : Recommended Minimum data (time, date, position, validity)
 - **$GPGGA** : Global Positioning System Fix Data (time, satellites, quality)
 - **$GPZDA** : Date & Time (le plus précis pour NTP)
+- **$GPGLL** : Geographic Position (position, heure sans date, validité) : source de synchro
+  additionnelle, datée par la dernière trame RMC
+- **$GPVTG** : Track Made Good and Ground Speed (cap et vitesse sur le fond)
+- **$PUBX,00** (u-blox) : précisions estimées et DOP, d'où l'incertitude temporelle annoncée
+- **$PUBX,04** (u-blox) : semaine GPS complète et temps dans la semaine, date non ambiguë
+  préférée à la date RMC sur deux chiffres (écart journalisé)
 
 Format typique d'une trame GPRMC :
 ```text
@@ -124,9 +130,11 @@ source = "gps"
 serial_port = "/dev/ttyUSB0"
 baud_rate = 9600
 sync_timeout = 30
-min_satellites = 4
 pps_enabled = true
 pps_gpio_pin = 18
+
+[clock.gps.sync_criteria]
+min_satellites = 4
 ```
 
 ## TODO : Implémentation complète
@@ -174,7 +182,7 @@ std::thread::spawn(move || {
         line.clear();
         if reader.read_line(&mut line).is_ok() {
             if let Some(timestamp) = parse_nmea_time(&line) {
-                gps_clock_clone.update_gps_time(timestamp, 8);
+                gps_clock_clone.update_gps_time(timestamp, Some(8));
             }
         }
     }
@@ -14,21 +14,591 @@ Architecture robuste :
 
 use crate::clock::GpsNmeaClock;
 use crate::config::GpsConfig;
-use crate::packet::NtpTimestamp;
-use crate::stats::{SatelliteInfo, ServerStats};
+use crate::gps_state::{apply_to_stats, GpsStateMachine};
+use crate::heartbeat;
+use crate::packet::{NtpTimestamp, NTP_UNIX_OFFSET};
+use crate::panic_guard::run_guarded;
+use crate::pps_log::PpsLogger;
+use crate::stats::{write_stats, GpsState, SatelliteInfo, ServerStats};
 use chrono::NaiveDateTime;
 use std::io::Read;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 
+/// Résultat du parsing d'une trame GGA
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum GgaFix {
+    /// Pas encore de fix (qualité 0) : le récepteur acquiert les satellites
+    Acquiring { satellites: u8 },
+
+    /// Fix valide avec la qualité, le nombre de satellites utilisés et le HDOP
+    Fix { quality: u8, satellites: u8, hdop: Option<f64> },
+}
+
+/// Suivi de progression du thread de lecture, surveillé par le watchdog
+///
+/// Le dernier instant de progression est stocké en millisecondes depuis
+/// `epoch` (+1), la valeur 0 signifiant "au repos" (attente de reconnexion) :
+/// un lecteur au repos n'est jamais considéré comme bloqué.
+struct ProgressWatchdog {
+    epoch: Instant,
+    last_progress_ms: AtomicU64,
+}
+
+impl ProgressWatchdog {
+    fn new() -> Self {
+        ProgressWatchdog {
+            epoch: Instant::now(),
+            last_progress_ms: AtomicU64::new(0),
+        }
+    }
+
+    /// Signale une progression (données reçues, pulse PPS, ouverture du port)
+    fn touch(&self) {
+        let now_ms = self.epoch.elapsed().as_millis() as u64;
+        self.last_progress_ms.store(now_ms + 1, Ordering::Relaxed);
+    }
+
+    /// Met le lecteur au repos (attente avant reconnexion)
+    fn set_idle(&self) {
+        self.last_progress_ms.store(0, Ordering::Relaxed);
+    }
+
+    /// Vrai si aucune progression n'a été signalée depuis plus de `timeout` à l'instant `now`
+    fn is_stalled(&self, now: Instant, timeout: Duration) -> bool {
+        let last = self.last_progress_ms.load(Ordering::Relaxed);
+        if last == 0 {
+            return false;
+        }
+
+        let now_ms = now.saturating_duration_since(self.epoch).as_millis() as u64;
+        now_ms.saturating_sub(last - 1) > timeout.as_millis() as u64
+    }
+}
+
+/// Durée d'écoute à chaque vitesse lors de la détection automatique du baud rate
+const AUTO_BAUD_PROBE_DURATION: Duration = Duration::from_millis(1500);
+
+/// Nombre de trames NMEA valides requises pour retenir une vitesse
+const AUTO_BAUD_MIN_SENTENCES: usize = 2;
+
+/// Longueur maximale conservée d'une ligne sans fin de ligne pendant la
+/// vérification NMEA (un flux binaire n'en contient jamais)
+const NMEA_VALIDATION_MAX_LINE: usize = 4096;
+
+/// Nombre de satellites inconnu (aucune trame GGA reçue)
+const SATELLITES_UNKNOWN: u8 = u8::MAX;
+
+/// Délai sans SNR dans les trames GSV au-delà duquel un satellite n'est plus
+/// considéré comme suivi (couché, masqué ou brouillé)
+const TRACKED_SATELLITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Délai maximal entre une trame de timing (`pps_time_sentence`) et le pulse
+/// suivant : au-delà, la trame est périmée et le pulse n'est pas daté
+const PPS_TIME_TAG_MAX_AGE: Duration = Duration::from_millis(1100);
+
+/// Délai après le démarrage avant de signaler une constellation requise jamais vue
+const MISSING_CONSTELLATION_WARN_DELAY: Duration = Duration::from_secs(120);
+
+/// Satellites suivis par constellation (trames GSV), pour les filtres
+/// `require_constellations` / `exclude_constellations`
+#[derive(Debug, Default)]
+pub(crate) struct ConstellationTracker {
+    /// Dernier instant où chaque satellite (constellation, PRN) avait un SNR
+    last_tracked: std::collections::HashMap<(String, u8), Instant>,
+}
+
+impl ConstellationTracker {
+    /// Enregistre les satellites d'une trame GSV (seuls ceux avec un SNR sont suivis)
+    pub(crate) fn record(&mut self, satellites: &[SatelliteInfo], now: Instant) {
+        for satellite in satellites.iter().filter(|satellite| satellite.snr > 0) {
+            self.last_tracked.insert((satellite.constellation.clone(), satellite.prn), now);
+        }
+        self.last_tracked
+            .retain(|_, seen| now.saturating_duration_since(*seen) <= TRACKED_SATELLITE_TIMEOUT);
+    }
+
+    /// Nombre de satellites suivis hors constellations exclues, 0 si une
+    /// constellation requise n'a aucun satellite suivi
+    pub(crate) fn effective_count(&self, require: &[String], exclude: &[String]) -> u8 {
+        if !self.missing(require).is_empty() {
+            return 0;
+        }
+
+        let count = self
+            .last_tracked
+            .keys()
+            .filter(|(constellation, _)| !exclude.iter().any(|name| name.eq_ignore_ascii_case(constellation)))
+            .count();
+        count.min(u8::MAX as usize - 1) as u8
+    }
+
+    /// Constellations requises sans aucun satellite suivi
+    pub(crate) fn missing<'a>(&self, require: &'a [String]) -> Vec<&'a str> {
+        require
+            .iter()
+            .filter(|name| {
+                !self
+                    .last_tracked
+                    .keys()
+                    .any(|(constellation, _)| name.eq_ignore_ascii_case(constellation))
+            })
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Vérifie le checksum d'une trame NMEA (`$...*hh`, XOR des octets entre `$` et `*`)
+pub(crate) fn nmea_checksum_valid(sentence: &str) -> bool {
+    let Some(body) = sentence.trim().strip_prefix('$') else {
+        return false;
+    };
+    let Some((data, checksum)) = body.split_once('*') else {
+        return false;
+    };
+
+    let computed = data.bytes().fold(0u8, |acc, b| acc ^ b);
+    u8::from_str_radix(checksum.get(..2).unwrap_or(""), 16) == Ok(computed)
+}
+
+/// Compte les trames NMEA au checksum valide dans un bloc d'octets reçus
+fn count_valid_sentences(bytes: &[u8]) -> usize {
+    String::from_utf8_lossy(bytes)
+        .split(['\r', '\n'])
+        .filter(|line| nmea_checksum_valid(line))
+        .count()
+}
+
+/// Marque d'ordre des octets UTF-8 injectée par certains ponts USB-série
+const UTF8_BOM: char = '\u{FEFF}';
+
+/// Extrait la prochaine ligne complète du tampon de réception
+///
+/// Les fins de ligne `\r\n`, `\n` et `\r` seul sont acceptées (un `\r\n` coupé
+/// entre deux lectures ne produit qu'une ligne vide supplémentaire, ignorée), et
+/// une marque BOM en tête de ligne est retirée.
+fn next_nmea_line(buffer: &mut String) -> Option<String> {
+    let pos = buffer.find(['\r', '\n'])?;
+    let line = buffer[..pos].trim_start_matches(UTF8_BOM).to_string();
+
+    let terminator = if buffer[pos..].starts_with("\r\n") { 2 } else { 1 };
+    buffer.drain(..pos + terminator);
+    Some(line)
+}
+
+/// Longueur du début de trame journalisé
+const NMEA_PREVIEW_CHARS: usize = 80;
+
+/// Début d'une trame pour les journaux (coupé sur une frontière de caractère)
+fn sentence_preview(line: &str) -> &str {
+    match line.char_indices().nth(NMEA_PREVIEW_CHARS) {
+        Some((end, _)) => &line[..end],
+        None => line,
+    }
+}
+
+/// Vrai si la trame respecte la longueur maximale (`gps.max_sentence_length`)
+///
+/// Les trames GSV multi-constellations et propriétaires dépassent souvent les
+/// 82 caractères de la norme ; bien au-delà, la trame agrège plusieurs trames
+/// dont le délimiteur a été perdu (erreur de trame) et est écartée.
+fn sentence_length_ok(line: &str, max_len: usize) -> bool {
+    if line.len() <= max_len {
+        return true;
+    }
+    warn!(
+        "Discarding {}-byte NMEA sentence (max {}), missing delimiter or framing error? {}",
+        line.len(),
+        max_len,
+        sentence_preview(line)
+    );
+    false
+}
+
+/// Vide le tampon de réception lorsque la ligne en cours dépasse déjà la
+/// longueur maximale sans délimiteur (flux binaire ou délimiteurs perdus)
+fn discard_oversized_partial(buffer: &mut String, max_len: usize) {
+    if buffer.len() > max_len {
+        warn!(
+            "Discarding {} bytes of NMEA data without line delimiter (max sentence length {})",
+            buffer.len(),
+            max_len
+        );
+        buffer.clear();
+    }
+}
+
+/// Sélectionne le premier baud rate pour lequel `probe` renvoie des trames NMEA valides
+///
+/// `probe` écoute brièvement le port à la vitesse donnée et retourne les octets reçus.
+fn select_baud_rate<F>(candidates: &[u32], mut probe: F) -> Option<u32>
+where
+    F: FnMut(u32) -> std::io::Result<Vec<u8>>,
+{
+    candidates.iter().copied().find(|&baud| match probe(baud) {
+        Ok(bytes) => {
+            let valid = count_valid_sentences(&bytes);
+            debug!("Auto-baud: {} valid NMEA sentences at {} baud", valid, baud);
+            valid >= AUTO_BAUD_MIN_SENTENCES
+        }
+        Err(e) => {
+            debug!("Auto-baud: probe at {} baud failed: {}", baud, e);
+            false
+        }
+    })
+}
+
+/// Pause après une lecture vide : certains adaptateurs USB-série retournent
+/// immédiatement `Ok(0)` au lieu de `TimedOut`, ce qui ferait tourner la boucle
+/// de lecture à 100% CPU. 1 ms reste négligeable pour la détection du PPS par CTS.
+const EMPTY_READ_PAUSE: Duration = Duration::from_millis(1);
+
+/// Lit le port série, avec une courte pause si aucune donnée n'est disponible
+fn read_or_pause<R: Read + ?Sized>(port: &mut R, buf: &mut [u8]) -> std::io::Result<usize> {
+    let result = port.read(buf);
+    if matches!(result, Ok(0)) {
+        std::thread::sleep(EMPTY_READ_PAUSE);
+    }
+    result
+}
+
+/// Erreur de lecture passagère : la boucle de lecture continue sans reconnexion
+///
+/// Timeout de lecture, appel interrompu par un signal (EINTR) ou port
+/// momentanément indisponible. Les autres erreurs (périphérique retiré,
+/// `BrokenPipe`...) provoquent une reconnexion complète, avec la réinitialisation
+/// de l'offset PPS qui l'accompagne.
+fn is_transient_read_error(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::TimedOut | std::io::ErrorKind::Interrupted | std::io::ErrorKind::WouldBlock
+    )
+}
+
+/// Attend une trame NMEA au checksum valide pendant au plus `timeout`
+///
+/// Retourne faux si aucune trame n'est reçue : le port s'ouvre mais le
+/// périphérique n'est pas un récepteur GPS (modem, imprimante 3D...) ou reste
+/// muet. `on_read` est appelé à chaque bloc d'octets reçu (watchdog).
+fn wait_for_nmea<R, F>(port: &mut R, timeout: Duration, mut on_read: F) -> std::io::Result<bool>
+where
+    R: Read + ?Sized,
+    F: FnMut(),
+{
+    let mut pending = String::new();
+    let mut read_buf = [0u8; 512];
+    let start = Instant::now();
+
+    while start.elapsed() < timeout {
+        match read_or_pause(port, &mut read_buf) {
+            Ok(0) => continue,
+            Ok(n) => {
+                on_read();
+                pending.push_str(&String::from_utf8_lossy(&read_buf[..n]));
+            }
+            Err(ref e) if is_transient_read_error(e) => continue,
+            Err(e) => return Err(e),
+        }
+
+        while let Some(line) = next_nmea_line(&mut pending) {
+            if nmea_checksum_valid(&line) {
+                return Ok(true);
+            }
+        }
+        if pending.len() > NMEA_VALIDATION_MAX_LINE {
+            pending.clear();
+        }
+    }
+
+    Ok(false)
+}
+
+/// Limite la fréquence d'une mise à jour des statistiques partagées
+///
+/// La première mise à jour passe toujours, les suivantes au plus une fois par
+/// `interval` : la donnée publiée n'a donc jamais plus de `interval` de retard.
+#[derive(Debug)]
+struct UpdateThrottle {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl UpdateThrottle {
+    fn new(interval: Duration) -> Self {
+        UpdateThrottle { interval, last: None }
+    }
+
+    /// Vrai (et réarme le délai) si une mise à jour est permise à l'instant `now`
+    fn ready(&mut self, now: Instant) -> bool {
+        let ready = self
+            .last
+            .is_none_or(|last| now.saturating_duration_since(last) >= self.interval);
+        if ready {
+            self.last = Some(now);
+        }
+        ready
+    }
+}
+
+/// Pause avant de relancer le lecteur GPS après une panique
+const GPS_PANIC_RESTART_DELAY: Duration = Duration::from_secs(5);
+
+/// Extrait le type de fix d'une trame GSA (champ 2 : 1 = aucun, 2 = 2D, 3 = 3D)
+fn parse_gsa_fix_type(sentence: &str) -> Option<u8> {
+    let field = sentence.split(',').nth(2)?;
+    match field.parse() {
+        Ok(fix_type @ 1..=3) => Some(fix_type),
+        _ => None,
+    }
+}
+
+/// Vitesse de la lumière en mètres par nanoseconde
+const SPEED_OF_LIGHT_M_PER_NS: f64 = 0.299_792_458;
+
+/// Estime la précision temporelle (ns) d'une trame u-blox `$PUBX,00`
+///
+/// `$PUBX,00` ne fournit pas directement le `tAcc` de UBX-NAV-PVT, mais la
+/// précision horizontale estimée (hAcc, m) et les DOP. L'erreur de pseudo-distance
+/// vaut σ = hAcc / HDOP et l'incertitude temporelle σt = TDOP × σ / c.
+/// Retourne `None` sans fix (statut NF) ou si un champ est illisible.
+fn parse_pubx_time_accuracy_ns(sentence: &str) -> Option<u64> {
+    let data = sentence.split('*').next()?;
+    let fields: Vec<&str> = data.split(',').collect();
+    if fields.len() < 18 || fields[1] != "00" || fields[8] == "NF" {
+        return None;
+    }
+
+    let h_acc_m: f64 = fields[9].parse().ok()?;
+    let hdop: f64 = fields[15].parse().ok()?;
+    let tdop: f64 = fields[17].parse().ok()?;
+    if !(h_acc_m.is_finite() && hdop > 0.0 && tdop >= 0.0) {
+        return None;
+    }
+
+    let range_error_m = h_acc_m / hdop;
+    Some((tdop * range_error_m / SPEED_OF_LIGHT_M_PER_NS).round() as u64)
+}
+
+/// Durée d'un jour UTC en secondes
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Durée d'une semaine GPS en secondes
+const SECONDS_PER_WEEK: u64 = 7 * SECONDS_PER_DAY;
+
+/// Epoch GPS (1980-01-06 00:00:00) en secondes NTP
+const GPS_EPOCH_NTP_SECONDS: u64 = 2_524_953_600;
+
+/// Âge maximal de la date semaine GPS utilisée pour vérifier les trames RMC
+const WEEK_DATE_MAX_AGE: Duration = Duration::from_secs(60);
+
+/// Temps NTP d'un instant exprimé en semaine GPS et temps dans la semaine
+///
+/// `tow_secs` est compté en temps GPS : l'écart GPS - UTC (secondes
+/// intercalaires, 18 s depuis 2017) est retranché pour obtenir l'UTC. Une
+/// semaine déjà exprimée en UTC (`$PUBX,04`) se convertit avec un écart nul.
+/// Le numéro de semaine doit être complet (sans repli modulo 1024).
+pub(crate) fn gps_week_to_ntp(week: u32, tow_secs: f64, gps_utc_offset: i32) -> Option<NtpTimestamp> {
+    if !(0.0..SECONDS_PER_WEEK as f64).contains(&tow_secs) {
+        return None;
+    }
+    let whole_secs = tow_secs.trunc() as u64;
+    let nanos = ((tow_secs - tow_secs.trunc()) * 1e9).round().min(999_999_999.0) as u32;
+    let gps_seconds = GPS_EPOCH_NTP_SECONDS + week as u64 * SECONDS_PER_WEEK + whole_secs;
+    let utc_seconds = gps_seconds.checked_add_signed(-(gps_utc_offset as i64))?;
+
+    Some(NtpTimestamp::from_seconds_and_nanos(utc_seconds, nanos))
+}
+
+/// Semaine et temps dans la semaine UTC d'une trame u-blox `$PUBX,04`
+///
+/// `$PUBX,04,hhmmss.ss,ddmmyy,UTC_TOW,UTC_WNO,LEAP_SEC,...` : le numéro de
+/// semaine est complet (pas de repli à 1024) et le temps dans la semaine est
+/// déjà corrigé des secondes intercalaires.
+fn parse_pubx_week_time(sentence: &str) -> Option<NtpTimestamp> {
+    let data = sentence.split('*').next()?;
+    let fields: Vec<&str> = data.split(',').collect();
+    if fields.len() < 6 || fields[0] != "$PUBX" || fields[1] != "04" {
+        return None;
+    }
+
+    let tow_secs: f64 = fields[4].parse().ok()?;
+    let week: u32 = fields[5].parse().ok()?;
+    gps_week_to_ntp(week, tow_secs, 0)
+}
+
+/// Date d'une trame RMC corrigée d'après la date semaine GPS de référence
+///
+/// L'heure RMC est conservée ; seul le nombre de jours est repris de la
+/// semaine GPS (année sur deux chiffres mal interprétée, repli de semaine du
+/// récepteur). Retourne le nombre de jours corrigés, 0 si les dates concordent.
+fn week_date_correction(rmc_seconds: u64, week_seconds: u64) -> i64 {
+    let delta = week_seconds as i64 - rmc_seconds as i64;
+    (delta as f64 / SECONDS_PER_DAY as f64).round() as i64
+}
+
+/// Fraction de seconde d'un champ horaire NMEA (hhmmss.s à hhmmss.sssssssss), en nanosecondes
+fn parse_subsec_nanos(time_str: &str) -> u32 {
+    match time_str.split_once('.') {
+        Some((_, frac_str))
+            if !frac_str.is_empty() && frac_str.len() <= 9 && frac_str.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            // Mettre à l'échelle selon le nombre de chiffres (.25 = 250 ms)
+            let frac_value: u32 = frac_str.parse().unwrap_or(0);
+            frac_value * 10u32.pow(9 - frac_str.len() as u32)
+        }
+        _ => 0,
+    }
+}
+
+/// Heure UTC d'une trame GLL valide : (secondes depuis minuit, fraction en ns)
+///
+/// `$GPGLL,lat,N,lon,E,hhmmss.ss,statut,mode` : statut A = valide, mode
+/// (NMEA 2.3+) N = données non valides.
+fn parse_gll_time(sentence: &str) -> Option<(u64, u32)> {
+    let data = sentence.split('*').next()?;
+    let fields: Vec<&str> = data.split(',').collect();
+    if fields.len() < 7 || fields[6] != "A" || fields.get(7) == Some(&"N") {
+        return None;
+    }
+
+    let time_str = fields[5];
+    let digits = time_str.get(..6).filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))?;
+    let hours: u64 = digits[0..2].parse().ok()?;
+    let minutes: u64 = digits[2..4].parse().ok()?;
+    let seconds: u64 = digits[4..6].parse().ok()?;
+    if hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+
+    Some((hours * 3600 + minutes * 60 + seconds, parse_subsec_nanos(time_str)))
+}
+
+/// Date une heure du jour (GLL ne contient pas de date) à partir d'un instant
+/// de référence récent (dernière trame RMC, secondes NTP)
+///
+/// Le jour le plus proche de la référence est retenu, ce qui gère le passage
+/// de minuit entre la trame RMC et la trame GLL.
+fn anchor_time_of_day(reference_secs: u64, seconds_of_day: u64) -> u64 {
+    let candidate = reference_secs - reference_secs % SECONDS_PER_DAY + seconds_of_day;
+    if candidate + SECONDS_PER_DAY / 2 < reference_secs {
+        candidate + SECONDS_PER_DAY
+    } else if candidate > reference_secs + SECONDS_PER_DAY / 2 {
+        candidate - SECONDS_PER_DAY
+    } else {
+        candidate
+    }
+}
+
+/// Cap et vitesse sur le fond (trame VTG)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GroundVelocity {
+    /// Cap vrai en degrés (absent à l'arrêt sur certains modules)
+    course_deg: Option<f64>,
+
+    /// Vitesse en km/h
+    speed_kmh: f64,
+}
+
+/// Nœuds vers km/h
+const KNOTS_TO_KMH: f64 = 1.852;
+
+/// Parse une trame VTG : `$GPVTG,cap,T,cap,M,vitesse,N,vitesse,K,mode`
+///
+/// Mode (NMEA 2.3+) N = données non valides. La vitesse en km/h est préférée,
+/// celle en nœuds sert de repli.
+fn parse_vtg(sentence: &str) -> Option<GroundVelocity> {
+    let data = sentence.split('*').next()?;
+    let fields: Vec<&str> = data.split(',').collect();
+    if fields.len() < 9 || fields[2] != "T" || fields[8] != "K" || fields.get(9) == Some(&"N") {
+        return None;
+    }
+
+    let speed_kmh = match fields[7].parse::<f64>() {
+        Ok(kmh) => kmh,
+        Err(_) => fields[5].parse::<f64>().ok()? * KNOTS_TO_KMH,
+    };
+    if !speed_kmh.is_finite() || speed_kmh < 0.0 {
+        return None;
+    }
+
+    Some(GroundVelocity {
+        course_deg: fields[1].parse().ok().filter(|course: &f64| (0.0..=360.0).contains(course)),
+        speed_kmh,
+    })
+}
+
+/// Réglages des lignes de contrôle appliqués à l'ouverture du port série
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SerialLineSettings {
+    /// État imposé à RTS (`None` = ligne laissée telle quelle)
+    rts: Option<bool>,
+
+    /// État imposé à DTR (`None` = ligne laissée telle quelle)
+    dtr: Option<bool>,
+
+    flow_control: serialport::FlowControl,
+}
+
+/// Traduit la configuration GPS en réglages du port série
+///
+/// Sans valeur explicite, RTS et DTR sont mis à l'état haut uniquement lorsque le
+/// PPS via CTS est activé (montage où ces lignes alimentent le signal PPS).
+fn serial_line_settings(config: &GpsConfig) -> SerialLineSettings {
+    let pps_default = config.pps_enabled.then_some(true);
+    let flow_control = match config.flow_control.as_str() {
+        "software" => serialport::FlowControl::Software,
+        "hardware" => serialport::FlowControl::Hardware,
+        _ => serialport::FlowControl::None,
+    };
+
+    SerialLineSettings {
+        rts: config.rts.or(pps_default),
+        dtr: config.dtr.or(pps_default),
+        flow_control,
+    }
+}
+
 /// Gestionnaire de lecture GPS
 pub struct GpsReader {
     config: GpsConfig,
     clock: Arc<GpsNmeaClock>,
     stats: Arc<std::sync::RwLock<ServerStats>>,
-    running: Arc<std::sync::atomic::AtomicBool>,
+    running: Arc<AtomicBool>,
     start_time: Instant,
+
+    /// Progression du thread de lecture (watchdog)
+    progress: ProgressWatchdog,
+
+    /// Génération du thread de lecture : incrémentée à chaque relance par le watchdog,
+    /// un thread d'une génération périmée s'arrête dès qu'il se débloque
+    generation: AtomicU64,
+
+    /// Machine d'états du récepteur (déconnecté, fix 2D/3D, PPS, holdover...)
+    state: std::sync::Mutex<GpsStateMachine>,
+
+    /// Secondes NTP de la dernière trame RMC valide (0 = aucune), qui date les trames GLL
+    last_rmc_seconds: AtomicU64,
+
+    /// Dernière date semaine GPS (`$PUBX,04`) et instant de réception, préférée
+    /// à la date RMC sur deux chiffres
+    week_date: std::sync::Mutex<Option<(NtpTimestamp, Instant)>>,
+
+    /// Écart entre date RMC et date semaine GPS déjà signalé
+    week_date_mismatch_warned: AtomicBool,
+
+    /// Satellites utilisés selon la dernière trame GGA (`SATELLITES_UNKNOWN` = aucune)
+    gga_satellites: AtomicU8,
+
+    /// Satellites suivis par constellation (filtres de constellations)
+    constellations: std::sync::Mutex<ConstellationTracker>,
+
+    /// Constellation requise jamais vue déjà signalée
+    missing_constellation_warned: AtomicBool,
+
+    /// Satellites en vue par talker GSV (`GP`, `GL`...), selon le dernier en-tête reçu
+    gsv_in_view: std::sync::Mutex<std::collections::BTreeMap<String, u8>>,
 }
 
 impl GpsReader {
@@ -38,81 +608,235 @@ impl GpsReader {
         clock: Arc<GpsNmeaClock>,
         stats: Arc<std::sync::RwLock<ServerStats>>,
     ) -> Self {
+        // Holdover tant que l'horloge extrapole encore depuis la dernière synchronisation
+        let holdover_timeout = Duration::from_secs(config.sync_timeout);
+
         GpsReader {
             config,
             clock,
             stats,
-            running: Arc::new(std::sync::atomic::AtomicBool::new(true)),
+            running: Arc::new(AtomicBool::new(true)),
             start_time: Instant::now(),
+            progress: ProgressWatchdog::new(),
+            generation: AtomicU64::new(0),
+            state: std::sync::Mutex::new(GpsStateMachine::new(holdover_timeout)),
+            last_rmc_seconds: AtomicU64::new(0),
+            week_date: std::sync::Mutex::new(None),
+            week_date_mismatch_warned: AtomicBool::new(false),
+            gga_satellites: AtomicU8::new(SATELLITES_UNKNOWN),
+            constellations: std::sync::Mutex::new(ConstellationTracker::default()),
+            missing_constellation_warned: AtomicBool::new(false),
+            gsv_in_view: std::sync::Mutex::new(std::collections::BTreeMap::new()),
         }
     }
 
     /// Démarre le thread de lecture GPS
-    /// Le thread tourne indéfiniment avec reconnexion automatique
+    /// Le thread tourne indéfiniment avec reconnexion automatique,
+    /// sous la surveillance d'un watchdog qui le relance s'il se bloque
     pub fn start(self) -> std::thread::JoinHandle<()> {
         info!("Starting GPS reader thread");
         info!("  Port: {}", self.config.serial_port);
-        info!("  Baud rate: {}", self.config.baud_rate);
+        if self.config.auto_baud {
+            info!("  Baud rate: auto {:?}", self.config.auto_baud_rates);
+        } else {
+            info!("  Baud rate: {}", self.config.baud_rate);
+        }
         info!("  PPS via CTS: {}", self.config.pps_enabled);
-        info!("  Min satellites: {}", self.config.min_satellites);
+        let lines = serial_line_settings(&self.config);
+        info!(
+            "  Flow control: {}, RTS: {:?}, DTR: {:?}",
+            lines.flow_control, lines.rts, lines.dtr
+        );
+        info!("  Sync criteria: {:?}", self.config.sync_criteria);
+        info!("  Watchdog timeout: {}s", self.config.watchdog_timeout_secs);
+
+        let reader = Arc::new(self);
+        std::thread::spawn(move || reader.supervise())
+    }
+
+    /// Boucle du watchdog : relance le thread de lecture s'il ne progresse plus
+    ///
+    /// Un thread bloqué dans `read()` ne peut pas être tué : il est abandonné
+    /// et s'arrêtera de lui-même s'il se débloque (génération périmée).
+    fn supervise(self: Arc<Self>) {
+        let timeout = Duration::from_secs(self.config.watchdog_timeout_secs);
+        let mut worker = Arc::clone(&self).spawn_worker();
+
+        while self.running.load(Ordering::Relaxed) && !worker.is_finished() {
+            std::thread::sleep(Duration::from_millis(500));
+
+            // Timeout à 0 : watchdog désactivé
+            if timeout.is_zero() || !self.progress.is_stalled(Instant::now(), timeout) {
+                continue;
+            }
+
+            warn!(
+                "GPS reader made no progress for {:?}, restarting it (watchdog)",
+                timeout
+            );
+
+            self.generation.fetch_add(1, Ordering::SeqCst);
+            self.progress.set_idle();
 
+            if let Ok(mut stats) = write_stats(&self.stats) {
+                stats.gps.watchdog_restarts += 1;
+            }
+            self.update_state(|machine, now| machine.on_disconnected(now));
+
+            worker = Arc::clone(&self).spawn_worker();
+        }
+
+        info!("GPS reader thread terminated");
+    }
+
+    /// Lance un thread de lecture pour la génération courante
+    ///
+    /// Une panique du lecteur (bug de parsing...) est interceptée et le lecteur
+    /// est relancé après une pause, au lieu de laisser un thread mort.
+    fn spawn_worker(self: Arc<Self>) -> std::thread::JoinHandle<()> {
+        let generation = self.generation.load(Ordering::SeqCst);
         std::thread::spawn(move || {
-            let mut reconnect_delay = Duration::from_secs(5);
-            let max_reconnect_delay = Duration::from_secs(60);
+            while self.is_current(generation) {
+                if run_guarded("gps_reader", &self.stats, || self.reconnect_loop(generation)).is_some() {
+                    break;
+                }
 
-            while self.running.load(std::sync::atomic::Ordering::Relaxed) {
-                match self.run_reader() {
-                    Ok(_) => {
-                        // Connexion réussie puis terminée normalement
-                        info!("GPS reader stopped normally");
-                        break;
-                    }
-                    Err(e) => {
-                        error!("GPS reader error: {:#}", e);
-                        error!("Reconnecting in {:?}...", reconnect_delay);
+                error!("Restarting GPS reader after panic in {:?}", GPS_PANIC_RESTART_DELAY);
+                self.update_state(|machine, now| machine.on_disconnected(now));
+                self.progress.set_idle();
+                std::thread::sleep(GPS_PANIC_RESTART_DELAY);
+            }
+        })
+    }
+
+    /// Vrai tant que le lecteur tourne et que cette génération n'a pas été remplacée
+    fn is_current(&self, generation: u64) -> bool {
+        self.running.load(Ordering::Relaxed) && self.generation.load(Ordering::SeqCst) == generation
+    }
 
-                        // Attendre avant de reconnecter
-                        std::thread::sleep(reconnect_delay);
+    /// Boucle de lecture avec reconnexion automatique
+    fn reconnect_loop(&self, generation: u64) {
+        let mut reconnect_delay = Duration::from_secs(5);
+        let max_reconnect_delay = Duration::from_secs(60);
 
-                        // Augmenter progressivement le délai (exponential backoff)
-                        reconnect_delay = std::cmp::min(
-                            reconnect_delay * 2,
-                            max_reconnect_delay,
-                        );
+        while self.is_current(generation) {
+            match self.run_reader(generation) {
+                Ok(_) => {
+                    // Connexion réussie puis terminée normalement
+                    info!("GPS reader stopped normally");
+                    break;
+                }
+                Err(e) => {
+                    error!("GPS reader error: {:#}", e);
+                    error!("Reconnecting in {:?}...", reconnect_delay);
+
+                    // Attendre avant de reconnecter (le watchdog ne surveille pas cette attente)
+                    if self.is_current(generation) {
+                        self.progress.set_idle();
                     }
+                    std::thread::sleep(reconnect_delay);
+
+                    // Augmenter progressivement le délai (exponential backoff)
+                    reconnect_delay = std::cmp::min(
+                        reconnect_delay * 2,
+                        max_reconnect_delay,
+                    );
                 }
             }
-
-            info!("GPS reader thread terminated");
-        })
+        }
     }
 
     /// Arrête le thread GPS proprement
     pub fn stop(&self) {
-        self.running.store(false, std::sync::atomic::Ordering::Relaxed);
+        self.running.store(false, Ordering::Relaxed);
     }
 
-    /// Boucle principale de lecture GPS
-    fn run_reader(&self) -> anyhow::Result<()> {
-        info!("Opening GPS serial port: {}", self.config.serial_port);
+    /// Détecte le baud rate en écoutant brièvement le port à chaque vitesse candidate
+    fn detect_baud_rate(&self) -> anyhow::Result<u32> {
+        info!("Detecting GPS baud rate among {:?}", self.config.auto_baud_rates);
+
+        let detected = select_baud_rate(&self.config.auto_baud_rates, |baud| {
+            self.progress.touch();
+
+            let mut port = self.open_port(baud)?;
+
+            let mut received = Vec::new();
+            let mut read_buf = [0u8; 512];
+            let start = Instant::now();
+            while start.elapsed() < AUTO_BAUD_PROBE_DURATION {
+                match read_or_pause(&mut port, &mut read_buf) {
+                    Ok(n) => received.extend_from_slice(&read_buf[..n]),
+                    Err(ref e) if is_transient_read_error(e) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+
+            Ok(received)
+        });
 
-        // Ouvrir le port série
-        let mut port = serialport::new(&self.config.serial_port, self.config.baud_rate)
+        let baud = detected.ok_or_else(|| {
+            anyhow::anyhow!("No valid NMEA sentences at any of {:?} baud", self.config.auto_baud_rates)
+        })?;
+
+        info!("GPS baud rate detected: {}", baud);
+        if let Ok(mut stats) = write_stats(&self.stats) {
+            stats.gps.detected_baud_rate = Some(baud);
+        }
+
+        Ok(baud)
+    }
+
+    /// Ouvre le port série et applique le contrôle de flux et l'état des lignes RTS/DTR
+    fn open_port(&self, baud_rate: u32) -> serialport::Result<Box<dyn serialport::SerialPort>> {
+        let settings = serial_line_settings(&self.config);
+        let mut port = serialport::new(&self.config.serial_port, baud_rate)
             .timeout(Duration::from_millis(100))
+            .flow_control(settings.flow_control)
             .open()?;
 
-        // Configuration des lignes de contrôle
-        port.write_request_to_send(true)?;
-        port.write_data_terminal_ready(true)?;
+        if let Some(rts) = settings.rts {
+            port.write_request_to_send(rts)?;
+        }
+        if let Some(dtr) = settings.dtr {
+            port.write_data_terminal_ready(dtr)?;
+        }
         port.clear(serialport::ClearBuffer::All)?;
 
+        Ok(port)
+    }
+
+    /// Boucle principale de lecture GPS
+    fn run_reader(&self, generation: u64) -> anyhow::Result<()> {
+        let baud_rate = if self.config.auto_baud {
+            self.detect_baud_rate()?
+        } else {
+            self.config.baud_rate
+        };
+
+        info!("Opening GPS serial port: {}", self.config.serial_port);
+        self.progress.touch();
+
+        let mut port = self.open_port(baud_rate)?;
+
         info!("GPS serial port opened successfully");
 
-        // Marquer GPS comme connecté dans les stats
-        if let Ok(mut stats) = self.stats.write() {
-            stats.gps.connected = true;
+        // Un port ouvert ne prouve pas qu'un récepteur GPS y est branché
+        let validation_timeout = Duration::from_secs(self.config.nmea_validation_timeout_secs);
+        let on_read = || {
+            self.progress.touch();
+            heartbeat::GPS_READER.beat();
+        };
+        if !validation_timeout.is_zero() && !wait_for_nmea(&mut port, validation_timeout, on_read)? {
+            anyhow::bail!(
+                "opened {} but no NMEA detected within {:?} — wrong device?",
+                self.config.serial_port,
+                validation_timeout
+            );
         }
 
+        // Marquer GPS comme connecté dans les stats
+        self.update_state(|machine, now| machine.on_connected(now));
+
         // État de lecture
         let mut buffer = String::new();
         let mut read_buf = [0u8; 512];
@@ -126,38 +850,65 @@ impl GpsReader {
         // Pour la correction PPS : stocker le dernier timestamp GPS reçu
         let mut last_gps_timestamp: Option<NtpTimestamp> = None;
 
+        // Heure UTC du prochain pulse annoncée par la trame de timing, et instant de réception
+        let mut pps_time_tag: Option<(NtpTimestamp, Instant)> = None;
+
+        // Journal optionnel des pulses PPS (instants comptés depuis le démarrage du lecteur)
+        let mut pps_logger = match self.config.pps_log_file {
+            Some(ref path) if self.config.pps_enabled => {
+                match PpsLogger::open(std::path::Path::new(path), self.start_time) {
+                    Ok(logger) => Some(logger),
+                    Err(e) => {
+                        warn!("Failed to open PPS log file {}: {}", path, e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
         // Pour le skyplot : stocker les satellites en vue
         let mut satellites_in_view: Vec<SatelliteInfo> = Vec::new();
         let mut last_satellite_update = Instant::now();
 
+        // Mises à jour de last_rx_ms regroupées (verrou des stats partagé avec le web)
+        let mut rx_stats_throttle = UpdateThrottle::new(Duration::from_millis(self.config.rx_stats_interval_ms));
+
         // Boucle de lecture
-        while self.running.load(std::sync::atomic::Ordering::Relaxed) {
+        while self.is_current(generation) {
+            heartbeat::GPS_READER.beat();
+
             // Lecture des données NMEA
-            match port.read(&mut read_buf) {
+            match read_or_pause(&mut port, &mut read_buf) {
                 Ok(n) if n > 0 => {
                     last_rx = Instant::now();
+                    self.progress.touch();
                     let s = String::from_utf8_lossy(&read_buf[..n]);
                     buffer.push_str(&s);
 
-                    // Mettre à jour last_rx_ms dans les stats
-                    if let Ok(mut stats) = self.stats.write() {
-                        stats.gps.last_rx_ms = 0; // Donnée juste reçue
+                    // Mettre à jour last_rx_ms dans les stats (premier bloc d'une rafale NMEA)
+                    if rx_stats_throttle.ready(last_rx) {
+                        if let Ok(mut stats) = write_stats(&self.stats) {
+                            stats.gps.last_rx_ms = 0; // Donnée juste reçue
+                        }
                     }
 
                     // Traitement ligne par ligne
-                    while let Some(pos) = buffer.find('\n') {
-                        let line = buffer.drain(..=pos).collect::<String>();
+                    while let Some(line) = next_nmea_line(&mut buffer) {
                         let trimmed = line.trim();
+                        if !sentence_length_ok(trimmed, self.config.max_sentence_length) {
+                            continue;
+                        }
 
-                        // Log toutes les trames pour debug (seulement les premières 80 chars)
-                        if trimmed.len() > 0 {
-                            let preview = if trimmed.len() > 80 { &trimmed[..80] } else { trimmed };
-                            debug!("NMEA: {}", preview);
+                        // Log toutes les trames pour debug (seulement les premiers caractères)
+                        if !trimmed.is_empty() {
+                            debug!("NMEA: {}", sentence_preview(trimmed));
                         }
 
                         // Parser les satellites (GPGSV)
                         if let Some(sats) = self.parse_gpgsv(trimmed) {
                             debug!("GPGSV parsed: {} satellites in this sentence", sats.len());
+                            self.record_constellations(&sats);
 
                             // Mettre à jour ou ajouter les satellites
                             for sat in sats {
@@ -172,13 +923,20 @@ impl GpsReader {
                             // Mettre à jour les stats toutes les 2 secondes (éviter trop de writes)
                             if last_satellite_update.elapsed() > Duration::from_secs(2) {
                                 debug!("Updating satellite stats: {} satellites total", satellites_in_view.len());
-                                if let Ok(mut stats) = self.stats.write() {
+                                if let Ok(mut stats) = write_stats(&self.stats) {
                                     stats.satellites = satellites_in_view.clone();
                                 }
                                 last_satellite_update = Instant::now();
                             }
                         }
 
+                        // Trame de timing datant le prochain pulse PPS
+                        if self.config.pps_time_sentence != "none" && self.drives_time(trimmed) {
+                            if let Some(tag) = parse_zda(trimmed) {
+                                pps_time_tag = Some((tag, Instant::now()));
+                            }
+                        }
+
                         // Parser le temps GPS (GPRMC)
                         if let Some(timestamp) = self.process_nmea_sentence(trimmed) {
                             nmea_count += 1;
@@ -186,20 +944,22 @@ impl GpsReader {
                             last_gps_timestamp = Some(timestamp);
 
                             // Mettre à jour les stats
-                            if let Ok(mut stats) = self.stats.write() {
+                            if let Ok(mut stats) = write_stats(&self.stats) {
                                 stats.gps.nmea_sentences = nmea_count;
                                 stats.gps.last_sync_secs = Some(self.start_time.elapsed().as_secs());
                             }
                         }
                     }
+                    discard_oversized_partial(&mut buffer, self.config.max_sentence_length);
                 }
                 Ok(_) => {
-                    // Pas de données, continuer
+                    // Pas de données (pause déjà faite par read_or_pause), continuer
                 }
-                Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {
-                    // Timeout normal, continuer
+                Err(ref e) if is_transient_read_error(e) => {
+                    // Timeout normal ou interruption par un signal, continuer
                 }
                 Err(e) => {
+                    // Erreur d'E/S réelle (périphérique retiré...) : reconnexion
                     return Err(e.into());
                 }
             }
@@ -215,26 +975,35 @@ impl GpsReader {
                             let interval = now.duration_since(last_pps_pulse);
                             last_pps_pulse = now;
                             pps_count += 1;
+                            self.progress.touch();
 
                             // Vérifier que l'intervalle est proche de 1 seconde
                             let interval_secs = interval.as_secs_f64();
+                            let mut measurement = None;
                             if (0.95..=1.05).contains(&interval_secs) {
                                 debug!(
                                     "PPS pulse detected (#{}) - interval: {:.6}s",
                                     pps_count, interval_secs
                                 );
 
-                                // Si on a un timestamp GPS précédent, calculer l'offset PPS
-                                // Le PPS actuel correspond au timestamp GPS + 1 seconde
-                                if let Some(prev_gps_ts) = last_gps_timestamp {
-                                    // Le PPS correspond au début de la seconde suivante
-                                    let gps_second_boundary = NtpTimestamp::from_seconds_and_nanos(
-                                        prev_gps_ts.seconds() as u64 + 1,
-                                        0,
-                                    );
+                                // Seconde GPS marquée par ce pulse : annoncée par la trame de
+                                // timing, sinon seconde suivant le dernier timestamp GPS
+                                let boundary = if self.config.pps_time_sentence == "none" {
+                                    last_gps_timestamp.map(|prev_gps_ts| {
+                                        NtpTimestamp::from_seconds_and_nanos(prev_gps_ts.seconds() as u64 + 1, 0)
+                                    })
+                                } else {
+                                    let announces_pulse = self.config.pps_time_sentence == "zda_ahead";
+                                    let tagged = tagged_pps_boundary(pps_time_tag.take(), now, announces_pulse);
+                                    if tagged.is_none() {
+                                        debug!("PPS pulse #{} without a recent {} time tag", pps_count, self.config.pps_time_sentence);
+                                    }
+                                    tagged
+                                };
 
+                                if let Some(gps_second_boundary) = boundary {
                                     // Mettre à jour l'offset PPS dans l'horloge
-                                    self.clock.update_pps_offset(now, gps_second_boundary);
+                                    measurement = self.clock.update_pps_offset(now, gps_second_boundary);
 
                                     debug!(
                                         "PPS offset updated for GPS second {}",
@@ -242,11 +1011,11 @@ impl GpsReader {
                                     );
 
                                     // Mettre à jour les stats PPS
-                                    if let Ok(mut stats) = self.stats.write() {
+                                    if let Ok(mut stats) = write_stats(&self.stats) {
                                         stats.gps.pps_count = pps_count;
-                                        stats.gps.pps_active = true;
                                         stats.gps.pps_offset = self.clock.get_pps_offset();
                                     }
+                                    self.update_state(|machine, now| machine.on_pps(now));
                                 }
                             } else if pps_count > 1 {
                                 // Premier pulse peut avoir un intervalle bizarre
@@ -257,9 +1026,16 @@ impl GpsReader {
                             }
 
                             // Mettre à jour le compte PPS même si l'intervalle est bizarre
-                            if let Ok(mut stats) = self.stats.write() {
+                            if let Ok(mut stats) = write_stats(&self.stats) {
                                 stats.gps.pps_count = pps_count;
                             }
+
+                            if let Some(ref mut logger) = pps_logger {
+                                if let Err(e) = logger.record(now, interval_secs, measurement.as_ref()) {
+                                    warn!("Failed to write PPS log, disabling it: {}", e);
+                                    pps_logger = None;
+                                }
+                            }
                         }
                     }
                     Ok(_) => {
@@ -271,10 +1047,16 @@ impl GpsReader {
                 }
             }
 
+            // Transitions liées au temps (perte du PPS, holdover)
+            self.update_state(|machine, now| machine.tick(now));
+
             // Mettre à jour last_rx_ms périodiquement
-            let rx_elapsed_ms = last_rx.elapsed().as_millis() as u64;
-            if let Ok(mut stats) = self.stats.write() {
-                stats.gps.last_rx_ms = rx_elapsed_ms;
+            let now = Instant::now();
+            if rx_stats_throttle.ready(now) {
+                let rx_elapsed_ms = now.saturating_duration_since(last_rx).as_millis() as u64;
+                if let Ok(mut stats) = write_stats(&self.stats) {
+                    stats.gps.last_rx_ms = rx_elapsed_ms;
+                }
             }
 
             // Log des stats périodiquement
@@ -288,205 +1070,567 @@ impl GpsReader {
         }
 
         // Marquer GPS comme déconnecté à la sortie
-        if let Ok(mut stats) = self.stats.write() {
-            stats.gps.connected = false;
-            stats.gps.pps_active = false;
+        // (sauf si le watchdog a déjà relancé un nouveau lecteur)
+        if self.generation.load(Ordering::SeqCst) == generation {
+            self.update_state(|machine, now| machine.on_disconnected(now));
         }
 
         Ok(())
     }
 
+    /// Vrai si le talker de la trame date l'horloge (`gps.time_source_talker`)
+    fn drives_time(&self, sentence: &str) -> bool {
+        let Some(talker) = sentence.strip_prefix('$').and_then(|body| body.get(..2)) else {
+            return false;
+        };
+        match self.config.time_source_talker.as_str() {
+            "auto" => talker == "GP" || talker == "GN",
+            pinned => talker == pinned,
+        }
+    }
+
     /// Traite une trame NMEA et met à jour l'horloge si valide
     /// Retourne le timestamp GPS si la trame a été traitée avec succès
-    fn process_nmea_sentence(&self, sentence: &str) -> Option<NtpTimestamp> {
+    pub(crate) fn process_nmea_sentence(&self, sentence: &str) -> Option<NtpTimestamp> {
         // On traite principalement GPRMC qui contient date + heure + statut
-        if sentence.starts_with("$GPRMC") || sentence.starts_with("$GNRMC") {
-            if let Some((timestamp, satellites)) = self.parse_gprmc(sentence) {
-                // Mettre à jour l'horloge GPS
+        if sentence.get(3..6) == Some("RMC") && self.drives_time(sentence) {
+            if let Some(timestamp) = parse_gprmc(sentence) {
+                let timestamp = self.apply_week_date(timestamp);
+
+                // Mettre à jour l'horloge GPS (satellites : dernière trame GGA)
+                let satellites = self.gga_satellites();
                 self.clock.update_gps_time(timestamp, satellites);
+                self.last_rmc_seconds.store(timestamp.seconds() as u64, Ordering::Relaxed);
 
                 debug!(
-                    "GPS time synchronized: {} seconds since NTP epoch, {} satellites",
+                    "GPS time synchronized: {} seconds since NTP epoch, {:?} satellites",
                     timestamp.seconds(),
                     satellites
                 );
 
-                // Mettre à jour les stats satellites
-                if let Ok(mut stats) = self.stats.write() {
-                    stats.gps.satellites = satellites;
-                    // Signal quality basé sur le nombre de satellites (0-10)
-                    stats.gps.signal_quality = (satellites.min(10)) as u8;
-                }
+                return Some(timestamp);
+            }
+        }
 
+        // GLL : heure sans date, datée par la dernière trame RMC (source de synchro additionnelle)
+        if sentence.get(3..6) == Some("GLL") && self.drives_time(sentence) {
+            if let Some(timestamp) = self.parse_gpgll(sentence) {
+                self.clock.update_gps_time(timestamp, self.gga_satellites());
+                debug!("GPS time synchronized from GLL: {} seconds since NTP epoch", timestamp.seconds());
                 return Some(timestamp);
             }
         }
 
+        // VTG : cap et vitesse sur le fond (une installation fixe doit rester à ~0 km/h)
+        if sentence.starts_with("$GPVTG") || sentence.starts_with("$GNVTG") {
+            if let Some(velocity) = parse_vtg(sentence) {
+                if let Ok(mut stats) = write_stats(&self.stats) {
+                    stats.gps.speed_kmh = Some(velocity.speed_kmh);
+                    stats.gps.course_deg = velocity.course_deg;
+                }
+            }
+        }
+
         // On peut aussi traiter GPGGA pour plus d'infos sur les satellites
         if sentence.starts_with("$GPGGA") || sentence.starts_with("$GNGGA") {
-            if let Some(sat_count) = self.parse_gpgga_satellites(sentence) {
-                debug!("GPS satellites in view: {}", sat_count);
-
-                // Mettre à jour les stats avec le vrai compte de satellites
-                if let Ok(mut stats) = self.stats.write() {
-                    stats.gps.satellites = sat_count;
-                    stats.gps.signal_quality = (sat_count.min(10)) as u8;
+            match parse_gpgga(sentence) {
+                Some(GgaFix::Fix { quality, satellites, hdop }) => {
+                    debug!("GPS satellites in view: {} (fix quality {}, HDOP {:?})", satellites, quality, hdop);
+                    self.clock.set_fix_quality(quality);
+                    self.clock.set_hdop(hdop);
+                    self.gga_satellites.store(satellites, Ordering::Relaxed);
+
+                    // Mettre à jour les stats avec le vrai compte de satellites
+                    let position = parse_gga_position(sentence);
+                    if let Ok(mut stats) = write_stats(&self.stats) {
+                        stats.gps.satellites_used = satellites;
+                        stats.gps.signal_quality = satellites.min(10);
+                        if let Some((latitude, longitude)) = position {
+                            stats.gps.latitude_deg = Some(latitude);
+                            stats.gps.longitude_deg = Some(longitude);
+                        }
+                    }
+                    self.update_state(|machine, now| machine.on_gga(quality, satellites, now));
                 }
+                Some(GgaFix::Acquiring { satellites }) => {
+                    debug!("GPS acquiring fix ({} satellites tracked)", satellites);
+                    self.clock.set_fix_quality(0);
+                    self.gga_satellites.store(0, Ordering::Relaxed);
+
+                    // Pas de fix : en acquisition, et non "0 satellite synchronisé"
+                    if let Ok(mut stats) = write_stats(&self.stats) {
+                        stats.gps.satellites_used = satellites;
+                        stats.gps.signal_quality = 0;
+                    }
+                    self.update_state(|machine, now| machine.on_gga(0, satellites, now));
+                }
+                None => {}
             }
         }
 
-        None
-    }
+        // GSV : satellites en vue par constellation (en-tête, même sans satellite listé)
+        if let Some((talker, in_view)) = parse_gsv_in_view(sentence) {
+            self.update_satellites_in_view(talker, in_view);
+        }
 
-    /// Parse une trame GPRMC et extrait le timestamp NTP
-    fn parse_gprmc(&self, sentence: &str) -> Option<(NtpTimestamp, u8)> {
-        let fields: Vec<&str> = sentence.split(',').collect();
+        // u-blox $PUBX,00 : précision estimée par le module
+        if sentence.starts_with("$PUBX,00") {
+            if let Some(accuracy_ns) = parse_pubx_time_accuracy_ns(sentence) {
+                debug!("GPS time accuracy estimate: {} ns", accuracy_ns);
+                self.clock.set_time_accuracy_ns(accuracy_ns);
+                if let Ok(mut stats) = write_stats(&self.stats) {
+                    stats.gps.time_accuracy_ns = Some(accuracy_ns);
+                }
+            }
+        }
 
-        // Vérifier format minimal GPRMC
-        if fields.len() < 10 {
-            return None;
+        // u-blox $PUBX,04 : date non ambiguë (semaine GPS complète)
+        if sentence.starts_with("$PUBX,04") {
+            if let Some(week_time) = parse_pubx_week_time(sentence) {
+                if let Ok(mut week_date) = self.week_date.lock() {
+                    *week_date = Some((week_time, Instant::now()));
+                }
+            }
         }
 
-        // Champ 2 : Statut (A = valide, V = invalide)
-        if fields[2] != "A" {
-            debug!("GPS fix not valid (status: {})", fields[2]);
-            return None;
+        // GSA : type de fix (2D/3D)
+        if sentence.starts_with("$GPGSA") || sentence.starts_with("$GNGSA") {
+            if let Some(fix_type) = parse_gsa_fix_type(sentence) {
+                self.update_state(|machine, now| machine.on_gsa(fix_type, now));
+            }
         }
 
-        // Champ 1 : Heure UTC (hhmmss.sss)
-        let time_str = fields[1];
-        if time_str.len() < 6 {
-            return None;
+        None
+    }
+
+    /// Recale la date d'une trame RMC sur la date semaine GPS récente, si disponible
+    ///
+    /// La date semaine GPS est extrapolée jusqu'à la trame RMC ; un désaccord
+    /// d'au moins un jour est journalisé (une fois tant qu'il persiste) et la
+    /// date semaine GPS l'emporte.
+    fn apply_week_date(&self, timestamp: NtpTimestamp) -> NtpTimestamp {
+        let reference = self.week_date.lock().ok().and_then(|week_date| *week_date);
+        let Some((week_time, received_at)) = reference else {
+            return timestamp;
+        };
+        let age = received_at.elapsed();
+        if age > WEEK_DATE_MAX_AGE {
+            return timestamp;
         }
 
-        // Champ 9 : Date (ddmmyy)
-        let date_str = fields[9];
-        if date_str.len() != 6 {
-            return None;
+        let expected = week_time.seconds() as u64 + age.as_secs_f64().round() as u64;
+        let rmc_seconds = timestamp.seconds() as u64;
+        let days = week_date_correction(rmc_seconds, expected);
+        if days == 0 {
+            self.week_date_mismatch_warned.store(false, Ordering::Relaxed);
+            return timestamp;
         }
 
-        // Parser avec chrono pour validation
-        let datetime_str = format!(
-            "20{}-{}-{} {}:{}:{}",
-            &date_str[4..6], // année
-            &date_str[2..4], // mois
-            &date_str[0..2], // jour
-            &time_str[0..2], // heure
-            &time_str[2..4], // minute
-            &time_str[4..6]  // seconde
-        );
+        let Some(corrected) = rmc_seconds.checked_add_signed(days * SECONDS_PER_DAY as i64) else {
+            return timestamp;
+        };
+        if !self.week_date_mismatch_warned.swap(true, Ordering::Relaxed) {
+            warn!(
+                "RMC date disagrees with GPS week date by {} day(s), using GPS week date ({} -> {} NTP seconds)",
+                days, rmc_seconds, corrected
+            );
+        }
+        NtpTimestamp((corrected << 32) | timestamp.fraction() as u64)
+    }
 
-        let parsed = NaiveDateTime::parse_from_str(&datetime_str, "%Y-%m-%d %H:%M:%S").ok()?;
+    /// Satellites utilisés selon la dernière trame GGA, `None` si le récepteur
+    /// n'en a émis aucune (critère `min_satellites` alors non vérifiable)
+    ///
+    /// Avec des filtres de constellations, borné par le nombre de satellites
+    /// suivis des constellations retenues (0 si une constellation requise manque)
+    fn gga_satellites(&self) -> Option<u8> {
+        let count = match self.gga_satellites.load(Ordering::Relaxed) {
+            SATELLITES_UNKNOWN => return None,
+            count => count,
+        };
 
-        // Convertir en timestamp NTP (secondes depuis 1900-01-01)
-        let unix_timestamp = parsed.and_utc().timestamp() as u64;
-        let ntp_timestamp_secs = unix_timestamp + 2_208_988_800; // NTP epoch offset
+        if !self.constellation_filters_active() {
+            return Some(count);
+        }
+        let tracker = self.constellations.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let allowed = tracker.effective_count(&self.config.require_constellations, &self.config.exclude_constellations);
+        Some(count.min(allowed))
+    }
 
-        // Extraire les fractions de seconde si présentes
-        let subsec_nanos = if time_str.len() > 7 && time_str.chars().nth(6) == Some('.') {
-            let frac_str = &time_str[7..];
-            let frac_value: u32 = frac_str.parse().unwrap_or(0);
-            // Convertir en nanosecondes (assuming 3 digits = milliseconds)
-            frac_value * 1_000_000
-        } else {
-            0
+    /// Met à jour le nombre de satellites en vue d'une constellation et leur
+    /// total dans les statistiques (écrit seulement s'il change)
+    fn update_satellites_in_view(&self, talker: &str, in_view: u8) {
+        let total = {
+            let mut by_talker = self.gsv_in_view.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if by_talker.insert(talker.to_string(), in_view) == Some(in_view) {
+                return;
+            }
+            by_talker.values().map(|&count| count as u32).sum::<u32>().min(u8::MAX as u32) as u8
         };
 
-        let ntp_timestamp = NtpTimestamp::from_seconds_and_nanos(ntp_timestamp_secs, subsec_nanos);
+        if let Ok(mut stats) = write_stats(&self.stats) {
+            stats.gps.satellites_in_view = total;
+        }
+    }
+
+    fn constellation_filters_active(&self) -> bool {
+        !self.config.require_constellations.is_empty() || !self.config.exclude_constellations.is_empty()
+    }
+
+    /// Enregistre les satellites d'une trame GSV et signale une fois les
+    /// constellations requises jamais vues après le délai de démarrage
+    fn record_constellations(&self, satellites: &[SatelliteInfo]) {
+        if !self.constellation_filters_active() {
+            return;
+        }
 
-        // Estimer le nombre de satellites (GPRMC ne le donne pas directement)
-        // On utilise une valeur par défaut, GPGGA nous donnera la vraie valeur
-        let satellites = self.config.min_satellites;
+        let now = Instant::now();
+        let mut tracker = self.constellations.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        tracker.record(satellites, now);
 
-        Some((ntp_timestamp, satellites))
+        if self.start_time.elapsed() < MISSING_CONSTELLATION_WARN_DELAY
+            || self.missing_constellation_warned.load(Ordering::Relaxed)
+        {
+            return;
+        }
+        let missing = tracker.missing(&self.config.require_constellations);
+        if !missing.is_empty() {
+            warn!(
+                "Required GNSS constellation(s) not tracked after {:?}: {} (satellite count for sync is 0)",
+                MISSING_CONSTELLATION_WARN_DELAY,
+                missing.join(", ")
+            );
+            self.missing_constellation_warned.store(true, Ordering::Relaxed);
+        }
     }
 
-    /// Parse une trame GPGGA pour extraire le nombre de satellites
-    fn parse_gpgga_satellites(&self, sentence: &str) -> Option<u8> {
-        let fields: Vec<&str> = sentence.split(',').collect();
+    /// Applique un événement à la machine d'états GPS, journalise la transition
+    /// éventuelle et reporte le nouvel état dans les statistiques
+    fn update_state<F>(&self, event: F)
+    where
+        F: FnOnce(&mut GpsStateMachine, Instant) -> Option<(GpsState, GpsState)>,
+    {
+        let Ok(mut machine) = self.state.lock() else {
+            return;
+        };
+
+        if let Some((from, to)) = event(&mut machine, Instant::now()) {
+            info!("GPS state: {:?} -> {:?}", from, to);
+            if let Ok(mut stats) = write_stats(&self.stats) {
+                apply_to_stats(to, &mut stats.gps);
+            }
+        }
+    }
 
-        if fields.len() < 8 {
+    /// Parse une trame GPGLL et la date avec la dernière trame RMC reçue
+    /// (`None` tant qu'aucune trame RMC valide n'a fourni de date)
+    fn parse_gpgll(&self, sentence: &str) -> Option<NtpTimestamp> {
+        let reference = self.last_rmc_seconds.load(Ordering::Relaxed);
+        if reference == 0 {
             return None;
         }
 
-        // Champ 7 : Nombre de satellites
-        fields[7].parse().ok()
+        let (seconds_of_day, nanos) = parse_gll_time(sentence)?;
+        Some(NtpTimestamp::from_seconds_and_nanos(
+            anchor_time_of_day(reference, seconds_of_day),
+            nanos,
+        ))
     }
 
-    /// Parse une trame GPGSV (GPS Satellites in View) pour extraire positions satellites
-    /// Format: $GPGSV,total_msgs,msg_num,total_sats,sat1_prn,sat1_elev,sat1_az,sat1_snr,...*checksum
+    /// Parse une trame GSV et compte les entrées de satellites malformées dans les statistiques
     fn parse_gpgsv(&self, sentence: &str) -> Option<Vec<SatelliteInfo>> {
-        // Vérifier que c'est bien une trame GSV
-        if !sentence.starts_with("$GPGSV") && !sentence.starts_with("$GLGSV")
-            && !sentence.starts_with("$GAGSV") && !sentence.starts_with("$GBGSV")
-            && !sentence.starts_with("$GNGSV") {
-            return None;
-        }
+        let (satellites, malformed) = parse_gsv(sentence)?;
 
-        debug!("Parsing GPGSV sentence: {}", sentence);
+        if malformed > 0 {
+            if let Ok(mut stats) = write_stats(&self.stats) {
+                stats.gps.malformed_satellites += malformed;
+            }
+        }
 
-        // Déterminer la constellation
-        let constellation = if sentence.starts_with("$GPGSV") {
-            "GPS"
-        } else if sentence.starts_with("$GLGSV") {
-            "GLONASS"
-        } else if sentence.starts_with("$GAGSV") {
-            "Galileo"
-        } else if sentence.starts_with("$GBGSV") {
-            "BeiDou"
+        if satellites.is_empty() {
+            None
         } else {
-            "GNSS" // Multi-constellation
-        };
+            Some(satellites)
+        }
+    }
+}
 
-        let fields: Vec<&str> = sentence.split(',').collect();
+/// Parse une trame GPRMC et extrait le timestamp NTP
+pub(crate) fn parse_gprmc(sentence: &str) -> Option<NtpTimestamp> {
+    let fields: Vec<&str> = sentence.split(',').collect();
 
-        // Minimum 4 champs (header + 3 champs info générale)
-        if fields.len() < 4 {
-            return None;
-        }
+    // Vérifier format minimal GPRMC
+    if fields.len() < 10 {
+        return None;
+    }
 
-        let mut satellites = Vec::new();
+    // Champ 2 : Statut (A = valide, V = invalide)
+    if fields[2] != "A" {
+        debug!("GPS fix not valid (status: {})", fields[2]);
+        return None;
+    }
 
-        // Parser jusqu'à 4 satellites par trame (champs 4-7, 8-11, 12-15, 16-19)
-        for i in 0..4 {
-            let base_idx = 4 + (i * 4);
+    // Champ 1 : Heure UTC (hhmmss.sss)
+    let time_str = fields[1];
+    if time_str.len() < 6 {
+        return None;
+    }
 
-            // Vérifier qu'on a assez de champs
-            if base_idx + 3 >= fields.len() {
-                break;
-            }
+    // Champ 9 : Date (ddmmyy)
+    let date_str = fields[9];
+    if date_str.len() != 6 {
+        return None;
+    }
 
-            // PRN du satellite
-            let prn: u8 = match fields[base_idx].parse() {
-                Ok(p) if p > 0 => p,
-                _ => continue, // Pas de satellite dans ce slot
-            };
+    // Parser avec chrono pour validation
+    let datetime_str = format!(
+        "20{}-{}-{} {}:{}:{}",
+        &date_str[4..6], // année
+        &date_str[2..4], // mois
+        &date_str[0..2], // jour
+        &time_str[0..2], // heure
+        &time_str[2..4], // minute
+        &time_str[4..6]  // seconde
+    );
 
-            // Élévation (0-90)
-            let elevation: u8 = fields[base_idx + 1].parse().unwrap_or(0);
+    let parsed = NaiveDateTime::parse_from_str(&datetime_str, "%Y-%m-%d %H:%M:%S").ok()?;
 
-            // Azimut (0-359)
-            let azimuth: u16 = fields[base_idx + 2].parse().unwrap_or(0);
+    // Convertir en timestamp NTP (secondes depuis 1900-01-01)
+    let unix_timestamp = parsed.and_utc().timestamp() as u64;
+    let ntp_timestamp_secs = unix_timestamp + NTP_UNIX_OFFSET;
 
-            // SNR (peut être vide si pas de signal)
-            let snr_field = fields[base_idx + 3].split('*').next().unwrap_or("");
-            let snr: u8 = snr_field.parse().unwrap_or(0);
+    // Extraire les fractions de seconde si présentes (hhmmss.s à hhmmss.sssssssss)
+    let subsec_nanos = parse_subsec_nanos(time_str);
 
-            satellites.push(SatelliteInfo {
-                prn,
-                elevation,
-                azimuth,
-                snr,
-                constellation: constellation.to_string(),
-            });
+    Some(NtpTimestamp::from_seconds_and_nanos(ntp_timestamp_secs, subsec_nanos))
+}
+
+/// Parse une trame ZDA (`$--ZDA,hhmmss.ss,dd,mm,yyyy,zh,zm*hh`) : date et heure UTC
+pub(crate) fn parse_zda(sentence: &str) -> Option<NtpTimestamp> {
+    if sentence.get(3..6) != Some("ZDA") || !sentence.starts_with('$') {
+        return None;
+    }
+
+    let fields: Vec<&str> = sentence.split(',').collect();
+    if fields.len() < 5 {
+        return None;
+    }
+
+    // Bruit série décodé avec pertes (U+FFFD) : pas de découpage hors des chiffres ASCII
+    let time_str = fields[1];
+    let digits = time_str.get(..6).filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))?;
+    let datetime_str = format!(
+        "{}-{}-{} {}:{}:{}",
+        fields[4],
+        fields[3],
+        fields[2],
+        &digits[0..2],
+        &digits[2..4],
+        &digits[4..6]
+    );
+    let parsed = NaiveDateTime::parse_from_str(&datetime_str, "%Y-%m-%d %H:%M:%S").ok()?;
+    let ntp_seconds = u64::try_from(parsed.and_utc().timestamp()).ok()? + NTP_UNIX_OFFSET;
+
+    Some(NtpTimestamp::from_seconds_and_nanos(ntp_seconds, parse_subsec_nanos(time_str)))
+}
+
+/// Seconde GPS marquée par un pulse reçu à `pulse`, d'après la dernière trame
+/// de timing (heure, instant de réception) : la seconde de la trame si elle
+/// annonce le pulse à venir (`announces_pulse`), sinon la suivante (trame datant
+/// le pulse précédent). `None` si la trame est absente, postérieure au pulse ou
+/// périmée
+pub(crate) fn tagged_pps_boundary(
+    tag: Option<(NtpTimestamp, Instant)>,
+    pulse: Instant,
+    announces_pulse: bool,
+) -> Option<NtpTimestamp> {
+    let (time, received_at) = tag?;
+    let age = pulse.checked_duration_since(received_at)?;
+    if age > PPS_TIME_TAG_MAX_AGE {
+        return None;
+    }
+
+    // Pulse au début d'une seconde entière : fraction éventuelle ignorée
+    let seconds = time.seconds() as u64 + u64::from(!announces_pulse);
+    Some(NtpTimestamp::from_seconds_and_nanos(seconds, 0))
+}
+
+/// Parse une trame GPGGA pour extraire la qualité du fix et le nombre de satellites
+///
+/// Pendant l'acquisition, le module émet des trames du type
+/// `$GPGGA,hhmmss,,,,,0,00,,,,,,,*..` : qualité 0 et satellites vides ou nuls.
+pub(crate) fn parse_gpgga(sentence: &str) -> Option<GgaFix> {
+    let fields: Vec<&str> = sentence.split(',').collect();
+
+    if fields.len() < 8 {
+        return None;
+    }
+
+    // Champ 6 : Qualité du fix (0 = invalide, 1 = GPS, 2 = DGPS...)
+    // Champ 7 : Nombre de satellites utilisés
+    let quality: u8 = match fields[6] {
+        "" => 0,
+        q => q.parse().ok()?,
+    };
+    let satellites: Option<u8> = match fields[7] {
+        "" => None,
+        n => match n.parse() {
+            Ok(count) => Some(count),
+            Err(_) => {
+                debug!("GGA sentence with malformed satellite count '{}': {}", n, sentence);
+                return None;
+            }
+        },
+    };
+
+    match (quality, satellites) {
+        (0, sats) => Some(GgaFix::Acquiring {
+            satellites: sats.unwrap_or(0),
+        }),
+        (quality, Some(satellites)) if satellites > 0 => {
+            // Champ 8 : HDOP (optionnel)
+            let hdop = fields.get(8).and_then(|field| field.parse().ok());
+            Some(GgaFix::Fix { quality, satellites, hdop })
         }
+        // Qualité annoncée sans satellite : trame incohérente
+        _ => None,
+    }
+}
 
-        if satellites.is_empty() {
-            None
-        } else {
-            Some(satellites)
+/// Extrait la position d'une trame GGA : `ddmm.mmmm,N,dddmm.mmmm,E` (champs 2 à 5)
+///
+/// Retourne (latitude, longitude) en degrés décimaux, négatifs au sud et à l'ouest.
+pub(crate) fn parse_gga_position(sentence: &str) -> Option<(f64, f64)> {
+    let data = sentence.split('*').next()?;
+    let fields: Vec<&str> = data.split(',').collect();
+    if fields.len() < 6 {
+        return None;
+    }
+
+    let latitude = nmea_coordinate(fields[2], fields[3], 'N', 'S', 90.0)?;
+    let longitude = nmea_coordinate(fields[4], fields[5], 'E', 'W', 180.0)?;
+    Some((latitude, longitude))
+}
+
+/// Convertit une coordonnée NMEA (degrés et minutes, `dddmm.mmmm`) en degrés décimaux
+fn nmea_coordinate(value: &str, hemisphere: &str, positive: char, negative: char, max: f64) -> Option<f64> {
+    let raw: f64 = value.parse().ok()?;
+    let degrees = (raw / 100.0).trunc();
+    let minutes = raw - degrees * 100.0;
+    if !(0.0..60.0).contains(&minutes) {
+        return None;
+    }
+
+    let decimal = degrees + minutes / 60.0;
+    if decimal > max {
+        return None;
+    }
+    match hemisphere.chars().next()? {
+        c if c == positive => Some(decimal),
+        c if c == negative => Some(-decimal),
+        _ => None,
+    }
+}
+
+/// Extrait d'une trame GSV son talker (`GP`, `GL`, `GA`...) et le nombre total
+/// de satellites en vue annoncé par l'en-tête (champ 3)
+pub(crate) fn parse_gsv_in_view(sentence: &str) -> Option<(&str, u8)> {
+    let talker = sentence.strip_prefix('$')?.get(..2)?;
+    if sentence.get(3..6) != Some("GSV") {
+        return None;
+    }
+
+    let field = sentence.split(',').nth(3)?;
+    let in_view = field.split('*').next()?.parse().ok()?;
+    Some((talker, in_view))
+}
+
+/// Parse une trame GPGSV (GPS Satellites in View) pour extraire positions satellites
+/// Format: $GPGSV,total_msgs,msg_num,total_sats,sat1_prn,sat1_elev,sat1_az,sat1_snr,...*checksum
+///
+/// Retourne les satellites lus et le nombre d'entrées malformées ignorées.
+pub(crate) fn parse_gsv(sentence: &str) -> Option<(Vec<SatelliteInfo>, u64)> {
+    // Vérifier que c'est bien une trame GSV
+    if !sentence.starts_with("$GPGSV") && !sentence.starts_with("$GLGSV")
+        && !sentence.starts_with("$GAGSV") && !sentence.starts_with("$GBGSV")
+        && !sentence.starts_with("$GNGSV") {
+        return None;
+    }
+
+    debug!("Parsing GPGSV sentence: {}", sentence);
+
+    // Déterminer la constellation
+    let constellation = if sentence.starts_with("$GPGSV") {
+        "GPS"
+    } else if sentence.starts_with("$GLGSV") {
+        "GLONASS"
+    } else if sentence.starts_with("$GAGSV") {
+        "Galileo"
+    } else if sentence.starts_with("$GBGSV") {
+        "BeiDou"
+    } else {
+        "GNSS" // Multi-constellation
+    };
+
+    let fields: Vec<&str> = sentence.split(',').collect();
+
+    // Minimum 4 champs (header + 3 champs info générale)
+    if fields.len() < 4 {
+        return None;
+    }
+
+    let mut satellites = Vec::new();
+    let mut malformed: u64 = 0;
+
+    // Parser jusqu'à 4 satellites par trame (champs 4-7, 8-11, 12-15, 16-19)
+    for i in 0..4 {
+        let base_idx = 4 + (i * 4);
+
+        // Vérifier qu'on a assez de champs
+        if base_idx + 3 >= fields.len() {
+            break;
         }
+
+        // PRN du satellite
+        let prn: u8 = match fields[base_idx].parse() {
+            Ok(p) if p > 0 => p,
+            _ => continue, // Pas de satellite dans ce slot
+        };
+
+        // Élévation (0-90), azimut (0-359), SNR (0-99, vide si pas de signal)
+        let snr_field = fields[base_idx + 3].split('*').next().unwrap_or("");
+        let values = (
+            parse_gsv_field(fields[base_idx + 1], 90),
+            parse_gsv_field(fields[base_idx + 2], 359),
+            parse_gsv_field(snr_field, 99),
+        );
+
+        // Valeur hors plage ou illisible : satellite ignoré
+        let (Some(elevation), Some(azimuth), Some(snr)) = values else {
+            debug!("Malformed GSV satellite entry for PRN {}: {:?}", prn, values);
+            malformed += 1;
+            continue;
+        };
+
+        satellites.push(SatelliteInfo {
+            prn,
+            elevation: elevation as u8,
+            azimuth,
+            snr: snr as u8,
+            constellation: constellation.to_string(),
+        });
+    }
+
+    Some((satellites, malformed))
+}
+
+/// Parse un champ numérique de satellite GSV : vide = 0, `None` si illisible ou > `max`
+fn parse_gsv_field(field: &str, max: u16) -> Option<u16> {
+    if field.is_empty() {
+        return Some(0);
     }
+
+    field.parse::<u16>().ok().filter(|value| *value <= max)
 }
 
 impl Drop for GpsReader {
@@ -498,55 +1642,632 @@ impl Drop for GpsReader {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    #[test]
-    fn test_parse_gprmc() {
-        use crate::stats::StatsManager;
-
-        let config = GpsConfig {
+    use crate::clock::{ClockSource, SystemClock};
+    use crate::leap_seconds::ntp_seconds_to_iso;
+    use crate::stats::StatsManager;
+
+    /// Configuration GPS de test
+    fn test_config() -> GpsConfig {
+        GpsConfig {
+            enabled: true,
             serial_port: "COM9".to_string(),
             baud_rate: 9600,
+            auto_baud: false,
+            auto_baud_rates: vec![9600, 4800],
             sync_timeout: 30,
-            min_satellites: 4,
+            sync_criteria: crate::config::SyncCriteria::default(),
             pps_enabled: true,
             pps_gpio_pin: None,
+            dgps_refid: "SBAS".to_string(),
+            watchdog_timeout_secs: 30,
+            pps_log_file: None,
+            rts: None,
+            dtr: None,
+            flow_control: "none".to_string(),
+            rx_stats_interval_ms: 250,
+            max_sentence_length: 256,
+            pps_offset_max_age_secs: 5,
+            nmea_only_precision: -3,
+            pps_offset_histogram: false,
+            nmea_validation_timeout_secs: 15,
+            require_constellations: Vec::new(),
+            exclude_constellations: Vec::new(),
+            pps_time_sentence: "none".to_string(),
+            time_source_talker: "auto".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_pubx_time_accuracy() {
+        // Exemple du manuel u-blox : hAcc 2.1 m, HDOP 0.92, TDOP 0.77 → ~5.9 ns
+        let sentence = "$PUBX,00,081350.00,4717.113210,N,00833.915187,E,546.589,G3,2.1,2.0,0.007,77.52,0.007,,0.92,1.19,0.77,9,0,0*5F";
+        assert_eq!(parse_pubx_time_accuracy_ns(sentence), Some(6));
+
+        // Sans fix ou autre message PUBX : pas d'estimation
+        let no_fix = "$PUBX,00,081350.00,0000.000000,N,00000.000000,E,0.000,NF,5303302,3750001,0.000,0.00,0.000,,99.99,99.99,99.99,0,0,0*20";
+        assert_eq!(parse_pubx_time_accuracy_ns(no_fix), None);
+        assert_eq!(parse_pubx_time_accuracy_ns("$PUBX,04,073731.00,091202,113851.00,1196,15D,1930035,-2660.664,43*71"), None);
+    }
+
+    #[test]
+    fn test_rx_stats_throttle() {
+        // Boucle de lecture tournant à 1 kHz (tempête de lectures vides) pendant 1 s
+        let start = Instant::now();
+        let mut throttle = UpdateThrottle::new(Duration::from_millis(250));
+        let writes: Vec<u64> = (0..1000u64)
+            .filter(|ms| throttle.ready(start + Duration::from_millis(*ms)))
+            .collect();
+
+        // 4 écritures au lieu de 1000, jamais plus de 250 ms sans rafraîchissement
+        assert_eq!(writes, vec![0, 250, 500, 750]);
+        assert!(throttle.ready(start + Duration::from_millis(1000)));
+    }
+
+    #[test]
+    fn test_serial_line_settings() {
+        // PPS via CTS : RTS/DTR hauts par défaut (comportement historique)
+        let settings = serial_line_settings(&test_config());
+        assert_eq!(settings.rts, Some(true));
+        assert_eq!(settings.dtr, Some(true));
+        assert_eq!(settings.flow_control, serialport::FlowControl::None);
+
+        // Sans PPS : lignes laissées telles quelles
+        let config = GpsConfig { pps_enabled: false, ..test_config() };
+        let settings = serial_line_settings(&config);
+        assert_eq!((settings.rts, settings.dtr), (None, None));
+
+        // Valeurs explicites prioritaires (module dont RTS est une ligne de reset)
+        let config = GpsConfig {
+            rts: Some(false),
+            dtr: Some(true),
+            pps_enabled: false,
+            flow_control: "hardware".to_string(),
+            ..test_config()
         };
+        let settings = serial_line_settings(&config);
+        assert_eq!((settings.rts, settings.dtr), (Some(false), Some(true)));
+        assert_eq!(settings.flow_control, serialport::FlowControl::Hardware);
+
+        let config = GpsConfig { flow_control: "software".to_string(), ..test_config() };
+        assert_eq!(serial_line_settings(&config).flow_control, serialport::FlowControl::Software);
+    }
 
+    /// Lecteur GPS de test (aucun port série n'est ouvert)
+    fn test_reader() -> GpsReader {
         let clock = Arc::new(GpsNmeaClock::new(30));
-        let stats_manager = StatsManager::new();
-        let reader = GpsReader::new(config, clock, stats_manager.clone_arc());
+        GpsReader::new(test_config(), clock, StatsManager::new().clone_arc())
+    }
+
+    #[test]
+    fn test_watchdog_progress_timeout() {
+        let watchdog = ProgressWatchdog::new();
+        let timeout = Duration::from_secs(30);
+
+        // Au repos : jamais bloqué
+        assert!(!watchdog.is_stalled(Instant::now() + Duration::from_secs(3600), timeout));
+
+        watchdog.touch();
+        let touched = Instant::now();
+        assert!(!watchdog.is_stalled(touched, timeout));
+        assert!(!watchdog.is_stalled(touched + Duration::from_secs(29), timeout));
+        assert!(watchdog.is_stalled(touched + Duration::from_secs(31), timeout));
+
+        // Une nouvelle progression réarme le watchdog
+        watchdog.touch();
+        assert!(!watchdog.is_stalled(Instant::now() + Duration::from_secs(29), timeout));
+
+        watchdog.set_idle();
+        assert!(!watchdog.is_stalled(Instant::now() + Duration::from_secs(3600), timeout));
+    }
+
+    #[test]
+    fn test_parse_gpgsv_out_of_range() {
+        let reader = test_reader();
+
+        // PRN 12 : élévation 95 ; PRN 15 : azimut 360 ; PRN 24 : valide
+        let sentence = "$GPGSV,1,1,03,12,95,120,38,15,45,360,40,24,30,200,*7A";
+        let sats = reader.parse_gpgsv(sentence).unwrap();
+
+        assert_eq!(sats.len(), 1);
+        assert_eq!(sats[0].prn, 24);
+        assert_eq!(sats[0].elevation, 30);
+        assert_eq!(sats[0].azimuth, 200);
+        assert_eq!(sats[0].snr, 0);
+        assert_eq!(reader.stats.read().unwrap().gps.malformed_satellites, 2);
+
+        // Uniquement des satellites invalides : aucune donnée
+        let sentence = "$GPGSV,1,1,01,07,45,120,150*7A";
+        assert!(reader.parse_gpgsv(sentence).is_none());
+        assert_eq!(reader.stats.read().unwrap().gps.malformed_satellites, 3);
+    }
+
+    #[test]
+    fn test_next_nmea_line_endings_and_bom() {
+        let rmc = "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A";
+
+        // Fins de ligne `\r` seul, `\n` seul et `\r\n`, BOM sur la première ligne
+        let mut buffer = format!("\u{FEFF}{rmc}\r{rmc}\n{rmc}\r\n$GPGGA,12");
+        for _ in 0..3 {
+            assert_eq!(next_nmea_line(&mut buffer).as_deref(), Some(rmc));
+        }
+
+        // Ligne incomplète conservée jusqu'à sa fin de ligne
+        assert_eq!(next_nmea_line(&mut buffer), None);
+        assert_eq!(buffer, "$GPGGA,12");
+
+        // `\r\n` coupé entre deux lectures : une ligne vide en plus
+        let mut buffer = format!("{rmc}\r");
+        assert_eq!(next_nmea_line(&mut buffer).as_deref(), Some(rmc));
+        buffer.push('\n');
+        assert_eq!(next_nmea_line(&mut buffer).as_deref(), Some(""));
+        assert!(buffer.is_empty());
+
+        assert_eq!(count_valid_sentences(format!("{rmc}\r{rmc}\r").as_bytes()), 2);
+    }
+
+    #[test]
+    fn test_long_sentences() {
+        // GSV multi-constellation non standard : 200 caractères et plus, conservée
+        let mut body = "GNGSV,1,1,15".to_string();
+        for prn in 1..=15 {
+            body.push_str(&format!(",{:02},{:02},{:03},{:02}", prn, 20 + prn, 10 * prn, 30 + prn));
+        }
+        let checksum = body.bytes().fold(0u8, |acc, b| acc ^ b);
+        let gsv = format!("${}*{:02X}", body, checksum);
+        assert!(gsv.len() >= 200 && gsv.len() <= 256, "{}", gsv.len());
+        assert!(nmea_checksum_valid(&gsv));
+        assert!(sentence_length_ok(&gsv, 256));
+        assert_eq!(parse_gsv(&gsv).unwrap().0.len(), 4);
+
+        // 5000 octets sans délimiteur : écartée, puis tampon vidé
+        let garbage = format!("$GPRMC,{}", "é".repeat(2_500));
+        assert!(!sentence_length_ok(&garbage, 256));
+        assert_eq!(sentence_preview(&garbage).chars().count(), NMEA_PREVIEW_CHARS);
+
+        let mut buffer = garbage.clone();
+        discard_oversized_partial(&mut buffer, 256);
+        assert!(buffer.is_empty());
+        let mut buffer = gsv[..100].to_string();
+        discard_oversized_partial(&mut buffer, 256);
+        assert_eq!(buffer.len(), 100);
+    }
+
+    #[test]
+    fn test_nmea_checksum() {
+        assert!(nmea_checksum_valid(
+            "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A\r"
+        ));
+        assert!(!nmea_checksum_valid(
+            "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6B"
+        ));
+        assert!(!nmea_checksum_valid("$GPRMC,123519,A"));
+        assert!(!nmea_checksum_valid("\u{fffd}\u{fffd}x*00"));
+    }
+
+    #[test]
+    fn test_select_baud_rate() {
+        let valid = "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A\r\n\
+                     $GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n";
+        let mut probed = Vec::new();
+
+        // 9600 : bruit (mauvaise vitesse), 4800 : erreur, 38400 : NMEA valide
+        let selected = select_baud_rate(&[9600, 4800, 38400, 115200], |baud| {
+            probed.push(baud);
+            match baud {
+                9600 => Ok(vec![0xF0, 0x0F, 0xAA, b'$', 0x55, b'\n']),
+                4800 => Err(std::io::Error::other("port busy")),
+                38400 => Ok(valid.as_bytes().to_vec()),
+                _ => Ok(valid.as_bytes().to_vec()),
+            }
+        });
+
+        assert_eq!(selected, Some(38400));
+        // On s'arrête à la première vitesse valide
+        assert_eq!(probed, vec![9600, 4800, 38400]);
+
+        // Une seule trame valide ne suffit pas ; aucune vitesse retenue
+        let single = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n";
+        assert_eq!(
+            select_baud_rate(&[9600, 4800], |_| Ok(single.as_bytes().to_vec())),
+            None
+        );
+    }
 
+    #[test]
+    fn test_parse_gprmc() {
         // Trame GPRMC valide
         let sentence = "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A";
-        let result = reader.parse_gprmc(sentence);
+        let result = parse_gprmc(sentence);
 
         assert!(result.is_some());
-        let (timestamp, _satellites) = result.unwrap();
+        let timestamp = result.unwrap();
         // Vérifier que le timestamp est dans une plage raisonnable
         assert!(timestamp.seconds() > 0);
     }
 
+    #[test]
+    fn test_gps_week_date() {
+        // Exemple u-blox : semaine 1196, 113851 s UTC = 2002-12-09 07:37:31 UTC
+        let pubx = "$PUBX,04,073731.00,091202,113851.00,1196,15D,1930035,-2660.664,43*71";
+        let week_time = parse_pubx_week_time(pubx).unwrap();
+        let expected = NaiveDateTime::parse_from_str("2002-12-09 07:37:31", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(week_time.seconds() as i64, expected.and_utc().timestamp() + NTP_UNIX_OFFSET as i64);
+
+        // Temps GPS : écart GPS - UTC de 18 s retranché (2026-01-04 00:00:00 UTC)
+        let gps_time = gps_week_to_ntp(2400, 18.5, 18).unwrap();
+        let expected = NaiveDateTime::parse_from_str("2026-01-04 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(gps_time.seconds() as i64, expected.and_utc().timestamp() + NTP_UNIX_OFFSET as i64);
+        assert_eq!(gps_time.fraction(), 1 << 31);
+        assert_eq!(gps_week_to_ntp(2400, 604_800.0, 18), None);
+
+        // Récepteur au repli de semaine : RMC daté de 1024 semaines plus tôt,
+        // la date semaine GPS l'emporte et l'heure RMC est conservée
+        let reader = test_reader();
+        reader.process_nmea_sentence("$PUBX,04,120000.00,160926,302400.00,2436,18,0,0.000,21*00");
+        let rmc = reader
+            .process_nmea_sentence("$GPRMC,120000.25,A,4916.45,N,12311.12,W,000.5,054.7,310107,020.3,E*00")
+            .unwrap();
+        let expected = NaiveDateTime::parse_from_str("2026-09-16 12:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(rmc.seconds() as i64, expected.and_utc().timestamp() + NTP_UNIX_OFFSET as i64);
+        assert_eq!(rmc.fraction(), 1 << 30);
+    }
+
+    #[test]
+    fn test_parse_gpgll_time() {
+        let reader = test_reader();
+        let gll = "$GNGLL,4916.45,N,12311.12,W,225444.50,A,A*69";
+
+        // Sans trame RMC, pas de date : l'heure GLL n'est pas utilisée
+        assert_eq!(reader.process_nmea_sentence(gll), None);
+
+        let rmc = "$GPRMC,225443,A,4916.45,N,12311.12,W,000.5,054.7,111124,020.3,E*6E";
+        let rmc_time = reader.process_nmea_sentence(rmc).unwrap();
+
+        // Même jour que la trame RMC, une seconde et demie plus tard
+        let gll_time = reader.process_nmea_sentence(gll).unwrap();
+        assert_eq!(gll_time.seconds(), rmc_time.seconds() + 1);
+        assert_eq!(gll_time.fraction(), 1 << 31);
+
+        // Statut V (invalide) ignoré
+        assert_eq!(reader.process_nmea_sentence("$GPGLL,4916.45,N,12311.12,W,225444,V,N*44"), None);
+
+        // Passage de minuit entre la trame RMC et la trame GLL
+        let before_midnight = 3_940_358_399;
+        assert_eq!(anchor_time_of_day(before_midnight, 0), before_midnight + 1);
+        assert_eq!(anchor_time_of_day(before_midnight + 1, 86_399), before_midnight);
+    }
+
+    #[test]
+    fn test_parse_gpvtg() {
+        let reader = test_reader();
+
+        reader.process_nmea_sentence("$GNVTG,054.7,T,034.4,M,005.5,N,010.2,K,A*3B");
+        let gps = reader.stats.read().unwrap().gps.clone();
+        assert_eq!(gps.speed_kmh, Some(10.2));
+        assert_eq!(gps.course_deg, Some(54.7));
+
+        // Mode N : données non valides
+        assert_eq!(parse_vtg("$GPVTG,,T,,M,0.0,N,,K,N*02"), None);
+
+        // Vitesse en nœuds seule, cap absent à l'arrêt
+        let velocity = parse_vtg("$GPVTG,,T,,M,1.0,N,,K,A").unwrap();
+        assert_eq!(velocity.course_deg, None);
+        assert!((velocity.speed_kmh - 1.852).abs() < 1e-9);
+    }
+
     #[test]
     fn test_parse_gpgga_satellites() {
-        use crate::stats::StatsManager;
+        // Trame GPGGA avec 8 satellites
+        let sentence = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+        let result = parse_gpgga(sentence);
+
+        assert_eq!(result, Some(GgaFix::Fix { quality: 1, satellites: 8, hdop: Some(0.9) }));
+
+        // Position : 48°07.038' N, 11°31.000' E
+        let (latitude, longitude) = parse_gga_position(sentence).unwrap();
+        assert!((latitude - 48.1173).abs() < 1e-9);
+        assert!((longitude - (11.0 + 31.0 / 60.0)).abs() < 1e-9);
+        let (latitude, longitude) = parse_gga_position("$GPGGA,123519,3351.000,S,15112.000,W,1,08").unwrap();
+        assert!(latitude < 0.0 && longitude < 0.0);
+        assert_eq!(parse_gga_position("$GPGGA,123519,,,,,0,00"), None);
+
+        // Champ non numérique : trame ignorée
+        assert_eq!(parse_gpgga("$GPGGA,123519,4807.038,N,01131.000,E,1,0A,0.9,545.4,M,46.9,M,,*00"), None);
+    }
 
+    #[test]
+    fn test_time_source_talker_pinned() {
         let config = GpsConfig {
-            serial_port: "COM9".to_string(),
-            baud_rate: 9600,
-            sync_timeout: 30,
-            min_satellites: 4,
-            pps_enabled: true,
-            pps_gpio_pin: None,
+            time_source_talker: "GN".to_string(),
+            ..test_config()
         };
-
         let clock = Arc::new(GpsNmeaClock::new(30));
-        let stats_manager = StatsManager::new();
-        let reader = GpsReader::new(config, clock, stats_manager.clone_arc());
+        let reader = GpsReader::new(config, Arc::clone(&clock), StatsManager::new().clone_arc());
 
-        // Trame GPGGA avec 8 satellites
-        let sentence = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
-        let result = reader.parse_gpgga_satellites(sentence);
+        // Solution GPS seule (11/11/2024) ignorée pour le temps
+        let gp = "$GPRMC,225443,A,4916.45,N,12311.12,W,000.5,054.7,111124,020.3,E";
+        assert_eq!(reader.process_nmea_sentence(gp), None);
+
+        // Solution combinée (12/11/2024) : date l'horloge
+        let gn = "$GNRMC,225443,A,4916.45,N,12311.12,W,000.5,054.7,121124,020.3,E";
+        let gn_time = reader.process_nmea_sentence(gn).unwrap();
+        assert!(clock.now().seconds_since(gn_time).abs() < 1.0);
+
+        // Nouvelle trame GP : l'horloge reste sur la solution GN
+        assert_eq!(reader.process_nmea_sentence(gp), None);
+        assert!(clock.now().seconds_since(gn_time).abs() < 1.0);
+
+        // Les autres talkers restent utilisés pour les satellites
+        reader.process_nmea_sentence("$GPGSV,1,1,01,01,40,083,46*4D");
+        assert_eq!(reader.stats.read().unwrap().gps.satellites_in_view, 1);
+
+        // "auto" : GP et GN
+        let reader = test_reader();
+        assert!(reader.process_nmea_sentence(gp).is_some());
+        assert!(reader.process_nmea_sentence(gn).is_some());
+    }
+
+    #[test]
+    fn test_satellites_used_vs_in_view() {
+        let reader = test_reader();
+        let gps = |reader: &GpsReader| reader.stats.read().unwrap().gps.clone();
+
+        // GGA : 8 satellites utilisés dans le fix
+        reader.process_nmea_sentence("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+        assert_eq!((gps(&reader).satellites_used, gps(&reader).satellites_in_view), (8, 0));
+
+        // GSV : 8 GPS et 2 GLONASS en vue, sans changer le compte GGA
+        reader.process_nmea_sentence("$GPGSV,2,1,08,01,40,083,46,02,17,308,41,12,07,344,39,14,22,228,45*75");
+        reader.process_nmea_sentence("$GPGSV,2,2,08,15,95,100,30*4B");
+        reader.process_nmea_sentence("$GLGSV,1,1,02,65,30,100,38,66,45,200,40*6A");
+        assert_eq!((gps(&reader).satellites_used, gps(&reader).satellites_in_view), (8, 10));
+
+        // Plus aucun GLONASS en vue (en-tête seul)
+        reader.process_nmea_sentence("$GLGSV,1,1,00*65");
+        assert_eq!(gps(&reader).satellites_in_view, 8);
+        assert_eq!(parse_gsv_in_view("$GPRMC,123519,A*6F"), None);
+    }
+
+    #[test]
+    fn test_parse_gpgga_acquisition() {
+        let reader = test_reader();
+
+        // Qualité 0, satellites nuls ou vides : acquisition en cours
+        let sentence = "$GPGGA,123519,,,,,0,00,,,,,,,*66";
+        assert_eq!(
+            parse_gpgga(sentence),
+            Some(GgaFix::Acquiring { satellites: 0 })
+        );
+
+        let sentence = "$GPGGA,123519,,,,,0,,,,,,,,*56";
+        assert_eq!(
+            parse_gpgga(sentence),
+            Some(GgaFix::Acquiring { satellites: 0 })
+        );
+
+        // Les stats indiquent l'acquisition, pas un fix à 0 satellite
+        reader.update_state(|machine, now| machine.on_connected(now));
+        reader.process_nmea_sentence("$GPGGA,123519,,,,,0,00,,,,,,,*66");
+        let stats = reader.stats.read().unwrap().clone();
+        assert!(stats.gps.acquiring);
+        assert_eq!(stats.gps.signal_quality, 0);
+
+        // Une trame normale met fin à l'acquisition
+        reader.process_nmea_sentence(
+            "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47",
+        );
+        let stats = reader.stats.read().unwrap().clone();
+        assert!(!stats.gps.acquiring);
+        assert_eq!(stats.gps.satellites_used, 8);
+    }
+
+    #[test]
+    fn test_gps_state_from_sentences() {
+        let reader = test_reader();
+        let state = |reader: &GpsReader| reader.stats.read().unwrap().gps.state;
+        assert_eq!(state(&reader), GpsState::Disconnected);
+
+        reader.update_state(|machine, now| machine.on_connected(now));
+        assert_eq!(state(&reader), GpsState::NoFix);
+
+        // Fix GGA avec 3 satellites : 2D
+        reader.process_nmea_sentence("$GPGGA,123519,4807.038,N,01131.000,E,1,03,0.9,545.4,M,46.9,M,,*44");
+        assert_eq!(state(&reader), GpsState::Fix2D);
+
+        // GSA 3D
+        reader.process_nmea_sentence("$GPGSA,A,3,04,05,,09,12,,,24,,,,,2.5,1.3,2.1*39");
+        assert_eq!(state(&reader), GpsState::Fix3D);
+
+        // PPS : verrouillé, booléen historique dérivé
+        reader.update_state(|machine, now| machine.on_pps(now));
+        assert_eq!(state(&reader), GpsState::PpsLocked);
+        assert!(reader.stats.read().unwrap().gps.pps_active);
+
+        // Perte du fix : holdover
+        reader.process_nmea_sentence("$GPGGA,123519,,,,,0,00,,,,,,,*66");
+        assert_eq!(state(&reader), GpsState::Holdover);
+        assert!(!reader.stats.read().unwrap().gps.pps_active);
+
+        assert_eq!(parse_gsa_fix_type("$GPGSA,A,1,,,,,,,,,,,,,,,*1E"), Some(1));
+        assert_eq!(parse_gsa_fix_type("$GPGSA,A,,*00"), None);
+    }
+
+    #[test]
+    fn test_empty_reads_do_not_busy_loop() {
+        /// Port qui ne renvoie jamais de données ni de timeout
+        struct EmptyPort;
 
-        assert_eq!(result, Some(8));
+        impl Read for EmptyPort {
+            fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+                Ok(0)
+            }
+        }
+
+        let mut port = EmptyPort;
+        let mut buf = [0u8; 16];
+        let window = Duration::from_millis(50);
+        let start = Instant::now();
+        let mut iterations = 0u32;
+
+        while start.elapsed() < window {
+            assert_eq!(read_or_pause(&mut port, &mut buf).unwrap(), 0);
+            iterations += 1;
+        }
+
+        // Au plus une itération par pause (sans pause : des millions)
+        let max = (window.as_millis() / EMPTY_READ_PAUSE.as_millis()) as u32 + 1;
+        assert!(iterations <= max, "{} iterations in {:?}", iterations, window);
+    }
+
+    #[test]
+    fn test_interrupted_read_does_not_reconnect() {
+        /// Port qui renvoie une suite d'erreurs puis des données
+        struct ScriptedPort(Vec<std::io::Result<&'static [u8]>>);
+
+        impl Read for ScriptedPort {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                match self.0.remove(0) {
+                    Ok(data) => {
+                        buf[..data.len()].copy_from_slice(data);
+                        Ok(data.len())
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+        }
+
+        let sentence: &'static [u8] = b"$GPGSA,A,1,,,,,,,,,,,,,,,*1E\r\n";
+
+        // EINTR et WouldBlock : la lecture continue jusqu'à la trame
+        let mut port = ScriptedPort(vec![
+            Err(std::io::ErrorKind::Interrupted.into()),
+            Err(std::io::ErrorKind::WouldBlock.into()),
+            Ok(sentence),
+        ]);
+        assert!(wait_for_nmea(&mut port, Duration::from_secs(5), || {}).unwrap());
+
+        // Périphérique retiré : erreur remontée, donc reconnexion
+        let mut port = ScriptedPort(vec![Err(std::io::ErrorKind::BrokenPipe.into()), Ok(sentence)]);
+        let err = wait_for_nmea(&mut port, Duration::from_secs(5), || {}).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn test_nmea_validation_window() {
+        let window = Duration::from_millis(50);
+
+        // Flux binaire d'un autre périphérique (modem, imprimante 3D) : rejeté
+        let garbage: Vec<u8> = (0..4096u32).map(|i| (i.wrapping_mul(37) % 251) as u8).collect();
+        let mut port = std::io::Cursor::new(garbage);
+        let start = Instant::now();
+        assert!(!wait_for_nmea(&mut port, window, || {}).unwrap());
+        assert!(start.elapsed() >= window);
+
+        // Texte avec fins de ligne mais sans checksum valide : rejeté
+        let mut port = std::io::Cursor::new(b"ok\r\nATZ\r\n$GPRMC,bad*42\r\n".to_vec());
+        assert!(!wait_for_nmea(&mut port, window, || {}).unwrap());
+
+        // Première trame valide après du bruit : accepté sans attendre la fin de la fenêtre
+        let mut stream = b"\xff\x00noise\r\n".to_vec();
+        stream.extend_from_slice(b"$GPGSA,A,1,,,,,,,,,,,,,,,*1E\r\n");
+        let mut port = std::io::Cursor::new(stream);
+        let mut reads = 0;
+        assert!(wait_for_nmea(&mut port, Duration::from_secs(5), || reads += 1).unwrap());
+        assert!(reads > 0);
+    }
+
+    #[test]
+    fn test_constellation_filters_effective_count() {
+        let satellite = |constellation: &str, prn: u8, snr: u8| SatelliteInfo {
+            prn,
+            elevation: 45,
+            azimuth: 180,
+            snr,
+            constellation: constellation.to_string(),
+        };
+        let names = |list: &[&str]| list.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+
+        let start = Instant::now();
+        let mut tracker = ConstellationTracker::default();
+        tracker.record(
+            &[
+                satellite("GPS", 1, 40),
+                satellite("GPS", 2, 35),
+                satellite("GPS", 3, 0), // En vue mais non suivi
+                satellite("GLONASS", 65, 38),
+                satellite("GLONASS", 66, 30),
+                satellite("Galileo", 11, 42),
+            ],
+            start,
+        );
+
+        assert_eq!(tracker.effective_count(&[], &[]), 5);
+        assert_eq!(tracker.effective_count(&[], &names(&["GLONASS"])), 3);
+        assert_eq!(tracker.effective_count(&names(&["galileo"]), &names(&["GLONASS"])), 3);
+
+        // Constellation requise absente : aucun satellite retenu
+        assert_eq!(tracker.effective_count(&names(&["BeiDou"]), &[]), 0);
+        assert_eq!(tracker.missing(&names(&["Galileo", "BeiDou"])), vec!["BeiDou"]);
+
+        // Galileo plus suivi depuis 10 s (brouillage) : la condition requise tombe
+        tracker.record(&[satellite("GPS", 1, 40)], start + Duration::from_secs(8));
+        tracker.record(&[satellite("GPS", 1, 40)], start + Duration::from_secs(11));
+        assert_eq!(tracker.effective_count(&[], &[]), 1);
+        assert_eq!(tracker.effective_count(&names(&["Galileo"]), &[]), 0);
+
+        // Via le lecteur : nombre GGA borné par les satellites retenus
+        let mut config = test_config();
+        config.exclude_constellations = names(&["GLONASS"]);
+        let reader = GpsReader::new(config, Arc::new(GpsNmeaClock::new(30)), StatsManager::new().clone_arc());
+        reader.process_nmea_sentence("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47");
+        reader.record_constellations(&[satellite("GPS", 1, 40), satellite("GLONASS", 65, 38)]);
+        assert_eq!(reader.gga_satellites(), Some(1));
+    }
+
+    #[test]
+    fn test_pps_offset_from_zda_time_tag() {
+        // Trame ZDA datant 12:35:20 UTC le 16/09/2026
+        let tag = parse_zda("$GPZDA,123520.00,16,09,2026,00,00*6B").unwrap();
+        assert_eq!(ntp_seconds_to_iso(tag.seconds() as u64).unwrap(), "2026-09-16T12:35:20Z");
+        assert_eq!(parse_zda("$GPRMC,123520.00,16,09,2026,00,00*6B"), None);
+        assert_eq!(parse_zda("$GPZDA,123520.00,31,02,2026,00,00*6B"), None);
+        assert_eq!(parse_zda("$GPZDA,\u{FFFD}1234,16,09,2026,00,00*6B"), None);
+        assert_eq!(parse_zda("$GPZDA,12352,16,09,2026,00,00*6B"), None);
+
+        // Pulse 300 ms après la trame : ZDA standard (émise après le pulse de
+        // 12:35:20), ce pulse marque 12:35:21 ; module annonçant le pulse à venir,
+        // il marque 12:35:20
+        let received_at = Instant::now();
+        let pulse = received_at + Duration::from_millis(300);
+        let boundary = tagged_pps_boundary(Some((tag, received_at)), pulse, false).unwrap();
+        assert_eq!(boundary.seconds(), tag.seconds() + 1);
+        assert_eq!(tagged_pps_boundary(Some((tag, received_at)), pulse, true), Some(tag));
+
+        // Trame périmée ou reçue après le pulse : pulse non daté
+        assert_eq!(tagged_pps_boundary(Some((tag, received_at)), received_at + Duration::from_secs(2), false), None);
+        assert_eq!(tagged_pps_boundary(Some((tag, pulse)), received_at, false), None);
+        assert_eq!(tagged_pps_boundary(None, pulse, false), None);
+
+        // Offset système - GPS calculé depuis la seconde annoncée
+        let clock = GpsNmeaClock::new(30);
+        let now = Instant::now();
+        let measurement = clock.update_pps_offset(now, boundary).unwrap();
+        let expected = SystemClock::new().now().seconds_since(boundary);
+        assert!((measurement.raw_offset_seconds - expected).abs() < 1.0, "{:?}", measurement);
+    }
+
+    #[test]
+    fn test_parse_gprmc_fractional_seconds() {
+        // Deux décimales : .25 = 250 ms
+        let sentence = "$GPRMC,123519.25,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A";
+        let timestamp = parse_gprmc(sentence).unwrap();
+        let nanos = ((timestamp.fraction() as u64 * 1_000_000_000) >> 32) as u32;
+        assert!((nanos as i64 - 250_000_000).abs() < 10);
+
+        // Trois décimales : .125 = 125 ms
+        let sentence = "$GPRMC,123519.125,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A";
+        let timestamp = parse_gprmc(sentence).unwrap();
+        let nanos = ((timestamp.fraction() as u64 * 1_000_000_000) >> 32) as u32;
+        assert!((nanos as i64 - 125_000_000).abs() < 10);
     }
 }
@@ -0,0 +1,262 @@
+/*!
+Machine d'états du récepteur GPS
+
+L'état du GPS est dérivé des événements du lecteur (connexion du port,
+trames GGA/GSA, pulses PPS) et du temps écoulé :
+
+```text
+Disconnected → NoFix → Fix2D/Fix3D → PpsLocked
+                  ↑                      ↓ (perte du fix)
+                  └──── (expiration) ── Holdover
+```
+
+- `Holdover` : le fix vient d'être perdu, l'horloge extrapole encore depuis
+  la dernière synchronisation (pendant `holdover_timeout`).
+- `PpsLocked` : fix valide et pulses PPS reçus récemment.
+
+Les booléens historiques des statistiques (`connected`, `acquiring`,
+`pps_active`) sont calculés à partir de cet état.
+*/
+
+use crate::stats::{GpsState, GpsStats};
+use std::time::{Duration, Instant};
+
+/// Délai sans trame GGA au-delà duquel le fix est considéré comme perdu
+const FIX_STALE_AFTER: Duration = Duration::from_secs(3);
+
+/// Délai sans pulse PPS au-delà duquel le verrouillage PPS est perdu
+const PPS_STALE_AFTER: Duration = Duration::from_secs(2);
+
+/// Machine d'états du GPS alimentée par les événements du lecteur
+#[derive(Debug)]
+pub struct GpsStateMachine {
+    state: GpsState,
+    connected: bool,
+
+    /// Fix annoncé par la dernière trame GGA (qualité > 0)
+    has_fix: bool,
+
+    /// Satellites utilisés selon la dernière trame GGA
+    satellites: u8,
+
+    /// Type de fix de la dernière trame GSA (1 = aucun, 2 = 2D, 3 = 3D)
+    fix_type: Option<u8>,
+
+    last_fix: Option<Instant>,
+    last_pps: Option<Instant>,
+
+    /// Durée du holdover après la perte du fix
+    holdover_timeout: Duration,
+}
+
+impl GpsStateMachine {
+    pub fn new(holdover_timeout: Duration) -> Self {
+        GpsStateMachine {
+            state: GpsState::Disconnected,
+            connected: false,
+            has_fix: false,
+            satellites: 0,
+            fix_type: None,
+            last_fix: None,
+            last_pps: None,
+            holdover_timeout,
+        }
+    }
+
+    /// État courant
+    #[allow(dead_code)]
+    pub fn state(&self) -> GpsState {
+        self.state
+    }
+
+    /// Port série ouvert
+    pub fn on_connected(&mut self, now: Instant) -> Option<(GpsState, GpsState)> {
+        self.connected = true;
+        self.update(now)
+    }
+
+    /// Port série fermé ou lecteur relancé : tout l'état du récepteur est oublié
+    pub fn on_disconnected(&mut self, now: Instant) -> Option<(GpsState, GpsState)> {
+        self.connected = false;
+        self.has_fix = false;
+        self.fix_type = None;
+        self.last_pps = None;
+        self.update(now)
+    }
+
+    /// Trame GGA : qualité du fix (0 = pas de fix) et satellites utilisés
+    pub fn on_gga(&mut self, quality: u8, satellites: u8, now: Instant) -> Option<(GpsState, GpsState)> {
+        self.has_fix = quality > 0;
+        self.satellites = satellites;
+        if self.has_fix {
+            self.last_fix = Some(now);
+        }
+        self.update(now)
+    }
+
+    /// Trame GSA : type de fix (1 = aucun, 2 = 2D, 3 = 3D)
+    pub fn on_gsa(&mut self, fix_type: u8, now: Instant) -> Option<(GpsState, GpsState)> {
+        self.fix_type = Some(fix_type);
+        self.update(now)
+    }
+
+    /// Pulse PPS à l'intervalle valide
+    pub fn on_pps(&mut self, now: Instant) -> Option<(GpsState, GpsState)> {
+        self.last_pps = Some(now);
+        self.update(now)
+    }
+
+    /// Réévalue les transitions liées au temps (expiration du fix, du PPS, du holdover)
+    pub fn tick(&mut self, now: Instant) -> Option<(GpsState, GpsState)> {
+        self.update(now)
+    }
+
+    /// Recalcule l'état ; retourne `(ancien, nouveau)` en cas de transition
+    fn update(&mut self, now: Instant) -> Option<(GpsState, GpsState)> {
+        let next = self.evaluate(now);
+        if next == self.state {
+            return None;
+        }
+
+        let previous = self.state;
+        self.state = next;
+        Some((previous, next))
+    }
+
+    fn evaluate(&self, now: Instant) -> GpsState {
+        if !self.connected {
+            return GpsState::Disconnected;
+        }
+
+        let recent = |instant: Option<Instant>, max_age: Duration| {
+            instant.is_some_and(|at| now.saturating_duration_since(at) <= max_age)
+        };
+
+        let fix_valid = self.has_fix && self.fix_type != Some(1) && recent(self.last_fix, FIX_STALE_AFTER);
+        if fix_valid {
+            if recent(self.last_pps, PPS_STALE_AFTER) {
+                return GpsState::PpsLocked;
+            }
+
+            // Sans trame GSA, il faut au moins 4 satellites pour un fix 3D
+            let three_d = match self.fix_type {
+                Some(fix_type) => fix_type >= 3,
+                None => self.satellites >= 4,
+            };
+            return if three_d { GpsState::Fix3D } else { GpsState::Fix2D };
+        }
+
+        if recent(self.last_fix, self.holdover_timeout) {
+            GpsState::Holdover
+        } else {
+            GpsState::NoFix
+        }
+    }
+}
+
+/// Reporte l'état dans les statistiques, ainsi que les booléens historiques qui en dérivent
+pub fn apply_to_stats(state: GpsState, stats: &mut GpsStats) {
+    stats.state = state;
+    stats.connected = state != GpsState::Disconnected;
+    stats.acquiring = state == GpsState::NoFix;
+    stats.pps_active = state == GpsState::PpsLocked;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_fix_and_pps_transitions() {
+        let start = Instant::now();
+        let mut machine = GpsStateMachine::new(Duration::from_secs(30));
+        assert_eq!(machine.state(), GpsState::Disconnected);
+
+        assert_eq!(
+            machine.on_connected(start),
+            Some((GpsState::Disconnected, GpsState::NoFix))
+        );
+
+        // GGA qualité 0 : toujours en acquisition, pas de transition
+        assert_eq!(machine.on_gga(0, 2, at(start, 1000)), None);
+
+        // Fix avec 3 satellites sans GSA : 2D ; GSA 3D : 3D
+        assert_eq!(
+            machine.on_gga(1, 3, at(start, 2000)),
+            Some((GpsState::NoFix, GpsState::Fix2D))
+        );
+        assert_eq!(
+            machine.on_gsa(3, at(start, 2100)),
+            Some((GpsState::Fix2D, GpsState::Fix3D))
+        );
+
+        // PPS reçu : verrouillé, puis perdu faute de nouveau pulse
+        assert_eq!(
+            machine.on_pps(at(start, 3000)),
+            Some((GpsState::Fix3D, GpsState::PpsLocked))
+        );
+        assert_eq!(machine.on_gga(1, 8, at(start, 3100)), None);
+        assert_eq!(
+            machine.tick(at(start, 5500)),
+            Some((GpsState::PpsLocked, GpsState::Fix3D))
+        );
+
+        // GSA "pas de fix" l'emporte sur la qualité GGA
+        assert_eq!(
+            machine.on_gsa(1, at(start, 5600)),
+            Some((GpsState::Fix3D, GpsState::Holdover))
+        );
+    }
+
+    #[test]
+    fn test_holdover_then_lost() {
+        let start = Instant::now();
+        let mut machine = GpsStateMachine::new(Duration::from_secs(10));
+        machine.on_connected(start);
+        machine.on_gga(1, 8, start);
+        machine.on_pps(start);
+        assert_eq!(machine.state(), GpsState::PpsLocked);
+
+        // Fix perdu : holdover tant que la dernière synchro est récente
+        assert_eq!(
+            machine.on_gga(0, 0, at(start, 1000)),
+            Some((GpsState::PpsLocked, GpsState::Holdover))
+        );
+        assert_eq!(machine.tick(at(start, 9000)), None);
+        assert_eq!(
+            machine.tick(at(start, 10_500)),
+            Some((GpsState::Holdover, GpsState::NoFix))
+        );
+
+        // Plus aucune trame GGA : le fix expire aussi
+        machine.on_gga(1, 8, at(start, 20_000));
+        assert_eq!(machine.state(), GpsState::Fix3D);
+        assert_eq!(
+            machine.tick(at(start, 24_000)),
+            Some((GpsState::Fix3D, GpsState::Holdover))
+        );
+
+        assert_eq!(
+            machine.on_disconnected(at(start, 25_000)),
+            Some((GpsState::Holdover, GpsState::Disconnected))
+        );
+    }
+
+    #[test]
+    fn test_booleans_derived_from_state() {
+        let mut stats = crate::stats::StatsManager::new().get().gps;
+
+        apply_to_stats(GpsState::NoFix, &mut stats);
+        assert!(stats.connected && stats.acquiring && !stats.pps_active);
+
+        apply_to_stats(GpsState::PpsLocked, &mut stats);
+        assert!(stats.connected && !stats.acquiring && stats.pps_active);
+
+        apply_to_stats(GpsState::Disconnected, &mut stats);
+        assert!(!stats.connected && !stats.acquiring && !stats.pps_active);
+    }
+}
@@ -0,0 +1,77 @@
+/*!
+Ligne de santé périodique dans les logs (`logging.health_interval_secs`)
+
+À la manière du tracking log de chrony, une ligne `clé=valeur` résume l'état
+de l'horloge à intervalle régulier, lue dans les statistiques partagées :
+
+```text
+Health: stratum=1 refid=PPS satellites=9 pps_active=true pps_offset_us=-1.250 requests_per_second=42
+```
+
+La trace reste exploitable par `grep` sans interroger l'API web.
+*/
+
+use crate::panic_guard::run_guarded;
+use crate::stats::ServerStats;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::info;
+
+/// Formate la ligne de santé d'un instantané des statistiques
+pub fn health_line(stats: &ServerStats) -> String {
+    let pps_offset = match stats.gps.pps_offset {
+        Some(offset) => format!("{:.3}", offset * 1e6),
+        None => "none".to_string(),
+    };
+
+    format!(
+        "Health: stratum={} refid={} satellites={} pps_active={} pps_offset_us={} requests_per_second={}",
+        stats.clock.stratum,
+        stats.clock.reference_id,
+        stats.gps.satellites_used,
+        stats.gps.pps_active,
+        pps_offset,
+        stats.ntp.requests_per_second
+    )
+}
+
+/// Démarre le thread qui journalise la ligne de santé toutes les `interval`
+pub fn start(interval: Duration, stats: Arc<RwLock<ServerStats>>) -> std::thread::JoinHandle<()> {
+    info!("Logging clock health every {:?}", interval);
+
+    std::thread::spawn(move || loop {
+        std::thread::sleep(interval);
+
+        let line = match stats.read() {
+            Ok(snapshot) => health_line(&snapshot),
+            Err(_) => continue,
+        };
+        run_guarded("health_log", &stats, || info!("{}", line));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::StatsManager;
+
+    #[test]
+    fn test_health_line_fields() {
+        let mut stats = StatsManager::new().get();
+        stats.clock.stratum = 1;
+        stats.clock.reference_id = "PPS".to_string();
+        stats.gps.satellites_used = 9;
+        stats.gps.pps_active = true;
+        stats.gps.pps_offset = Some(-0.000_001_25);
+        stats.ntp.requests_per_second = 42;
+
+        assert_eq!(
+            health_line(&stats),
+            "Health: stratum=1 refid=PPS satellites=9 pps_active=true pps_offset_us=-1.250 requests_per_second=42"
+        );
+
+        // Sans mesure PPS : valeur explicite plutôt que champ absent
+        stats.gps.pps_offset = None;
+        assert!(health_line(&stats).contains(" pps_offset_us=none "));
+    }
+}
@@ -0,0 +1,150 @@
+/*!
+Battements de cœur des threads internes
+
+Chaque boucle de thread (lecteur GPS, réception NTP, mise à jour des
+statistiques) horodate sa dernière progression dans un atomique partagé, à
+chaque itération. La section `threads` de `/api/stats` en dérive l'âge de la
+dernière progression : un thread bloqué (lecture série figée, verrou...) y
+apparaît `stale` sans avoir à lire les journaux.
+*/
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Origine des horodatages (instant du premier battement ou de la première lecture)
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+fn epoch() -> Instant {
+    *EPOCH.get_or_init(Instant::now)
+}
+
+/// État d'un thread selon son dernier battement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThreadState {
+    /// Aucun battement (sous-système désactivé ou pas encore démarré)
+    NotStarted,
+
+    /// Dernière progression plus récente que le seuil
+    Running,
+
+    /// Aucune progression depuis plus que le seuil
+    Stale,
+}
+
+/// Santé d'un thread exposée dans `/api/stats`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadHealth {
+    pub name: String,
+    pub state: ThreadState,
+
+    /// Âge de la dernière progression (millisecondes)
+    pub last_progress_age_ms: Option<u64>,
+
+    /// Seuil au-delà duquel le thread est considéré bloqué (millisecondes)
+    pub stale_after_ms: u64,
+}
+
+/// Battement de cœur d'un thread
+pub struct Heartbeat {
+    name: &'static str,
+    stale_after: Duration,
+
+    /// Millisecondes depuis `EPOCH` + 1 (0 = jamais)
+    last_ms: AtomicU64,
+}
+
+impl Heartbeat {
+    pub const fn new(name: &'static str, stale_after: Duration) -> Self {
+        Heartbeat {
+            name,
+            stale_after,
+            last_ms: AtomicU64::new(0),
+        }
+    }
+
+    /// Signale une progression du thread
+    pub fn beat(&self) {
+        self.beat_at(Instant::now());
+    }
+
+    fn beat_at(&self, now: Instant) {
+        let since_epoch = now.saturating_duration_since(epoch()).as_millis() as u64;
+        self.last_ms.store(since_epoch + 1, Ordering::Relaxed);
+    }
+
+    /// Santé du thread à l'instant `now`
+    pub fn health(&self, now: Instant) -> ThreadHealth {
+        let last_ms = self.last_ms.load(Ordering::Relaxed);
+        let age_ms = (last_ms > 0).then(|| {
+            let now_ms = now.saturating_duration_since(epoch()).as_millis() as u64 + 1;
+            now_ms.saturating_sub(last_ms)
+        });
+
+        let stale_after_ms = self.stale_after.as_millis() as u64;
+        let state = match age_ms {
+            None => ThreadState::NotStarted,
+            Some(age) if age > stale_after_ms => ThreadState::Stale,
+            Some(_) => ThreadState::Running,
+        };
+
+        ThreadHealth {
+            name: self.name.to_string(),
+            state,
+            last_progress_age_ms: age_ms,
+            stale_after_ms,
+        }
+    }
+}
+
+/// Boucle de lecture du port série (itération toutes les 100 ms au plus)
+pub static GPS_READER: Heartbeat = Heartbeat::new("gps_reader", Duration::from_secs(5));
+
+/// Boucles de réception UDP (timeout de lecture de 500 ms)
+pub static NTP_RECEIVER: Heartbeat = Heartbeat::new("ntp_receiver", Duration::from_secs(5));
+
+/// Mise à jour des statistiques partagées (toutes les secondes)
+pub static STATS_UPDATER: Heartbeat = Heartbeat::new("stats_updater", Duration::from_secs(5));
+
+/// Santé de tous les threads surveillés
+pub fn report() -> Vec<ThreadHealth> {
+    let now = Instant::now();
+    [&GPS_READER, &NTP_RECEIVER, &STATS_UPDATER]
+        .iter()
+        .map(|heartbeat| heartbeat.health(now))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_heartbeat_reported_stale() {
+        let heartbeat = Heartbeat::new("test", Duration::from_secs(5));
+        let start = epoch();
+
+        let health = heartbeat.health(start);
+        assert_eq!(health.state, ThreadState::NotStarted);
+        assert_eq!(health.last_progress_age_ms, None);
+
+        heartbeat.beat_at(start);
+        let health = heartbeat.health(start + Duration::from_secs(1));
+        assert_eq!(health.state, ThreadState::Running);
+        assert_eq!(health.last_progress_age_ms, Some(1000));
+
+        // Plus de battement depuis 6 s (seuil 5 s) : thread bloqué
+        let health = heartbeat.health(start + Duration::from_secs(6));
+        assert_eq!(health.state, ThreadState::Stale);
+        assert_eq!(health.last_progress_age_ms, Some(6000));
+        assert_eq!(health.stale_after_ms, 5000);
+
+        // Nouveau battement : de nouveau actif
+        heartbeat.beat_at(start + Duration::from_secs(7));
+        assert_eq!(heartbeat.health(start + Duration::from_secs(7)).state, ThreadState::Running);
+
+        assert_eq!(report().len(), 3);
+    }
+}
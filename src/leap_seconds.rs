@@ -0,0 +1,186 @@
+/*!
+Table des secondes intercalaires (fichier `leap-seconds.list` de l'IANA)
+
+Le temps GPS ne connaît pas les secondes intercalaires : il est en avance sur
+l'UTC de `TAI - UTC - 19` secondes (18 s depuis le 1er janvier 2017). La table
+fournit ce décalage à une date donnée, la prochaine seconde intercalaire
+annoncée et la date d'expiration du fichier.
+
+Format du fichier (timestamps en secondes NTP depuis 1900) :
+
+```text
+#@    3960057600
+2272060800    10    # 1 Jan 1972
+3692217600    37    # 1 Jan 2017
+```
+
+Les lignes `#` sont des commentaires, sauf `#@` (date d'expiration).
+*/
+
+use crate::packet::NTP_UNIX_OFFSET;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Écart fixe TAI - GPS (secondes)
+const TAI_GPS_OFFSET: i32 = 19;
+
+/// Durée d'un jour en secondes (annonce de la seconde intercalaire le dernier jour)
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Date ISO 8601 UTC d'un instant NTP (`None` avant 1970)
+pub fn ntp_seconds_to_iso(ntp_seconds: u64) -> Option<String> {
+    let unix_seconds = ntp_seconds.checked_sub(NTP_UNIX_OFFSET)?;
+    chrono::DateTime::from_timestamp(unix_seconds as i64, 0)
+        .map(|date| date.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+}
+
+/// Seconde intercalaire à venir
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpcomingLeap {
+    /// Instant NTP à partir duquel le nouveau décalage s'applique (00:00 UTC)
+    pub ntp_seconds: u64,
+
+    /// Vrai pour une seconde ajoutée (23:59:60), faux pour une seconde retirée
+    pub insertion: bool,
+}
+
+/// Table des décalages TAI - UTC
+#[derive(Debug, Clone, Default)]
+pub struct LeapSecondTable {
+    /// (instant NTP d'entrée en vigueur, TAI - UTC), triés par date
+    entries: Vec<(u64, i32)>,
+
+    /// Date d'expiration du fichier (secondes NTP)
+    expires: Option<u64>,
+}
+
+impl LeapSecondTable {
+    /// Charge un fichier `leap-seconds.list`
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read leap seconds file {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("Invalid leap seconds file {}", path.display()))
+    }
+
+    /// Parse le contenu d'un fichier `leap-seconds.list`
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut table = LeapSecondTable::default();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if let Some(expires) = line.strip_prefix("#@") {
+                table.expires = Some(expires.trim().parse().context("Invalid expiration line")?);
+                continue;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let data = line.split('#').next().unwrap_or("");
+            let mut fields = data.split_whitespace();
+            let (Some(time), Some(offset)) = (fields.next(), fields.next()) else {
+                anyhow::bail!("Malformed line: {}", line);
+            };
+            let time = time.parse().with_context(|| format!("Invalid timestamp: {}", line))?;
+            let offset = offset.parse().with_context(|| format!("Invalid offset: {}", line))?;
+            table.entries.push((time, offset));
+        }
+
+        if table.entries.is_empty() {
+            anyhow::bail!("No leap second entries");
+        }
+        table.entries.sort_by_key(|&(time, _)| time);
+
+        Ok(table)
+    }
+
+    /// TAI - UTC à l'instant NTP donné (`None` avant la première entrée)
+    pub fn tai_utc_at(&self, ntp_seconds: u64) -> Option<i32> {
+        self.entries
+            .iter()
+            .take_while(|&&(time, _)| time <= ntp_seconds)
+            .last()
+            .map(|&(_, offset)| offset)
+    }
+
+    /// GPS - UTC à l'instant NTP donné (18 s depuis 2017)
+    pub fn gps_utc_at(&self, ntp_seconds: u64) -> Option<i32> {
+        self.tai_utc_at(ntp_seconds).map(|offset| offset - TAI_GPS_OFFSET)
+    }
+
+    /// Prochaine seconde intercalaire annoncée après l'instant NTP donné
+    pub fn next_leap_after(&self, ntp_seconds: u64) -> Option<UpcomingLeap> {
+        let current = self.tai_utc_at(ntp_seconds)?;
+        self.entries
+            .iter()
+            .find(|&&(time, _)| time > ntp_seconds)
+            .map(|&(time, offset)| UpcomingLeap {
+                ntp_seconds: time,
+                insertion: offset > current,
+            })
+    }
+
+    /// Seconde intercalaire à la fin du jour UTC en cours (à annoncer dans le leap indicator)
+    pub fn leap_today(&self, ntp_seconds: u64) -> Option<UpcomingLeap> {
+        self.next_leap_after(ntp_seconds)
+            .filter(|leap| leap.ntp_seconds - ntp_seconds <= SECONDS_PER_DAY)
+    }
+
+    /// Vrai si le fichier a dépassé sa date d'expiration
+    pub fn is_expired(&self, ntp_seconds: u64) -> bool {
+        self.expires.is_some_and(|expires| ntp_seconds >= expires)
+    }
+
+    /// Date d'expiration du fichier (secondes NTP)
+    pub fn expires(&self) -> Option<u64> {
+        self.expires
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SNIPPET: &str = "\
+#	Updated through IERS Bulletin C 67
+#$	 3913697179
+#@	 3960057600
+#
+2272060800	10	# 1 Jan 1972
+2287785600	11	# 1 Jul 1972
+3644697600	36	# 1 Jul 2015
+3692217600	37	# 1 Jan 2017
+#h	16edd0f0 3666784f 37db7917 1be67e94 e7643264
+";
+
+    /// 2024-11-11T12:00:00Z en secondes NTP
+    const NOV_2024: u64 = NTP_UNIX_OFFSET + 1_731_326_400;
+
+    #[test]
+    fn test_parse_and_offset_at_date() {
+        let table = LeapSecondTable::parse(SNIPPET).unwrap();
+
+        assert_eq!(table.tai_utc_at(NOV_2024), Some(37));
+        assert_eq!(table.gps_utc_at(NOV_2024), Some(18));
+        assert_eq!(table.gps_utc_at(3_692_217_599), Some(17));
+        assert_eq!(table.tai_utc_at(2_272_060_799), None);
+
+        assert_eq!(table.next_leap_after(NOV_2024), None);
+        assert!(!table.is_expired(NOV_2024));
+        assert!(table.is_expired(3_960_057_600));
+
+        // Dernier jour avant la seconde intercalaire du 1er janvier 2017
+        let leap = UpcomingLeap { ntp_seconds: 3_692_217_600, insertion: true };
+        assert_eq!(table.next_leap_after(3_690_000_000), Some(leap));
+        assert_eq!(table.leap_today(3_690_000_000), None);
+        assert_eq!(table.leap_today(3_692_217_600 - 3_600), Some(leap));
+        assert_eq!(ntp_seconds_to_iso(leap.ntp_seconds).as_deref(), Some("2017-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed() {
+        assert!(LeapSecondTable::parse("# commentaire seul\n").is_err());
+        assert!(LeapSecondTable::parse("2272060800\n").is_err());
+        assert!(LeapSecondTable::parse("abc 10\n").is_err());
+    }
+}
@@ -0,0 +1,162 @@
+/*!
+Fichier de log avec rotation par taille
+
+Le fichier configuré (`logging.log_file`) reçoit les mêmes événements que la
+console. Au-delà de `logging.max_file_bytes`, il est renommé en `<fichier>.1`,
+les rotations précédentes sont décalées (`.1` → `.2`...) et seules les
+`logging.max_files` plus récentes sont conservées : un serveur chargé avec
+`log_requests = true` ne peut plus remplir le disque.
+
+Le subscriber tracing est initialisé avant la lecture de la configuration :
+la couche fichier écrit dans un puits global, ouvert une fois la configuration
+chargée (les premiers messages du démarrage ne vont que sur la console).
+*/
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Fichier de log ouvert, partagé par la couche tracing
+static LOG_FILE: OnceLock<Mutex<RotatingFile>> = OnceLock::new();
+
+/// Fichier en ajout avec rotation lorsque sa taille dépasse la limite
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+
+    /// Taille maximale avant rotation (octets, 0 = illimitée)
+    max_bytes: u64,
+
+    /// Nombre de fichiers de rotation conservés (`<fichier>.1` à `<fichier>.N`)
+    max_files: usize,
+}
+
+impl RotatingFile {
+    pub fn open(path: &Path, max_bytes: u64, max_files: usize) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+
+        Ok(RotatingFile {
+            path: path.to_path_buf(),
+            file,
+            size,
+            max_bytes,
+            max_files,
+        })
+    }
+
+    /// Décale les rotations existantes, supprime la plus ancienne et rouvre un fichier vide
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+
+        if self.max_files == 0 {
+            std::fs::remove_file(&self.path)?;
+        } else {
+            let _ = std::fs::remove_file(rotated_path(&self.path, self.max_files));
+            for index in (1..self.max_files).rev() {
+                let from = rotated_path(&self.path, index);
+                if from.exists() {
+                    std::fs::rename(&from, rotated_path(&self.path, index + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        }
+
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.max_bytes > 0 && self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Chemin de la rotation numéro `index` (`<fichier>.<index>`)
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(format!(".{}", index));
+    PathBuf::from(rotated)
+}
+
+/// Ouvre le fichier de log global ; faux s'il était déjà ouvert
+pub fn install(file: RotatingFile) -> bool {
+    LOG_FILE.set(Mutex::new(file)).is_ok()
+}
+
+/// Writer de la couche tracing : écrit dans le fichier global s'il est ouvert
+#[derive(Clone, Copy, Default)]
+pub struct LogFileWriter;
+
+impl Write for LogFileWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match LOG_FILE.get() {
+            Some(file) => file
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .write(buf),
+            None => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match LOG_FILE.get() {
+            Some(file) => file.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogFileWriter {
+    type Writer = LogFileWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        *self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_prunes_old_files() {
+        let dir = std::env::temp_dir().join(format!("pendulum-log-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("pendulum.log");
+
+        // Limite de 100 octets, 2 rotations conservées
+        let mut file = RotatingFile::open(&path, 100, 2).unwrap();
+        let line = [b'x'; 39];
+        for index in 0..10u8 {
+            let mut entry = line.to_vec();
+            entry.push(b'0' + index);
+            file.write_all(&entry).unwrap();
+        }
+
+        // 40 octets par ligne, 2 lignes par fichier : 5 fichiers produits, 3 conservés
+        assert_eq!(std::fs::read(&path).unwrap().len(), 80);
+        assert!(std::fs::read_to_string(&path).unwrap().ends_with('9'));
+        assert!(std::fs::read_to_string(rotated_path(&path, 1)).unwrap().ends_with('7'));
+        assert!(std::fs::read_to_string(rotated_path(&path, 2)).unwrap().ends_with('5'));
+        assert!(!rotated_path(&path, 3).exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,26 +1,69 @@
+mod audit_log;
+mod auth;
 mod clock;
 mod config;
+mod config_reload;
 mod gps_nmea;
 mod gps_reader;
+mod gps_state;
+mod health_log;
+mod heartbeat;
+mod leap_seconds;
+mod log_file;
+mod nmea_report;
 mod packet;
+mod packet_decode;
+mod panic_guard;
+mod peers;
+mod phc;
+mod pps_log;
+mod request_count;
 mod security;
 mod server;
+mod shutdown;
+mod snr_log;
+mod socket_options;
 mod stats;
+mod stats_bin;
+mod stats_schema;
+mod timesync;
+mod tx_timestamp;
 mod web_server;
 
 use anyhow::{Context, Result};
-use clock::{ClockSource, GpsNmeaClock, SystemClock};
+use audit_log::AuditLog;
+use clock::{
+    refid_from_str, ClockSource, GpsNmeaClock, InstrumentedClock, ManualClock, OffsetHistory, PriorityClock,
+    PrioritySource, SystemClock,
+};
 use config::Config;
+use config_reload::ConfigReloader;
 use gps_reader::GpsReader;
+use packet_decode::RequestPolicy;
+use phc::PhcClock;
+use request_count::RequestCountFile;
 use server::NtpServer;
+use shutdown::ShutdownMode;
+use snr_log::SnrRecorder;
 use stats::StatsManager;
-use std::path::PathBuf;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tracing::{error, info, warn};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
-use web_server::WebServer;
+use web_server::{DisplayTimezone, WebServer};
+
+/// Option de ligne de commande : arrêt dès le premier Ctrl+C (service)
+const NO_CONFIRM_SHUTDOWN_FLAG: &str = "--no-confirm-shutdown";
 
 fn main() -> Result<()> {
+    // Mode hors ligne : rapport de diagnostic d'une capture NMEA (ni port série ni socket)
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("--validate-nmea") {
+        let path = args.get(2).context("Usage: pendulum --validate-nmea <file>")?;
+        return nmea_report::run(std::path::Path::new(path));
+    }
+
     // Initialiser les logs
     init_logging()?;
 
@@ -31,18 +74,282 @@ fn main() -> Result<()> {
     let config_path = get_config_path();
     let config = load_or_create_config(&config_path)?;
 
+    if let Some(ref path) = config.logging.log_file {
+        let max_bytes = config.logging.max_file_bytes;
+        match log_file::RotatingFile::open(std::path::Path::new(path), max_bytes, config.logging.max_files) {
+            Ok(file) => {
+                log_file::install(file);
+                info!(
+                    "Logging to {} (rotation at {} bytes, {} files kept)",
+                    path, max_bytes, config.logging.max_files
+                );
+            }
+            Err(e) => warn!("Failed to open log file {}: {}", path, e),
+        }
+    }
+
     // Afficher la configuration
     info!("Configuration:");
     info!("  Bind address: {}", config.server.bind_address);
-    info!("  Clock source: {}", config.clock.source);
+    if !config.server.additional_ports.is_empty() {
+        info!("  Additional ports: {:?}", config.server.additional_ports);
+    }
+    if config.clock.sources.is_empty() {
+        info!("  Clock source: {}", config.clock.source);
+    } else {
+        info!("  Clock sources: {:?}", config.clock.sources);
+    }
     info!("  Rate limiting: {}", config.security.enable_rate_limiting);
 
     // Créer le gestionnaire de statistiques d'abord
     let stats_manager = StatsManager::new();
+    if config.webserver.stats_lock_metrics {
+        info!("Measuring shared stats lock contention (exposed on /metrics)");
+        stats::STATS_LOCK_CONTENTION.enable();
+    }
     let stats_arc = stats_manager.clone_arc();
 
-    // Créer la source d'horloge appropriée
-    let clock: Arc<dyn ClockSource> = match config.clock.source.as_str() {
+    // Horloge manuelle : temps fixé par l'API (banc de calibration hors réseau)
+    let manual_clock = (config.clock.source == "manual").then(|| {
+        info!(
+            "Using manual clock (stratum {} once set via PUT /api/clock/set)",
+            config.clock.manual_stratum
+        );
+        Arc::new(ManualClock::new(config.clock.manual_stratum))
+    });
+
+    // Créer la source d'horloge appropriée (ou la liste de sources par priorité)
+    let clock: Arc<dyn ClockSource> = if let Some(ref manual) = manual_clock {
+        Arc::clone(manual) as Arc<dyn ClockSource>
+    } else if config.clock.sources.is_empty() {
+        create_clock(&config.clock.source, &config, &stats_arc)
+    } else {
+        info!(
+            "Clock sources by priority: {:?} (switch holdoff {}s)",
+            config.clock.sources, config.clock.source_switch_holdoff_secs
+        );
+        let sources = config
+            .clock
+            .sources
+            .iter()
+            .map(|name| PrioritySource {
+                name: name.clone(),
+                clock: create_clock(name, &config, &stats_arc),
+            })
+            .collect();
+        Arc::new(PriorityClock::new(
+            sources,
+            std::time::Duration::from_secs(config.clock.source_switch_holdoff_secs),
+        ))
+    };
+
+    // Instrumentation optionnelle : historique de l'offset brut horloge/système
+    let offset_history = if config.clock.record_offset_history {
+        info!(
+            "Recording clock offset history ({} samples max)",
+            config.clock.offset_history_size
+        );
+        Some(Arc::new(OffsetHistory::new(config.clock.offset_history_size)))
+    } else {
+        None
+    };
+
+    let clock: Arc<dyn ClockSource> = match offset_history {
+        Some(ref history) => Arc::new(InstrumentedClock::new(clock, Arc::clone(history))),
+        None => clock,
+    };
+
+    // Afficher les infos de l'horloge
+    info!("Clock information:");
+    info!("  Stratum: {}", clock.stratum());
+    info!("  Precision: 2^{} seconds", clock.precision());
+    info!(
+        "  Reference ID: {}",
+        String::from_utf8_lossy(&clock.reference_id())
+    );
+
+    // Initialiser les infos d'horloge dans les stats
+    stats_manager.update_clock(|clock_info| {
+        clock_info.stratum = clock.stratum();
+        clock_info.reference_id = String::from_utf8_lossy(&clock.reference_id()).to_string();
+        clock_info.precision = clock.precision();
+    });
+
+    // Créer le serveur NTP (démarré plus bas), son rate limiter est partagé avec l'API web
+    let server = NtpServer::new(config.clone(), Arc::clone(&clock), Arc::clone(&stats_arc));
+
+    // Table des secondes intercalaires optionnelle (une table illisible n'empêche pas de démarrer)
+    let server = match config.clock.leap_seconds_file {
+        Some(ref path) => match leap_seconds::LeapSecondTable::load(std::path::Path::new(path)) {
+            Ok(table) => {
+                let now = clock.now().seconds() as u64;
+                info!("Leap seconds table loaded from {}", path);
+                match table.gps_utc_at(now) {
+                    Some(offset) => info!("  GPS - UTC offset: {}s", offset),
+                    None => warn!("  Current time precedes the leap seconds table"),
+                }
+                if let Some(leap) = table.next_leap_after(now) {
+                    info!(
+                        "  Next leap second: {}",
+                        leap_seconds::ntp_seconds_to_iso(leap.ntp_seconds).unwrap_or_default()
+                    );
+                }
+                if table.is_expired(now) {
+                    warn!(
+                        "  Leap seconds table expired on {}, upcoming leap seconds may be missing",
+                        table.expires().and_then(leap_seconds::ntp_seconds_to_iso).unwrap_or_default()
+                    );
+                }
+                server.with_leap_seconds(Arc::new(table))
+            }
+            Err(e) => {
+                warn!("Leap seconds table ignored: {:#}", e);
+                server
+            }
+        },
+        None => server,
+    };
+
+    // Journal d'audit optionnel (un fichier inaccessible n'empêche pas de démarrer)
+    let server = match config.logging.audit_log_file {
+        Some(ref path) => {
+            let logging = &config.logging;
+            match AuditLog::open(
+                std::path::Path::new(path),
+                logging.audit_sample_rate,
+                logging.audit_max_file_bytes,
+                logging.audit_max_files,
+            ) {
+                Ok(audit_log) => {
+                    info!("Audit log: {} (1 response in {})", path, logging.audit_sample_rate);
+                    server.with_audit_log(audit_log)
+                }
+                Err(e) => {
+                    warn!("Failed to open audit log {}: {}", path, e);
+                    server
+                }
+            }
+        }
+        None => server,
+    };
+
+    // Total des requêtes servies persisté entre redémarrages
+    let server = match config.server.requests_count_file {
+        Some(ref path) => {
+            let request_count = RequestCountFile::open(
+                Path::new(path),
+                std::time::Duration::from_secs(config.server.requests_count_flush_secs),
+            );
+            info!("Request count file: {} ({} requests served so far)", path, request_count.base());
+            server.with_request_count_file(request_count)
+        }
+        None => server,
+    };
+
+    // Démarrer le serveur web
+    let web_bind = format!("{}:{}", config.webserver.bind_address, config.webserver.port);
+    info!("Starting web interface on http://{}", web_bind);
+    let mut web_server = WebServer::new(
+        web_bind,
+        Arc::clone(&stats_arc),
+        Arc::clone(&clock),
+    );
+    if let Some(history) = offset_history {
+        web_server = web_server.with_offset_history(history);
+    }
+    if let Some(ref timezone) = config.webserver.display_timezone {
+        // Déjà validé au chargement de la configuration
+        info!("Dashboard display timezone: {} (NTP always serves UTC)", timezone);
+        web_server = web_server.with_display_timezone(DisplayTimezone::parse(timezone)?);
+    }
+    if let Some(limiter) = server.rate_limiter() {
+        web_server = web_server.with_rate_limiter(limiter);
+    }
+    if let Some(ref token) = config.webserver.admin_token {
+        web_server = web_server.with_admin_token(token.clone());
+    }
+    web_server = web_server.with_request_policy(RequestPolicy::from_config(&config));
+    web_server = web_server.with_config_reloader(Arc::new(ConfigReloader::new(
+        config_path.clone(),
+        config.clone(),
+        server.rate_limiter(),
+    )));
+    if let Some(manual) = manual_clock {
+        if config.webserver.admin_token.is_none() {
+            warn!("Manual clock source without webserver.admin_token: PUT /api/clock/set is refused");
+        }
+        web_server = web_server.with_manual_clock(manual);
+    }
+    web_server = web_server.with_ws_backpressure(
+        config.webserver.ws_channel_capacity,
+        config.webserver.ws_max_consecutive_lags,
+    );
+    web_server = web_server.with_max_ws_connections(config.webserver.max_ws_connections);
+    web_server = web_server.with_skyplot_elevation_mask(config.webserver.skyplot_elevation_mask_deg);
+    web_server = web_server.with_static_root_delay_us(config.server.static_root_delay_us);
+    if config.webserver.realtime_server_health {
+        web_server = web_server.with_realtime_server_health();
+    }
+    if config.webserver.start_delay == "after_sync" {
+        web_server = web_server.with_start_after_sync(std::time::Duration::from_secs(
+            config.webserver.start_delay_timeout_secs,
+        ));
+    }
+    let _web_thread = web_server.start();
+
+    // Historique SNR des satellites (diagnostic d'antenne)
+    if let Some(ref snr_csv_file) = config.logging.snr_csv_file {
+        let recorder = SnrRecorder::new(
+            PathBuf::from(snr_csv_file),
+            std::time::Duration::from_secs(config.logging.snr_csv_interval_secs),
+            config.logging.snr_csv_max_bytes,
+            Arc::clone(&stats_arc),
+        );
+        let _snr_thread = recorder.start();
+    }
+
+    // Ligne de santé périodique de l'horloge
+    if config.logging.health_interval_secs > 0 {
+        let _health_thread = health_log::start(
+            std::time::Duration::from_secs(config.logging.health_interval_secs),
+            Arc::clone(&stats_arc),
+        );
+    }
+
+    // Gérer Ctrl+C : confirmation à double pression en terminal interactif,
+    // arrêt immédiat sous un gestionnaire de services
+    let shutdown_requested = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let shutdown_mode = ShutdownMode::select(
+        std::env::args().any(|arg| arg == NO_CONFIRM_SHUTDOWN_FLAG),
+        std::io::stdin().is_terminal(),
+    );
+    shutdown::install(shutdown_mode, Arc::clone(&shutdown_requested))?;
+
+    info!("Starting NTP server...");
+    info!("Web interface: http://localhost:8080");
+    match shutdown_mode {
+        ShutdownMode::Confirm => info!("Press Ctrl+C twice (within 5 seconds) to stop"),
+        ShutdownMode::Immediate => info!("Press Ctrl+C to stop"),
+    }
+
+    // Démarrer le serveur avec le flag shutdown
+    match server.run(Arc::clone(&shutdown_requested)) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            error!("Server error: {:#}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Crée une source d'horloge par son nom ("system", "gps" ou "phc"), en démarrant
+/// le lecteur GPS ou la mesure de l'offset PHC si nécessaire
+fn create_clock(
+    source: &str,
+    config: &Config,
+    stats_arc: &Arc<std::sync::RwLock<stats::ServerStats>>,
+) -> Arc<dyn ClockSource> {
+    match source {
         "system" => {
             info!("Using system clock");
             Arc::new(SystemClock::new())
@@ -50,13 +357,35 @@ fn main() -> Result<()> {
         "gps" => {
             if let Some(ref gps_config) = config.clock.gps {
                 info!("Using GPS clock");
+                if !config.clock.system_clock_trusted {
+                    info!("  System clock untrusted: waiting for GPS time before serving");
+                }
                 info!("  Enabled: {}", gps_config.enabled);
                 info!("  Serial port: {}", gps_config.serial_port);
-                info!("  Baud rate: {}", gps_config.baud_rate);
+                if gps_config.auto_baud {
+                    info!("  Baud rate: auto {:?}", gps_config.auto_baud_rates);
+                } else {
+                    info!("  Baud rate: {}", gps_config.baud_rate);
+                }
                 info!("  PPS via CTS: {}", gps_config.pps_enabled);
-                info!("  Min satellites: {}", gps_config.min_satellites);
-
-                let gps_clock = Arc::new(GpsNmeaClock::new(gps_config.sync_timeout));
+                if let Some(ref pps_log_file) = gps_config.pps_log_file {
+                    info!("  PPS log file: {}", pps_log_file);
+                }
+                info!("  Sync criteria: {:?}", gps_config.sync_criteria);
+
+                let gps_clock = Arc::new(
+                    GpsNmeaClock::new(gps_config.sync_timeout)
+                        .with_sync_criteria(gps_config.sync_criteria.clone())
+                        .with_dgps_refid(refid_from_str(&gps_config.dgps_refid))
+                        .with_pps_offset_max_age(gps_config.pps_offset_max_age_secs)
+                        .with_nmea_only_precision(gps_config.nmea_only_precision)
+                        .with_pps_offset_histogram(gps_config.pps_offset_histogram)
+                        .with_system_clock_trusted(config.clock.system_clock_trusted)
+                        .with_divergence_limit(
+                            config.clock.max_gps_system_divergence_ms.map(std::time::Duration::from_millis),
+                            config.clock.refuse_on_divergence,
+                        ),
+                );
 
                 // Démarrer le thread de lecture GPS si activé
                 if gps_config.enabled {
@@ -65,7 +394,7 @@ fn main() -> Result<()> {
                     let reader = GpsReader::new(
                         gps_config.clone(),
                         Arc::clone(&gps_clock),
-                        Arc::clone(&stats_arc),
+                        Arc::clone(stats_arc),
                     );
 
                     // Démarrer le thread GPS (avec reconnexion automatique)
@@ -88,91 +417,19 @@ fn main() -> Result<()> {
                 std::process::exit(1);
             }
         }
+        "phc" => {
+            info!("Using system clock with uncertainty measured against PHC {}", config.clock.phc_device);
+            let phc_clock = Arc::new(
+                PhcClock::new(&config.clock.phc_device, config.clock.phc_samples)
+                    .with_utc_offset(config.clock.phc_utc_offset_secs),
+            );
+            let _phc_thread = phc_clock.start(Arc::clone(stats_arc));
+            phc_clock as Arc<dyn ClockSource>
+        }
         _ => {
-            error!("Unknown clock source: {}", config.clock.source);
+            error!("Unknown clock source: {}", source);
             std::process::exit(1);
         }
-    };
-
-    // Afficher les infos de l'horloge
-    info!("Clock information:");
-    info!("  Stratum: {}", clock.stratum());
-    info!("  Precision: 2^{} seconds", clock.precision());
-    info!(
-        "  Reference ID: {}",
-        String::from_utf8_lossy(&clock.reference_id())
-    );
-
-    // Initialiser les infos d'horloge dans les stats
-    stats_manager.update_clock(|clock_info| {
-        clock_info.stratum = clock.stratum();
-        clock_info.reference_id = String::from_utf8_lossy(&clock.reference_id()).to_string();
-        clock_info.precision = clock.precision();
-    });
-
-    // Démarrer le serveur web
-    let web_bind = format!("{}:{}", config.webserver.bind_address, config.webserver.port);
-    info!("Starting web interface on http://{}", web_bind);
-    let web_server = WebServer::new(
-        web_bind,
-        Arc::clone(&stats_arc),
-        Arc::clone(&clock),
-    );
-    let _web_thread = web_server.start();
-
-    // Gérer Ctrl+C avec confirmation à double pression
-    let shutdown_requested = Arc::new(std::sync::atomic::AtomicBool::new(false));
-    let ctrl_c_count = Arc::new(std::sync::atomic::AtomicU8::new(0));
-
-    let shutdown_clone = Arc::clone(&shutdown_requested);
-    let count_clone = Arc::clone(&ctrl_c_count);
-
-    ctrlc::set_handler(move || {
-        let count = count_clone.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-
-        if count == 0 {
-            // Première pression
-            warn!("Ctrl+C détecté. Appuyez à nouveau dans les 5 secondes pour arrêter le serveur.");
-
-            // Thread qui désamorce après 5 secondes
-            let count_disarm = Arc::clone(&count_clone);
-            std::thread::spawn(move || {
-                std::thread::sleep(std::time::Duration::from_secs(5));
-                let current = count_disarm.load(std::sync::atomic::Ordering::SeqCst);
-                if current == 1 {
-                    // Pas de deuxième pression, désamorcer
-                    count_disarm.store(0, std::sync::atomic::Ordering::SeqCst);
-                    info!("Arrêt annulé. Le serveur continue.");
-                }
-            });
-        } else {
-            // Deuxième pression (ou plus)
-            warn!("Arrêt confirmé. Fermeture du serveur...");
-            shutdown_clone.store(true, std::sync::atomic::Ordering::SeqCst);
-            // Forcer la sortie si le serveur ne répond pas après 2 secondes
-            std::thread::spawn(|| {
-                std::thread::sleep(std::time::Duration::from_secs(2));
-                error!("Arrêt forcé (timeout)");
-                std::process::exit(0);
-            });
-        }
-    })
-    .context("Failed to set Ctrl+C handler")?;
-
-    // Créer et démarrer le serveur NTP avec le flag shutdown
-    let server = NtpServer::new(config, clock, Arc::clone(&stats_arc));
-
-    info!("Starting NTP server...");
-    info!("Web interface: http://localhost:8080");
-    info!("Press Ctrl+C twice (within 5 seconds) to stop");
-
-    // Démarrer le serveur avec le flag shutdown
-    match server.run(Arc::clone(&shutdown_requested)) {
-        Ok(_) => Ok(()),
-        Err(e) => {
-            error!("Server error: {:#}", e);
-            Err(e)
-        }
     }
 }
 
@@ -182,8 +439,15 @@ fn init_logging() -> Result<()> {
         .or_else(|_| EnvFilter::try_new("info"))
         .context("Failed to create log filter")?;
 
+    // Couche fichier : écrit dès que le fichier est ouvert (après lecture de la configuration)
     tracing_subscriber::registry()
         .with(fmt::layer().with_target(false).with_thread_ids(false))
+        .with(
+            fmt::layer()
+                .with_target(false)
+                .with_ansi(false)
+                .with_writer(log_file::LogFileWriter),
+        )
         .with(filter)
         .init();
 
@@ -192,10 +456,9 @@ fn init_logging() -> Result<()> {
 
 /// Obtient le chemin du fichier de configuration
 fn get_config_path() -> PathBuf {
-    // Vérifier les arguments de ligne de commande
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() > 1 {
-        return PathBuf::from(&args[1]);
+    // Vérifier les arguments de ligne de commande (premier argument hors options)
+    if let Some(path) = std::env::args().skip(1).find(|arg| !arg.starts_with("--")) {
+        return PathBuf::from(path);
     }
 
     // Sinon, utiliser le chemin par défaut
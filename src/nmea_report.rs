@@ -0,0 +1,272 @@
+/*!
+Rapport de diagnostic d'une capture NMEA (`pendulum --validate-nmea <fichier>`)
+
+Passe les parseurs du lecteur GPS sur un fichier capturé, sans ouvrir de port
+série ni de socket, et affiche :
+
+- le nombre de trames par type (`GPRMC`, `GPGSV`, `PUBX`...) ;
+- le taux d'échec des checksums ;
+- les satellites vus par constellation (trames GSV) ;
+- l'intervalle de temps couvert (trames RMC valides) ;
+- les trames rejetées par les parseurs, avec la raison.
+
+Un dump collé dans une demande de support devient ainsi un rapport partageable.
+*/
+
+use crate::gps_reader::{nmea_checksum_valid, parse_gpgga, parse_gprmc, parse_gsv};
+use crate::leap_seconds::ntp_seconds_to_iso;
+use crate::packet::NtpTimestamp;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::Path;
+
+/// Nombre maximal de trames rejetées détaillées dans le rapport
+const MAX_LISTED_FAILURES: usize = 20;
+
+/// Marque d'ordre des octets UTF-8 (début de fichier ou ponts USB-série)
+const UTF8_BOM: char = '\u{FEFF}';
+
+/// Trame rejetée par un parseur
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFailure {
+    /// Numéro de ligne dans le fichier (à partir de 1)
+    pub line: usize,
+    pub sentence: String,
+    pub reason: &'static str,
+}
+
+/// Rapport de diagnostic d'une capture NMEA
+#[derive(Debug, Default)]
+pub struct NmeaReport {
+    /// Lignes non vides lues
+    pub lines: usize,
+
+    /// Trames au checksum valide, par type (`GPRMC`, `GNGGA`...)
+    pub sentence_counts: BTreeMap<String, usize>,
+
+    /// Trames `$...` au checksum absent ou faux
+    pub checksum_failures: usize,
+
+    /// Trames RMC valides mais sans fix (statut V)
+    pub rmc_without_fix: usize,
+
+    /// PRN des satellites vus, par constellation
+    pub satellites: BTreeMap<String, BTreeSet<u8>>,
+
+    /// Premier et dernier instant des trames RMC valides
+    pub first_time: Option<NtpTimestamp>,
+    pub last_time: Option<NtpTimestamp>,
+
+    pub failures: Vec<ParseFailure>,
+}
+
+impl NmeaReport {
+    /// Analyse le contenu d'une capture (fins de ligne `\r\n`, `\n` ou `\r`)
+    pub fn analyze(text: &str) -> Self {
+        let mut report = NmeaReport::default();
+
+        for (index, line) in text.lines().enumerate() {
+            for raw in line.split('\r') {
+                let sentence = raw.trim_start_matches(UTF8_BOM).trim();
+                if sentence.is_empty() {
+                    continue;
+                }
+                report.lines += 1;
+                report.analyze_sentence(index + 1, sentence);
+            }
+        }
+
+        report
+    }
+
+    fn analyze_sentence(&mut self, line: usize, sentence: &str) {
+        let Some(body) = sentence.strip_prefix('$') else {
+            self.fail(line, sentence, "not an NMEA sentence (missing '$')");
+            return;
+        };
+        if !body.contains('*') {
+            self.checksum_failures += 1;
+            self.fail(line, sentence, "missing checksum");
+            return;
+        }
+        if !nmea_checksum_valid(sentence) {
+            self.checksum_failures += 1;
+            self.fail(line, sentence, "checksum mismatch");
+            return;
+        }
+
+        let kind = body.split([',', '*']).next().unwrap_or_default().to_string();
+        *self.sentence_counts.entry(kind.clone()).or_default() += 1;
+
+        match kind.get(2..).unwrap_or_default() {
+            "RMC" => match parse_gprmc(sentence) {
+                Some(timestamp) => {
+                    if self.first_time.is_none_or(|first| timestamp.0 < first.0) {
+                        self.first_time = Some(timestamp);
+                    }
+                    if self.last_time.is_none_or(|last| timestamp.0 > last.0) {
+                        self.last_time = Some(timestamp);
+                    }
+                }
+                None if sentence.split(',').nth(2) == Some("V") => self.rmc_without_fix += 1,
+                None => self.fail(line, sentence, "unreadable RMC time or date"),
+            },
+            "GGA" if parse_gpgga(sentence).is_none() => {
+                self.fail(line, sentence, "unreadable GGA fix quality or satellite count");
+            }
+            "GSV" => match parse_gsv(sentence) {
+                Some((satellites, malformed)) => {
+                    for satellite in satellites {
+                        self.satellites.entry(satellite.constellation).or_default().insert(satellite.prn);
+                    }
+                    if malformed > 0 {
+                        self.fail(line, sentence, "GSV satellite elevation, azimuth or SNR out of range");
+                    }
+                }
+                None => self.fail(line, sentence, "truncated GSV sentence"),
+            },
+            _ => {}
+        }
+    }
+
+    fn fail(&mut self, line: usize, sentence: &str, reason: &'static str) {
+        self.failures.push(ParseFailure {
+            line,
+            sentence: sentence.to_string(),
+            reason,
+        });
+    }
+
+    /// Nombre de trames `$...` (checksum valide ou non)
+    fn nmea_sentences(&self) -> usize {
+        self.sentence_counts.values().sum::<usize>() + self.checksum_failures
+    }
+
+    /// Taux d'échec des checksums (0 à 1)
+    pub fn checksum_failure_rate(&self) -> f64 {
+        match self.nmea_sentences() {
+            0 => 0.0,
+            total => self.checksum_failures as f64 / total as f64,
+        }
+    }
+}
+
+impl fmt::Display for NmeaReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Lines: {}", self.lines)?;
+        writeln!(
+            f,
+            "NMEA sentences: {} ({} checksum failures, {:.2}%)",
+            self.nmea_sentences(),
+            self.checksum_failures,
+            self.checksum_failure_rate() * 100.0
+        )?;
+
+        writeln!(f, "\nSentence types:")?;
+        for (kind, count) in &self.sentence_counts {
+            writeln!(f, "  {:<8} {}", kind, count)?;
+        }
+        if self.rmc_without_fix > 0 {
+            writeln!(f, "  ({} RMC sentences without fix)", self.rmc_without_fix)?;
+        }
+
+        writeln!(f, "\nSatellites in view:")?;
+        if self.satellites.is_empty() {
+            writeln!(f, "  none (no GSV sentence)")?;
+        }
+        for (constellation, prns) in &self.satellites {
+            let list: Vec<String> = prns.iter().map(u8::to_string).collect();
+            writeln!(f, "  {:<8} {} ({})", constellation, prns.len(), list.join(", "))?;
+        }
+
+        writeln!(f, "\nTime span:")?;
+        match (self.first_time, self.last_time) {
+            (Some(first), Some(last)) => {
+                let iso = |timestamp: NtpTimestamp| ntp_seconds_to_iso(timestamp.seconds() as u64).unwrap_or_default();
+                writeln!(
+                    f,
+                    "  {} to {} ({:.0} s)",
+                    iso(first),
+                    iso(last),
+                    last.seconds_since(first)
+                )?;
+            }
+            _ => writeln!(f, "  unknown (no valid RMC sentence)")?,
+        }
+
+        writeln!(f, "\nParse failures: {}", self.failures.len())?;
+        for failure in self.failures.iter().take(MAX_LISTED_FAILURES) {
+            writeln!(f, "  line {}: {} — {}", failure.line, failure.reason, failure.sentence)?;
+        }
+        if self.failures.len() > MAX_LISTED_FAILURES {
+            writeln!(f, "  ... and {} more", self.failures.len() - MAX_LISTED_FAILURES)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lit une capture NMEA et affiche son rapport de diagnostic
+pub fn run(path: &Path) -> anyhow::Result<()> {
+    let bytes = std::fs::read(path).map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let report = NmeaReport::analyze(&String::from_utf8_lossy(&bytes));
+
+    println!("NMEA capture: {}\n", path.display());
+    print!("{}", report);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Extrait d'une capture u-blox (deux secondes), avec une ligne corrompue
+    /// à la transmission et une trame GSV hors plage
+    const CAPTURE: &str = "\u{FEFF}$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,160926,003.1,W*6F\r\n\
+        $GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n\
+        $GPGSV,2,1,08,01,40,083,46,02,17,308,41,12,07,344,39,14,22,228,45*75\r\n\
+        $GLGSV,1,1,02,65,30,100,38,66,45,200,40*6A\r\n\
+        $GPGSV,2,2,08,15,95,100,30*4B\r\n\
+        $GPRMC,123520,A,4807.038,N,01131.000,E,022.4,084.4,160926,003.1,W*65\r\n\
+        $GPGGA,123520,4807.0\x01\r\n\
+        $GPGGA,123520,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*00\r\n";
+
+    #[test]
+    fn test_validate_nmea_capture() {
+        let path = std::env::temp_dir().join(format!("pendulum-capture-{}.nmea", std::process::id()));
+        std::fs::write(&path, CAPTURE).unwrap();
+        run(&path).unwrap();
+
+        let report = NmeaReport::analyze(&String::from_utf8_lossy(&std::fs::read(&path).unwrap()));
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(report.lines, 8);
+        assert_eq!(report.sentence_counts["GPRMC"], 2);
+        assert_eq!(report.sentence_counts["GPGGA"], 1);
+        assert_eq!(report.sentence_counts["GPGSV"], 2);
+        assert_eq!(report.sentence_counts["GLGSV"], 1);
+
+        // Ligne tronquée (sans checksum) et checksum faux sur 8 trames
+        assert_eq!(report.checksum_failures, 2);
+        assert!((report.checksum_failure_rate() - 0.25).abs() < 1e-9);
+
+        assert_eq!(report.satellites["GPS"], BTreeSet::from([1, 2, 12, 14]));
+        assert_eq!(report.satellites["GLONASS"], BTreeSet::from([65, 66]));
+
+        let span = report.last_time.unwrap().seconds_since(report.first_time.unwrap());
+        assert!((span - 1.0).abs() < 1e-6);
+
+        let reasons: Vec<(usize, &str)> = report.failures.iter().map(|f| (f.line, f.reason)).collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (5, "GSV satellite elevation, azimuth or SNR out of range"),
+                (7, "missing checksum"),
+                (8, "checksum mismatch"),
+            ]
+        );
+        let rendered = report.to_string();
+        assert!(rendered.contains("GLONASS  2 (65, 66)"));
+        assert!(rendered.contains("2026-09-16T12:35:19Z to 2026-09-16T12:35:20Z (1 s)"));
+    }
+}
@@ -1,6 +1,7 @@
 use thiserror::Error;
 
 /// Erreurs liées au parsing des paquets NTP
+#[allow(clippy::enum_variant_names)]
 #[derive(Error, Debug)]
 pub enum NtpError {
     #[error("Invalid packet size: expected {expected}, got {actual}")]
@@ -66,6 +67,14 @@ impl NtpMode {
     }
 }
 
+/// Différence entre l'epoch NTP (1900-01-01) et l'epoch Unix (1970-01-01) en secondes
+///
+/// Seule définition du décalage : toute conversion NTP/Unix doit y faire référence.
+pub const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+// 70 années de 365 jours et 17 années bissextiles (1904 à 1968, 1900 ne l'est pas)
+const _: () = assert!(NTP_UNIX_OFFSET == (70 * 365 + 17) * 86_400);
+
 /// Structure représentant un timestamp NTP (64 bits)
 /// Format: 32 bits de secondes + 32 bits de fraction
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -99,6 +108,124 @@ impl NtpTimestamp {
     pub fn fraction(&self) -> u32 {
         self.0 as u32
     }
+
+    /// Écart signé en secondes entre ce timestamp et un timestamp antérieur
+    /// (soustraction modulo 2^64, donc correcte au passage d'ère NTP)
+    pub fn seconds_since(&self, earlier: NtpTimestamp) -> f64 {
+        self.0.wrapping_sub(earlier.0) as i64 as f64 / (1u64 << 32) as f64
+    }
+
+    /// Arrondit la fraction au plus proche en ne conservant que ses `bits` bits de poids fort
+    /// (les bits de poids faible sont mis à zéro ; l'arrondi, et non la troncature,
+    /// évite de biaiser le temps annoncé vers le passé)
+    pub fn rounded_to_bits(self, bits: u8) -> Self {
+        if bits >= 32 {
+            return self;
+        }
+
+        let dropped = 32 - bits as u32;
+        let half = 1u64 << (dropped - 1);
+        let mask = !((1u64 << dropped) - 1);
+        NtpTimestamp(self.0.wrapping_add(half) & mask)
+    }
+}
+
+/// Champ d'extension NTPv4 (RFC 7822) : type et longueur sur 2 octets chacun,
+/// puis la valeur complétée de zéros (longueur totale multiple de 4)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionField {
+    pub field_type: u16,
+    pub value: Vec<u8>,
+}
+
+impl ExtensionField {
+    /// Longueur minimale d'un dernier champ d'extension sans MAC (RFC 7822 §7.5)
+    pub const MIN_LAST_LEN: usize = 28;
+
+    /// Type expérimental (hors registre IANA) de l'incertitude du serveur
+    pub const UNCERTAINTY_TYPE: u16 = 0xF5E1;
+
+    /// Incertitude temporelle mesurée par le serveur : borne d'erreur en
+    /// nanosecondes (u64 big-endian)
+    pub fn uncertainty(error_bound_ns: u64) -> Self {
+        ExtensionField {
+            field_type: Self::UNCERTAINTY_TYPE,
+            value: error_bound_ns.to_be_bytes().to_vec(),
+        }
+    }
+
+    /// Encode le champ (en-tête de 4 octets, valeur, remplissage)
+    pub fn encode(&self) -> Vec<u8> {
+        let len = (4 + self.value.len()).next_multiple_of(4).max(Self::MIN_LAST_LEN);
+
+        let mut bytes = Vec::with_capacity(len);
+        bytes.extend_from_slice(&self.field_type.to_be_bytes());
+        bytes.extend_from_slice(&(len as u16).to_be_bytes());
+        bytes.extend_from_slice(&self.value);
+        bytes.resize(len, 0);
+        bytes
+    }
+}
+
+/// Code d'authentification (RFC 5905 §7.3) : identifiant de clé puis condensé
+/// MD5 (16 octets) ou SHA-1 (20 octets) de la clé suivie de l'en-tête
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mac {
+    pub key_id: u32,
+    digest: [u8; Mac::MAX_DIGEST_LEN],
+    digest_len: u8,
+}
+
+impl Mac {
+    /// Longueur maximale d'un condensé (SHA-1)
+    pub const MAX_DIGEST_LEN: usize = 20;
+
+    /// Longueurs d'un MAC complet (identifiant compris) : MD5 puis SHA-1
+    const LENGTHS: [usize; 2] = [4 + 16, 4 + Self::MAX_DIGEST_LEN];
+
+    /// MAC d'un condensé (tronqué à `MAX_DIGEST_LEN` octets)
+    pub fn new(key_id: u32, digest: &[u8]) -> Self {
+        let digest_len = digest.len().min(Self::MAX_DIGEST_LEN);
+        let mut bytes = [0u8; Self::MAX_DIGEST_LEN];
+        bytes[..digest_len].copy_from_slice(&digest[..digest_len]);
+        Mac {
+            key_id,
+            digest: bytes,
+            digest_len: digest_len as u8,
+        }
+    }
+
+    pub fn digest(&self) -> &[u8] {
+        &self.digest[..self.digest_len as usize]
+    }
+
+    /// Longueur du MAC dans le paquet (identifiant compris) ; il en occupe
+    /// toujours la fin, et porte sur tout ce qui le précède
+    pub fn wire_len(&self) -> usize {
+        4 + self.digest_len as usize
+    }
+
+    /// MAC suivant l'en-tête, après d'éventuels champs d'extension
+    ///
+    /// Comme le prévoit la RFC 7822 §7.5, 20 ou 24 octets restant après les
+    /// champs d'extension désignent un MAC ; sinon, les champs sont parcourus
+    /// (longueur d'au moins 16 octets, multiple de 4) jusqu'à la fin du paquet.
+    fn after_extensions(mut rest: &[u8]) -> Option<Self> {
+        loop {
+            if Self::LENGTHS.contains(&rest.len()) {
+                let key_id = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]);
+                return Some(Mac::new(key_id, &rest[4..]));
+            }
+            if rest.len() < 16 {
+                return None;
+            }
+            let field_len = u16::from_be_bytes([rest[2], rest[3]]) as usize;
+            if field_len < 16 || !field_len.is_multiple_of(4) || field_len > rest.len() {
+                return None;
+            }
+            rest = &rest[field_len..];
+        }
+    }
 }
 
 /// Structure du paquet NTP (48 octets)
@@ -135,6 +262,9 @@ pub struct NtpPacket {
 
     // Octets 40-47
     pub transmit_timestamp: NtpTimestamp,
+
+    // Octets 48-67 (MD5) ou 48-71 (SHA-1) : authentification optionnelle
+    pub mac: Option<Mac>,
 }
 
 impl NtpPacket {
@@ -157,6 +287,7 @@ impl NtpPacket {
             originate_timestamp: NtpTimestamp::default(),
             receive_timestamp: NtpTimestamp::default(),
             transmit_timestamp: NtpTimestamp::default(),
+            mac: None,
         }
     }
 
@@ -176,7 +307,7 @@ impl NtpPacket {
         let mode = NtpMode::from_u8(li_vn_mode & 0x07)?;
 
         // Validation de la version (accepter NTPv1 à v4 pour compatibilité)
-        if version < 1 || version > 4 {
+        if !(1..=4).contains(&version) {
             return Err(NtpError::InvalidVersion(version));
         }
 
@@ -224,11 +355,13 @@ impl NtpPacket {
             originate_timestamp,
             receive_timestamp,
             transmit_timestamp,
+            mac: Mac::after_extensions(&bytes[Self::SIZE..]),
         })
     }
 
-    /// Convertit le paquet en bytes pour transmission (big-endian)
-    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+    /// Convertit le paquet en bytes pour transmission (big-endian) : en-tête de
+    /// 48 octets, sur lequel porte le MAC éventuel (voir `to_bytes_with_mac`)
+    pub fn to_bytes(self) -> [u8; Self::SIZE] {
         let mut bytes = [0u8; Self::SIZE];
 
         // Octet 0: LI + VN + Mode
@@ -265,6 +398,33 @@ impl NtpPacket {
         bytes
     }
 
+    /// Sérialise le paquet suivi de son MAC (identifiant de clé puis condensé)
+    pub fn to_bytes_with_mac(self) -> Vec<u8> {
+        let mut bytes = self.to_bytes().to_vec();
+        if let Some(mac) = self.mac {
+            bytes.extend_from_slice(&mac.key_id.to_be_bytes());
+            bytes.extend_from_slice(mac.digest());
+        }
+        bytes
+    }
+
+    /// Sérialise le paquet complété par des octets nuls jusqu'à `len` octets
+    /// (au moins `SIZE`), pour réserver la place des extensions/MAC de la requête
+    pub fn to_bytes_padded(self, len: usize) -> Vec<u8> {
+        let mut bytes = self.to_bytes().to_vec();
+        bytes.resize(len.max(Self::SIZE), 0);
+        bytes
+    }
+
+    /// Sérialise le paquet suivi de champs d'extension
+    pub fn to_bytes_with_extensions(self, extensions: &[ExtensionField]) -> Vec<u8> {
+        let mut bytes = self.to_bytes().to_vec();
+        for extension in extensions {
+            bytes.extend_from_slice(&extension.encode());
+        }
+        bytes
+    }
+
     /// Valide qu'il s'agit d'une requête client valide
     #[allow(dead_code)]
     pub fn is_valid_client_request(&self) -> bool {
@@ -279,6 +439,29 @@ impl NtpPacket {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_rounded_to_bits() {
+        let ts = NtpTimestamp((3_900_000_000u64 << 32) | 0x1234_5678);
+
+        // 16 bits conservés : les 16 bits de poids faible sont nuls
+        let rounded = ts.rounded_to_bits(16);
+        assert_eq!(rounded.0 & 0xFFFF, 0);
+        assert_eq!(rounded.fraction(), 0x1234_0000);
+
+        // Arrondi au plus proche, pas troncature
+        let ts = NtpTimestamp((3_900_000_000u64 << 32) | 0x1234_8000);
+        assert_eq!(ts.rounded_to_bits(16).fraction(), 0x1235_0000);
+
+        // La retenue se propage aux secondes
+        let ts = NtpTimestamp((3_900_000_000u64 << 32) | 0xFFFF_F800);
+        let rounded = ts.rounded_to_bits(20);
+        assert_eq!(rounded.seconds(), 3_900_000_001);
+        assert_eq!(rounded.fraction(), 0);
+
+        // 32 bits : pleine résolution, inchangé
+        assert_eq!(ts.rounded_to_bits(32), ts);
+    }
+
     #[test]
     fn test_ntp_timestamp_conversion() {
         let ts = NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 500_000_000);
@@ -299,5 +482,37 @@ mod tests {
         assert_eq!(parsed.version, 4);
         assert_eq!(parsed.mode, NtpMode::Server);
         assert_eq!(parsed.stratum, 1);
+        assert_eq!(parsed.mac, None);
+    }
+
+    #[test]
+    fn test_mac_round_trip() {
+        let mut packet = NtpPacket::new_server_response();
+        packet.mac = Some(Mac::new(42, &[0xAB; 16]));
+
+        // MD5 : 48 + 4 + 16 octets
+        let bytes = packet.to_bytes_with_mac();
+        assert_eq!(bytes.len(), 68);
+        assert_eq!(&bytes[48..52], &42u32.to_be_bytes());
+        let parsed = NtpPacket::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed.mac, packet.mac);
+        assert_eq!(parsed.to_bytes(), packet.to_bytes());
+
+        // SHA-1 : 48 + 4 + 20 octets
+        packet.mac = Some(Mac::new(7, &[0xCD; 20]));
+        let parsed = NtpPacket::from_bytes(&packet.to_bytes_with_mac()).unwrap();
+        assert_eq!(parsed.mac.unwrap().digest(), &[0xCD; 20]);
+
+        // Autre longueur : champs d'extension, pas de MAC
+        let extended = packet.to_bytes_with_extensions(&[ExtensionField::uncertainty(5)]);
+        assert_eq!(NtpPacket::from_bytes(&extended).unwrap().mac, None);
+
+        // Champs d'extension suivis d'un MAC : MAC détecté en fin de paquet
+        let mut signed = extended.clone();
+        signed.extend_from_slice(&7u32.to_be_bytes());
+        signed.extend_from_slice(&[0xEF; 20]);
+        let mac = NtpPacket::from_bytes(&signed).unwrap().mac.unwrap();
+        assert_eq!((mac.key_id, mac.digest()), (7, &[0xEF; 20][..]));
+        assert_eq!(mac.wire_len(), signed.len() - extended.len());
     }
 }
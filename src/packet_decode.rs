@@ -0,0 +1,235 @@
+/*!
+Décodeur de paquets NTP pour le diagnostic (`POST /api/decode`)
+
+Un paquet capturé (tcpdump, Wireshark), collé en hexadécimal ou en base64,
+passe par les mêmes étapes que sur le chemin de réponse, avec les options du
+serveur (`RequestPolicy`) : version 0, `NtpPacket::from_bytes`, mode 1 servi
+en mode serveur, `PacketValidator::validate_request` puis port source 123.
+Le rapport donne les champs décodés (timestamps bruts et en UTC) et la raison
+du rejet éventuel, sans rien envoyer.
+
+L'adresse de l'émetteur (`source`, facultative) permet d'évaluer les règles
+qui en dépendent : sans elle, le port source n'est pas vérifié et l'émetteur
+n'est un pair symétrique que si `server.symmetric_peers` est vide.
+*/
+
+use crate::config::Config;
+use crate::packet::{NtpMode, NtpPacket, NtpTimestamp, NTP_UNIX_OFFSET};
+use crate::security::{IpNetwork, PacketValidator};
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+/// Port NTP standard (règle `security.reject_source_port_123`)
+const NTP_PORT: u16 = 123;
+
+/// Corps de POST /api/decode : paquet en hexadécimal ou en base64
+#[derive(Debug, Deserialize)]
+pub struct DecodeRequest {
+    #[serde(default)]
+    pub hex: Option<String>,
+
+    #[serde(default)]
+    pub base64: Option<String>,
+
+    /// Émetteur du paquet capturé (`ip:port`)
+    #[serde(default)]
+    pub source: Option<SocketAddr>,
+}
+
+/// Options du serveur qui décident de l'acceptation d'une requête
+#[derive(Debug, Clone, Default)]
+pub struct RequestPolicy {
+    /// `security.accept_version_zero`
+    pub accept_version_zero: bool,
+
+    /// Pairs symétriques (`server.symmetric_peers`, vide = tous), `None` sans
+    /// `server.allow_symmetric`
+    pub symmetric_peers: Option<Vec<IpNetwork>>,
+
+    /// `security.answer_symmetric_as_server`
+    pub answer_symmetric_as_server: bool,
+
+    /// `security.reject_source_port_123`
+    pub reject_source_port_123: bool,
+}
+
+impl RequestPolicy {
+    /// Politique du serveur NTP lancé avec cette configuration (CIDR déjà validés)
+    pub fn from_config(config: &Config) -> Self {
+        RequestPolicy {
+            accept_version_zero: config.security.accept_version_zero,
+            symmetric_peers: config
+                .server
+                .allow_symmetric
+                .then(|| config.server.symmetric_peers.iter().filter_map(|peer| peer.parse().ok()).collect()),
+            answer_symmetric_as_server: config.security.answer_symmetric_as_server,
+            reject_source_port_123: config.security.reject_source_port_123,
+        }
+    }
+
+    /// Vrai si l'émetteur est un pair symétrique autorisé
+    fn is_peer(&self, source: Option<SocketAddr>) -> bool {
+        match (&self.symmetric_peers, source) {
+            (None, _) => false,
+            (Some(peers), _) if peers.is_empty() => true,
+            (Some(peers), Some(source)) => peers.iter().any(|network| network.contains(source.ip())),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+impl DecodeRequest {
+    /// Octets du paquet (espaces et `:` ignorés en hexadécimal)
+    pub fn bytes(&self) -> Result<Vec<u8>, String> {
+        match (&self.hex, &self.base64) {
+            (Some(hex), None) => decode_hex(hex),
+            (None, Some(encoded)) => base64::engine::general_purpose::STANDARD
+                .decode(encoded.trim())
+                .map_err(|e| format!("invalid base64: {}", e)),
+            _ => Err("exactly one of 'hex' or 'base64' is required".to_string()),
+        }
+    }
+}
+
+fn decode_hex(text: &str) -> Result<Vec<u8>, String> {
+    let digits: Vec<u8> = text.bytes().filter(|b| !b.is_ascii_whitespace() && *b != b':').collect();
+    if !digits.len().is_multiple_of(2) {
+        return Err("invalid hex: odd number of digits".to_string());
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| "invalid hex: non-hexadecimal digit".to_string())
+        })
+        .collect()
+}
+
+/// Timestamp NTP en UTC (RFC 3339, microsecondes), `None` pour un timestamp nul
+pub fn ntp_timestamp_to_rfc3339(timestamp: NtpTimestamp) -> Option<String> {
+    if timestamp.0 == 0 {
+        return None;
+    }
+    let nanos = ((timestamp.fraction() as u64 * 1_000_000_000) >> 32) as u32;
+    DateTime::from_timestamp(timestamp.seconds() as i64 - NTP_UNIX_OFFSET as i64, nanos)
+        .map(|utc| utc.to_rfc3339_opts(SecondsFormat::Micros, true))
+}
+
+/// Timestamp décodé : valeur 32.32 brute et forme lisible
+#[derive(Debug, Serialize)]
+pub struct DecodedTimestamp {
+    /// Valeur brute en hexadécimal (`0xSSSSSSSSFFFFFFFF`)
+    pub raw: String,
+    pub utc: Option<String>,
+}
+
+impl From<NtpTimestamp> for DecodedTimestamp {
+    fn from(timestamp: NtpTimestamp) -> Self {
+        DecodedTimestamp {
+            raw: format!("0x{:016x}", timestamp.0),
+            utc: ntp_timestamp_to_rfc3339(timestamp),
+        }
+    }
+}
+
+/// Champs de l'en-tête NTP
+#[derive(Debug, Serialize)]
+pub struct DecodedPacket {
+    pub leap_indicator: u8,
+    pub version: u8,
+    pub mode: String,
+    pub stratum: u8,
+    pub poll: i8,
+    pub precision: i8,
+    pub root_delay: u32,
+    pub root_dispersion: u32,
+
+    /// Reference ID en hexadécimal (`0x47505300` pour "GPS")
+    pub reference_id: String,
+    pub reference_timestamp: DecodedTimestamp,
+    pub originate_timestamp: DecodedTimestamp,
+    pub receive_timestamp: DecodedTimestamp,
+    pub transmit_timestamp: DecodedTimestamp,
+}
+
+/// Rapport de décodage
+#[derive(Debug, Serialize)]
+pub struct DecodeReport {
+    /// Longueur reçue (octets, extensions et MAC compris)
+    pub length: usize,
+
+    /// Champs décodés (absents si le paquet est illisible)
+    pub packet: Option<DecodedPacket>,
+
+    /// Requête acceptée par la validation du serveur
+    pub valid: bool,
+
+    /// Raison du rejet
+    pub reason: Option<String>,
+}
+
+/// Décode et valide un paquet comme le ferait le serveur avec cette politique
+pub fn decode(bytes: &[u8], policy: &RequestPolicy, source: Option<SocketAddr>) -> DecodeReport {
+    // Version 0 traitée comme une version 4 si autorisé
+    let mut bytes = bytes.to_vec();
+    if policy.accept_version_zero && bytes.first().is_some_and(|b| (b >> 3) & 0x07 == 0) {
+        bytes[0] |= 4 << 3;
+    }
+
+    let packet = match NtpPacket::from_bytes(&bytes) {
+        Ok(packet) => packet,
+        Err(e) => {
+            return DecodeReport {
+                length: bytes.len(),
+                packet: None,
+                valid: false,
+                reason: Some(e.to_string()),
+            }
+        }
+    };
+
+    let mut request = packet;
+    let symmetric_as_client = policy.answer_symmetric_as_server
+        && request.mode == NtpMode::SymmetricActive
+        && !policy.is_peer(source);
+    if symmetric_as_client {
+        request.mode = NtpMode::Client;
+    }
+
+    let reason = match PacketValidator::validate_request(&request, policy.symmetric_peers.is_some()) {
+        Err(e) => Some(e.to_string()),
+        Ok(()) if policy.reject_source_port_123
+            && request.mode == NtpMode::Client
+            && !symmetric_as_client
+            && source.is_some_and(|source| source.port() == NTP_PORT) =>
+        {
+            Some("Client request from source port 123 (security.reject_source_port_123)".to_string())
+        }
+        Ok(()) => None,
+    };
+
+    DecodeReport {
+        length: bytes.len(),
+        packet: Some(DecodedPacket {
+            leap_indicator: packet.leap_indicator as u8,
+            version: packet.version,
+            mode: format!("{:?}", packet.mode),
+            stratum: packet.stratum,
+            poll: packet.poll,
+            precision: packet.precision,
+            root_delay: packet.root_delay,
+            root_dispersion: packet.root_dispersion,
+            reference_id: format!("0x{:08x}", packet.reference_identifier),
+            reference_timestamp: packet.reference_timestamp.into(),
+            originate_timestamp: packet.originate_timestamp.into(),
+            receive_timestamp: packet.receive_timestamp.into(),
+            transmit_timestamp: packet.transmit_timestamp.into(),
+        }),
+        valid: reason.is_none(),
+        reason,
+    }
+}
@@ -0,0 +1,85 @@
+/*!
+Isolation des paniques des sous-systèmes (threads GPS, web, enregistreurs...)
+
+Un thread qui panique meurt silencieusement si son `JoinHandle` est ignoré :
+le serveur continuerait alors à servir l'horloge de repli sans que rien ne
+signale la perte du GPS. Chaque corps de thread est donc exécuté sous
+`catch_unwind` : la panique est journalisée en erreur et comptée dans les
+statistiques partagées (`subsystem_panics`, `last_panic`), et l'appelant
+décide de relancer le sous-système.
+*/
+
+use crate::stats::ServerStats;
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, RwLock};
+use tracing::error;
+
+/// Exécute `f` en interceptant une éventuelle panique
+///
+/// Retourne `Some(résultat)` si `f` s'est terminé normalement, `None` s'il a paniqué.
+pub fn run_guarded<F, R>(subsystem: &str, stats: &Arc<RwLock<ServerStats>>, f: F) -> Option<R>
+where
+    F: FnOnce() -> R,
+{
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => Some(result),
+        Err(payload) => {
+            let message = panic_message(payload.as_ref());
+            error!("Subsystem '{}' panicked: {}", subsystem, message);
+
+            // Une panique pendant une écriture des stats empoisonne le verrou :
+            // on le récupère pour que les autres sous-systèmes continuent à publier
+            let mut guard = stats.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+            guard.subsystem_panics += 1;
+            guard.last_panic = Some(format!("{}: {}", subsystem, message));
+            drop(guard);
+            stats.clear_poison();
+
+            None
+        }
+    }
+}
+
+/// Extrait le message d'une panique (`panic!("...")` ou `panic!("{}", ...)`)
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::StatsManager;
+
+    #[test]
+    fn test_panic_is_caught_and_counted() {
+        let stats = StatsManager::new();
+        let shared = stats.clone_arc();
+
+        // Sans panique : résultat transmis, aucun compteur
+        assert_eq!(run_guarded("test", &shared, || 42), Some(42));
+        assert_eq!(stats.get().subsystem_panics, 0);
+
+        // Panique volontaire dans un thread : interceptée, journalisée et comptée
+        let thread_stats = Arc::clone(&shared);
+        let result = std::thread::spawn(move || {
+            run_guarded("gps_reader", &thread_stats, || {
+                let sentence: Vec<&str> = Vec::new();
+                sentence[3].len()
+            })
+        })
+        .join()
+        .expect("the panic must not escape the guard");
+
+        assert_eq!(result, None);
+        let snapshot = stats.get();
+        assert_eq!(snapshot.subsystem_panics, 1);
+        assert!(snapshot.last_panic.unwrap().starts_with("gps_reader: index out of bounds"));
+    }
+}
@@ -0,0 +1,215 @@
+/*!
+Associations symétriques passives (RFC 5905, mode 1 → mode 2)
+
+Un pair ntpd configuré avec `peer` envoie des paquets symétriques actifs
+(mode 1) et attend des réponses symétriques passives (mode 2). Chaque pair
+accepté (`server.symmetric_peers`, vide = tous) obtient une association
+éphémère :
+
+- les paquets reçus deux fois (même transmit timestamp) sont écartés ;
+- l'originate timestamp du pair est comparé au dernier transmit timestamp
+  qui lui a été envoyé (pair redémarré ou paquet dans le désordre) ;
+- l'association est libérée après 8 intervalles de poll sans paquet, et le
+  nombre d'associations est borné.
+
+Le serveur reste la référence : l'état du pair n'est jamais utilisé pour
+discipliner l'horloge.
+*/
+
+use crate::packet::NtpPacket;
+use crate::security::IpNetwork;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Nombre d'intervalles de poll sans paquet avant libération d'une association
+const UNREACH_POLLS: u32 = 8;
+
+/// Bornes du poll du pair utilisé pour l'expiration (RFC 5905 MINPOLL/MAXPOLL)
+const MIN_POLL: i8 = 4;
+const MAX_POLL: i8 = 17;
+
+/// État d'une association symétrique passive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerState {
+    pub first_seen: Instant,
+    pub last_seen: Instant,
+
+    /// Paquets symétriques actifs acceptés
+    pub packets: u64,
+
+    /// Stratum et poll annoncés par le pair dans son dernier paquet
+    pub stratum: u8,
+    pub poll: i8,
+
+    /// Transmit timestamp du dernier paquet reçu (détection des doublons)
+    pub last_received_transmit: u64,
+
+    /// Transmit timestamp de la dernière réponse envoyée au pair
+    pub last_sent_transmit: Option<u64>,
+}
+
+impl PeerState {
+    /// Durée sans paquet au-delà de laquelle le pair est considéré injoignable
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(UNREACH_POLLS as u64 * (1u64 << self.poll.clamp(MIN_POLL, MAX_POLL)))
+    }
+}
+
+/// Raison du refus d'un paquet symétrique actif
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum PeerRejection {
+    #[error("peer not in symmetric_peers")]
+    NotAllowed,
+
+    #[error("duplicate symmetric packet")]
+    Duplicate,
+
+    #[error("too many symmetric associations")]
+    TableFull,
+}
+
+/// Table des associations symétriques passives
+pub struct SymmetricPeers {
+    /// Pairs autorisés (vide = tous)
+    allowlist: Vec<IpNetwork>,
+    peers: Mutex<HashMap<IpAddr, PeerState>>,
+    capacity: usize,
+}
+
+impl SymmetricPeers {
+    /// Nombre maximum d'associations simultanées
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    pub fn new(allowlist: Vec<IpNetwork>, capacity: usize) -> Self {
+        SymmetricPeers {
+            allowlist,
+            peers: Mutex::new(HashMap::new()),
+            capacity,
+        }
+    }
+
+    /// Enregistre un paquet symétrique actif reçu à `now` ; en cas de refus,
+    /// aucune réponse ne doit être envoyée
+    ///
+    /// Retourne vrai si l'originate timestamp du pair correspond à la dernière
+    /// réponse envoyée (toujours vrai pour le premier paquet de l'association).
+    pub fn receive(&self, ip: IpAddr, packet: &NtpPacket, now: Instant) -> Result<bool, PeerRejection> {
+        if !self.is_allowed(ip) {
+            return Err(PeerRejection::NotAllowed);
+        }
+
+        let mut peers = self.peers.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        peers.retain(|_, peer| now.duration_since(peer.last_seen) < peer.timeout());
+
+        let transmit = packet.transmit_timestamp.0;
+        match peers.get_mut(&ip) {
+            Some(peer) => {
+                if peer.last_received_transmit == transmit {
+                    return Err(PeerRejection::Duplicate);
+                }
+                let in_sequence = peer
+                    .last_sent_transmit
+                    .is_none_or(|sent| sent == packet.originate_timestamp.0);
+
+                peer.last_seen = now;
+                peer.packets += 1;
+                peer.stratum = packet.stratum;
+                peer.poll = packet.poll;
+                peer.last_received_transmit = transmit;
+                Ok(in_sequence)
+            }
+            None => {
+                if peers.len() >= self.capacity {
+                    return Err(PeerRejection::TableFull);
+                }
+                peers.insert(
+                    ip,
+                    PeerState {
+                        first_seen: now,
+                        last_seen: now,
+                        packets: 1,
+                        stratum: packet.stratum,
+                        poll: packet.poll,
+                        last_received_transmit: transmit,
+                        last_sent_transmit: None,
+                    },
+                );
+                Ok(true)
+            }
+        }
+    }
+
+    /// Vrai si l'adresse est un pair autorisé (`server.symmetric_peers`, vide = tous)
+    pub fn is_allowed(&self, ip: IpAddr) -> bool {
+        self.allowlist.is_empty() || self.allowlist.iter().any(|network| network.contains(ip))
+    }
+
+    /// Mémorise le transmit timestamp de la réponse envoyée au pair
+    pub fn record_sent(&self, ip: IpAddr, transmit_timestamp: u64) {
+        let mut peers = self.peers.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(peer) = peers.get_mut(&ip) {
+            peer.last_sent_transmit = Some(transmit_timestamp);
+        }
+    }
+
+    /// État de l'association d'un pair
+    #[allow(dead_code)]
+    pub fn get(&self, ip: IpAddr) -> Option<PeerState> {
+        self.peers.lock().ok()?.get(&ip).copied()
+    }
+
+    /// Nombre d'associations actives
+    pub fn len(&self) -> usize {
+        self.peers.lock().map(|peers| peers.len()).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet::{NtpMode, NtpTimestamp};
+
+    fn active_packet(transmit: u64, originate: u64) -> NtpPacket {
+        let mut packet = NtpPacket::new_server_response();
+        packet.mode = NtpMode::SymmetricActive;
+        packet.stratum = 2;
+        packet.poll = 6;
+        packet.transmit_timestamp = NtpTimestamp(transmit);
+        packet.originate_timestamp = NtpTimestamp(originate);
+        packet
+    }
+
+    #[test]
+    fn test_symmetric_association_lifecycle() {
+        let allowed: IpAddr = "192.0.2.10".parse().unwrap();
+        let other: IpAddr = "198.51.100.1".parse().unwrap();
+        let peers = SymmetricPeers::new(vec!["192.0.2.0/24".parse().unwrap()], 1);
+        let start = Instant::now();
+
+        assert_eq!(peers.receive(other, &active_packet(1, 0), start), Err(PeerRejection::NotAllowed));
+
+        // Premier paquet : association créée, puis doublon écarté
+        assert_eq!(peers.receive(allowed, &active_packet(100, 0), start), Ok(true));
+        assert_eq!(peers.receive(allowed, &active_packet(100, 0), start), Err(PeerRejection::Duplicate));
+        peers.record_sent(allowed, 500);
+
+        // Paquet suivant : originate = notre dernière réponse
+        let later = start + Duration::from_secs(64);
+        assert_eq!(peers.receive(allowed, &active_packet(200, 500), later), Ok(true));
+        assert_eq!(peers.receive(allowed, &active_packet(300, 42), later), Ok(false));
+        let state = peers.get(allowed).unwrap();
+        assert_eq!((state.packets, state.stratum, state.poll), (3, 2, 6));
+
+        // Capacité atteinte pour un autre pair autorisé
+        let second: IpAddr = "192.0.2.11".parse().unwrap();
+        assert_eq!(peers.receive(second, &active_packet(1, 0), later), Err(PeerRejection::TableFull));
+
+        // 8 polls (8 × 64 s) sans paquet : association libérée
+        let expired = later + Duration::from_secs(8 * 64);
+        assert_eq!(peers.receive(second, &active_packet(1, 0), expired), Ok(true));
+        assert!(peers.get(allowed).is_none());
+        assert_eq!(peers.len(), 1);
+    }
+}
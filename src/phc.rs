@@ -0,0 +1,377 @@
+/*!
+Source d'horloge "phc" : temps système servi avec l'incertitude mesurée
+contre l'horloge matérielle PTP (PHC) de la carte réseau
+
+Sur un hôte dont la carte est disciplinée par PTP (ptp4l), puis l'horloge
+système par phc2sys, la référence est le PHC. Le serveur continue de servir
+le temps système, mais mesure chaque seconde l'écart PHC - système par
+l'ioctl Linux `PTP_SYS_OFFSET_EXTENDED` sur `/dev/ptpN` : le noyau renvoie
+des triplets (système avant, PHC, système après) lus au plus près du
+matériel.
+
+Pour chaque triplet, l'instant PHC est comparé au milieu de la fenêtre de
+lecture système ; la moyenne des écarts donne l'offset, la fenêtre la plus
+courte borne l'incertitude de lecture :
+
+```text
+offset = moyenne(phc - (avant + après) / 2)
+incertitude = |offset| + min(après - avant) / 2
+```
+
+Sous ptp4l, le PHC compte en TAI : l'écart TAI - UTC (`clock.phc_utc_offset_secs`,
+37 s depuis 2017, 0 si le PHC est en UTC) est retranché de chaque lecture.
+
+L'incertitude est annoncée en precision et root dispersion. Sans mesure
+récente (PHC illisible, `ptp4l` arrêté), ou si l'écart dépasse
+`MAX_SYNC_OFFSET_NS` (phc2sys arrêté, écart TAI - UTC mal configuré), la
+source est non synchronisée (stratum 16), comme l'horloge système seule.
+
+Disponible uniquement sous Linux ; ailleurs, la lecture échoue avec
+`ErrorKind::Unsupported`.
+*/
+
+use crate::clock::{precision_from_ns, ClockSource, SystemClock};
+use crate::packet::NtpTimestamp;
+use crate::panic_guard::run_guarded;
+use crate::stats::ServerStats;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Nombre maximal de triplets par ioctl (`PTP_MAX_SAMPLES` du noyau)
+pub const PTP_MAX_SAMPLES: usize = 25;
+
+/// Période de mesure de l'offset PHC - système
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Âge au-delà duquel une mesure n'est plus représentative
+const MAX_MEASUREMENT_AGE: Duration = Duration::from_secs(5);
+
+/// Écart PHC - système au-delà duquel l'horloge système n'est plus considérée
+/// disciplinée par le PHC (1 ms)
+const MAX_SYNC_OFFSET_NS: u64 = 1_000_000;
+
+/// Triplet d'une lecture : système avant, PHC, système après (ns depuis l'epoch Unix)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhcSample {
+    pub system_before_ns: i64,
+    pub phc_ns: i64,
+    pub system_after_ns: i64,
+}
+
+/// Écart PHC - système mesuré sur une série de triplets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhcOffset {
+    /// Offset moyen PHC - système (ns, positif si le PHC est en avance)
+    pub offset_ns: i64,
+
+    /// Fenêtre de lecture système la plus courte (ns)
+    pub min_delay_ns: u64,
+
+    /// Triplets retenus (fenêtre non négative)
+    pub samples: usize,
+}
+
+impl PhcOffset {
+    /// Incertitude du temps système servi par rapport au PHC (ns)
+    pub fn uncertainty_ns(&self) -> u64 {
+        self.offset_ns.unsigned_abs() + self.min_delay_ns.div_ceil(2)
+    }
+}
+
+/// Moyenne des écarts PHC - milieu de fenêtre système
+///
+/// Les triplets à fenêtre négative (horloge système ajustée pendant la
+/// lecture) sont ignorés ; `None` si aucun n'est exploitable.
+pub fn average_offset(samples: &[PhcSample]) -> Option<PhcOffset> {
+    let mut sum: i128 = 0;
+    let mut count = 0usize;
+    let mut min_delay = u64::MAX;
+
+    for sample in samples {
+        let delay = sample.system_after_ns - sample.system_before_ns;
+        if delay < 0 {
+            continue;
+        }
+        let midpoint = sample.system_before_ns + delay / 2;
+        sum += (sample.phc_ns - midpoint) as i128;
+        min_delay = min_delay.min(delay as u64);
+        count += 1;
+    }
+
+    (count > 0).then(|| PhcOffset {
+        offset_ns: (sum / count as i128) as i64,
+        min_delay_ns: min_delay,
+        samples: count,
+    })
+}
+
+/// Lit `samples` triplets (1 à `PTP_MAX_SAMPLES`) sur le périphérique PHC
+pub fn read_samples(device: &Path, samples: usize) -> std::io::Result<Vec<PhcSample>> {
+    imp::read_samples(device, samples.clamp(1, PTP_MAX_SAMPLES))
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::{PhcSample, PTP_MAX_SAMPLES};
+    use std::fs::File;
+    use std::io;
+    use std::os::fd::AsRawFd;
+    use std::path::Path;
+
+    /// `struct ptp_clock_time` (linux/ptp_clock.h)
+    #[repr(C)]
+    #[derive(Clone, Copy, Default)]
+    struct PtpClockTime {
+        sec: i64,
+        nsec: u32,
+        reserved: u32,
+    }
+
+    /// `struct ptp_sys_offset_extended` : triplets (système, PHC, système)
+    #[repr(C)]
+    struct PtpSysOffsetExtended {
+        n_samples: u32,
+        rsv: [u32; 3],
+        ts: [[PtpClockTime; 3]; PTP_MAX_SAMPLES],
+    }
+
+    /// `_IOWR('=', 9, struct ptp_sys_offset_extended)`
+    const PTP_SYS_OFFSET_EXTENDED: u64 =
+        (3 << 30) | ((std::mem::size_of::<PtpSysOffsetExtended>() as u64) << 16) | ((b'=' as u64) << 8) | 9;
+
+    fn nanos(time: PtpClockTime) -> i64 {
+        time.sec * 1_000_000_000 + time.nsec as i64
+    }
+
+    pub fn read_samples(device: &Path, samples: usize) -> io::Result<Vec<PhcSample>> {
+        let file = File::open(device)?;
+        let mut request = PtpSysOffsetExtended {
+            n_samples: samples as u32,
+            rsv: [0; 3],
+            ts: [[PtpClockTime::default(); 3]; PTP_MAX_SAMPLES],
+        };
+
+        // SAFETY: `request` a la disposition mémoire attendue par l'ioctl et reste
+        // valide pendant l'appel
+        let result = unsafe { libc::ioctl(file.as_raw_fd(), PTP_SYS_OFFSET_EXTENDED as _, &mut request) };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(request.ts[..samples]
+            .iter()
+            .map(|[before, phc, after]| PhcSample {
+                system_before_ns: nanos(*before),
+                phc_ns: nanos(*phc),
+                system_after_ns: nanos(*after),
+            })
+            .collect())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::PhcSample;
+    use std::io;
+    use std::path::Path;
+
+    pub fn read_samples(_device: &Path, _samples: usize) -> io::Result<Vec<PhcSample>> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "PTP hardware clocks require Linux"))
+    }
+}
+
+/// Horloge système dont l'incertitude est mesurée contre un PHC
+pub struct PhcClock {
+    device: PathBuf,
+
+    /// Triplets par mesure (`clock.phc_samples`)
+    samples: usize,
+
+    /// Écart TAI - UTC retranché des lectures PHC (ns)
+    utc_offset_ns: i64,
+
+    /// Dernière mesure, son instant et son horodatage NTP (reference timestamp)
+    last: Mutex<Option<(PhcOffset, Instant, NtpTimestamp)>>,
+
+    system_clock: SystemClock,
+}
+
+impl PhcClock {
+    pub fn new(device: impl Into<PathBuf>, samples: usize) -> Self {
+        PhcClock {
+            device: device.into(),
+            samples,
+            utc_offset_ns: 0,
+            last: Mutex::new(None),
+            system_clock: SystemClock::new(),
+        }
+    }
+
+    /// Écart TAI - UTC du PHC (secondes, 0 si le PHC compte en UTC)
+    pub fn with_utc_offset(mut self, secs: i64) -> Self {
+        self.utc_offset_ns = secs * 1_000_000_000;
+        self
+    }
+
+    /// Enregistre une mesure d'offset
+    pub fn update(&self, offset: PhcOffset) {
+        let measured_at = self.system_clock.now();
+        if let Ok(mut last) = self.last.lock() {
+            *last = Some((offset, Instant::now(), measured_at));
+        }
+    }
+
+    /// Dernière mesure encore représentative
+    fn offset(&self) -> Option<PhcOffset> {
+        let last = *self.last.lock().ok()?;
+        last.filter(|(_, at, _)| at.elapsed() <= MAX_MEASUREMENT_AGE)
+            .map(|(offset, _, _)| offset)
+    }
+
+    /// Dernière mesure, si l'horloge système suit le PHC à `MAX_SYNC_OFFSET_NS` près
+    fn synced_offset(&self) -> Option<PhcOffset> {
+        self.offset()
+            .filter(|offset| offset.offset_ns.unsigned_abs() <= MAX_SYNC_OFFSET_NS)
+    }
+
+    /// Mesure l'offset PHC (ramené en UTC) - système une fois
+    fn measure(&self) -> std::io::Result<Option<PhcOffset>> {
+        let mut samples = read_samples(&self.device, self.samples)?;
+        for sample in &mut samples {
+            sample.phc_ns -= self.utc_offset_ns;
+        }
+        Ok(average_offset(&samples))
+    }
+
+    /// Démarre le thread de mesure périodique de l'offset
+    pub fn start(self: &Arc<Self>, stats: Arc<RwLock<ServerStats>>) -> std::thread::JoinHandle<()> {
+        info!("Measuring PHC offset on {} ({} samples/s)", self.device.display(), self.samples);
+
+        let clock = Arc::clone(self);
+        std::thread::spawn(move || {
+            run_guarded("phc_offset", &stats, || {
+                let mut failing = false;
+                loop {
+                    match clock.measure() {
+                        Ok(Some(offset)) => {
+                            failing = false;
+                            clock.update(offset);
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            // Une seule alerte par panne, pas une par seconde
+                            if !failing {
+                                warn!("Failed to read PHC {}: {}", clock.device.display(), e);
+                            }
+                            failing = true;
+                        }
+                    }
+                    std::thread::sleep(POLL_INTERVAL);
+                }
+            });
+        })
+    }
+}
+
+impl ClockSource for PhcClock {
+    fn now(&self) -> NtpTimestamp {
+        self.system_clock.now()
+    }
+
+    fn reference_id(&self) -> [u8; 4] {
+        if self.synced_offset().is_some() {
+            *b"PTP\0"
+        } else {
+            *b"LOCL"
+        }
+    }
+
+    fn stratum(&self) -> u8 {
+        if self.synced_offset().is_some() {
+            1
+        } else {
+            16
+        }
+    }
+
+    fn precision(&self) -> i8 {
+        match self.offset() {
+            Some(offset) => precision_from_ns(offset.min_delay_ns.div_ceil(2)).max(self.system_clock.precision()),
+            None => self.system_clock.precision(),
+        }
+    }
+
+    fn time_accuracy_ns(&self) -> Option<u64> {
+        self.offset().map(|offset| offset.uncertainty_ns())
+    }
+
+    fn last_update(&self) -> NtpTimestamp {
+        // Instant de la dernière mesure ; sans mesure, l'instant présent
+        self.last
+            .lock()
+            .ok()
+            .and_then(|last| last.map(|(_, _, measured_at)| measured_at))
+            .unwrap_or_else(|| self.system_clock.now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(system_before_ns: i64, phc_ns: i64, system_after_ns: i64) -> PhcSample {
+        PhcSample {
+            system_before_ns,
+            phc_ns,
+            system_after_ns,
+        }
+    }
+
+    #[test]
+    fn test_phc_offset_averaging() {
+        const BASE: i64 = 1_790_000_000_000_000_000;
+        let samples = [
+            // Milieu BASE+1000, PHC 400 ns en avance, fenêtre 2000 ns
+            sample(BASE, BASE + 1_400, BASE + 2_000),
+            // Milieu BASE+10_300, PHC 600 ns en avance, fenêtre 600 ns
+            sample(BASE + 10_000, BASE + 10_900, BASE + 10_600),
+            // Fenêtre négative (horloge système ajustée pendant la lecture) : ignoré
+            sample(BASE + 20_000, BASE + 50_000, BASE + 19_000),
+            // Milieu BASE+30_500, PHC 500 ns en avance, fenêtre 1000 ns
+            sample(BASE + 30_000, BASE + 31_000, BASE + 31_000),
+        ];
+
+        let offset = average_offset(&samples).unwrap();
+        assert_eq!(offset.offset_ns, 500);
+        assert_eq!(offset.min_delay_ns, 600);
+        assert_eq!(offset.samples, 3);
+        assert_eq!(offset.uncertainty_ns(), 800);
+
+        // PHC en retard : offset négatif, incertitude en valeur absolue
+        let behind = average_offset(&[sample(BASE, BASE - 250, BASE + 100)]).unwrap();
+        assert_eq!(behind.offset_ns, -300);
+        assert_eq!(behind.uncertainty_ns(), 350);
+
+        assert_eq!(average_offset(&[]), None);
+
+        // L'incertitude mesurée est annoncée par l'horloge
+        let clock = PhcClock::new("/dev/ptp0", 5);
+        assert_eq!(clock.stratum(), 16);
+        clock.update(offset);
+        assert_eq!(clock.stratum(), 1);
+        assert_eq!(&clock.reference_id(), b"PTP\0");
+        assert_eq!(clock.time_accuracy_ns(), Some(800));
+        assert!(clock.last_update().0 <= clock.now().0);
+
+        // Écart hors borne (PHC en TAI non corrigé) : plus de stratum 1
+        clock.update(PhcOffset {
+            offset_ns: 37_000_000_000,
+            min_delay_ns: 600,
+            samples: 3,
+        });
+        assert_eq!(clock.stratum(), 16);
+        assert_eq!(&clock.reference_id(), b"LOCL");
+    }
+}
@@ -0,0 +1,97 @@
+/*!
+Journal haute résolution des pulses PPS au format CSV
+
+Outil de caractérisation : une ligne par front montant PPS, avec l'instant
+monotone du pulse, l'intervalle mesuré depuis le pulse précédent, l'offset
+instantané et l'offset filtré (EWMA). Ces données alimentent directement un
+outil de calcul de déviation d'Allan.
+
+Les champs d'offset restent vides lorsque l'offset n'a pas pu être calculé
+(intervalle hors plage, pas encore de temps GPS, horloge système non fiable).
+Volume élevé (une ligne par seconde) : désactivé par défaut.
+*/
+
+use crate::clock::PpsMeasurement;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::Instant;
+
+/// En-tête des fichiers CSV
+pub const CSV_HEADER: &str = "monotonic_ns,interval_s,raw_offset_s,ewma_offset_s,sample_count\n";
+
+/// Formate la ligne CSV d'un pulse
+pub fn csv_line(monotonic_ns: u128, interval_s: f64, measurement: Option<&PpsMeasurement>) -> String {
+    match measurement {
+        Some(m) => format!(
+            "{},{:.9},{:.9},{:.9},{}\n",
+            monotonic_ns, interval_s, m.raw_offset_seconds, m.ewma_offset_seconds, m.sample_count
+        ),
+        None => format!("{},{:.9},,,\n", monotonic_ns, interval_s),
+    }
+}
+
+/// Enregistreur des pulses PPS (ajout en fin de fichier)
+pub struct PpsLogger {
+    file: File,
+    origin: Instant,
+}
+
+impl PpsLogger {
+    /// Ouvre le fichier en ajout ; les instants sont comptés depuis `origin`
+    pub fn open(path: &Path, origin: Instant) -> std::io::Result<Self> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if file.metadata()?.len() == 0 {
+            file.write_all(CSV_HEADER.as_bytes())?;
+        }
+
+        Ok(PpsLogger { file, origin })
+    }
+
+    /// Ajoute la ligne d'un pulse
+    pub fn record(
+        &mut self,
+        pulse: Instant,
+        interval_s: f64,
+        measurement: Option<&PpsMeasurement>,
+    ) -> std::io::Result<()> {
+        let monotonic_ns = pulse.saturating_duration_since(self.origin).as_nanos();
+        self.file
+            .write_all(csv_line(monotonic_ns, interval_s, measurement).as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_pulse_produces_csv_line() {
+        let path = std::env::temp_dir().join(format!("pendulum-pps-{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let origin = Instant::now();
+        let mut logger = PpsLogger::open(&path, origin).unwrap();
+        let measurement = PpsMeasurement {
+            raw_offset_seconds: 0.000_012_5,
+            ewma_offset_seconds: 0.000_010,
+            sample_count: 42,
+        };
+        logger
+            .record(origin + Duration::from_millis(1500), 1.000_001, Some(&measurement))
+            .unwrap();
+        logger.record(origin + Duration::from_millis(2500), 1.2, None).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER.trim_end());
+        assert_eq!(lines[1], "1500000000,1.000001000,0.000012500,0.000010000,42");
+        assert_eq!(lines[2], "2500000000,1.200000000,,,");
+
+        let columns = CSV_HEADER.trim_end().split(',').count();
+        assert!(lines.iter().all(|line| line.split(',').count() == columns));
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,95 @@
+/*!
+Compteur de requêtes servies persistant (`server.requests_count_file`)
+
+Comptabilité d'exploitation : le total des requêtes servies depuis la mise en
+service survit aux redémarrages et aux mises à jour. Le fichier contient un
+seul entier décimal, relu au démarrage puis réécrit toutes les
+`server.requests_count_flush_secs` et à l'arrêt (au plus une période de
+requêtes perdue sur un arrêt brutal).
+
+Un fichier absent ou illisible repart de zéro, sans empêcher le démarrage.
+*/
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::warn;
+
+/// Fichier d'état du compteur de requêtes servies
+pub struct RequestCountFile {
+    path: PathBuf,
+
+    /// Total relu au démarrage
+    base: u64,
+
+    /// Période de réécriture
+    flush_interval: Duration,
+}
+
+impl RequestCountFile {
+    /// Ouvre le fichier d'état et relit le total persisté
+    pub fn open(path: &Path, flush_interval: Duration) -> Self {
+        let base = match std::fs::read_to_string(path) {
+            Ok(content) => content.trim().parse().unwrap_or_else(|_| {
+                warn!("Corrupt request count file {}, starting from zero", path.display());
+                0
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => {
+                warn!("Failed to read request count file {}: {}, starting from zero", path.display(), e);
+                0
+            }
+        };
+
+        RequestCountFile {
+            path: path.to_path_buf(),
+            base,
+            flush_interval,
+        }
+    }
+
+    /// Total persisté lors de l'exécution précédente
+    pub fn base(&self) -> u64 {
+        self.base
+    }
+
+    pub fn flush_interval(&self) -> Duration {
+        self.flush_interval
+    }
+
+    /// Réécrit le total (fichier temporaire puis renommage : jamais de fichier tronqué)
+    pub fn save(&self, total: u64) -> std::io::Result<()> {
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        std::fs::write(&temporary, format!("{}\n", total))?;
+        std::fs::rename(&temporary, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_count_resumes_and_resets_on_corruption() {
+        let path = std::env::temp_dir().join(format!("pendulum-requests-{}.count", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let interval = Duration::from_secs(60);
+
+        // Premier démarrage : pas de fichier
+        let counter = RequestCountFile::open(&path, interval);
+        assert_eq!(counter.base(), 0);
+        counter.save(1_234).unwrap();
+
+        // Redémarrage : reprise du total persisté
+        assert_eq!(RequestCountFile::open(&path, interval).base(), 1_234);
+
+        // Fichier corrompu : repart de zéro et se réécrit proprement
+        std::fs::write(&path, "12\u{0}garbage").unwrap();
+        let counter = RequestCountFile::open(&path, interval);
+        assert_eq!(counter.base(), 0);
+        counter.save(5).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "5\n");
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
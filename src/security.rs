@@ -1,22 +1,92 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::net::IpAddr;
-use std::sync::{Arc, RwLock};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant};
 use tracing::{warn, debug};
 
+/// Réseau IP en notation CIDR (ex: "203.0.113.0/24", "2001:db8::/32")
+/// Une adresse seule équivaut à un préfixe /32 (IPv4) ou /128 (IPv6)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    address: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNetwork {
+    /// Vrai si l'adresse appartient à ce réseau
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.address, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix_len as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix_len as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+
+    /// Longueur du préfixe (plus elle est grande, plus le réseau est spécifique)
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, prefix) = match s.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (s, None),
+        };
+
+        let address: IpAddr = address
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid IP address in '{}'", s))?;
+        let max_prefix = if address.is_ipv4() { 32 } else { 128 };
+
+        let prefix_len = match prefix {
+            Some(prefix) => prefix
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|len| *len <= max_prefix)
+                .ok_or_else(|| format!("Invalid prefix length in '{}'", s))?,
+            None => max_prefix,
+        };
+
+        Ok(IpNetwork { address, prefix_len })
+    }
+}
+
 /// Gestionnaire de rate limiting par IP
 pub struct RateLimiter {
     /// Map: IP -> état du rate limiting
     limits: Arc<RwLock<HashMap<IpAddr, RateLimitState>>>,
 
-    /// Nombre maximum de requêtes par seconde
-    max_requests_per_second: u32,
+    /// Nombre maximum de requêtes par seconde (modifiable à chaud via /api/ratelimit)
+    max_requests_per_second: AtomicU32,
+
+    /// Limites spécifiques par réseau (ex: passerelle NAT d'un bureau)
+    overrides: Vec<(IpNetwork, u32)>,
+
+    /// Espacement minimal entre deux réponses à une même IP (zéro = aucun)
+    min_interval: Duration,
 
     /// Fenêtre de temps pour le nettoyage des anciennes entrées
     cleanup_interval: Duration,
 
     /// Dernier nettoyage
     last_cleanup: Arc<RwLock<Instant>>,
+
+    /// Source de temps (`Instant::now` en production, horloge simulée en test)
+    time_source: Box<dyn Fn() -> Instant + Send + Sync>,
 }
 
 #[derive(Debug, Clone)]
@@ -29,26 +99,81 @@ struct RateLimitState {
 
     /// Dernière requête vue
     last_request: Instant,
+
+    /// Dernière requête autorisée (espacement minimal)
+    last_allowed: Option<Instant>,
 }
 
 impl RateLimiter {
     pub fn new(max_requests_per_second: u32) -> Self {
         RateLimiter {
             limits: Arc::new(RwLock::new(HashMap::new())),
-            max_requests_per_second,
+            max_requests_per_second: AtomicU32::new(max_requests_per_second),
+            overrides: Vec::new(),
+            min_interval: Duration::ZERO,
             cleanup_interval: Duration::from_secs(60),
             last_cleanup: Arc::new(RwLock::new(Instant::now())),
+            time_source: Box::new(Instant::now),
         }
     }
 
+    /// Remplace la source de temps (tests déterministes : fenêtres et nettoyage
+    /// sans dépendre de l'horloge réelle)
+    #[allow(dead_code)]
+    pub fn with_time_source<F>(mut self, time_source: F) -> Self
+    where
+        F: Fn() -> Instant + Send + Sync + 'static,
+    {
+        self.last_cleanup = Arc::new(RwLock::new(time_source()));
+        self.time_source = Box::new(time_source);
+        self
+    }
+
+    /// Définit des limites spécifiques par réseau ; le réseau le plus spécifique l'emporte
+    pub fn with_overrides(mut self, overrides: Vec<(IpNetwork, u32)>) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Espacement minimal entre deux réponses à une même IP
+    /// (`security.min_client_interval_ms`) : les requêtes plus rapprochées sont
+    /// rejetées, même sous la limite par seconde. Les réseaux en override en sont
+    /// exemptés : une passerelle NAT y porte de nombreux clients
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// Limite de l'override le plus spécifique contenant cette IP (sinon la
+    /// limite par défaut s'applique)
+    fn override_for(&self, ip: IpAddr) -> Option<u32> {
+        self.overrides
+            .iter()
+            .filter(|(network, _)| network.contains(ip))
+            .max_by_key(|(network, _)| network.prefix_len())
+            .map(|(_, limit)| *limit)
+    }
+
+    /// Limite par défaut actuelle (requêtes par seconde et par IP)
+    pub fn max_requests_per_second(&self) -> u32 {
+        self.max_requests_per_second.load(Ordering::Relaxed)
+    }
+
+    /// Modifie la limite par défaut à chaud (les overrides par réseau sont inchangés)
+    pub fn set_max_requests_per_second(&self, max_requests_per_second: u32) {
+        self.max_requests_per_second
+            .store(max_requests_per_second, Ordering::Relaxed);
+    }
+
     /// Vérifie si une requête depuis cette IP est autorisée
     /// Retourne true si autorisé, false si rate limited
     pub fn check_rate_limit(&self, ip: IpAddr) -> bool {
-        let now = Instant::now();
+        let now = (self.time_source)();
 
         // Nettoyage périodique des anciennes entrées
         self.cleanup_old_entries(now);
 
+        let override_limit = self.override_for(ip);
         let mut limits = match self.limits.write() {
             Ok(guard) => guard,
             Err(_) => {
@@ -61,6 +186,7 @@ impl RateLimiter {
             request_count: 0,
             window_start: now,
             last_request: now,
+            last_allowed: None,
         });
 
         // Si plus d'une seconde s'est écoulée, réinitialiser la fenêtre
@@ -68,20 +194,34 @@ impl RateLimiter {
             state.request_count = 1;
             state.window_start = now;
             state.last_request = now;
-            return true;
-        }
+        } else {
+            // Incrémenter le compteur
+            state.request_count += 1;
+            state.last_request = now;
 
-        // Incrémenter le compteur
-        state.request_count += 1;
-        state.last_request = now;
+            let limit = override_limit.unwrap_or_else(|| self.max_requests_per_second());
+            if state.request_count > limit {
+                debug!(
+                    "Rate limit exceeded for IP {}: {} requests/sec",
+                    ip, state.request_count
+                );
+                return false;
+            }
+        }
 
-        if state.request_count > self.max_requests_per_second {
-            debug!(
-                "Rate limit exceeded for IP {}: {} requests/sec",
-                ip, state.request_count
-            );
-            return false;
+        // Espacement minimal depuis la dernière requête autorisée (hors overrides :
+        // tous les clients derrière une passerelle NAT partagent son adresse)
+        if let (Some(last_allowed), None) = (state.last_allowed, override_limit) {
+            if now.duration_since(last_allowed) < self.min_interval {
+                debug!(
+                    "Request from IP {} within minimum interval ({:?} since last answer)",
+                    ip,
+                    now.duration_since(last_allowed)
+                );
+                return false;
+            }
         }
+        state.last_allowed = Some(now);
 
         true
     }
@@ -99,7 +239,8 @@ impl RateLimiter {
         }
 
         if let Ok(mut limits) = self.limits.write() {
-            let inactive_threshold = Duration::from_secs(60);
+            // Une IP oubliée avant la fin de son espacement minimal serait répondue trop tôt
+            let inactive_threshold = Duration::from_secs(60).max(self.min_interval);
             limits.retain(|_, state| {
                 now.duration_since(state.last_request) < inactive_threshold
             });
@@ -125,6 +266,124 @@ pub struct RateLimiterStats {
     pub tracked_ips: usize,
 }
 
+/// Limite globale du débit de requêtes, sans état par client (token bucket)
+///
+/// Utilisée en mode anycast : le comportement ne dépend que du débit total reçu
+/// par le nœud, jamais de l'historique d'une IP, si bien que tous les nœuds
+/// répondent de la même façon à un client donné.
+pub struct GlobalRateLimiter {
+    bucket: Mutex<TokenBucket>,
+    max_requests_per_second: u32,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl GlobalRateLimiter {
+    /// Débit maximal soutenu, avec une rafale d'au plus une seconde de requêtes
+    pub fn new(max_requests_per_second: u32) -> Self {
+        GlobalRateLimiter {
+            bucket: Mutex::new(TokenBucket {
+                tokens: max_requests_per_second as f64,
+                last_refill: Instant::now(),
+            }),
+            max_requests_per_second,
+        }
+    }
+
+    /// Consomme un jeton ; faux si le débit global est dépassé
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&self, now: Instant) -> bool {
+        let Ok(mut bucket) = self.bucket.lock() else {
+            return true;
+        };
+
+        let capacity = self.max_requests_per_second as f64;
+        let elapsed = now.saturating_duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * capacity).min(capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Détection des requêtes retransmises (même IP, même transmit timestamp)
+///
+/// Un client sans réponse renvoie le paquet à l'identique : ces doublons
+/// reçoivent une réponse mais ne doivent pas gonfler le nombre de requêtes.
+/// Le cache est borné en durée et en nombre d'entrées.
+pub struct DuplicateDetector {
+    cache: Mutex<DuplicateCache>,
+    window: Duration,
+    capacity: usize,
+}
+
+#[derive(Default)]
+struct DuplicateCache {
+    seen: HashSet<(IpAddr, u64)>,
+    order: VecDeque<(Instant, IpAddr, u64)>,
+}
+
+impl DuplicateDetector {
+    /// Durée de mémorisation d'une requête
+    pub const DEFAULT_WINDOW: Duration = Duration::from_secs(2);
+
+    /// Nombre maximum de requêtes mémorisées
+    pub const DEFAULT_CAPACITY: usize = 4096;
+
+    pub fn new(window: Duration, capacity: usize) -> Self {
+        DuplicateDetector {
+            cache: Mutex::new(DuplicateCache::default()),
+            window,
+            capacity,
+        }
+    }
+
+    /// Retourne true si la requête a déjà été vue récemment, sinon la mémorise
+    pub fn is_duplicate(&self, ip: IpAddr, transmit_timestamp: u64) -> bool {
+        let now = Instant::now();
+        let mut cache = match self.cache.lock() {
+            Ok(guard) => guard,
+            Err(_) => return false,
+        };
+
+        // Expiration des entrées anciennes, puis respect de la capacité
+        while let Some(&(seen_at, old_ip, old_ts)) = cache.order.front() {
+            if now.duration_since(seen_at) < self.window && cache.order.len() < self.capacity {
+                break;
+            }
+            cache.order.pop_front();
+            cache.seen.remove(&(old_ip, old_ts));
+        }
+
+        if cache.seen.contains(&(ip, transmit_timestamp)) {
+            return true;
+        }
+
+        if self.capacity > 0 {
+            cache.seen.insert((ip, transmit_timestamp));
+            cache.order.push_back((now, ip, transmit_timestamp));
+        }
+        false
+    }
+
+    /// Nombre de requêtes actuellement mémorisées
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.cache.lock().map(|cache| cache.order.len()).unwrap_or(0)
+    }
+}
+
 /// Gestionnaire de listes blanches/noires IP
 pub struct IpFilter {
     whitelist: Vec<IpAddr>,
@@ -175,15 +434,16 @@ impl IpFilter {
 pub struct PacketValidator;
 
 impl PacketValidator {
-    /// Valide un paquet NTP reçu
-    pub fn validate_request(packet: &crate::packet::NtpPacket) -> Result<(), ValidationError> {
+    /// Valide un paquet NTP reçu (mode client, ou symétrique actif si `allow_symmetric`)
+    pub fn validate_request(packet: &crate::packet::NtpPacket, allow_symmetric: bool) -> Result<(), ValidationError> {
         // Vérifier la version NTP (accepter v1 à v4 pour compatibilité)
         if packet.version < 1 || packet.version > 4 {
             return Err(ValidationError::InvalidVersion(packet.version));
         }
 
-        // Vérifier le mode (doit être client = 3)
-        if packet.mode != crate::packet::NtpMode::Client {
+        // Vérifier le mode (client = 3, ou symétrique actif = 1 si autorisé)
+        let symmetric = allow_symmetric && packet.mode == crate::packet::NtpMode::SymmetricActive;
+        if packet.mode != crate::packet::NtpMode::Client && !symmetric {
             return Err(ValidationError::InvalidMode);
         }
 
@@ -221,9 +481,21 @@ mod tests {
     use super::*;
     use std::net::Ipv4Addr;
 
+    /// Horloge simulée partagée entre le test et le rate limiter
+    fn manual_clock() -> (Arc<Mutex<Instant>>, impl Fn() -> Instant + Send + Sync + 'static) {
+        let clock = Arc::new(Mutex::new(Instant::now()));
+        let source = Arc::clone(&clock);
+        (clock, move || *source.lock().unwrap())
+    }
+
+    fn advance(clock: &Mutex<Instant>, millis: u64) {
+        *clock.lock().unwrap() += Duration::from_millis(millis);
+    }
+
     #[test]
     fn test_rate_limiter() {
-        let limiter = RateLimiter::new(10);
+        let (_clock, source) = manual_clock();
+        let limiter = RateLimiter::new(10).with_time_source(source);
         let ip = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
 
         // Devrait autoriser les 10 premières requêtes
@@ -235,6 +507,176 @@ mod tests {
         assert!(!limiter.check_rate_limit(ip));
     }
 
+    #[test]
+    fn test_rate_limiter_window_rollover() {
+        let (clock, source) = manual_clock();
+        let limiter = RateLimiter::new(3).with_time_source(source);
+        let ip = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+
+        for _ in 0..3 {
+            assert!(limiter.check_rate_limit(ip));
+        }
+        assert!(!limiter.check_rate_limit(ip));
+
+        // Toujours dans la fenêtre d'une seconde
+        advance(&clock, 999);
+        assert!(!limiter.check_rate_limit(ip));
+
+        // Nouvelle fenêtre : compteur remis à zéro
+        advance(&clock, 1);
+        for _ in 0..3 {
+            assert!(limiter.check_rate_limit(ip));
+        }
+        assert!(!limiter.check_rate_limit(ip));
+    }
+
+    #[test]
+    fn test_min_client_interval() {
+        let (clock, source) = manual_clock();
+        let limiter = RateLimiter::new(100)
+            .with_min_interval(Duration::from_millis(1000))
+            .with_time_source(source);
+        let ip = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+        let other = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+
+        // Client interrogeant toutes les 50 ms pendant 3 s (20 req/s, sous la limite)
+        let mut answered = Vec::new();
+        for tick in 0..60 {
+            if limiter.check_rate_limit(ip) {
+                answered.push(tick * 50);
+            }
+            advance(&clock, 50);
+        }
+        assert_eq!(answered, vec![0, 1000, 2000]);
+
+        // L'espacement est propre à chaque IP
+        assert!(limiter.check_rate_limit(other));
+        assert!(!limiter.check_rate_limit(other));
+
+        // Réseau en override (passerelle NAT) : seule sa limite par seconde s'applique
+        let limiter = RateLimiter::new(100)
+            .with_min_interval(Duration::from_millis(1000))
+            .with_overrides(vec![("203.0.113.0/24".parse().unwrap(), 5)])
+            .with_time_source(manual_clock().1);
+        let nat_gateway: IpAddr = "203.0.113.42".parse().unwrap();
+        for _ in 0..5 {
+            assert!(limiter.check_rate_limit(nat_gateway));
+        }
+        assert!(!limiter.check_rate_limit(nat_gateway));
+    }
+
+    #[test]
+    fn test_rate_limiter_cleanup() {
+        let (clock, source) = manual_clock();
+        let limiter = RateLimiter::new(10).with_time_source(source);
+        let a = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+        let b = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+        let c = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 3));
+
+        limiter.check_rate_limit(a);
+        limiter.check_rate_limit(b);
+        advance(&clock, 30_000);
+        limiter.check_rate_limit(a);
+        assert_eq!(limiter.stats().tracked_ips, 2);
+
+        // 61 s : nettoyage, B inactive depuis plus de 60 s est oubliée
+        advance(&clock, 31_000);
+        limiter.check_rate_limit(c);
+        assert_eq!(limiter.stats().tracked_ips, 2);
+
+        // 100 s : A inactive depuis 70 s, mais pas de nettoyage avant 121 s
+        advance(&clock, 39_000);
+        limiter.check_rate_limit(c);
+        assert_eq!(limiter.stats().tracked_ips, 2);
+
+        advance(&clock, 21_000);
+        limiter.check_rate_limit(c);
+        assert_eq!(limiter.stats().tracked_ips, 1);
+    }
+
+    #[test]
+    fn test_rate_limit_overrides() {
+        let (_clock, source) = manual_clock();
+        let limiter = RateLimiter::new(2)
+            .with_overrides(vec![
+                ("203.0.113.0/24".parse().unwrap(), 5),
+                ("203.0.113.7".parse().unwrap(), 3),
+            ])
+            .with_time_source(source);
+
+        let nat_gateway: IpAddr = "203.0.113.42".parse().unwrap();
+        let specific: IpAddr = "203.0.113.7".parse().unwrap();
+        let other: IpAddr = "198.51.100.1".parse().unwrap();
+
+        // Réseau en override : sa propre limite
+        for _ in 0..5 {
+            assert!(limiter.check_rate_limit(nat_gateway));
+        }
+        assert!(!limiter.check_rate_limit(nat_gateway));
+
+        // L'override le plus spécifique l'emporte
+        for _ in 0..3 {
+            assert!(limiter.check_rate_limit(specific));
+        }
+        assert!(!limiter.check_rate_limit(specific));
+
+        // Autres IPs : limite par défaut
+        for _ in 0..2 {
+            assert!(limiter.check_rate_limit(other));
+        }
+        assert!(!limiter.check_rate_limit(other));
+    }
+
+    #[test]
+    fn test_ip_network_parse() {
+        let network: IpNetwork = "10.0.0.0/8".parse().unwrap();
+        assert!(network.contains("10.1.2.3".parse().unwrap()));
+        assert!(!network.contains("11.0.0.1".parse().unwrap()));
+        assert!(!network.contains("::1".parse().unwrap()));
+
+        let v6: IpNetwork = "2001:db8::/32".parse().unwrap();
+        assert!(v6.contains("2001:db8::1".parse().unwrap()));
+
+        let any: IpNetwork = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains("192.0.2.1".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<IpNetwork>().is_err());
+        assert!("not-an-ip/8".parse::<IpNetwork>().is_err());
+    }
+
+    #[test]
+    fn test_global_rate_limiter() {
+        let limiter = GlobalRateLimiter::new(10);
+        let start = Instant::now();
+
+        // Rafale d'une seconde autorisée, puis refus
+        assert!((0..10).all(|_| limiter.try_acquire_at(start)));
+        assert!(!limiter.try_acquire_at(start));
+
+        // 100 ms plus tard : 1 jeton regagné
+        let later = start + Duration::from_millis(100);
+        assert!(limiter.try_acquire_at(later));
+        assert!(!limiter.try_acquire_at(later));
+    }
+
+    #[test]
+    fn test_duplicate_detector() {
+        let detector = DuplicateDetector::new(Duration::from_secs(60), 2);
+        let ip: IpAddr = "192.0.2.1".parse().unwrap();
+        let other: IpAddr = "192.0.2.2".parse().unwrap();
+
+        assert!(!detector.is_duplicate(ip, 1));
+        assert!(detector.is_duplicate(ip, 1));
+
+        // Même timestamp depuis une autre IP : requête distincte
+        assert!(!detector.is_duplicate(other, 1));
+
+        // Capacité bornée : la plus ancienne entrée est oubliée
+        assert!(!detector.is_duplicate(ip, 2));
+        assert!(detector.len() <= 2);
+        assert!(!detector.is_duplicate(ip, 1));
+    }
+
     #[test]
     fn test_ip_filter_blacklist() {
         let filter = IpFilter::new(
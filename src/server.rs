@@ -1,20 +1,98 @@
-use crate::clock::ClockSource;
+use crate::audit_log::AuditLog;
+use crate::auth::{self, SymmetricKey};
+use crate::clock::{ntp_short_from_ns, ntp_short_from_us, refid_from_str, root_dispersion_ns, ClockSource};
 use crate::config::Config;
-use crate::packet::{LeapIndicator, NtpMode, NtpPacket, NtpTimestamp};
-use crate::security::{IpFilter, PacketValidator, RateLimiter};
-use crate::stats::ServerStats as SharedServerStats;
+use crate::heartbeat;
+use crate::leap_seconds::{ntp_seconds_to_iso, LeapSecondTable};
+use crate::packet::{ExtensionField, LeapIndicator, NtpError, NtpMode, NtpPacket, NtpTimestamp};
+use crate::panic_guard::run_guarded;
+use crate::peers::SymmetricPeers;
+use crate::request_count::RequestCountFile;
+use crate::security::{
+    DuplicateDetector, GlobalRateLimiter, IpFilter, PacketValidator, RateLimiter, ValidationError,
+};
+use crate::stats::{write_stats, LatencyAccumulator, RateSmoother, ServerStats as SharedServerStats, ValidationErrorCounts};
+use crate::socket_options::{self, ErrorQueueMessage};
+use crate::tx_timestamp;
 use anyhow::{Context, Result};
-use std::net::UdpSocket;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tracing::{debug, error, info, warn};
 
+/// Taille maximale d'un paquet NTP accepté en TCP (en-tête + extensions + MAC)
+const MAX_TCP_PACKET_SIZE: usize = 1024;
+
+/// Stratum annoncé en mode dégradé non synchronisé (convention de l'horloge locale)
+const DEGRADED_STRATUM: u8 = 10;
+
+/// Root dispersion annoncée en mode dégradé : 1 seconde (format 16.16)
+const DEGRADED_ROOT_DISPERSION: u32 = 1 << 16;
+
+/// Plus petit transmit timestamp plausible : 2024-01-01 (comparé via `seconds_since`,
+/// donc valable au-delà du passage à l'ère NTP 1 en 2036)
+const PLAUSIBLE_MIN_TIMESTAMP: NtpTimestamp = NtpTimestamp(3_913_056_000 << 32);
+
+/// Taille maximale d'un datagramme NTP reçu (en-tête, champs d'extension et MAC)
+const MAX_UDP_PACKET_SIZE: usize = 1024;
+
+/// Délai d'inactivité avant fermeture d'une connexion TCP
+const TCP_IDLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Envois en attente de leur horodatage noyau conservés par socket
+const TX_PENDING_MAX: usize = 64;
+
+/// Port NTP standard
+const NTP_PORT: u16 = 123;
+
+/// Premier port non privilégié (les ports inférieurs exigent root ou CAP_NET_BIND_SERVICE)
+const FIRST_UNPRIVILEGED_PORT: u16 = 1024;
+
+/// Erreur de bind, avec la marche à suivre si un port privilégié est refusé
+fn bind_error(error: std::io::Error, what: &str, addr: SocketAddr) -> anyhow::Error {
+    if error.kind() == std::io::ErrorKind::PermissionDenied && addr.port() < FIRST_UNPRIVILEGED_PORT {
+        anyhow::anyhow!(
+            "Failed to bind {} on {}: binding port {} requires root or CAP_NET_BIND_SERVICE; \
+             try `sudo setcap cap_net_bind_service=+ep <binary>` or run as root, \
+             or use a port >= {} for testing ({})",
+            what,
+            addr,
+            addr.port(),
+            FIRST_UNPRIVILEGED_PORT,
+            error
+        )
+    } else {
+        anyhow::Error::new(error).context(format!("Failed to bind {} on {}", what, addr))
+    }
+}
+
 /// Statistiques du serveur
 pub struct ServerStats {
     pub requests_received: std::sync::atomic::AtomicU64,
     pub requests_processed: std::sync::atomic::AtomicU64,
     pub requests_rejected: std::sync::atomic::AtomicU64,
+    pub requests_duplicate: std::sync::atomic::AtomicU64,
+    pub poll_anomalies: std::sync::atomic::AtomicU64,
+    pub source_port_123_rejected: std::sync::atomic::AtomicU64,
+    pub auth_failures: std::sync::atomic::AtomicU64,
+    pub icmp_errors: std::sync::atomic::AtomicU64,
     pub errors: std::sync::atomic::AtomicU64,
+
+    /// Rejets par règle de validation
+    pub validation_errors: ValidationErrorCounters,
+
+    /// Latence de traitement T3 - T2, agrégée sans verrou
+    pub processing_latency: LatencyAccumulator,
+
+    /// Latence d'émission logicielle, moyenne glissante en nanosecondes
+    /// (0 = aucune mesure)
+    pub tx_software_latency_ns: std::sync::atomic::AtomicU64,
+
+    /// Dernière réponse émise (millisecondes depuis `started`)
+    pub last_tx_ms: std::sync::atomic::AtomicU64,
+    started: Instant,
 }
 
 impl ServerStats {
@@ -23,10 +101,48 @@ impl ServerStats {
             requests_received: std::sync::atomic::AtomicU64::new(0),
             requests_processed: std::sync::atomic::AtomicU64::new(0),
             requests_rejected: std::sync::atomic::AtomicU64::new(0),
+            requests_duplicate: std::sync::atomic::AtomicU64::new(0),
+            poll_anomalies: std::sync::atomic::AtomicU64::new(0),
+            source_port_123_rejected: std::sync::atomic::AtomicU64::new(0),
+            auth_failures: std::sync::atomic::AtomicU64::new(0),
+            icmp_errors: std::sync::atomic::AtomicU64::new(0),
             errors: std::sync::atomic::AtomicU64::new(0),
+            validation_errors: ValidationErrorCounters::default(),
+            processing_latency: LatencyAccumulator::default(),
+            tx_software_latency_ns: std::sync::atomic::AtomicU64::new(0),
+            last_tx_ms: std::sync::atomic::AtomicU64::new(0),
+            started: Instant::now(),
         }
     }
 
+    /// Intègre une latence d'émission à la moyenne glissante (1/16) qui lisse
+    /// la gigue d'ordonnancement
+    fn record_tx_latency(&self, latency_ns: u64) {
+        let latency_ns = latency_ns.max(1);
+        let _ = self.tx_software_latency_ns.fetch_update(
+            std::sync::atomic::Ordering::Relaxed,
+            std::sync::atomic::Ordering::Relaxed,
+            |previous| Some(if previous == 0 { latency_ns } else { (previous * 15 + latency_ns) / 16 }),
+        );
+    }
+
+    /// Latence d'émission logicielle moyenne, si mesurée
+    fn tx_latency_ns(&self) -> Option<u64> {
+        Some(self.tx_software_latency_ns.load(std::sync::atomic::Ordering::Relaxed)).filter(|&ns| ns != 0)
+    }
+
+    /// Horodate la dernière réponse émise
+    fn record_tx(&self) {
+        let since_start = self.started.elapsed().as_millis() as u64;
+        self.last_tx_ms.store(since_start, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Millisecondes écoulées depuis la dernière réponse (depuis le démarrage si aucune)
+    fn ms_since_last_tx(&self) -> u64 {
+        let since_start = self.started.elapsed().as_millis() as u64;
+        since_start.saturating_sub(self.last_tx_ms.load(std::sync::atomic::Ordering::Relaxed))
+    }
+
     pub fn log_stats(&self) {
         let received = self.requests_received.load(std::sync::atomic::Ordering::Relaxed);
         let processed = self.requests_processed.load(std::sync::atomic::Ordering::Relaxed);
@@ -46,14 +162,134 @@ impl Default for ServerStats {
     }
 }
 
+/// Compteurs de rejet par règle de validation, incrémentés sans verrou et
+/// recopiés dans les stats partagées par le thread de statistiques
+#[derive(Default)]
+pub struct ValidationErrorCounters {
+    pub invalid_version: std::sync::atomic::AtomicU64,
+    pub invalid_mode: std::sync::atomic::AtomicU64,
+    pub zero_transmit_timestamp: std::sync::atomic::AtomicU64,
+    pub invalid_stratum: std::sync::atomic::AtomicU64,
+    pub malformed: std::sync::atomic::AtomicU64,
+}
+
+impl ValidationErrorCounters {
+    /// Valeurs courantes, au format des stats partagées
+    pub fn snapshot(&self) -> ValidationErrorCounts {
+        let load = |counter: &std::sync::atomic::AtomicU64| counter.load(std::sync::atomic::Ordering::Relaxed);
+        ValidationErrorCounts {
+            invalid_version: load(&self.invalid_version),
+            invalid_mode: load(&self.invalid_mode),
+            zero_transmit_timestamp: load(&self.zero_transmit_timestamp),
+            invalid_stratum: load(&self.invalid_stratum),
+            malformed: load(&self.malformed),
+        }
+    }
+}
+
 /// Serveur NTP
 pub struct NtpServer<C: ClockSource + ?Sized> {
     config: Config,
     clock: Arc<C>,
-    rate_limiter: Option<RateLimiter>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    duplicate_detector: Option<DuplicateDetector>,
+    global_limiter: Option<GlobalRateLimiter>,
+    leap_seconds: Option<Arc<LeapSecondTable>>,
     ip_filter: IpFilter,
     stats: Arc<ServerStats>,
     shared_stats: Arc<std::sync::RwLock<SharedServerStats>>,
+
+    /// Horodatage d'émission noyau actif (désactivé si un socket le refuse)
+    tx_timestamping: std::sync::atomic::AtomicBool,
+
+    /// Connexions TCP en cours, bornées par `server.max_tcp_connections`
+    tcp_connections: std::sync::atomic::AtomicUsize,
+
+    /// Politiques de réponse par port d'écoute (`server.listen_policies`)
+    listen_policies: Vec<(u16, SocketPolicy)>,
+
+    /// Politique des ports sans politique propre
+    default_policy: SocketPolicy,
+
+    /// Associations symétriques passives (`server.allow_symmetric`)
+    symmetric_peers: Option<SymmetricPeers>,
+
+    /// Journal d'audit échantillonné des réponses (`logging.audit_log_file`)
+    audit_log: Option<AuditLog>,
+
+    /// Total des requêtes servies persisté entre redémarrages (`server.requests_count_file`)
+    request_count: Option<RequestCountFile>,
+
+    /// Clés symétriques par identifiant (`security.keys`)
+    keys: HashMap<u32, SymmetricKey>,
+}
+
+/// Envois d'un socket en attente de leur horodatage noyau, numérotés comme
+/// le noyau (`SOF_TIMESTAMPING_OPT_ID` : rang du datagramme sur le socket).
+/// Propre à la boucle de réception du socket : aucun verrou partagé
+#[derive(Default)]
+struct TxPending {
+    /// Rang du prochain datagramme émis
+    next_id: u32,
+
+    /// (rang, horloge système avant l'envoi), du plus ancien au plus récent
+    sent: std::collections::VecDeque<(u32, std::time::Duration)>,
+}
+
+impl TxPending {
+    /// Enregistre un envoi réussi
+    fn push(&mut self, sent_at: std::time::Duration) {
+        if self.sent.len() == TX_PENDING_MAX {
+            self.sent.pop_front();
+        }
+        self.sent.push_back((self.next_id, sent_at));
+        self.next_id = self.next_id.wrapping_add(1);
+    }
+
+    /// Instant d'envoi du datagramme horodaté ; les envois plus anciens, dont
+    /// l'horodatage ne viendra plus, sont oubliés
+    fn take(&mut self, id: u32) -> Option<std::time::Duration> {
+        let Some(position) = self.sent.iter().position(|(sent_id, _)| *sent_id == id) else {
+            // Rang en avance sur le compteur local (envoi échoué compté par le noyau) :
+            // resynchronisation sur le noyau
+            if id.wrapping_sub(self.next_id) < u32::MAX / 2 {
+                self.sent.clear();
+                self.next_id = id.wrapping_add(1);
+            }
+            return None;
+        };
+        let (_, sent_at) = self.sent[position];
+        self.sent.drain(..=position);
+        Some(sent_at)
+    }
+}
+
+/// Politique de réponse d'un socket d'écoute
+#[derive(Default)]
+struct SocketPolicy {
+    /// Plancher de stratum annoncé (remplace `advertised_stratum_min`)
+    stratum_min: Option<u8>,
+
+    /// Reference ID annoncé quel que soit le stratum
+    reference_id: Option<[u8; 4]>,
+
+    /// Rate limiter propre au socket (remplace le rate limiter global)
+    rate_limiter: Option<Arc<RateLimiter>>,
+
+    /// Réponse en cache (`server.response_template`)
+    template: std::sync::RwLock<Option<ResponseTemplate>>,
+}
+
+/// Réponse pré-construite, valable pour une seconde et un stratum d'horloge
+#[derive(Clone, Copy)]
+struct ResponseTemplate {
+    /// Seconde NTP de réception pour laquelle le modèle a été construit
+    second: u32,
+
+    /// Stratum de l'horloge à la construction (changement d'état = reconstruction)
+    clock_stratum: u8,
+
+    packet: NtpPacket,
 }
 
 impl<C: ClockSource + ?Sized> NtpServer<C> {
@@ -62,86 +298,383 @@ impl<C: ClockSource + ?Sized> NtpServer<C> {
         clock: Arc<C>,
         shared_stats: Arc<std::sync::RwLock<SharedServerStats>>,
     ) -> Self {
-        let rate_limiter = if config.security.enable_rate_limiting {
-            Some(RateLimiter::new(config.security.max_requests_per_second))
+        // Mode anycast : aucun état par client, seule la limite globale s'applique
+        let anycast = config.server.anycast_mode;
+        if anycast {
+            info!(
+                "Anycast mode: no per-client state, global limit {} requests/s",
+                config.security.global_max_requests_per_second
+            );
+        }
+
+        let min_client_interval = std::time::Duration::from_millis(config.security.min_client_interval_ms);
+        let rate_limiter = if config.security.enable_rate_limiting && !anycast {
+            // Overrides déjà validés au chargement de la configuration
+            let overrides = config
+                .security
+                .rate_limit_overrides
+                .iter()
+                .filter_map(|o| Some((o.cidr.parse().ok()?, o.max_requests_per_second)))
+                .collect();
+            Some(Arc::new(
+                RateLimiter::new(config.security.max_requests_per_second)
+                    .with_overrides(overrides)
+                    .with_min_interval(min_client_interval),
+            ))
+        } else {
+            None
+        };
+
+        let duplicate_detector = if config.server.detect_duplicate_requests && !anycast {
+            Some(DuplicateDetector::new(
+                DuplicateDetector::DEFAULT_WINDOW,
+                DuplicateDetector::DEFAULT_CAPACITY,
+            ))
         } else {
             None
         };
 
+        let global_limiter = anycast
+            .then(|| GlobalRateLimiter::new(config.security.global_max_requests_per_second));
+
         let ip_filter = IpFilter::new(
             config.security.ip_whitelist.clone(),
             config.security.ip_blacklist.clone(),
         );
 
+        let config_tx_timestamping = config.server.tx_timestamping;
+
+        // Pairs symétriques (CIDR déjà validés au chargement de la configuration)
+        let symmetric_peers = config.server.allow_symmetric.then(|| {
+            let allowlist = config
+                .server
+                .symmetric_peers
+                .iter()
+                .filter_map(|peer| peer.parse().ok())
+                .collect();
+            SymmetricPeers::new(allowlist, SymmetricPeers::DEFAULT_CAPACITY)
+        });
+
+        // Politiques par port (déjà validées au chargement de la configuration)
+        let listen_policies = config
+            .server
+            .listen_policies
+            .iter()
+            .map(|policy| {
+                let rate_limiter = policy
+                    .max_requests_per_second
+                    .filter(|_| config.security.enable_rate_limiting && !anycast)
+                    .map(|max| Arc::new(RateLimiter::new(max).with_min_interval(min_client_interval)));
+                (
+                    policy.port,
+                    SocketPolicy {
+                        stratum_min: policy.advertised_stratum_min,
+                        reference_id: policy.reference_id.as_deref().map(refid_from_str),
+                        rate_limiter,
+                        template: Default::default(),
+                    },
+                )
+            })
+            .collect();
+
+        let keys = auth::keys_from_config(&config.security.keys);
+
         NtpServer {
             config,
             clock,
             rate_limiter,
+            duplicate_detector,
+            global_limiter,
+            leap_seconds: None,
             ip_filter,
             stats: Arc::new(ServerStats::new()),
             shared_stats,
+            tx_timestamping: std::sync::atomic::AtomicBool::new(config_tx_timestamping),
+            tcp_connections: std::sync::atomic::AtomicUsize::new(0),
+            listen_policies,
+            default_policy: SocketPolicy::default(),
+            symmetric_peers,
+            audit_log: None,
+            request_count: None,
+            keys,
+        }
+    }
+
+    /// Table des secondes intercalaires : annonce dans le leap indicator et
+    /// décalage GPS - UTC dans les statistiques
+    pub fn with_leap_seconds(mut self, table: Arc<LeapSecondTable>) -> Self {
+        self.leap_seconds = Some(table);
+        self
+    }
+
+    /// Enregistre un échantillon des réponses envoyées dans un journal d'audit
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Reprend `requests_total` du fichier d'état et l'y réécrit périodiquement
+    pub fn with_request_count_file(mut self, request_count: RequestCountFile) -> Self {
+        self.request_count = Some(request_count);
+        self
+    }
+
+    /// Total des requêtes servies, y compris celles des exécutions précédentes
+    fn requests_total(&self) -> u64 {
+        let base = self.request_count.as_ref().map_or(0, RequestCountFile::base);
+        base + self.stats.requests_processed.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Réécrit le total des requêtes servies dans le fichier d'état
+    fn flush_request_count(&self) {
+        if let Some(ref request_count) = self.request_count {
+            if let Err(e) = request_count.save(self.requests_total()) {
+                warn!("Failed to save request count: {}", e);
+            }
         }
     }
 
     /// Démarre le serveur NTP
     pub fn run(&self, shutdown: Arc<std::sync::atomic::AtomicBool>) -> Result<()> {
-        let socket = UdpSocket::bind(&self.config.server.bind_address)
-            .context("Failed to bind UDP socket")?;
+        let sockets = self.bind_sockets()?;
 
-        // Configurer un timeout pour recv_from afin de pouvoir vérifier le shutdown flag
-        socket.set_read_timeout(Some(std::time::Duration::from_millis(500)))
-            .context("Failed to set socket read timeout")?;
+        let tcp_listener = if self.config.server.tcp_enabled {
+            Some(self.bind_tcp_listener()?)
+        } else {
+            None
+        };
 
-        info!("NTP server listening on {}", self.config.server.bind_address);
         info!("Clock source: {}", self.config.clock.source);
         info!("Stratum: {}", self.clock.stratum());
 
-        // Thread pour logger les stats périodiquement et mettre à jour les stats partagées
-        let stats_clone = Arc::clone(&self.stats);
-        let shared_stats_clone = Arc::clone(&self.shared_stats);
-        std::thread::spawn(move || {
-            let mut last_requests = 0u64;
-            let mut last_tx = Instant::now();
-
-            loop {
-                std::thread::sleep(std::time::Duration::from_secs(1));
-
-                // Calculer requests per second
-                let current_requests = stats_clone.requests_processed.load(std::sync::atomic::Ordering::Relaxed);
-                let requests_per_second = (current_requests - last_requests) as u32;
-                last_requests = current_requests;
-
-                // Mettre à jour les stats partagées
-                if let Ok(mut stats) = shared_stats_clone.write() {
-                    stats.ntp.requests_per_second = requests_per_second;
-
-                    // Mettre à jour last_tx_ms
-                    let tx_elapsed_ms = last_tx.elapsed().as_millis() as u64;
-                    if stats.ntp.last_tx_ms == 0 {
-                        // Un TX vient de se produire, réinitialiser le timer
-                        last_tx = Instant::now();
-                    } else {
-                        stats.ntp.last_tx_ms = tx_elapsed_ms;
-                    }
+        self.serve(sockets, tcp_listener, shutdown)
+    }
+
+    /// Adresse d'écoute principale (`bind_address`)
+    fn primary_address(&self) -> Result<SocketAddr> {
+        self.config
+            .server
+            .bind_address
+            .to_socket_addrs()
+            .context("Invalid bind address")?
+            .next()
+            .context("Bind address did not resolve")
+    }
+
+    /// Ouvre le socket principal (`bind_address`) et un socket par port additionnel
+    /// (même adresse IP, `additional_ports`)
+    fn bind_sockets(&self) -> Result<Vec<UdpSocket>> {
+        let primary = self.primary_address()?;
+        if primary.port() != NTP_PORT {
+            warn!(
+                "NTP server bound to port {} instead of {}: standard NTP clients will not reach it",
+                primary.port(),
+                NTP_PORT
+            );
+        }
+
+        let mut addresses = vec![primary];
+        for port in &self.config.server.additional_ports {
+            let mut addr = primary;
+            addr.set_port(*port);
+            addresses.push(addr);
+        }
+
+        let mut sockets = Vec::with_capacity(addresses.len());
+        for addr in addresses {
+            let socket = UdpSocket::bind(addr)
+                .map_err(|e| bind_error(e, "UDP socket", addr))?;
+
+            // Configurer un timeout pour recv_from afin de pouvoir vérifier le shutdown flag
+            socket.set_read_timeout(Some(std::time::Duration::from_millis(500)))
+                .context("Failed to set socket read timeout")?;
+
+            if self.tx_timestamping.load(std::sync::atomic::Ordering::Relaxed) {
+                if let Err(e) = tx_timestamp::enable(&socket) {
+                    warn!("TX timestamping unavailable on {}: {}", addr, e);
+                    self.tx_timestamping.store(false, std::sync::atomic::Ordering::Relaxed);
+                }
+            }
+
+            if self.config.server.dont_fragment {
+                if let Err(e) = socket_options::set_dont_fragment(&socket) {
+                    warn!("Don't-fragment unavailable on {}: {}", addr, e);
                 }
+            }
 
-                // Log toutes les 60 secondes
-                if current_requests % 60 == 0 {
-                    stats_clone.log_stats();
+            if self.config.server.log_icmp_errors {
+                if let Err(e) = socket_options::enable_recv_err(&socket) {
+                    warn!("ICMP error reporting unavailable on {}: {}", addr, e);
                 }
             }
+
+            info!("NTP server listening on {}", addr);
+            sockets.push(socket);
+        }
+
+        Ok(sockets)
+    }
+
+    /// Ouvre le listener TCP expérimental sur `bind_address`
+    fn bind_tcp_listener(&self) -> Result<TcpListener> {
+        let addr = self.primary_address()?;
+        let listener = TcpListener::bind(addr)
+            .map_err(|e| bind_error(e, "TCP listener", addr))?;
+
+        // Non bloquant pour pouvoir vérifier le shutdown flag entre deux connexions
+        listener
+            .set_nonblocking(true)
+            .context("Failed to set TCP listener non-blocking")?;
+
+        warn!("NTP over TCP listening on {} (EXPERIMENTAL)", addr);
+        Ok(listener)
+    }
+
+    /// Sert les requêtes sur les sockets donnés jusqu'à la demande d'arrêt
+    ///
+    /// Une boucle de réception par socket ; l'horloge, les stats et le rate limiter
+    /// sont partagés. Chaque réponse repart par le socket qui a reçu la requête.
+    /// Le listener TCP optionnel a sa propre boucle d'acceptation.
+    fn serve(
+        &self,
+        sockets: Vec<UdpSocket>,
+        tcp_listener: Option<TcpListener>,
+        shutdown: Arc<std::sync::atomic::AtomicBool>,
+    ) -> Result<()> {
+        std::thread::scope(|scope| {
+            // Thread pour logger les stats périodiquement et mettre à jour les stats partagées
+            let shutdown_updater = &shutdown;
+            scope.spawn(move || run_guarded("stats_updater", &self.shared_stats, || self.stats_update_loop(shutdown_updater)));
+
+            for socket in &sockets {
+                let shutdown = &shutdown;
+                scope.spawn(move || self.receive_loop(socket, shutdown));
+            }
+
+            if let Some(ref listener) = tcp_listener {
+                let shutdown = &shutdown;
+                scope.spawn(move || self.tcp_accept_loop(listener, shutdown, scope));
+            }
         });
 
-        let mut buffer = [0u8; NtpPacket::SIZE];
+        info!("NTP server stopped");
+        Ok(())
+    }
+
+    /// Publie chaque seconde les compteurs du chemin de réponse dans les stats partagées
+    ///
+    /// Seul ce thread prend le verrou des statistiques pour les requêtes traitées :
+    /// le chemin de réponse n'incrémente que des atomiques.
+    fn stats_update_loop(&self, shutdown: &std::sync::atomic::AtomicBool) {
+        let mut last_requests = 0u64;
+        let mut last_rejected = 0u64;
+        let mut last_flush = Instant::now();
+        let mut smoothed_rate = RateSmoother::new(self.config.server.requests_rate_window_secs);
+
+        while !shutdown.load(std::sync::atomic::Ordering::Relaxed) {
+            heartbeat::STATS_UPDATER.beat();
+            std::thread::sleep(std::time::Duration::from_secs(1));
+
+            // Calculer requests per second
+            let current_requests = self.stats.requests_processed.load(std::sync::atomic::Ordering::Relaxed);
+            let requests_per_second = (current_requests - last_requests) as u32;
+            last_requests = current_requests;
+            let requests_per_second_smoothed = smoothed_rate.update(requests_per_second as f64);
+
+            let current_rejected = self.stats.requests_rejected.load(std::sync::atomic::Ordering::Relaxed);
+            let rejected_per_second = (current_rejected - last_rejected) as u32;
+            last_rejected = current_rejected;
+
+            if let Ok(mut stats) = write_stats(&self.shared_stats) {
+                stats.ntp.requests_per_second = requests_per_second;
+                stats.ntp.requests_per_second_smoothed = requests_per_second_smoothed;
+                stats.ntp.rejected_per_second = rejected_per_second;
+                self.publish_stats(&mut stats);
+            }
+
+            if self
+                .request_count
+                .as_ref()
+                .is_some_and(|request_count| last_flush.elapsed() >= request_count.flush_interval())
+            {
+                self.flush_request_count();
+                last_flush = Instant::now();
+            }
+
+            // Log toutes les 60 secondes
+            if current_requests.is_multiple_of(60) {
+                self.stats.log_stats();
+            }
+        }
+
+        // Arrêt : dernier total persisté
+        self.flush_request_count();
+    }
+
+    /// Recopie les compteurs atomiques et l'état de l'horloge dans les stats partagées
+    fn publish_stats(&self, stats: &mut SharedServerStats) {
+        let load = |counter: &std::sync::atomic::AtomicU64| counter.load(std::sync::atomic::Ordering::Relaxed);
+        stats.ntp.requests_total = self.requests_total();
+        stats.ntp.duplicate_requests = load(&self.stats.requests_duplicate);
+        stats.ntp.poll_anomalies = load(&self.stats.poll_anomalies);
+        stats.ntp.source_port_123_rejected = load(&self.stats.source_port_123_rejected);
+        stats.ntp.auth_failures = load(&self.stats.auth_failures);
+        stats.ntp.icmp_errors = load(&self.stats.icmp_errors);
+        stats.ntp.validation_errors = self.stats.validation_errors.snapshot();
+        if let Some(ref peers) = self.symmetric_peers {
+            stats.ntp.symmetric_peers = peers.len();
+        }
+        stats.ntp.last_tx_ms = self.stats.ms_since_last_tx();
+        stats.ntp.processing_latency = self.stats.processing_latency.snapshot();
+        stats.ntp.tx_software_latency_ns = self.stats.tx_latency_ns();
+
+        let timestamp = self.clock.now();
+        stats.clock.current_timestamp = timestamp.seconds() as u64;
+        stats.clock.current_fraction_ns = ((timestamp.fraction() as u64 * 1_000_000_000) >> 32) as u32;
+        stats.clock.stratum = self.clock.stratum();
+        stats.clock.reference_id = String::from_utf8_lossy(&self.clock.reference_id()).to_string();
+        stats.clock.precision = self.clock.precision();
+        stats.clock.active_source = self.clock.active_source();
+        stats.clock.divergence_alarm = self.clock.divergence_alarm();
+
+        if let Some(ref table) = self.leap_seconds {
+            let now = timestamp.seconds() as u64;
+            stats.clock.leap_offset_seconds = table.gps_utc_at(now);
+            stats.clock.next_leap_second = table
+                .next_leap_after(now)
+                .and_then(|leap| ntp_seconds_to_iso(leap.ntp_seconds));
+        }
+    }
+
+    /// Champ d'extension portant l'incertitude mesurée (`server.emit_uncertainty_ext`),
+    /// pour les requêtes NTPv4 lorsque la source d'horloge fournit une estimation.
+    /// Réservé aux requêtes au moins aussi longues que la réponse étendue : pas
+    /// d'amplification (76 octets pour une requête de 48)
+    fn uncertainty_extension(&self, request: &NtpPacket, request_len: usize) -> Option<ExtensionField> {
+        if !self.config.server.emit_uncertainty_ext
+            || request.version != 4
+            || request_len < NtpPacket::SIZE + ExtensionField::MIN_LAST_LEN
+        {
+            return None;
+        }
+        self.clock.time_accuracy_ns().map(ExtensionField::uncertainty)
+    }
+
+    /// Boucle de réception d'un socket
+    fn receive_loop(&self, socket: &UdpSocket, shutdown: &std::sync::atomic::AtomicBool) {
+        let mut buffer = [0u8; MAX_UDP_PACKET_SIZE];
+        let mut tx_pending = TxPending::default();
 
         loop {
+            heartbeat::NTP_RECEIVER.beat();
+
             // Vérifier si l'arrêt a été demandé
             if shutdown.load(std::sync::atomic::Ordering::Relaxed) {
-                info!("Shutdown signal received, stopping NTP server...");
+                info!("Shutdown signal received, stopping NTP listener...");
                 break;
             }
 
-            match self.handle_request(&socket, &mut buffer) {
+            match self.handle_request(socket, &mut buffer, &mut tx_pending) {
                 Ok(_) => {}
                 Err(e) => {
                     // Ignorer les timeouts (normaux pour pouvoir vérifier shutdown)
@@ -150,27 +683,208 @@ impl<C: ClockSource + ?Sized> NtpServer<C> {
                             || io_error.kind() == std::io::ErrorKind::TimedOut {
                             continue;
                         }
+
+                        // Erreur ICMP en attente (client injoignable) : pas une erreur du serveur
+                        if self.config.server.log_icmp_errors && socket_options::is_icmp_error(io_error) {
+                            self.drain_error_queue(socket, &mut tx_pending);
+                            continue;
+                        }
                     }
                     error!("Error handling request: {:#}", e);
                     self.stats.errors.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                 }
             }
         }
+    }
 
-        info!("NTP server stopped");
-        Ok(())
+    /// Boucle d'acceptation TCP : une connexion = un thread (dans le scope du serveur),
+    /// dans la limite de `server.max_tcp_connections`
+    fn tcp_accept_loop<'scope, 'env>(
+        &'env self,
+        listener: &'env TcpListener,
+        shutdown: &'env std::sync::atomic::AtomicBool,
+        scope: &'scope std::thread::Scope<'scope, 'env>,
+    ) {
+        let max = self.config.server.max_tcp_connections;
+        while !shutdown.load(std::sync::atomic::Ordering::Relaxed) {
+            match listener.accept() {
+                Ok((stream, client_addr)) => {
+                    let acquired = self.tcp_connections.fetch_update(
+                        std::sync::atomic::Ordering::AcqRel,
+                        std::sync::atomic::Ordering::Acquire,
+                        |active| (max == 0 || active < max).then_some(active + 1),
+                    );
+                    if acquired.is_err() {
+                        // Le flux est fermé en sortant de portée
+                        debug!("TCP connection from {} refused: {} connections open", client_addr, max);
+                        continue;
+                    }
+                    scope.spawn(move || {
+                        self.handle_tcp_connection(stream, client_addr);
+                        self.tcp_connections.fetch_sub(1, std::sync::atomic::Ordering::AcqRel);
+                    });
+                }
+                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(std::time::Duration::from_millis(100));
+                }
+                Err(e) => {
+                    error!("Error accepting TCP connection: {}", e);
+                    self.stats.errors.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                }
+            }
+        }
+    }
+
+    /// Gère une connexion TCP : paquets NTP préfixés de leur longueur (2 octets big-endian),
+    /// jusqu'à fermeture par le client ou inactivité
+    fn handle_tcp_connection(&self, mut stream: TcpStream, client_addr: SocketAddr) {
+        if stream.set_nonblocking(false).is_err()
+            || stream.set_read_timeout(Some(TCP_IDLE_TIMEOUT)).is_err()
+        {
+            return;
+        }
+
+        let mut length = [0u8; 2];
+        let mut buffer = [0u8; MAX_TCP_PACKET_SIZE];
+        let policy = self.policy_for(stream.local_addr().ok());
+
+        loop {
+            // Fin de connexion ou inactivité : fermeture silencieuse
+            if stream.read_exact(&mut length).is_err() {
+                return;
+            }
+
+            let size = u16::from_be_bytes(length) as usize;
+            if size > MAX_TCP_PACKET_SIZE {
+                warn!("TCP packet too large from {}: {} bytes", client_addr, size);
+                return;
+            }
+            if stream.read_exact(&mut buffer[..size]).is_err() {
+                return;
+            }
+
+            // TIMESTAMP T2: Moment de réception (paquet complet lu)
+            let receive_time = self.clock.now();
+
+            let result = self.process_request(&buffer[..size], client_addr, receive_time, policy, |bytes| {
+                let mut frame = Vec::with_capacity(2 + bytes.len());
+                frame.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+                frame.extend_from_slice(bytes);
+                stream.write_all(&frame)
+            });
+
+            if let Err(e) = result {
+                debug!("TCP connection from {} closed: {:#}", client_addr, e);
+                self.stats.errors.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                return;
+            }
+        }
     }
 
     /// Gère une requête NTP
-    fn handle_request(&self, socket: &UdpSocket, buffer: &mut [u8]) -> Result<()> {
+    fn handle_request(&self, socket: &UdpSocket, buffer: &mut [u8], tx_pending: &mut TxPending) -> Result<()> {
         // Réception du paquet
         let (size, client_addr) = socket.recv_from(buffer)?;
 
         // TIMESTAMP T2: Moment de réception (le plus tôt possible après recv_from)
         let receive_time = self.clock.now();
 
+        let policy = self.policy_for(socket.local_addr().ok());
+
+        // Horloge système juste avant l'envoi, comparée à l'horodatage noyau
+        let tx_timestamping = self.tx_timestamping.load(std::sync::atomic::Ordering::Relaxed);
+        let mut sent_at = None;
+
+        let result = self.process_request(&buffer[..size], client_addr, receive_time, policy, |bytes| {
+            if tx_timestamping {
+                sent_at = SystemTime::now().duration_since(UNIX_EPOCH).ok();
+            }
+            socket.send_to(bytes, client_addr).map(|_| ())
+        });
+
+        if let Some(sent_at) = sent_at.filter(|_| result.is_ok()) {
+            self.record_tx_timestamp(socket, tx_pending, sent_at);
+        }
+
+        result
+    }
+
+    /// Mesure la latence d'émission logicielle (envoi → horodatage noyau), sans
+    /// attendre : les horodatages déjà disponibles sont rapprochés de leur envoi
+    fn record_tx_timestamp(&self, socket: &UdpSocket, pending: &mut TxPending, sent_at: std::time::Duration) {
+        pending.push(sent_at);
+        self.drain_error_queue(socket, pending);
+    }
+
+    /// Vide la file d'erreurs du socket : horodatages d'émission rapprochés de
+    /// leur envoi, erreurs ICMP journalisées et comptées
+    fn drain_error_queue(&self, socket: &UdpSocket, pending: &mut TxPending) {
+        let messages = match socket_options::read_error_queue(socket) {
+            Ok(messages) => messages,
+            Err(e) => {
+                debug!("Failed to read socket error queue: {}", e);
+                return;
+            }
+        };
+
+        for message in messages {
+            match message {
+                ErrorQueueMessage::TxTimestamp(timestamp) => {
+                    let Some(sent_at) = pending.take(timestamp.id) else {
+                        continue;
+                    };
+                    let latency_ns = timestamp.time.saturating_sub(sent_at).as_nanos().min(u64::MAX as u128) as u64;
+                    self.stats.record_tx_latency(latency_ns);
+                }
+                ErrorQueueMessage::Icmp(icmp) => {
+                    self.stats.icmp_errors.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    match icmp.destination {
+                        Some(client) => info!("Client {} unreachable: {}", client, icmp),
+                        None => info!("Client unreachable: {}", icmp),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Politique de réponse du socket ayant reçu la requête (adresse locale)
+    fn policy_for(&self, local_addr: Option<SocketAddr>) -> &SocketPolicy {
+        if self.listen_policies.is_empty() {
+            return &self.default_policy;
+        }
+
+        local_addr
+            .and_then(|addr| self.listen_policies.iter().find(|(port, _)| *port == addr.port()))
+            .map(|(_, policy)| policy)
+            .unwrap_or(&self.default_policy)
+    }
+
+    /// Traite une requête reçue (UDP ou TCP) et envoie la réponse via `send`
+    fn process_request<F>(
+        &self,
+        data: &[u8],
+        client_addr: SocketAddr,
+        receive_time: NtpTimestamp,
+        policy: &SocketPolicy,
+        send: F,
+    ) -> Result<()>
+    where
+        F: FnOnce(&[u8]) -> std::io::Result<()>,
+    {
         self.stats.requests_received.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
+        // Pas de temps absolu fiable (RTC non fiable et GPS pas encore reçu) : ne pas répondre
+        if !self.clock.has_absolute_time() {
+            let divergence_alarm = self.clock.divergence_alarm();
+            if divergence_alarm {
+                debug!("Request from {} dropped: GPS and system clock diverge", client_addr);
+            } else {
+                debug!("Request from {} dropped: no absolute time available yet", client_addr);
+            }
+            self.stats.requests_rejected.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            return Ok(());
+        }
+
         // Extraction de l'IP du client
         let client_ip = client_addr.ip();
 
@@ -181,8 +895,8 @@ impl<C: ClockSource + ?Sized> NtpServer<C> {
             return Ok(());
         }
 
-        // Vérification du rate limiting
-        if let Some(ref limiter) = self.rate_limiter {
+        // Vérification du rate limiting (limite propre au socket si configurée)
+        if let Some(limiter) = policy.rate_limiter.as_ref().or(self.rate_limiter.as_ref()) {
             if !limiter.check_rate_limit(client_ip) {
                 warn!("Request from {} rejected by rate limiter", client_addr);
                 self.stats.requests_rejected.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
@@ -190,23 +904,107 @@ impl<C: ClockSource + ?Sized> NtpServer<C> {
             }
         }
 
+        // Limite globale du mode anycast (indépendante de l'IP du client)
+        if let Some(ref limiter) = self.global_limiter {
+            if !limiter.try_acquire() {
+                debug!("Request from {} rejected by global rate limit", client_addr);
+                self.stats.requests_rejected.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                return Ok(());
+            }
+        }
+
+        // Version 0 (clients anciens) traitée comme une version 4 si autorisé
+        let patched;
+        let data = if self.config.security.accept_version_zero
+            && data.first().is_some_and(|b| (b >> 3) & 0x07 == 0)
+        {
+            debug!("Version 0 request from {} answered as version 4", client_addr);
+            patched = {
+                let mut bytes = data.to_vec();
+                bytes[0] |= 4 << 3;
+                bytes
+            };
+            &patched[..]
+        } else {
+            data
+        };
+
         // Parse du paquet NTP
-        let request_packet = match NtpPacket::from_bytes(&buffer[..size]) {
+        let mut request_packet = match NtpPacket::from_bytes(data) {
             Ok(packet) => packet,
             Err(e) => {
                 warn!("Failed to parse NTP packet from {}: {}", client_addr, e);
                 self.stats.requests_rejected.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                let counters = &self.stats.validation_errors;
+                let counter = match e {
+                    NtpError::InvalidVersion(_) => &counters.invalid_version,
+                    NtpError::InvalidMode(_) => &counters.invalid_mode,
+                    NtpError::InvalidStratum(_) => &counters.invalid_stratum,
+                    NtpError::InvalidSize { .. } => &counters.malformed,
+                };
+                counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                 return Ok(());
             }
         };
 
+        // Client mal configuré en mode symétrique actif (hors pair configuré) :
+        // servi comme une requête client, sans association
+        let symmetric_as_client = self.config.security.answer_symmetric_as_server
+            && request_packet.mode == NtpMode::SymmetricActive
+            && !self.symmetric_peers.as_ref().is_some_and(|peers| peers.is_allowed(client_ip));
+        if symmetric_as_client {
+            debug!("Symmetric active packet from non-peer {} answered in server mode", client_addr);
+            request_packet.mode = NtpMode::Client;
+        }
+
         // Validation du paquet
-        if let Err(e) = PacketValidator::validate_request(&request_packet) {
+        if let Err(e) = PacketValidator::validate_request(&request_packet, self.symmetric_peers.is_some()) {
             warn!("Invalid NTP request from {}: {}", client_addr, e);
             self.stats.requests_rejected.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            let counters = &self.stats.validation_errors;
+            let counter = match e {
+                ValidationError::InvalidVersion(_) => &counters.invalid_version,
+                ValidationError::InvalidMode => &counters.invalid_mode,
+                ValidationError::ZeroTransmitTimestamp => &counters.zero_transmit_timestamp,
+                ValidationError::InvalidStratum(_) => &counters.invalid_stratum,
+            };
+            counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            return Ok(());
+        }
+
+        // Requête client depuis le port 123 : adresse d'un autre serveur usurpée
+        // pour provoquer une boucle de réponses entre serveurs. Les paquets mode 1
+        // servis en mode serveur en sont exemptés (ntpd émet depuis le port 123)
+        if self.config.security.reject_source_port_123
+            && request_packet.mode == NtpMode::Client
+            && !symmetric_as_client
+            && client_addr.port() == NTP_PORT
+        {
+            debug!("Client request from {} dropped: source port {}", client_addr, NTP_PORT);
+            self.stats.requests_rejected.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            self.stats.source_port_123_rejected.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
             return Ok(());
         }
 
+        // MAC de la requête (en fin de paquet, après d'éventuels champs d'extension)
+        // vérifié si des clés sont configurées ; sans clé, le MAC est ignoré et la
+        // réponse part sans authentification
+        let authenticated = match request_packet.mac {
+            Some(mac) if !self.keys.is_empty() => {
+                match auth::verify_mac(&self.keys, &data[..data.len() - mac.wire_len()], &mac) {
+                    Ok(key) => Some((mac.key_id, key)),
+                    Err(e) => {
+                        // debug : n'importe quel émetteur peut produire un MAC invalide
+                        debug!("Authenticated request from {} dropped: {}", client_addr, e);
+                        self.stats.requests_rejected.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        self.stats.auth_failures.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        return Ok(());
+                    }
+                }
+            }
+            _ => None,
+        };
+
         if self.config.logging.log_requests {
             debug!(
                 "NTP request from {}: version={}, mode={:?}, stratum={}",
@@ -214,35 +1012,90 @@ impl<C: ClockSource + ?Sized> NtpServer<C> {
             );
         }
 
+        // Pair symétrique actif : association mise à jour, paquet écarté si refusé
+        let symmetric_peers = self
+            .symmetric_peers
+            .as_ref()
+            .filter(|_| request_packet.mode == NtpMode::SymmetricActive);
+        if let Some(peers) = symmetric_peers {
+            match peers.receive(client_ip, &request_packet, Instant::now()) {
+                Ok(true) => {}
+                Ok(false) => debug!("Symmetric peer {} out of sequence (restarted?)", client_addr),
+                Err(e) => {
+                    debug!("Symmetric packet from {} dropped: {}", client_addr, e);
+                    self.stats.requests_rejected.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    return Ok(());
+                }
+            }
+        }
+
+        // Poll aberrant : répondu (valeur bornée), mais compté comme anomalie
+        if !self.poll_is_sane(request_packet.poll) {
+            debug!("Request from {} with out-of-range poll {}", client_addr, request_packet.poll);
+            self.stats.poll_anomalies.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+
+        // Retransmission d'une requête déjà reçue : répondue, mais comptée à part
+        let duplicate = self
+            .duplicate_detector
+            .as_ref()
+            .is_some_and(|detector| detector.is_duplicate(client_ip, request_packet.transmit_timestamp.0));
+
         // Création de la réponse
-        let response = self.create_response(&request_packet, receive_time);
+        let response = self.response_for(&request_packet, receive_time, policy);
 
         // TIMESTAMP T3: Moment de transmission (le plus tard possible avant send_to)
         let transmit_time = self.clock.now();
         let mut response = response;
         response.transmit_timestamp = transmit_time;
 
-        // Sérialisation et envoi
-        let response_bytes = response.to_bytes();
-        socket.send_to(&response_bytes, client_addr)?;
+        // Résolution réduite optionnelle (anti-fingerprinting de l'horloge)
+        if let Some(bits) = self.config.security.timestamp_precision_bits {
+            response.receive_timestamp = response.receive_timestamp.rounded_to_bits(bits);
+            response.transmit_timestamp = response.transmit_timestamp.rounded_to_bits(bits);
+        }
+
+        self.log_response_invariants(&request_packet, &response, policy);
 
-        self.stats.requests_processed.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        // Sérialisation et envoi : réponse signée avec la clé du client, champ
+        // d'extension d'incertitude, ou longueur de la requête si l'espace
+        // MAC/extensions est reproduit
+        if let Some((key_id, key)) = authenticated {
+            response.mac = Some(key.sign(key_id, &response.to_bytes()));
+            send(&response.to_bytes_with_mac())?;
+        } else if let Some(extension) = self.uncertainty_extension(&request_packet, data.len()) {
+            send(&response.to_bytes_with_extensions(&[extension]))?;
+        } else if self.config.server.pad_response_to_request {
+            send(&response.to_bytes_padded(data.len()))?;
+        } else {
+            send(&response.to_bytes())?;
+        }
 
-        // Mettre à jour les stats partagées
-        let total_requests = self.stats.requests_processed.load(std::sync::atomic::Ordering::Relaxed);
-        if let Ok(mut stats) = self.shared_stats.write() {
-            stats.ntp.requests_total = total_requests;
-            stats.ntp.last_tx_ms = 0; // TX vient de se produire
+        if let Some(ref audit_log) = self.audit_log {
+            if let Err(e) = audit_log.record(client_ip, &request_packet, &response) {
+                warn!("Failed to write audit log: {}", e);
+            }
+        }
+
+        if let Some(peers) = symmetric_peers {
+            peers.record_sent(client_ip, response.transmit_timestamp.0);
+        }
 
-            // Mettre à jour clock info
-            let timestamp = self.clock.now();
-            stats.clock.current_timestamp = timestamp.seconds() as u64;
-            stats.clock.current_fraction_ns = ((timestamp.fraction() as u64 * 1_000_000_000) >> 32) as u32;
-            stats.clock.stratum = self.clock.stratum();
-            stats.clock.reference_id = String::from_utf8_lossy(&self.clock.reference_id()).to_string();
-            stats.clock.precision = self.clock.precision();
+        if duplicate {
+            debug!("Duplicate request from {} answered", client_addr);
+            self.stats.requests_duplicate.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            return Ok(());
         }
 
+        // Compteurs du chemin de réponse : atomiques seulement, publiés par le
+        // thread de mise à jour des statistiques (pas de verrou par requête)
+        self.stats.requests_processed.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.stats.record_tx();
+
+        // Latence de traitement T3 - T2 (proxy de la qualité côté serveur)
+        let latency_us = transmit_time.seconds_since(receive_time) * 1_000_000.0;
+        self.stats.processing_latency.record(latency_us);
+
         if self.config.logging.log_requests {
             debug!("NTP response sent to {}", client_addr);
         }
@@ -250,45 +1103,147 @@ impl<C: ClockSource + ?Sized> NtpServer<C> {
         Ok(())
     }
 
-    /// Crée une réponse NTP
-    fn create_response(&self, request: &NtpPacket, receive_time: NtpTimestamp) -> NtpPacket {
-        let mut response = NtpPacket::new_server_response();
+    /// Vrai si le poll de la requête est dans la plage plausible configurée
+    fn poll_is_sane(&self, poll: i8) -> bool {
+        (self.config.server.poll_sane_min..=self.config.server.poll_sane_max).contains(&poll)
+    }
 
-        // Leap Indicator: copier depuis la source d'horloge ou mettre à 0
-        response.leap_indicator = LeapIndicator::NoWarning;
+    /// Mode de la réponse selon celui de la requête
+    fn response_mode(request: &NtpPacket) -> NtpMode {
+        if request.mode == NtpMode::SymmetricActive {
+            NtpMode::SymmetricPassive
+        } else {
+            NtpMode::Server
+        }
+    }
 
-        // Version: copier depuis la requête
-        response.version = request.version;
+    /// Poll de la réponse : intervalle configuré, ou écho borné de la requête
+    fn response_poll(&self, request: &NtpPacket) -> i8 {
+        if self.config.server.poll_mode == "echo" {
+            request
+                .poll
+                .clamp(self.config.server.poll_sane_min, self.config.server.poll_sane_max)
+        } else {
+            self.config.server.poll_interval
+        }
+    }
 
-        // Mode: Server (4)
-        response.mode = NtpMode::Server;
+    /// Réponse à une requête : recopie du modèle en cache de la politique
+    /// (`server.response_template`) s'il est encore valable, sinon construction
+    /// complète
+    fn response_for(&self, request: &NtpPacket, receive_time: NtpTimestamp, policy: &SocketPolicy) -> NtpPacket {
+        if !self.config.server.response_template {
+            return self.create_response(request, receive_time, policy);
+        }
 
-        // Stratum: obtenir depuis la source d'horloge
-        response.stratum = self.clock.stratum();
+        // Leap indicator et reference timestamp dépendent de la seconde,
+        // stratum, refid et dispersion de l'état de l'horloge
+        let second = receive_time.seconds();
+        let clock_stratum = self.clock.stratum();
+        let cached = *policy.template.read().unwrap_or_else(|poisoned| poisoned.into_inner());
 
-        // Poll: copier depuis la requête
-        response.poll = request.poll;
+        match cached {
+            Some(template) if template.second == second && template.clock_stratum == clock_stratum => {
+                let mut response = template.packet;
+                response.version = request.version;
+                response.mode = Self::response_mode(request);
+                response.poll = self.response_poll(request);
+                if !self.config.server.minimal_reply {
+                    response.originate_timestamp = request.transmit_timestamp;
+                    response.receive_timestamp = receive_time;
+                }
+                response
+            }
+            _ => {
+                let response = self.create_response(request, receive_time, policy);
+                *policy.template.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(ResponseTemplate {
+                    second,
+                    clock_stratum,
+                    packet: response,
+                });
+                response
+            }
+        }
+    }
+
+    /// Crée une réponse NTP
+    fn create_response(&self, request: &NtpPacket, receive_time: NtpTimestamp, policy: &SocketPolicy) -> NtpPacket {
+        let mut response = NtpPacket::new_server_response();
+
+        // Non synchronisée (stratum 16) : alarme, ou mode dégradé si l'opérateur préfère
+        // servir un temps approximatif plutôt que rien (réseaux fermés)
+        let unsynced = self.clock.stratum() >= 16;
+        let degraded = unsynced && self.config.server.unsynced_behavior == "nowarning_degraded";
+
+        // Leap Indicator: alarme (3) tant que l'horloge n'est pas synchronisée (stratum 16),
+        // pour que les clients ignorent notre temps jusqu'à la synchronisation
+        // Synchronisée : seconde intercalaire annoncée le dernier jour UTC qui la précède
+        response.leap_indicator = if degraded {
+            LeapIndicator::NoWarning
+        } else if unsynced {
+            LeapIndicator::AlarmCondition
+        } else {
+            match self
+                .leap_seconds
+                .as_ref()
+                .and_then(|table| table.leap_today(receive_time.seconds() as u64))
+            {
+                Some(leap) if leap.insertion => LeapIndicator::LastMinute61Seconds,
+                Some(_) => LeapIndicator::LastMinute59Seconds,
+                None => LeapIndicator::NoWarning,
+            }
+        };
+
+        // Version: copier depuis la requête
+        response.version = request.version;
+
+        // Mode: Server (4), ou symétrique passif (2) en réponse à un pair symétrique actif
+        response.mode = Self::response_mode(request);
+
+        // Stratum: obtenir depuis la source d'horloge, borné par le plancher configuré
+        response.stratum = if degraded {
+            DEGRADED_STRATUM.max(self.stratum_floor(policy).unwrap_or(1))
+        } else {
+            self.advertised_stratum(policy)
+        };
+
+        // Poll: intervalle recommandé par le serveur, ou écho de la requête borné
+        // à la plage plausible (jamais de -128 ou 127 renvoyé tel quel)
+        response.poll = self.response_poll(request);
 
         // Precision: obtenir depuis la source d'horloge
         response.precision = self.clock.precision();
 
-        // Root delay et dispersion (0 pour stratum 1)
-        response.root_delay = 0;
-        response.root_dispersion = 0;
+        // Root delay et dispersion (délai fixe configuré, 0 par défaut pour un stratum 1 ;
+        // incertitude de la source si connue, bornée par le chemin PPS ou NMEA pour le GPS,
+        // élargie à l'écart GPS/système en cas de divergence ; dispersion élevée en mode dégradé)
+        response.root_delay = ntp_short_from_us(self.config.server.static_root_delay_us);
+        response.root_dispersion = if degraded {
+            DEGRADED_ROOT_DISPERSION
+        } else {
+            ntp_short_from_ns(root_dispersion_ns(self.clock.as_ref()))
+        };
 
         // Reference identifier: obtenir depuis la source d'horloge
-        let ref_id_bytes = self.clock.reference_id();
-        response.reference_identifier = u32::from_be_bytes(ref_id_bytes);
+        response.reference_identifier = self.advertised_reference_id(response.stratum, policy);
 
         // Reference timestamp: temps de la dernière synchronisation
-        // Pour un serveur stratum 1, c'est le temps actuel
-        response.reference_timestamp = self.clock.now();
+        // Pour un serveur stratum 1, c'est le temps actuel ; pour l'horloge système,
+        // la dernière synchronisation par l'OS lorsqu'elle est connue
+        response.reference_timestamp = self.clock.last_update();
 
-        // Originate timestamp (T1): copier le transmit timestamp de la requête
-        response.originate_timestamp = request.transmit_timestamp;
+        if self.config.server.minimal_reply {
+            // Réponse minimale : seul le transmit timestamp est fourni,
+            // le client ne peut pas calculer d'offset aller-retour
+            response.originate_timestamp = NtpTimestamp::default();
+            response.receive_timestamp = NtpTimestamp::default();
+        } else {
+            // Originate timestamp (T1): copier le transmit timestamp de la requête
+            response.originate_timestamp = request.transmit_timestamp;
 
-        // Receive timestamp (T2): temps de réception capturé plus tôt
-        response.receive_timestamp = receive_time;
+            // Receive timestamp (T2): temps de réception capturé plus tôt
+            response.receive_timestamp = receive_time;
+        }
 
         // Transmit timestamp (T3): sera rempli juste avant l'envoi
         response.transmit_timestamp = NtpTimestamp::default();
@@ -296,24 +1251,178 @@ impl<C: ClockSource + ?Sized> NtpServer<C> {
         response
     }
 
+    /// Vérifie les invariants d'une réponse complète (transmit timestamp rempli)
+    ///
+    /// - originate = transmit de la requête (sauf réponse minimale)
+    /// - receive <= transmit (T2 <= T3)
+    /// - transmit non nul et postérieur à 1970 (ère 0)
+    /// - stratum = celui de l'horloge borné par le plancher (ou stratum du mode dégradé)
+    fn check_response_invariants(
+        &self,
+        request: &NtpPacket,
+        response: &NtpPacket,
+        policy: &SocketPolicy,
+    ) -> Result<(), String> {
+        if !self.config.server.minimal_reply {
+            if response.originate_timestamp != request.transmit_timestamp {
+                return Err(format!(
+                    "originate {:#x} != request transmit {:#x}",
+                    response.originate_timestamp.0, request.transmit_timestamp.0
+                ));
+            }
+            if response.receive_timestamp.0 > response.transmit_timestamp.0 {
+                return Err(format!(
+                    "receive {:#x} after transmit {:#x}",
+                    response.receive_timestamp.0, response.transmit_timestamp.0
+                ));
+            }
+        }
+
+        if response.transmit_timestamp.0 == 0 || response.transmit_timestamp.seconds_since(PLAUSIBLE_MIN_TIMESTAMP) < 0.0 {
+            return Err(format!("implausible transmit seconds {}", response.transmit_timestamp.seconds()));
+        }
+
+        let degraded = self.clock.stratum() >= 16 && self.config.server.unsynced_behavior == "nowarning_degraded";
+        if !degraded && response.stratum != self.advertised_stratum(policy) {
+            return Err(format!(
+                "stratum {} != advertised clock stratum {}",
+                response.stratum,
+                self.advertised_stratum(policy)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Vérification des invariants de réponse, active uniquement en debug et en
+    /// test. Journalisée sans interrompre le service : un trafic valide peut les
+    /// enfreindre (stratum relu entre-temps, horloge reculée entre T2 et T3)
+    fn log_response_invariants(&self, request: &NtpPacket, response: &NtpPacket, policy: &SocketPolicy) -> bool {
+        if !cfg!(debug_assertions) {
+            return true;
+        }
+        match self.check_response_invariants(request, response, policy) {
+            Ok(()) => true,
+            Err(violation) => {
+                warn!("NTP response invariant violated: {}", violation);
+                false
+            }
+        }
+    }
+
+    /// Plancher de stratum : celui du socket, sinon `advertised_stratum_min`
+    fn stratum_floor(&self, policy: &SocketPolicy) -> Option<u8> {
+        policy.stratum_min.or(self.config.server.advertised_stratum_min)
+    }
+
+    /// Stratum annoncé : celui de l'horloge, jamais meilleur que le plancher
+    fn advertised_stratum(&self, policy: &SocketPolicy) -> u8 {
+        let stratum = self.clock.stratum();
+        match self.stratum_floor(policy) {
+            Some(floor) => stratum.max(floor),
+            None => stratum,
+        }
+    }
+
+    /// Reference ID annoncé pour un stratum donné
+    ///
+    /// Un identifiant ASCII ("GPS", "PPS"...) n'a de sens qu'au stratum 1.
+    /// Si le plancher force un stratum supérieur alors que l'horloge est primaire,
+    /// on annonce une adresse IPv4 comme le ferait un serveur secondaire :
+    /// l'adresse d'écoute si elle est explicite, sinon 127.0.0.1.
+    /// Le reference ID de la politique du socket, s'il existe, l'emporte.
+    fn advertised_reference_id(&self, stratum: u8, policy: &SocketPolicy) -> u32 {
+        if let Some(refid) = policy.reference_id {
+            return u32::from_be_bytes(refid);
+        }
+
+        let clock_stratum = self.clock.stratum();
+        if stratum > 1 && clock_stratum == 1 {
+            let ip = self
+                .config
+                .server
+                .bind_address
+                .parse::<std::net::SocketAddr>()
+                .ok()
+                .and_then(|addr| match addr.ip() {
+                    std::net::IpAddr::V4(v4) if !v4.is_unspecified() => Some(v4),
+                    _ => None,
+                })
+                .unwrap_or(std::net::Ipv4Addr::LOCALHOST);
+            return u32::from(ip);
+        }
+
+        u32::from_be_bytes(self.clock.reference_id())
+    }
+
     /// Retourne les statistiques du serveur
     #[allow(dead_code)]
     pub fn stats(&self) -> &Arc<ServerStats> {
         &self.stats
     }
+
+    /// Rate limiter partagé (absent si le rate limiting est désactivé),
+    /// pour l'ajustement à chaud via l'API web
+    pub fn rate_limiter(&self) -> Option<Arc<RateLimiter>> {
+        self.rate_limiter.clone()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::clock::SystemClock;
+    use crate::clock::{GpsNmeaClock, SystemClock};
     use crate::packet::NtpMode;
+    use crate::stats::StatsManager;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Horloge de test : chaque lecture avance d'un pas fixe
+    struct SteppingClock {
+        next: AtomicU64,
+        step: u64,
+    }
+
+    impl SteppingClock {
+        fn new(start: NtpTimestamp, step: NtpTimestamp) -> Self {
+            SteppingClock {
+                next: AtomicU64::new(start.0),
+                step: step.0,
+            }
+        }
+    }
+
+    impl ClockSource for SteppingClock {
+        fn now(&self) -> NtpTimestamp {
+            NtpTimestamp(self.next.fetch_add(self.step, Ordering::SeqCst))
+        }
+
+        fn reference_id(&self) -> [u8; 4] {
+            *b"GPS\0"
+        }
+
+        fn stratum(&self) -> u8 {
+            1
+        }
+
+        fn precision(&self) -> i8 {
+            -20
+        }
+    }
+
+    /// Construit une requête client valide sérialisée
+    fn client_request_bytes() -> [u8; NtpPacket::SIZE] {
+        let mut request = NtpPacket::new_server_response();
+        request.mode = NtpMode::Client;
+        request.stratum = 0;
+        request.transmit_timestamp = NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0);
+        request.to_bytes()
+    }
 
     #[test]
     fn test_create_response() {
         let config = Config::default();
         let clock = Arc::new(SystemClock::new());
-        let server = NtpServer::new(config, clock);
+        let server = NtpServer::new(config, clock, StatsManager::new().clone_arc());
 
         let mut request = NtpPacket::new_server_response();
         request.mode = NtpMode::Client;
@@ -321,11 +1430,1159 @@ mod tests {
         request.transmit_timestamp = NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0);
 
         let receive_time = NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 0);
-        let response = server.create_response(&request, receive_time);
+        let response = server.create_response(&request, receive_time, &SocketPolicy::default());
 
         assert_eq!(response.version, 4);
         assert_eq!(response.mode, NtpMode::Server);
         assert_eq!(response.originate_timestamp, request.transmit_timestamp);
         assert_eq!(response.receive_timestamp, receive_time);
     }
+
+    #[test]
+    fn test_minimal_reply() {
+        let request = NtpPacket::from_bytes(&client_request_bytes()).unwrap();
+        let receive_time = NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 0);
+
+        // Par défaut : réponse complète
+        let server = NtpServer::new(Config::default(), Arc::new(SystemClock::new()), StatsManager::new().clone_arc());
+        let response = server.create_response(&request, receive_time, &SocketPolicy::default());
+        assert_eq!(response.originate_timestamp, request.transmit_timestamp);
+        assert_eq!(response.receive_timestamp, receive_time);
+
+        // Réponse minimale : originate et receive à zéro
+        let mut config = Config::default();
+        config.server.minimal_reply = true;
+        let server = NtpServer::new(config, Arc::new(SystemClock::new()), StatsManager::new().clone_arc());
+        let response = server.create_response(&request, receive_time, &SocketPolicy::default());
+        assert_eq!(response.originate_timestamp, NtpTimestamp::default());
+        assert_eq!(response.receive_timestamp, NtpTimestamp::default());
+        assert_eq!(response.mode, NtpMode::Server);
+    }
+
+    #[test]
+    fn test_response_template_matches_full_builder() {
+        let mut config = Config::default();
+        config.server.response_template = true;
+        config.server.poll_mode = "echo".to_string();
+        let clock = Arc::new(SteppingClock::new(
+            NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0),
+            NtpTimestamp(0),
+        ));
+        let server = NtpServer::new(config, clock, StatsManager::new().clone_arc());
+        let policy = SocketPolicy::default();
+
+        // Première requête : construction complète et mise en cache
+        let first = NtpPacket::from_bytes(&client_request_bytes()).unwrap();
+        let receive_time = NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 1_000);
+        server.response_for(&first, receive_time, &policy);
+        assert!(policy.template.read().unwrap().is_some());
+
+        // Requêtes suivantes de la même seconde : version, mode, poll et
+        // timestamps différents, réponse identique octet pour octet
+        for (version, mode, poll, nanos) in [
+            (3, NtpMode::Client, 6, 2_000),
+            (4, NtpMode::SymmetricActive, 10, 500_000_000),
+            (4, NtpMode::Client, 127, 999_999_999),
+        ] {
+            let mut request = first;
+            request.version = version;
+            request.mode = mode;
+            request.poll = poll;
+            request.transmit_timestamp = NtpTimestamp::from_seconds_and_nanos(3_900_000_000, nanos);
+            let receive_time = NtpTimestamp::from_seconds_and_nanos(3_900_000_001, nanos);
+
+            let templated = server.response_for(&request, receive_time, &policy);
+            let built = server.create_response(&request, receive_time, &policy);
+            assert_eq!(templated.to_bytes(), built.to_bytes());
+        }
+
+        // Seconde suivante : modèle reconstruit
+        let receive_time = NtpTimestamp::from_seconds_and_nanos(3_900_000_002, 0);
+        server.response_for(&first, receive_time, &policy);
+        assert_eq!(policy.template.read().unwrap().unwrap().second, 3_900_000_002);
+    }
+
+    #[test]
+    fn test_unsynced_clock_sets_alarm() {
+        let request = NtpPacket::from_bytes(&client_request_bytes()).unwrap();
+        let receive_time = NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 0);
+
+        // Horloge système seule : stratum 16, non synchronisée
+        let server = NtpServer::new(
+            Config::default(),
+            Arc::new(SystemClock::new()),
+            StatsManager::new().clone_arc(),
+        );
+        let response = server.create_response(&request, receive_time, &SocketPolicy::default());
+        assert_eq!(response.stratum, 16);
+        assert_eq!(response.leap_indicator, LeapIndicator::AlarmCondition);
+        assert_eq!(response.to_bytes()[0] >> 6, 3);
+
+        // Horloge synchronisée (stratum 1) : pas d'alarme
+        let clock = Arc::new(SteppingClock::new(
+            NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0),
+            NtpTimestamp::from_seconds_and_nanos(0, 1_000),
+        ));
+        let server = NtpServer::new(Config::default(), clock, StatsManager::new().clone_arc());
+        let response = server.create_response(&request, receive_time, &SocketPolicy::default());
+        assert_eq!(response.leap_indicator, LeapIndicator::NoWarning);
+    }
+
+    /// Réponse complète et cohérente pour les tests d'invariants
+    fn consistent_response(server: &NtpServer<SystemClock>, request: &NtpPacket) -> NtpPacket {
+        let receive_time = server.clock.now();
+        let mut response = server.create_response(request, receive_time, &SocketPolicy::default());
+        response.transmit_timestamp = server.clock.now();
+        response
+    }
+
+    #[test]
+    fn test_response_invariants_hold() {
+        let request = NtpPacket::from_bytes(&client_request_bytes()).unwrap();
+        let server = NtpServer::new(Config::default(), Arc::new(SystemClock::new()), StatsManager::new().clone_arc());
+        let response = consistent_response(&server, &request);
+        assert_eq!(server.check_response_invariants(&request, &response, &SocketPolicy::default()), Ok(()));
+
+        // Chaque invariant est détecté
+        let mut swapped = response;
+        swapped.receive_timestamp = NtpTimestamp(response.transmit_timestamp.0 + 1);
+        assert!(server.check_response_invariants(&request, &swapped, &SocketPolicy::default()).unwrap_err().contains("receive"));
+
+        let mut zeroed = response;
+        zeroed.transmit_timestamp = NtpTimestamp::default();
+        zeroed.receive_timestamp = NtpTimestamp::default();
+        assert!(server.check_response_invariants(&request, &zeroed, &SocketPolicy::default()).unwrap_err().contains("implausible"));
+
+        // Après le passage à l'ère NTP 1 (février 2036), les secondes repartent de zéro
+        let mut era1 = response;
+        era1.receive_timestamp = NtpTimestamp::from_seconds_and_nanos(100, 0);
+        era1.transmit_timestamp = NtpTimestamp::from_seconds_and_nanos(100, 0);
+        assert_eq!(server.check_response_invariants(&request, &era1, &SocketPolicy::default()), Ok(()));
+
+        let mut stratum = response;
+        stratum.stratum = 1;
+        assert!(server.check_response_invariants(&request, &stratum, &SocketPolicy::default()).unwrap_err().contains("stratum"));
+    }
+
+    #[test]
+    fn test_inconsistent_response_is_logged() {
+        let request = NtpPacket::from_bytes(&client_request_bytes()).unwrap();
+        let server = NtpServer::new(Config::default(), Arc::new(SystemClock::new()), StatsManager::new().clone_arc());
+        let mut response = consistent_response(&server, &request);
+        assert!(server.log_response_invariants(&request, &response, &SocketPolicy::default()));
+
+        // Violation journalisée, sans panique
+        response.originate_timestamp = NtpTimestamp(request.transmit_timestamp.0 ^ 1);
+        assert!(!server.log_response_invariants(&request, &response, &SocketPolicy::default()));
+    }
+
+    #[test]
+    fn test_unsynced_behavior() {
+        let request = NtpPacket::from_bytes(&client_request_bytes()).unwrap();
+        let receive_time = NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 0);
+
+        // Par défaut : alarme conforme RFC
+        let server = NtpServer::new(Config::default(), Arc::new(SystemClock::new()), StatsManager::new().clone_arc());
+        let response = server.create_response(&request, receive_time, &SocketPolicy::default());
+        assert_eq!(response.leap_indicator, LeapIndicator::AlarmCondition);
+        assert_eq!(response.stratum, 16);
+
+        // Mode dégradé : pas d'alarme, stratum élevé et dispersion de 1 s
+        let mut config = Config::default();
+        config.server.unsynced_behavior = "nowarning_degraded".to_string();
+        let server = NtpServer::new(config.clone(), Arc::new(SystemClock::new()), StatsManager::new().clone_arc());
+        let response = server.create_response(&request, receive_time, &SocketPolicy::default());
+        assert_eq!(response.leap_indicator, LeapIndicator::NoWarning);
+        assert_eq!(response.stratum, DEGRADED_STRATUM);
+        assert_eq!(response.root_dispersion, DEGRADED_ROOT_DISPERSION);
+
+        // Horloge synchronisée : mode dégradé sans effet
+        let clock = Arc::new(SteppingClock::new(
+            NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0),
+            NtpTimestamp::from_seconds_and_nanos(0, 1_000),
+        ));
+        let server = NtpServer::new(config, clock, StatsManager::new().clone_arc());
+        let response = server.create_response(&request, receive_time, &SocketPolicy::default());
+        assert_eq!(response.stratum, 1);
+        assert_eq!(response.root_dispersion, 0);
+    }
+
+    #[test]
+    fn test_leap_second_announced_on_last_day() {
+        let table = LeapSecondTable::parse("3644697600 36\n3692217600 37\n").unwrap();
+        let request = NtpPacket::from_bytes(&client_request_bytes()).unwrap();
+        let clock = Arc::new(SteppingClock::new(
+            NtpTimestamp::from_seconds_and_nanos(3_692_217_000, 0),
+            NtpTimestamp::from_seconds_and_nanos(0, 1_000),
+        ));
+        let server = NtpServer::new(Config::default(), clock, StatsManager::new().clone_arc())
+            .with_leap_seconds(Arc::new(table));
+
+        // 31 décembre 2016 : 23:59:60 annoncé
+        let last_day = NtpTimestamp::from_seconds_and_nanos(3_692_217_600 - 3_600, 0);
+        let response = server.create_response(&request, last_day, &SocketPolicy::default());
+        assert_eq!(response.leap_indicator, LeapIndicator::LastMinute61Seconds);
+
+        // Deux jours avant : rien à annoncer
+        let before = NtpTimestamp::from_seconds_and_nanos(3_692_217_600 - 2 * 86_400, 0);
+        let response = server.create_response(&request, before, &SocketPolicy::default());
+        assert_eq!(response.leap_indicator, LeapIndicator::NoWarning);
+    }
+
+    #[test]
+    fn test_poll_modes() {
+        let mut request = NtpPacket::from_bytes(&client_request_bytes()).unwrap();
+        request.poll = 10;
+        let receive_time = NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 0);
+
+        // Par défaut : poll_interval de la configuration
+        let mut config = Config::default();
+        config.server.poll_interval = 6;
+        let server = NtpServer::new(config.clone(), Arc::new(SystemClock::new()), StatsManager::new().clone_arc());
+        assert_eq!(server.create_response(&request, receive_time, &SocketPolicy::default()).poll, 6);
+
+        // Mode écho : poll de la requête
+        config.server.poll_mode = "echo".to_string();
+        let server = NtpServer::new(config, Arc::new(SystemClock::new()), StatsManager::new().clone_arc());
+        assert_eq!(server.create_response(&request, receive_time, &SocketPolicy::default()).poll, 10);
+    }
+
+    #[test]
+    fn test_absurd_poll_clamped_and_counted() {
+        let mut config = Config::default();
+        config.server.poll_mode = "echo".to_string();
+        let stats = StatsManager::new();
+        let server = NtpServer::new(config, Arc::new(SystemClock::new()), stats.clone_arc());
+
+        let server_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client.set_read_timeout(Some(std::time::Duration::from_secs(2))).unwrap();
+        let mut buffer = [0u8; NtpPacket::SIZE];
+        let mut reply = [0u8; NtpPacket::SIZE];
+
+        let mut query = |poll: i8| {
+            let mut bytes = client_request_bytes();
+            bytes[2] = poll as u8;
+            client.send_to(&bytes, server_socket.local_addr().unwrap()).unwrap();
+            server.handle_request(&server_socket, &mut buffer, &mut TxPending::default()).unwrap();
+            let (len, _) = client.recv_from(&mut reply).unwrap();
+            NtpPacket::from_bytes(&reply[..len]).unwrap().poll
+        };
+
+        // Valeurs extrêmes bornées à la plage plausible (-6..=17 par défaut)
+        assert_eq!(query(-128), -6);
+        assert_eq!(query(127), 17);
+
+        // Poll plausible : recopié et non compté
+        assert_eq!(query(10), 10);
+
+        assert_eq!(server.stats.poll_anomalies.load(Ordering::Relaxed), 2);
+        server.publish_stats(&mut write_stats(&server.shared_stats).unwrap());
+        assert_eq!(stats.get().ntp.poll_anomalies, 2);
+    }
+
+    /// Trame NMEA complète (`$<corps>*<checksum>`)
+    fn nmea(body: &str) -> String {
+        let checksum = body.bytes().fold(0u8, |acc, byte| acc ^ byte);
+        format!("${}*{:02X}", body, checksum)
+    }
+
+    #[test]
+    fn test_gps_pipeline_serves_gps_time() {
+        use crate::gps_reader::GpsReader;
+
+        // 2031-05-20 12:00:00 UTC : loin de l'horloge système réelle
+        const GPS_START: u64 = 4_146_033_600;
+
+        let config = Config::default();
+        let gps_config: crate::config::GpsConfig = toml::from_str(r#"serial_port = "/dev/null""#).unwrap();
+        let stats = StatsManager::new();
+        let clock = Arc::new(GpsNmeaClock::new(gps_config.sync_timeout));
+        let reader = GpsReader::new(gps_config, Arc::clone(&clock), stats.clone_arc());
+
+        // Trois secondes de flux : RMC + GGA, puis le pulse PPS de la seconde suivante
+        // (pulses espacés d'une seconde, le dernier à l'instant présent)
+        let first_pulse = Instant::now() - std::time::Duration::from_secs(2);
+        for second in 0..3u64 {
+            let time = format!("1200{:02}.00", second);
+            let rmc = nmea(&format!("GPRMC,{},A,4851.50,N,00221.00,E,0.0,0.0,200531,,,A", time));
+            let gga = nmea(&format!("GPGGA,{},4851.50,N,00221.00,E,1,09,0.9,35.0,M,47.0,M,,", time));
+
+            let rmc_time = reader.process_nmea_sentence(&rmc).unwrap();
+            assert_eq!(rmc_time.seconds() as u64, GPS_START + second);
+            reader.process_nmea_sentence(&gga);
+
+            let boundary = NtpTimestamp::from_seconds_and_nanos(GPS_START + second + 1, 0);
+            let pulse = first_pulse + std::time::Duration::from_secs(second);
+            clock.update_pps_offset(pulse, boundary).unwrap();
+        }
+
+        let server = NtpServer::new(config, Arc::clone(&clock), stats.clone_arc());
+        let server_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client.set_read_timeout(Some(std::time::Duration::from_secs(2))).unwrap();
+        let mut buffer = [0u8; NtpPacket::SIZE];
+        let mut reply = [0u8; NtpPacket::SIZE];
+
+        client.send_to(&client_request_bytes(), server_socket.local_addr().unwrap()).unwrap();
+        server.handle_request(&server_socket, &mut buffer, &mut TxPending::default()).unwrap();
+        let (len, _) = client.recv_from(&mut reply).unwrap();
+        let response = NtpPacket::from_bytes(&reply[..len]).unwrap();
+
+        // Stratum 1, référence PPS (fix GPS et offset PPS récent)
+        assert_eq!(response.stratum, 1);
+        assert_eq!(response.reference_identifier.to_be_bytes(), *b"PPS\0");
+        assert_eq!(response.leap_indicator, LeapIndicator::NoWarning);
+
+        // Temps GPS injecté (dernier pulse à 12:00:03), et non l'horloge système
+        let last_pulse = NtpTimestamp::from_seconds_and_nanos(GPS_START + 3, 0);
+        let since_pulse = response.transmit_timestamp.seconds_since(last_pulse);
+        assert!((0.0..1.0).contains(&since_pulse), "{} s after the last pulse", since_pulse);
+        assert!(response.receive_timestamp.seconds_since(last_pulse) >= 0.0);
+    }
+
+    #[test]
+    fn test_symmetric_active_answered_as_server() {
+        let clock = Arc::new(GpsNmeaClock::new(10));
+        clock.update_gps_time(NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0), Some(8));
+
+        let mut symmetric_active = client_request_bytes();
+        symmetric_active[0] = (symmetric_active[0] & !0b111) | NtpMode::SymmetricActive as u8;
+
+        let query = |config: Config| {
+            let server = NtpServer::new(config, Arc::clone(&clock), StatsManager::new().clone_arc());
+            let mut reply = None;
+            server
+                .process_request(
+                    &symmetric_active,
+                    "192.0.2.1:123".parse().unwrap(),
+                    clock.now(),
+                    &SocketPolicy::default(),
+                    |response| {
+                        reply = Some(NtpPacket::from_bytes(response).unwrap());
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            reply
+        };
+
+        // Option active : mode 1 → mode 4, comme une requête client
+        let mut config = Config::default();
+        config.security.answer_symmetric_as_server = true;
+        let response = query(config.clone()).unwrap();
+        assert_eq!(response.mode, NtpMode::Server);
+        assert_eq!(
+            response.originate_timestamp,
+            NtpPacket::from_bytes(&symmetric_active).unwrap().transmit_timestamp
+        );
+
+        // Pair configuré : l'association symétrique (mode 2) reste prioritaire
+        config.server.allow_symmetric = true;
+        config.server.symmetric_peers = vec!["192.0.2.0/24".to_string()];
+        assert_eq!(query(config.clone()).unwrap().mode, NtpMode::SymmetricPassive);
+
+        // Hors des pairs configurés : servi en mode serveur
+        config.server.symmetric_peers = vec!["198.51.100.0/24".to_string()];
+        assert_eq!(query(config).unwrap().mode, NtpMode::Server);
+
+        // Option désactivée (défaut) : paquet écarté
+        assert!(query(Config::default()).is_none());
+
+        // Avec reject_source_port_123 : le mode 1 depuis le port 123 reste servi
+        let mut config = Config::default();
+        config.security.answer_symmetric_as_server = true;
+        config.security.reject_source_port_123 = true;
+        assert_eq!(query(config).unwrap().mode, NtpMode::Server);
+    }
+
+    #[test]
+    fn test_symmetric_active_answered_in_passive_mode() {
+        let server_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let peer = UdpSocket::bind("127.0.0.1:0").unwrap();
+        peer.set_read_timeout(Some(std::time::Duration::from_millis(200))).unwrap();
+        let mut buffer = [0u8; NtpPacket::SIZE];
+        let mut reply = [0u8; NtpPacket::SIZE];
+
+        let mut symmetric_active = client_request_bytes();
+        symmetric_active[0] = (symmetric_active[0] & !0b111) | NtpMode::SymmetricActive as u8;
+
+        // Désactivé par défaut : paquet mode 1 rejeté, sans réponse
+        let stats = StatsManager::new();
+        let server = NtpServer::new(Config::default(), Arc::new(SystemClock::new()), stats.clone_arc());
+        peer.send_to(&symmetric_active, server_socket.local_addr().unwrap()).unwrap();
+        server.handle_request(&server_socket, &mut buffer, &mut TxPending::default()).unwrap();
+        assert!(peer.recv_from(&mut reply).is_err());
+        server.publish_stats(&mut write_stats(&server.shared_stats).unwrap());
+        assert_eq!(stats.get().ntp.validation_errors.invalid_mode, 1);
+
+        let mut config = Config::default();
+        config.server.allow_symmetric = true;
+        config.server.symmetric_peers = vec!["127.0.0.0/8".to_string()];
+        let stats = StatsManager::new();
+        let server = NtpServer::new(config, Arc::new(SystemClock::new()), stats.clone_arc());
+
+        // Mode 1 → mode 2, originate = transmit du pair
+        peer.send_to(&symmetric_active, server_socket.local_addr().unwrap()).unwrap();
+        server.handle_request(&server_socket, &mut buffer, &mut TxPending::default()).unwrap();
+        let (len, _) = peer.recv_from(&mut reply).unwrap();
+        let response = NtpPacket::from_bytes(&reply[..len]).unwrap();
+        let request = NtpPacket::from_bytes(&symmetric_active).unwrap();
+        assert_eq!(response.mode, NtpMode::SymmetricPassive);
+        assert_eq!(response.originate_timestamp, request.transmit_timestamp);
+        assert_eq!(response.stratum, server.advertised_stratum(&SocketPolicy::default()));
+
+        // Association suivie ; retransmission du même paquet écartée
+        let association = server.symmetric_peers.as_ref().unwrap().get(peer.local_addr().unwrap().ip()).unwrap();
+        assert_eq!(association.last_sent_transmit, Some(response.transmit_timestamp.0));
+        server.publish_stats(&mut write_stats(&server.shared_stats).unwrap());
+        assert_eq!(stats.get().ntp.symmetric_peers, 1);
+
+        peer.send_to(&symmetric_active, server_socket.local_addr().unwrap()).unwrap();
+        server.handle_request(&server_socket, &mut buffer, &mut TxPending::default()).unwrap();
+        assert!(peer.recv_from(&mut reply).is_err());
+
+        // Les clients (mode 3) restent servis en mode serveur
+        peer.send_to(&client_request_bytes(), server_socket.local_addr().unwrap()).unwrap();
+        server.handle_request(&server_socket, &mut buffer, &mut TxPending::default()).unwrap();
+        let (len, _) = peer.recv_from(&mut reply).unwrap();
+        assert_eq!(NtpPacket::from_bytes(&reply[..len]).unwrap().mode, NtpMode::Server);
+    }
+
+    #[test]
+    fn test_validation_error_counters() {
+        let stats = StatsManager::new();
+        let server = NtpServer::new(Config::default(), Arc::new(SystemClock::new()), stats.clone_arc());
+
+        let server_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut buffer = [0u8; NtpPacket::SIZE];
+
+        let mut send = |bytes: &[u8]| {
+            client.send_to(bytes, server_socket.local_addr().unwrap()).unwrap();
+            server.handle_request(&server_socket, &mut buffer, &mut TxPending::default()).unwrap();
+        };
+
+        // Version 0 (rejetée dès le parsing)
+        let mut bytes = client_request_bytes();
+        bytes[0] &= !0b0011_1000;
+        send(&bytes);
+
+        // Mode serveur (4) au lieu de client
+        let mut bytes = client_request_bytes();
+        bytes[0] = (bytes[0] & !0b111) | 4;
+        send(&bytes);
+
+        // Transmit timestamp nul
+        let mut bytes = client_request_bytes();
+        bytes[40..48].fill(0);
+        send(&bytes);
+
+        // Stratum 16
+        let mut bytes = client_request_bytes();
+        bytes[1] = 16;
+        send(&bytes);
+
+        // Paquet tronqué
+        send(&client_request_bytes()[..20]);
+
+        server.publish_stats(&mut write_stats(&server.shared_stats).unwrap());
+        let counts = stats.get().ntp.validation_errors;
+        assert_eq!(counts.invalid_version, 1);
+        assert_eq!(counts.invalid_mode, 1);
+        assert_eq!(counts.zero_transmit_timestamp, 1);
+        assert_eq!(counts.invalid_stratum, 1);
+        assert_eq!(counts.malformed, 1);
+    }
+
+    #[test]
+    fn test_duplicate_requests_counted() {
+        let mut config = Config::default();
+        config.server.detect_duplicate_requests = true;
+        let stats = StatsManager::new();
+        let server = NtpServer::new(config, Arc::new(SystemClock::new()), stats.clone_arc());
+
+        let server_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client
+            .set_read_timeout(Some(std::time::Duration::from_millis(200)))
+            .unwrap();
+        let mut buffer = [0u8; NtpPacket::SIZE];
+        let mut reply = [0u8; 128];
+
+        // Le même paquet envoyé deux fois : deux réponses, un seul traité
+        for _ in 0..2 {
+            client
+                .send_to(&client_request_bytes(), server_socket.local_addr().unwrap())
+                .unwrap();
+            server.handle_request(&server_socket, &mut buffer, &mut TxPending::default()).unwrap();
+            assert!(client.recv_from(&mut reply).is_ok());
+        }
+
+        assert_eq!(server.stats.requests_processed.load(Ordering::Relaxed), 1);
+        assert_eq!(server.stats.requests_duplicate.load(Ordering::Relaxed), 1);
+
+        // Compteurs publiés par le thread de statistiques, pas par requête
+        assert_eq!(stats.get().ntp.requests_total, 0);
+        assert_eq!(stats.get().ntp.duplicate_requests, 0);
+        server.publish_stats(&mut write_stats(&server.shared_stats).unwrap());
+        assert_eq!(stats.get().ntp.requests_total, 1);
+        assert_eq!(stats.get().ntp.duplicate_requests, 1);
+    }
+
+    #[test]
+    fn test_accept_version_zero() {
+        let mut bytes = client_request_bytes();
+        bytes[0] &= !0b0011_1000;
+
+        let query = |config: Config| {
+            let server = NtpServer::new(config, Arc::new(SystemClock::new()), StatsManager::new().clone_arc());
+            let mut reply = None;
+            server
+                .process_request(
+                    &bytes,
+                    "127.0.0.1:40000".parse().unwrap(),
+                    NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 0),
+                    &SocketPolicy::default(),
+                    |response| {
+                        reply = Some(response.to_vec());
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            reply
+        };
+
+        // Par défaut : version 0 rejetée
+        assert!(query(Config::default()).is_none());
+
+        // Option activée : réponse en version 4
+        let mut config = Config::default();
+        config.security.accept_version_zero = true;
+        let reply = query(config).expect("version 0 request should be answered");
+        let response = NtpPacket::from_bytes(&reply).unwrap();
+        assert_eq!(response.version, 4);
+        assert_eq!(response.mode, NtpMode::Server);
+    }
+
+    #[test]
+    fn test_response_padded_to_request_length() {
+        // Requête de 48 octets suivie d'un MAC de 20 octets (key ID + digest MD5)
+        let mut bytes = client_request_bytes().to_vec();
+        bytes.extend_from_slice(&[0xAB; 20]);
+
+        let query = |config: Config, request: &[u8]| {
+            let server = NtpServer::new(config, Arc::new(SystemClock::new()), StatsManager::new().clone_arc());
+            let mut reply = Vec::new();
+            server
+                .process_request(
+                    request,
+                    "127.0.0.1:40000".parse().unwrap(),
+                    NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 0),
+                    &SocketPolicy::default(),
+                    |response| {
+                        reply = response.to_vec();
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            reply
+        };
+
+        // Par défaut : toujours 48 octets
+        assert_eq!(query(Config::default(), &bytes).len(), NtpPacket::SIZE);
+
+        let mut config = Config::default();
+        config.server.pad_response_to_request = true;
+        let reply = query(config.clone(), &bytes);
+        assert_eq!(reply.len(), NtpPacket::SIZE + 20);
+        assert!(reply[NtpPacket::SIZE..].iter().all(|&b| b == 0));
+        assert_eq!(NtpPacket::from_bytes(&reply).unwrap().mode, NtpMode::Server);
+
+        // Requête sans MAC : réponse de 48 octets
+        assert_eq!(query(config, &client_request_bytes()).len(), NtpPacket::SIZE);
+    }
+
+    #[test]
+    fn test_anycast_mode_uses_global_limit_only() {
+        let mut config = Config::default();
+        config.server.anycast_mode = true;
+        config.server.detect_duplicate_requests = true;
+        config.security.max_requests_per_second = 1;
+        config.security.global_max_requests_per_second = 3;
+        let server = NtpServer::new(config, Arc::new(SystemClock::new()), StatsManager::new().clone_arc());
+
+        // Aucun état par client
+        assert!(server.rate_limiter().is_none());
+        assert!(server.duplicate_detector.is_none());
+
+        let bytes = client_request_bytes();
+        let mut answered = 0;
+        for client in ["192.0.2.1:123", "192.0.2.1:123", "198.51.100.7:123", "203.0.113.9:123"] {
+            server
+                .process_request(
+                    &bytes,
+                    client.parse().unwrap(),
+                    NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 0),
+                    &SocketPolicy::default(),
+                    |_| {
+                        answered += 1;
+                        Ok(())
+                    },
+                )
+                .unwrap();
+        }
+
+        // Limite par IP (1/s) ignorée, limite globale (3) appliquée tous clients confondus
+        assert_eq!(answered, 3);
+        assert_eq!(server.stats.requests_rejected.load(std::sync::atomic::Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_processing_latency_recorded() {
+        let config = Config::default();
+        // Chaque lecture d'horloge avance de 100 µs
+        let clock = Arc::new(SteppingClock::new(
+            NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0),
+            NtpTimestamp::from_seconds_and_nanos(0, 100_000),
+        ));
+        let stats = StatsManager::new();
+        let server = NtpServer::new(config, clock, stats.clone_arc());
+
+        let server_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut buffer = [0u8; NtpPacket::SIZE];
+
+        for _ in 0..2 {
+            client
+                .send_to(&client_request_bytes(), server_socket.local_addr().unwrap())
+                .unwrap();
+            server.handle_request(&server_socket, &mut buffer, &mut TxPending::default()).unwrap();
+        }
+
+        // Lectures entre T2 et T3 : reference_timestamp puis T3 → 2 pas
+        server.publish_stats(&mut write_stats(&server.shared_stats).unwrap());
+        let latency = stats.get().ntp.processing_latency;
+        assert_eq!(latency.samples, 2);
+        assert!((latency.mean_us - 200.0).abs() < 0.01);
+        assert!((latency.max_us - 200.0).abs() < 0.01);
+        assert!(latency.jitter_us < 0.01);
+    }
+
+    #[test]
+    fn test_counters_accurate_under_concurrent_load() {
+        let mut config = Config::default();
+        config.security.enable_rate_limiting = false;
+        let stats = StatsManager::new();
+        let server = NtpServer::new(config, Arc::new(SystemClock::new()), stats.clone_arc());
+        let bytes = client_request_bytes();
+
+        const THREADS: u8 = 8;
+        const REQUESTS: u16 = 1000;
+
+        std::thread::scope(|scope| {
+            for thread in 0..THREADS {
+                let server = &server;
+                let bytes = &bytes;
+                scope.spawn(move || {
+                    for n in 0..REQUESTS {
+                        let [high, low] = n.to_be_bytes();
+                        let client = SocketAddr::from(([10, thread, high, low], 40000));
+                        server
+                            .process_request(bytes, client, server.clock.now(), &server.default_policy, |_| Ok(()))
+                            .unwrap();
+                    }
+                });
+            }
+        });
+
+        server.publish_stats(&mut write_stats(&server.shared_stats).unwrap());
+        let expected = THREADS as u64 * REQUESTS as u64;
+        let published = stats.get().ntp;
+        assert_eq!(published.requests_total, expected);
+        assert_eq!(published.processing_latency.samples, expected);
+        assert!(published.last_tx_ms < 1_000);
+    }
+
+    #[test]
+    fn test_static_root_delay_conversion() {
+        assert_eq!(ntp_short_from_us(0), 0);
+        assert_eq!(ntp_short_from_us(1_000_000), 0x0001_0000);
+        assert_eq!(ntp_short_from_us(250_000), 0x0000_4000);
+        // 500 µs = 32,768 unités de 2^-16 s, arrondi au plus proche
+        assert_eq!(ntp_short_from_us(500), 33);
+        assert_eq!(ntp_short_from_us(7), 0);
+
+        let mut config = Config::default();
+        config.server.static_root_delay_us = 250_000;
+        let server = NtpServer::new(config, Arc::new(SystemClock::new()), StatsManager::new().clone_arc());
+        let request = NtpPacket::from_bytes(&client_request_bytes()).unwrap();
+        let response = server.create_response(&request, server.clock.now(), &SocketPolicy::default());
+        assert_eq!(response.root_delay, 0x0000_4000);
+    }
+
+    #[test]
+    fn test_reject_source_port_123() {
+        let query = |config: Config, client: &str| {
+            let server = NtpServer::new(config, Arc::new(SystemClock::new()), StatsManager::new().clone_arc());
+            let mut answered = false;
+            server
+                .process_request(
+                    &client_request_bytes(),
+                    client.parse().unwrap(),
+                    NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 0),
+                    &SocketPolicy::default(),
+                    |_| {
+                        answered = true;
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            (answered, server.stats.source_port_123_rejected.load(Ordering::Relaxed))
+        };
+
+        // Par défaut : clients anciens émettant depuis le port 123 servis
+        assert_eq!(query(Config::default(), "192.0.2.1:123"), (true, 0));
+
+        let mut config = Config::default();
+        config.security.reject_source_port_123 = true;
+        assert_eq!(query(config.clone(), "192.0.2.1:123"), (false, 1));
+
+        // Port éphémère : servi normalement
+        assert_eq!(query(config, "192.0.2.1:40000"), (true, 0));
+    }
+
+    #[test]
+    fn test_uncertainty_extension_appended() {
+        let clock = Arc::new(GpsNmeaClock::new(10));
+        clock.update_gps_time(NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0), Some(8));
+        clock.update_pps_offset(Instant::now(), NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 0));
+        clock.set_time_accuracy_ns(1_250);
+
+        let query = |config: Config, request: &[u8]| {
+            let server = NtpServer::new(config, Arc::clone(&clock), StatsManager::new().clone_arc());
+            let mut reply = Vec::new();
+            server
+                .process_request(
+                    request,
+                    "127.0.0.1:40000".parse().unwrap(),
+                    clock.now(),
+                    &SocketPolicy::default(),
+                    |response| {
+                        reply = response.to_vec();
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            reply
+        };
+
+        // Requête portant elle-même un champ d'extension de 28 octets
+        let mut extended = client_request_bytes().to_vec();
+        extended.extend_from_slice(&ExtensionField::uncertainty(0).encode());
+
+        // Par défaut : paquet de 48 octets
+        assert_eq!(query(Config::default(), &extended).len(), NtpPacket::SIZE);
+
+        // Requête de 48 octets : pas de champ (réponse plus longue que la requête)
+        let mut config = Config::default();
+        config.server.emit_uncertainty_ext = true;
+        assert_eq!(query(config.clone(), &client_request_bytes()).len(), NtpPacket::SIZE);
+
+        let reply = query(config, &extended);
+
+        // En-tête NTP inchangé, suivi d'un champ de 28 octets (minimum sans MAC)
+        assert_eq!(reply.len(), NtpPacket::SIZE + ExtensionField::MIN_LAST_LEN);
+        assert_eq!(NtpPacket::from_bytes(&reply).unwrap().mode, NtpMode::Server);
+        let extension = &reply[NtpPacket::SIZE..];
+        assert_eq!(u16::from_be_bytes([extension[0], extension[1]]), ExtensionField::UNCERTAINTY_TYPE);
+        assert_eq!(u16::from_be_bytes([extension[2], extension[3]]) as usize, ExtensionField::MIN_LAST_LEN);
+        assert_eq!(u64::from_be_bytes(extension[4..12].try_into().unwrap()), 1_250);
+        assert!(extension[12..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_tx_pending_pairs_by_id() {
+        let ms = std::time::Duration::from_millis;
+        let mut pending = TxPending::default();
+        pending.push(ms(10));
+        pending.push(ms(20));
+        pending.push(ms(30));
+
+        // Horodatage du datagramme 1 arrivé après l'envoi du 2 : rapproché de son envoi
+        assert_eq!(pending.take(1), Some(ms(20)));
+        // Datagramme 0 oublié (horodatage perdu), déjà consommé : rien
+        assert_eq!(pending.take(0), None);
+        assert_eq!(pending.take(1), None);
+        assert_eq!(pending.take(2), Some(ms(30)));
+
+        // Rang en avance (envoi compté par le noyau mais pas localement) : resynchronisation
+        pending.push(ms(40));
+        assert_eq!(pending.take(5), None);
+        pending.push(ms(50));
+        assert_eq!(pending.take(6), Some(ms(50)));
+
+        // Moyenne glissante des latences, sans verrou
+        let stats = ServerStats::new();
+        assert_eq!(stats.tx_latency_ns(), None);
+        stats.record_tx_latency(1_600);
+        assert_eq!(stats.tx_latency_ns(), Some(1_600));
+        stats.record_tx_latency(0);
+        assert_eq!(stats.tx_latency_ns(), Some(1_500));
+    }
+
+    #[test]
+    fn test_symmetric_key_authentication() {
+        let clock = Arc::new(GpsNmeaClock::new(10));
+        clock.update_gps_time(NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0), Some(8));
+
+        let mut config = Config::default();
+        config.security.keys = vec![crate::config::KeyConfig {
+            id: 10,
+            algorithm: "sha1".to_string(),
+            key: "HEX:0123456789abcdef".to_string(),
+        }];
+        let keys = auth::keys_from_config(&config.security.keys);
+        let server = NtpServer::new(config, Arc::clone(&clock), StatsManager::new().clone_arc());
+
+        let query = |request: &[u8]| {
+            let mut reply = Vec::new();
+            server
+                .process_request(
+                    request,
+                    "127.0.0.1:40000".parse().unwrap(),
+                    clock.now(),
+                    &SocketPolicy::default(),
+                    |response| {
+                        reply = response.to_vec();
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            reply
+        };
+
+        // Requête signée : réponse signée avec la même clé, vérifiable par le client
+        let header = client_request_bytes();
+        let mut request = header.to_vec();
+        let mac = keys[&10].sign(10, &header);
+        request.extend_from_slice(&mac.key_id.to_be_bytes());
+        request.extend_from_slice(mac.digest());
+
+        let reply = query(&request);
+        assert_eq!(reply.len(), NtpPacket::SIZE + 24);
+        let response = NtpPacket::from_bytes(&reply).unwrap();
+        let response_mac = response.mac.unwrap();
+        assert_eq!(response_mac.key_id, 10);
+        assert!(auth::verify_mac(&keys, &reply[..NtpPacket::SIZE], &response_mac).is_ok());
+
+        // Condensé altéré : requête écartée sans réponse
+        *request.last_mut().unwrap() ^= 1;
+        assert!(query(&request).is_empty());
+        assert_eq!(server.stats.requests_rejected.load(Ordering::Relaxed), 1);
+        assert_eq!(server.stats.auth_failures.load(Ordering::Relaxed), 1);
+
+        // MAC après un champ d'extension : vérifié sur l'en-tête et l'extension
+        let mut extended = header.to_vec();
+        extended.extend_from_slice(&ExtensionField::uncertainty(0).encode());
+        let mac = keys[&10].sign(10, &extended);
+        let unsigned_len = extended.len();
+        extended.extend_from_slice(&mac.key_id.to_be_bytes());
+        extended.extend_from_slice(mac.digest());
+        assert_eq!(NtpPacket::from_bytes(&query(&extended)).unwrap().mac.unwrap().key_id, 10);
+
+        extended[unsigned_len - 1] ^= 1;
+        assert!(query(&extended).is_empty());
+        assert_eq!(server.stats.auth_failures.load(Ordering::Relaxed), 2);
+
+        // Requête sans MAC : toujours servie, sans authentification
+        let reply = query(&header);
+        assert_eq!(reply.len(), NtpPacket::SIZE);
+    }
+
+    #[test]
+    fn test_advertised_stratum_floor() {
+        let mut config = Config::default();
+        config.server.advertised_stratum_min = Some(2);
+        let clock = Arc::new(SteppingClock::new(
+            NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0),
+            NtpTimestamp::from_seconds_and_nanos(0, 1_000),
+        ));
+        let server = NtpServer::new(config, clock, StatsManager::new().clone_arc());
+
+        let request = NtpPacket::from_bytes(&client_request_bytes()).unwrap();
+        let receive_time = NtpTimestamp::from_seconds_and_nanos(3_900_000_001, 0);
+        let response = server.create_response(&request, receive_time, &SocketPolicy::default());
+
+        // L'horloge GPS annonce stratum 1, le plancher impose 2 et un refid IP
+        assert_eq!(response.stratum, 2);
+        assert_ne!(response.reference_identifier, u32::from_be_bytes(*b"GPS\0"));
+        assert_eq!(
+            response.reference_identifier,
+            u32::from(std::net::Ipv4Addr::LOCALHOST)
+        );
+
+        // Sans plancher, le stratum de l'horloge est annoncé tel quel
+        let clock = Arc::new(SteppingClock::new(
+            NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0),
+            NtpTimestamp::from_seconds_and_nanos(0, 1_000),
+        ));
+        let server = NtpServer::new(Config::default(), clock, StatsManager::new().clone_arc());
+        let response = server.create_response(&request, receive_time, &SocketPolicy::default());
+        assert_eq!(response.stratum, 1);
+        assert_eq!(response.reference_identifier, u32::from_be_bytes(*b"GPS\0"));
+    }
+
+    #[test]
+    fn test_untrusted_rtc_refuses_until_gps() {
+        let clock = Arc::new(GpsNmeaClock::new(30).with_system_clock_trusted(false));
+        let server = NtpServer::new(Config::default(), Arc::clone(&clock), StatsManager::new().clone_arc());
+
+        let server_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client
+            .set_read_timeout(Some(std::time::Duration::from_millis(200)))
+            .unwrap();
+        let mut buffer = [0u8; NtpPacket::SIZE];
+        let mut reply = [0u8; 128];
+
+        // Avant toute trame GPS : pas de réponse
+        client
+            .send_to(&client_request_bytes(), server_socket.local_addr().unwrap())
+            .unwrap();
+        server.handle_request(&server_socket, &mut buffer, &mut TxPending::default()).unwrap();
+        assert!(client.recv_from(&mut reply).is_err());
+        assert_eq!(server.stats.requests_rejected.load(Ordering::Relaxed), 1);
+
+        // Une fois le temps GPS reçu, le serveur répond
+        clock.update_gps_time(NtpTimestamp::from_seconds_and_nanos(4_000_000_000, 0), Some(8));
+        client
+            .send_to(&client_request_bytes(), server_socket.local_addr().unwrap())
+            .unwrap();
+        server.handle_request(&server_socket, &mut buffer, &mut TxPending::default()).unwrap();
+        let (size, _) = client.recv_from(&mut reply).unwrap();
+        let response = NtpPacket::from_bytes(&reply[..size]).unwrap();
+        assert_eq!(response.transmit_timestamp.seconds(), 4_000_000_000);
+    }
+
+    #[test]
+    fn test_multiple_sockets_answer() {
+        let server = Arc::new(NtpServer::new(
+            Config::default(),
+            Arc::new(SystemClock::new()),
+            StatsManager::new().clone_arc(),
+        ));
+
+        let sockets: Vec<UdpSocket> = (0..2)
+            .map(|_| {
+                let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+                socket
+                    .set_read_timeout(Some(std::time::Duration::from_millis(100)))
+                    .unwrap();
+                socket
+            })
+            .collect();
+        let addrs: Vec<_> = sockets.iter().map(|s| s.local_addr().unwrap()).collect();
+
+        let shutdown = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let server_clone = Arc::clone(&server);
+        let shutdown_clone = Arc::clone(&shutdown);
+        let handle = std::thread::spawn(move || server_clone.serve(sockets, None, shutdown_clone));
+
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client
+            .set_read_timeout(Some(std::time::Duration::from_secs(2)))
+            .unwrap();
+
+        for addr in &addrs {
+            client.send_to(&client_request_bytes(), addr).unwrap();
+            let mut buf = [0u8; 128];
+            let (size, from) = client.recv_from(&mut buf).unwrap();
+
+            // La réponse repart par le socket qui a reçu la requête
+            assert_eq!(from, *addr);
+            let response = NtpPacket::from_bytes(&buf[..size]).unwrap();
+            assert_eq!(response.mode, NtpMode::Server);
+        }
+
+        shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
+        handle.join().unwrap().unwrap();
+        assert_eq!(server.stats().requests_processed.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_listen_policy_per_socket() {
+        let sockets: Vec<UdpSocket> = (0..2)
+            .map(|_| {
+                let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+                socket
+                    .set_read_timeout(Some(std::time::Duration::from_millis(100)))
+                    .unwrap();
+                socket
+            })
+            .collect();
+        let internal = sockets[0].local_addr().unwrap();
+        let dmz = sockets[1].local_addr().unwrap();
+
+        // Port DMZ : stratum 2 au minimum et reference ID générique
+        let mut config = Config::default();
+        config.server.listen_policies = vec![crate::config::ListenPolicy {
+            port: dmz.port(),
+            advertised_stratum_min: Some(2),
+            reference_id: Some("DMZ".to_string()),
+            max_requests_per_second: None,
+        }];
+        let clock = Arc::new(SteppingClock::new(
+            NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0),
+            NtpTimestamp::from_seconds_and_nanos(0, 1_000),
+        ));
+        let server = Arc::new(NtpServer::new(config, clock, StatsManager::new().clone_arc()));
+
+        let shutdown = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let server_clone = Arc::clone(&server);
+        let shutdown_clone = Arc::clone(&shutdown);
+        let handle = std::thread::spawn(move || server_clone.serve(sockets, None, shutdown_clone));
+
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client
+            .set_read_timeout(Some(std::time::Duration::from_secs(2)))
+            .unwrap();
+        let query = |addr| {
+            client.send_to(&client_request_bytes(), addr).unwrap();
+            let mut buf = [0u8; 128];
+            let (size, _) = client.recv_from(&mut buf).unwrap();
+            NtpPacket::from_bytes(&buf[..size]).unwrap()
+        };
+
+        let response = query(internal);
+        assert_eq!(response.stratum, 1);
+        assert_eq!(response.reference_identifier.to_be_bytes(), *b"GPS\0");
+
+        let response = query(dmz);
+        assert_eq!(response.stratum, 2);
+        assert_eq!(response.reference_identifier.to_be_bytes(), *b"DMZ\0");
+
+        shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
+        handle.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_tcp_query() {
+        let server = Arc::new(NtpServer::new(
+            Config::default(),
+            Arc::new(SystemClock::new()),
+            StatsManager::new().clone_arc(),
+        ));
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let shutdown = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let server_clone = Arc::clone(&server);
+        let shutdown_clone = Arc::clone(&shutdown);
+        let handle = std::thread::spawn(move || {
+            server_clone.serve(Vec::new(), Some(listener), shutdown_clone)
+        });
+
+        let mut client = TcpStream::connect(addr).unwrap();
+        client
+            .set_read_timeout(Some(std::time::Duration::from_secs(2)))
+            .unwrap();
+
+        // Deux requêtes sur la même connexion
+        for _ in 0..2 {
+            let request = client_request_bytes();
+            client.write_all(&(request.len() as u16).to_be_bytes()).unwrap();
+            client.write_all(&request).unwrap();
+
+            let mut length = [0u8; 2];
+            client.read_exact(&mut length).unwrap();
+            let mut reply = vec![0u8; u16::from_be_bytes(length) as usize];
+            client.read_exact(&mut reply).unwrap();
+
+            let response = NtpPacket::from_bytes(&reply).unwrap();
+            assert_eq!(response.mode, NtpMode::Server);
+            assert_eq!(
+                response.originate_timestamp,
+                NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0)
+            );
+        }
+        drop(client);
+
+        shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
+        handle.join().unwrap().unwrap();
+        assert_eq!(server.stats().requests_processed.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_tcp_connection_limit() {
+        let mut config = Config::default();
+        config.server.max_tcp_connections = 1;
+        let server = Arc::new(NtpServer::new(
+            config,
+            Arc::new(SystemClock::new()),
+            StatsManager::new().clone_arc(),
+        ));
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.set_nonblocking(true).unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let shutdown = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let server_clone = Arc::clone(&server);
+        let shutdown_clone = Arc::clone(&shutdown);
+        let handle = std::thread::spawn(move || {
+            server_clone.serve(Vec::new(), Some(listener), shutdown_clone)
+        });
+
+        let query = |client: &mut TcpStream| -> std::io::Result<()> {
+            client.set_read_timeout(Some(std::time::Duration::from_secs(2)))?;
+            let request = client_request_bytes();
+            client.write_all(&(request.len() as u16).to_be_bytes())?;
+            client.write_all(&request)?;
+            let mut length = [0u8; 2];
+            client.read_exact(&mut length)?;
+            let mut reply = vec![0u8; u16::from_be_bytes(length) as usize];
+            client.read_exact(&mut reply)
+        };
+
+        // La première connexion est servie, la seconde fermée dès l'acceptation
+        let mut first = TcpStream::connect(addr).unwrap();
+        query(&mut first).unwrap();
+        let mut second = TcpStream::connect(addr).unwrap();
+        assert!(query(&mut second).is_err());
+
+        // La place se libère à la fermeture de la première
+        drop(first);
+        let served = (0..50).any(|_| {
+            let mut client = TcpStream::connect(addr).unwrap();
+            let ok = query(&mut client).is_ok();
+            if !ok {
+                std::thread::sleep(std::time::Duration::from_millis(20));
+            }
+            ok
+        });
+        assert!(served);
+
+        shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
+        handle.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_privileged_port_bind_error() {
+        let denied = || std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+
+        // Port 123 refusé : message actionnable
+        let message = bind_error(denied(), "UDP socket", "0.0.0.0:123".parse().unwrap()).to_string();
+        assert!(message.contains("requires root or CAP_NET_BIND_SERVICE"), "{}", message);
+        assert!(message.contains("setcap cap_net_bind_service=+ep"));
+
+        // Port non privilégié ou autre erreur : message générique
+        let message = bind_error(denied(), "UDP socket", "0.0.0.0:1123".parse().unwrap()).to_string();
+        assert_eq!(message, "Failed to bind UDP socket on 0.0.0.0:1123");
+        let in_use = std::io::Error::from(std::io::ErrorKind::AddrInUse);
+        let message = bind_error(in_use, "UDP socket", "0.0.0.0:123".parse().unwrap()).to_string();
+        assert_eq!(message, "Failed to bind UDP socket on 0.0.0.0:123");
+    }
 }
@@ -0,0 +1,128 @@
+/*!
+Arrêt du serveur sur signal
+
+En terminal interactif, Ctrl+C demande une confirmation : une seconde
+pression dans les 5 secondes arrête le serveur, sinon l'arrêt est annulé.
+Sous un gestionnaire de services (systemd, supervisord), qui envoie un seul
+signal et attend un arrêt immédiat, le premier signal arrête le serveur :
+mode choisi par `--no-confirm-shutdown` ou lorsque l'entrée standard n'est
+pas un terminal.
+
+SIGTERM (signal d'arrêt de systemd et de `kill`) arrête toujours le serveur
+sans confirmation, par le même drapeau d'arrêt.
+*/
+
+use anyhow::{Context, Result};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// Fenêtre de confirmation de la seconde pression
+const CONFIRM_WINDOW: Duration = Duration::from_secs(5);
+
+/// Délai avant sortie forcée si le serveur ne s'arrête pas
+const FORCED_EXIT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Réaction au premier signal d'arrêt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownMode {
+    /// Seconde pression requise dans la fenêtre de confirmation (terminal interactif)
+    Confirm,
+
+    /// Arrêt dès le premier signal (service)
+    Immediate,
+}
+
+impl ShutdownMode {
+    /// Mode selon l'option `--no-confirm-shutdown` et la nature de l'entrée standard
+    pub fn select(no_confirm_flag: bool, stdin_is_terminal: bool) -> Self {
+        if no_confirm_flag || !stdin_is_terminal {
+            ShutdownMode::Immediate
+        } else {
+            ShutdownMode::Confirm
+        }
+    }
+}
+
+/// Demande l'arrêt du serveur, avec sortie forcée s'il ne répond pas
+fn request_shutdown(shutdown: &AtomicBool) {
+    shutdown.store(true, Ordering::SeqCst);
+    std::thread::spawn(|| {
+        std::thread::sleep(FORCED_EXIT_TIMEOUT);
+        error!("Arrêt forcé (timeout)");
+        std::process::exit(0);
+    });
+}
+
+/// Installe les gestionnaires de Ctrl+C (SIGINT) et de SIGTERM positionnant `shutdown`
+pub fn install(mode: ShutdownMode, shutdown: Arc<AtomicBool>) -> Result<()> {
+    install_sigterm(&shutdown)?;
+
+    let ctrl_c_count = Arc::new(AtomicU8::new(0));
+
+    ctrlc::set_handler(move || {
+        if mode == ShutdownMode::Immediate {
+            warn!("Signal d'arrêt reçu. Fermeture du serveur...");
+            request_shutdown(&shutdown);
+            return;
+        }
+
+        let count = ctrl_c_count.fetch_add(1, Ordering::SeqCst);
+
+        if count == 0 {
+            // Première pression
+            warn!("Ctrl+C détecté. Appuyez à nouveau dans les 5 secondes pour arrêter le serveur.");
+
+            // Thread qui désamorce après la fenêtre de confirmation
+            let count_disarm = Arc::clone(&ctrl_c_count);
+            std::thread::spawn(move || {
+                std::thread::sleep(CONFIRM_WINDOW);
+                if count_disarm.load(Ordering::SeqCst) == 1 {
+                    // Pas de deuxième pression, désamorcer
+                    count_disarm.store(0, Ordering::SeqCst);
+                    info!("Arrêt annulé. Le serveur continue.");
+                }
+            });
+        } else {
+            // Deuxième pression (ou plus)
+            warn!("Arrêt confirmé. Fermeture du serveur...");
+            request_shutdown(&shutdown);
+        }
+    })
+    .context("Failed to set Ctrl+C handler")
+}
+
+/// SIGTERM positionne directement le drapeau d'arrêt (« arrêter maintenant »)
+fn install_sigterm(shutdown: &Arc<AtomicBool>) -> Result<()> {
+    signal_hook::flag::register(signal_hook::consts::SIGTERM, Arc::clone(shutdown))
+        .context("Failed to set SIGTERM handler")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shutdown_mode_selection() {
+        // Terminal interactif : confirmation par double pression
+        assert_eq!(ShutdownMode::select(false, true), ShutdownMode::Confirm);
+
+        // Option explicite ou entrée standard non interactive (systemd, supervisord) : immédiat
+        assert_eq!(ShutdownMode::select(true, true), ShutdownMode::Immediate);
+        assert_eq!(ShutdownMode::select(false, false), ShutdownMode::Immediate);
+        assert_eq!(ShutdownMode::select(true, false), ShutdownMode::Immediate);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sigterm_sets_shutdown_flag() {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        install_sigterm(&shutdown).unwrap();
+
+        // Signal délivré au thread appelant : le gestionnaire a tourné au retour de raise()
+        assert_eq!(unsafe { libc::raise(libc::SIGTERM) }, 0);
+        assert!(shutdown.load(Ordering::SeqCst));
+    }
+}
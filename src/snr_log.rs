@@ -0,0 +1,172 @@
+/*!
+Enregistrement de l'historique SNR des satellites au format CSV
+
+Outil de diagnostic d'antenne : à intervalle régulier, une ligne par
+satellite en vue (horodatage, constellation, PRN, élévation, azimut, SNR)
+est ajoutée à un fichier CSV exploitable dans un tableur.
+
+La taille du fichier est bornée : au-delà de la limite configurée, il est
+renommé en `<fichier>.1` (en écrasant la rotation précédente) et un nouveau
+fichier est commencé.
+*/
+
+use crate::panic_guard::run_guarded;
+use crate::stats::{SatelliteInfo, ServerStats};
+use chrono::{DateTime, Utc};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// En-tête des fichiers CSV
+pub const CSV_HEADER: &str = "timestamp,constellation,prn,elevation,azimuth,snr\n";
+
+/// Formate une ligne CSV par satellite, toutes horodatées au même instant
+pub fn csv_rows(timestamp: DateTime<Utc>, satellites: &[SatelliteInfo]) -> String {
+    let timestamp = timestamp.format("%Y-%m-%dT%H:%M:%SZ");
+
+    satellites
+        .iter()
+        .map(|sat| {
+            format!(
+                "{},{},{},{},{},{}\n",
+                timestamp, sat.constellation, sat.prn, sat.elevation, sat.azimuth, sat.snr
+            )
+        })
+        .collect()
+}
+
+/// Enregistreur périodique du SNR des satellites
+pub struct SnrRecorder {
+    path: PathBuf,
+    interval: Duration,
+    max_bytes: u64,
+    stats: Arc<RwLock<ServerStats>>,
+}
+
+impl SnrRecorder {
+    pub fn new(
+        path: PathBuf,
+        interval: Duration,
+        max_bytes: u64,
+        stats: Arc<RwLock<ServerStats>>,
+    ) -> Self {
+        SnrRecorder {
+            path,
+            interval,
+            max_bytes,
+            stats,
+        }
+    }
+
+    /// Démarre le thread d'enregistrement
+    pub fn start(self) -> std::thread::JoinHandle<()> {
+        info!(
+            "Recording satellite SNR to {} every {:?}",
+            self.path.display(),
+            self.interval
+        );
+
+        std::thread::spawn(move || loop {
+            std::thread::sleep(self.interval);
+
+            let satellites = match self.stats.read() {
+                Ok(stats) => stats.satellites.clone(),
+                Err(_) => continue,
+            };
+
+            let result = run_guarded("snr_log", &self.stats, || self.append(Utc::now(), &satellites));
+            if let Some(Err(e)) = result {
+                warn!("Failed to write satellite SNR CSV: {}", e);
+            }
+        })
+    }
+
+    /// Ajoute les lignes d'un relevé, avec rotation si le fichier dépasse la limite
+    fn append(&self, timestamp: DateTime<Utc>, satellites: &[SatelliteInfo]) -> std::io::Result<()> {
+        if satellites.is_empty() {
+            return Ok(());
+        }
+
+        let size = std::fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        if self.max_bytes > 0 && size >= self.max_bytes {
+            std::fs::rename(&self.path, rotated_path(&self.path))?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        if file.metadata()?.len() == 0 {
+            file.write_all(CSV_HEADER.as_bytes())?;
+        }
+        file.write_all(csv_rows(timestamp, satellites).as_bytes())
+    }
+}
+
+/// Chemin du fichier après rotation (`<fichier>.1`)
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::StatsManager;
+    use chrono::TimeZone;
+
+    fn satellite(prn: u8, elevation: u8, azimuth: u16, snr: u8) -> SatelliteInfo {
+        SatelliteInfo {
+            prn,
+            elevation,
+            azimuth,
+            snr,
+            constellation: "GPS".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_csv_rows_match_satellites() {
+        let timestamp = Utc.with_ymd_and_hms(2024, 11, 11, 12, 0, 0).unwrap();
+        let satellites = vec![satellite(5, 45, 120, 38), satellite(12, 10, 300, 0)];
+
+        let csv = csv_rows(timestamp, &satellites);
+        let rows: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(rows.len(), satellites.len());
+        assert_eq!(rows[0], "2024-11-11T12:00:00Z,GPS,5,45,120,38");
+        assert_eq!(rows[1], "2024-11-11T12:00:00Z,GPS,12,10,300,0");
+        assert_eq!(CSV_HEADER.trim_end().split(',').count(), rows[0].split(',').count());
+    }
+
+    #[test]
+    fn test_append_rotates_file() {
+        let path = std::env::temp_dir().join(format!("pendulum-snr-{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(rotated_path(&path));
+
+        let recorder = SnrRecorder::new(
+            path.clone(),
+            Duration::from_secs(10),
+            100,
+            StatsManager::new().clone_arc(),
+        );
+        let timestamp = Utc.with_ymd_and_hms(2024, 11, 11, 12, 0, 0).unwrap();
+        let satellites = vec![satellite(5, 45, 120, 38), satellite(12, 10, 300, 0)];
+
+        // Premier relevé : en-tête + 2 lignes (> 100 octets)
+        recorder.append(timestamp, &satellites).unwrap();
+        let first = std::fs::read_to_string(&path).unwrap();
+        assert!(first.starts_with(CSV_HEADER));
+        assert_eq!(first.lines().count(), 3);
+
+        // Deuxième relevé : le fichier plein est renommé, un nouveau est commencé
+        recorder.append(timestamp, &satellites).unwrap();
+        assert_eq!(std::fs::read_to_string(rotated_path(&path)).unwrap(), first);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), first);
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(rotated_path(&path));
+    }
+}
@@ -0,0 +1,315 @@
+/*!
+Options des sockets UDP d'écoute (Linux)
+
+- Don't fragment (`IP_MTU_DISCOVER` = `IP_PMTUDISC_DO`) : une réponse trop
+  grande pour le chemin est refusée par le noyau plutôt que fragmentée
+  (`EMSGSIZE` à l'envoi).
+- `IP_RECVERR` : les erreurs ICMP reçues en retour d'une réponse (port
+  inaccessible : le client a fermé son socket après la requête) sont placées
+  dans la file d'erreurs du socket avec l'adresse du client concerné, au lieu
+  de ressortir sans contexte sur un `recv_from`/`send_to` ultérieur.
+
+La file d'erreurs est partagée avec l'horodatage d'émission (`tx_timestamp`) :
+un seul lecteur, `read_error_queue`, la vide et retourne chaque message
+reconnu (horodatage ou erreur ICMP) pour que l'appelant traite les deux.
+
+Disponible uniquement sous Linux ; ailleurs, l'activation échoue avec
+`ErrorKind::Unsupported`.
+*/
+
+use crate::tx_timestamp::TxTimestamp;
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+
+/// Nombre maximal de messages lus par vidage de la file d'erreurs
+const MAX_MESSAGES_PER_DRAIN: usize = 64;
+
+/// Erreur ICMP rapportée par le noyau pour un datagramme émis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IcmpError {
+    /// Destination du datagramme en erreur (le client)
+    pub destination: Option<SocketAddr>,
+
+    /// Code d'erreur système associé (`ECONNREFUSED` pour un port inaccessible)
+    pub errno: i32,
+
+    /// Type et code ICMP (ICMPv4 ou ICMPv6 selon le socket)
+    pub icmp_type: u8,
+    pub icmp_code: u8,
+}
+
+impl std::fmt::Display for IcmpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} (ICMP type {} code {})",
+            io::Error::from_raw_os_error(self.errno),
+            self.icmp_type,
+            self.icmp_code
+        )
+    }
+}
+
+/// Positionne le bit don't-fragment sur les datagrammes émis
+pub fn set_dont_fragment(socket: &UdpSocket) -> io::Result<()> {
+    imp::set_dont_fragment(socket)
+}
+
+/// Place les erreurs ICMP dans la file d'erreurs du socket
+pub fn enable_recv_err(socket: &UdpSocket) -> io::Result<()> {
+    imp::enable_recv_err(socket)
+}
+
+/// Message de la file d'erreurs d'un socket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorQueueMessage {
+    /// Horodatage d'émission d'un datagramme (`tx_timestamp`)
+    TxTimestamp(TxTimestamp),
+
+    /// Erreur ICMP en retour d'un datagramme émis (`enable_recv_err`)
+    Icmp(IcmpError),
+}
+
+/// Vide la file d'erreurs sans bloquer ; les messages non reconnus sont écartés
+pub fn read_error_queue(socket: &UdpSocket) -> io::Result<Vec<ErrorQueueMessage>> {
+    let mut messages = Vec::new();
+    for _ in 0..MAX_MESSAGES_PER_DRAIN {
+        match imp::read_message(socket)? {
+            Some(Some(message)) => messages.push(message),
+            Some(None) => {}
+            None => break,
+        }
+    }
+    Ok(messages)
+}
+
+/// Vrai si l'erreur d'une opération sur le socket provient d'une erreur ICMP
+/// en attente (client injoignable) plutôt que d'une défaillance locale
+pub fn is_icmp_error(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::ConnectionRefused | io::ErrorKind::HostUnreachable | io::ErrorKind::NetworkUnreachable
+    )
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::{ErrorQueueMessage, IcmpError};
+    use crate::tx_timestamp::TxTimestamp;
+    use std::io;
+    use std::mem::{size_of, MaybeUninit};
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, UdpSocket};
+    use std::os::fd::AsRawFd;
+    use std::time::Duration;
+
+    /// Taille du tampon de messages de contrôle (scm_timestamping, sock_extended_err
+    /// et adresse émettrice)
+    const CONTROL_BUFFER_SIZE: usize = 256;
+
+    /// Positionne une option entière (IPv4 ou IPv6 selon l'adresse locale du socket)
+    fn set_ip_option(socket: &UdpSocket, v4: libc::c_int, v6: libc::c_int, value: libc::c_int) -> io::Result<()> {
+        let (level, name) = match socket.local_addr()? {
+            SocketAddr::V4(_) => (libc::IPPROTO_IP, v4),
+            SocketAddr::V6(_) => (libc::IPPROTO_IPV6, v6),
+        };
+
+        // SAFETY: pointeur et taille désignent `value`, valide pendant l'appel
+        let result = unsafe {
+            libc::setsockopt(
+                socket.as_raw_fd(),
+                level,
+                name,
+                &value as *const libc::c_int as *const libc::c_void,
+                size_of::<libc::c_int>() as libc::socklen_t,
+            )
+        };
+
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    pub fn set_dont_fragment(socket: &UdpSocket) -> io::Result<()> {
+        // IP_PMTUDISC_DO et IPV6_PMTUDISC_DO ont la même valeur
+        set_ip_option(socket, libc::IP_MTU_DISCOVER, libc::IPV6_MTU_DISCOVER, libc::IP_PMTUDISC_DO)
+    }
+
+    pub fn enable_recv_err(socket: &UdpSocket) -> io::Result<()> {
+        set_ip_option(socket, libc::IP_RECVERR, libc::IPV6_RECVERR, 1)
+    }
+
+    /// Adresse de destination du datagramme en erreur (champ `msg_name`)
+    fn socket_addr(storage: &libc::sockaddr_storage) -> Option<SocketAddr> {
+        match storage.ss_family as libc::c_int {
+            libc::AF_INET => {
+                // SAFETY: famille AF_INET, la zone contient un sockaddr_in
+                let addr = unsafe { &*(storage as *const _ as *const libc::sockaddr_in) };
+                Some(SocketAddr::V4(SocketAddrV4::new(
+                    Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr)),
+                    u16::from_be(addr.sin_port),
+                )))
+            }
+            libc::AF_INET6 => {
+                // SAFETY: famille AF_INET6, la zone contient un sockaddr_in6
+                let addr = unsafe { &*(storage as *const _ as *const libc::sockaddr_in6) };
+                Some(SocketAddr::V6(SocketAddrV6::new(
+                    Ipv6Addr::from(addr.sin6_addr.s6_addr),
+                    u16::from_be(addr.sin6_port),
+                    addr.sin6_flowinfo,
+                    addr.sin6_scope_id,
+                )))
+            }
+            _ => None,
+        }
+    }
+
+    /// Lit un message de la file d'erreurs sans bloquer : `None` si elle est
+    /// vide, `Some(None)` pour un message qui n'est ni un horodatage numéroté
+    /// ni une erreur ICMP
+    pub fn read_message(socket: &UdpSocket) -> io::Result<Option<Option<ErrorQueueMessage>>> {
+        let fd = socket.as_raw_fd();
+
+        let mut data = [0u8; 64];
+        let mut iov = libc::iovec {
+            iov_base: data.as_mut_ptr() as *mut libc::c_void,
+            iov_len: data.len(),
+        };
+        let mut control = [0u64; CONTROL_BUFFER_SIZE / 8];
+        // SAFETY: sockaddr_storage est valide lorsqu'il est mis à zéro
+        let mut name: libc::sockaddr_storage = unsafe { MaybeUninit::zeroed().assume_init() };
+
+        // SAFETY: msghdr mis à zéro puis renseigné avec des tampons valides
+        let mut msg: libc::msghdr = unsafe { MaybeUninit::zeroed().assume_init() };
+        msg.msg_name = &mut name as *mut libc::sockaddr_storage as *mut libc::c_void;
+        msg.msg_namelen = size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr() as *mut libc::c_void;
+        msg.msg_controllen = CONTROL_BUFFER_SIZE as _;
+
+        // SAFETY: msg désigne des tampons valides pendant l'appel
+        let received = unsafe { libc::recvmsg(fd, &mut msg, libc::MSG_ERRQUEUE | libc::MSG_DONTWAIT) };
+        if received < 0 {
+            let error = io::Error::last_os_error();
+            return match error.kind() {
+                io::ErrorKind::WouldBlock => Ok(None),
+                _ => Err(error),
+            };
+        }
+
+        // Horodatage d'émission : instant (SCM_TIMESTAMPING) et rang du datagramme
+        // (sock_extended_err d'origine SO_EE_ORIGIN_TIMESTAMPING), dans deux
+        // messages de contrôle ; erreur ICMP : sock_extended_err d'origine ICMP
+        let mut time = None;
+        let mut id = None;
+
+        // SAFETY: parcours des messages de contrôle remplis par recvmsg
+        unsafe {
+            let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+            while !cmsg.is_null() {
+                let level = (*cmsg).cmsg_level;
+                let kind = (*cmsg).cmsg_type;
+                if level == libc::SOL_SOCKET && kind == libc::SCM_TIMESTAMPING {
+                    // scm_timestamping : [logiciel, obsolète, matériel]
+                    let software = std::ptr::read_unaligned(libc::CMSG_DATA(cmsg) as *const libc::timespec);
+                    if software.tv_sec > 0 || software.tv_nsec > 0 {
+                        time = Some(Duration::new(software.tv_sec as u64, software.tv_nsec as u32));
+                    }
+                } else if (level == libc::IPPROTO_IP && kind == libc::IP_RECVERR)
+                    || (level == libc::IPPROTO_IPV6 && kind == libc::IPV6_RECVERR)
+                {
+                    let error = std::ptr::read_unaligned(libc::CMSG_DATA(cmsg) as *const libc::sock_extended_err);
+                    if error.ee_origin == libc::SO_EE_ORIGIN_ICMP || error.ee_origin == libc::SO_EE_ORIGIN_ICMP6 {
+                        return Ok(Some(Some(ErrorQueueMessage::Icmp(IcmpError {
+                            destination: socket_addr(&name),
+                            errno: error.ee_errno as i32,
+                            icmp_type: error.ee_type,
+                            icmp_code: error.ee_code,
+                        }))));
+                    }
+                    if error.ee_origin == libc::SO_EE_ORIGIN_TIMESTAMPING {
+                        id = Some(error.ee_data);
+                    }
+                }
+                cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+            }
+        }
+
+        Ok(Some(time.zip(id).map(|(time, id)| ErrorQueueMessage::TxTimestamp(TxTimestamp { id, time }))))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::ErrorQueueMessage;
+    use std::io;
+    use std::net::UdpSocket;
+
+    fn unsupported() -> io::Error {
+        io::Error::new(io::ErrorKind::Unsupported, "Socket error queue options are only available on Linux")
+    }
+
+    pub fn set_dont_fragment(_socket: &UdpSocket) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub fn enable_recv_err(_socket: &UdpSocket) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub fn read_message(_socket: &UdpSocket) -> io::Result<Option<Option<ErrorQueueMessage>>> {
+        Ok(None)
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_socket_options() {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        set_dont_fragment(&socket).unwrap();
+        enable_recv_err(&socket).unwrap();
+
+        // File vide : rien à lire, sans bloquer
+        assert!(read_error_queue(&socket).unwrap().is_empty());
+
+        // IPv6 : options appliquées si la pile est disponible
+        if let Ok(socket) = UdpSocket::bind("[::1]:0") {
+            set_dont_fragment(&socket).unwrap();
+            enable_recv_err(&socket).unwrap();
+        }
+    }
+
+    #[test]
+    #[ignore = "requires ICMP port unreachable on loopback and SO_TIMESTAMPING; run with --ignored"]
+    fn test_port_unreachable_after_tx_timestamp() {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        enable_recv_err(&socket).unwrap();
+        crate::tx_timestamp::enable(&socket).unwrap();
+
+        // Port fermé : le noyau répond par un ICMP port inaccessible sur la boucle
+        // locale, placé dans la file après l'horodatage d'émission
+        let closed = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let destination = closed.local_addr().unwrap();
+        drop(closed);
+        socket.send_to(&[0u8; 48], destination).unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+
+        // Horodatage et erreur ICMP lus tous les deux, dans l'ordre de la file
+        let messages = read_error_queue(&socket).unwrap();
+        assert_eq!(messages.len(), 2, "{:?}", messages);
+        assert!(matches!(messages[0], ErrorQueueMessage::TxTimestamp(TxTimestamp { id: 0, .. })));
+        let ErrorQueueMessage::Icmp(error) = messages[1] else {
+            panic!("ICMP error expected after the TX timestamp: {:?}", messages);
+        };
+        assert_eq!(error.destination, Some(destination));
+        assert_eq!(error.errno, libc::ECONNREFUSED);
+        assert_eq!((error.icmp_type, error.icmp_code), (3, 3));
+        assert!(read_error_queue(&socket).unwrap().is_empty());
+    }
+}
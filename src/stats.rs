@@ -1,5 +1,8 @@
+use crate::heartbeat::ThreadHealth;
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LockResult, RwLock, RwLockWriteGuard, TryLockError};
+use std::time::Instant;
 
 /// Informations sur un satellite GPS
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,15 +37,62 @@ pub struct ServerStats {
 
     /// Liste des satellites en vue
     pub satellites: Vec<SatelliteInfo>,
+
+    /// Nombre de paniques interceptées dans les threads des sous-systèmes
+    pub subsystem_panics: u64,
+
+    /// Dernière panique interceptée ("sous-système: message")
+    pub last_panic: Option<String>,
+
+    /// Santé des threads internes (renseignée à la lecture, voir `heartbeat`)
+    #[serde(default)]
+    pub threads: Vec<ThreadHealth>,
+}
+
+/// État du récepteur GPS (voir `gps_state`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GpsState {
+    /// Port série fermé
+    #[default]
+    Disconnected,
+
+    /// Connecté, pas de fix (acquisition)
+    NoFix,
+
+    /// Fix 2D (moins de 4 satellites ou GSA 2D)
+    #[serde(rename = "fix_2d")]
+    Fix2D,
+
+    /// Fix 3D
+    #[serde(rename = "fix_3d")]
+    Fix3D,
+
+    /// Fix valide et pulses PPS reçus
+    PpsLocked,
+
+    /// Fix perdu récemment, l'horloge extrapole depuis la dernière synchronisation
+    Holdover,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GpsStats {
+    /// État du récepteur (les booléens ci-dessous en sont dérivés)
+    pub state: GpsState,
+
     /// GPS connecté et fonctionnel
     pub connected: bool,
 
-    /// Nombre de satellites visibles
-    pub satellites: u8,
+    /// GPS en acquisition (trames GGA sans fix, qualité 0)
+    pub acquiring: bool,
+
+    /// Satellites utilisés dans le fix (trame GGA)
+    #[serde(alias = "satellites")]
+    pub satellites_used: u8,
+
+    /// Satellites en vue, toutes constellations (en-têtes des trames GSV)
+    #[serde(default)]
+    pub satellites_in_view: u8,
 
     /// Qualité du signal (0-10)
     pub signal_quality: u8,
@@ -64,6 +114,32 @@ pub struct GpsStats {
 
     /// Offset PPS actuel (secondes)
     pub pps_offset: Option<f64>,
+
+    /// Nombre de relances du thread de lecture par le watchdog
+    pub watchdog_restarts: u64,
+
+    /// Nombre d'entrées satellites GSV ignorées (valeurs hors plage ou illisibles)
+    pub malformed_satellites: u64,
+
+    /// Baud rate retenu par la détection automatique
+    pub detected_baud_rate: Option<u32>,
+
+    /// Précision temporelle estimée par le module (ns, trames u-blox `$PUBX,00`)
+    pub time_accuracy_ns: Option<u64>,
+
+    /// Vitesse sur le fond (km/h, trames VTG) : une installation fixe doit rester
+    /// proche de 0, une valeur élevée trahit des trajets multiples
+    pub speed_kmh: Option<f64>,
+
+    /// Cap vrai sur le fond (degrés, trames VTG)
+    pub course_deg: Option<f64>,
+
+    /// Position de l'antenne (degrés décimaux, positifs au nord et à l'est,
+    /// dernière trame GGA avec fix)
+    #[serde(default)]
+    pub latitude_deg: Option<f64>,
+    #[serde(default)]
+    pub longitude_deg: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,11 +150,171 @@ pub struct NtpStats {
     /// Nombre de requêtes traitées dans la dernière seconde
     pub requests_per_second: u32,
 
+    /// Débit lissé par moyenne exponentielle (`server.requests_rate_window_secs`)
+    #[serde(default)]
+    pub requests_per_second_smoothed: f64,
+
+    /// Nombre de requêtes rejetées dans la dernière seconde (validation, rate limiting, ACL)
+    pub rejected_per_second: u32,
+
+    /// Retransmissions d'une requête déjà reçue (répondues, non comptées dans requests_total)
+    pub duplicate_requests: u64,
+
+    /// Requêtes au champ poll hors de la plage plausible (clients défectueux ou sondes)
+    pub poll_anomalies: u64,
+
+    /// Requêtes client ignorées car émises depuis le port 123
+    /// (`security.reject_source_port_123`)
+    #[serde(default)]
+    pub source_port_123_rejected: u64,
+
+    /// Requêtes authentifiées écartées : clé inconnue ou MAC invalide (`security.keys`)
+    #[serde(default)]
+    pub auth_failures: u64,
+
+    /// Erreurs ICMP reçues en retour des réponses (clients injoignables,
+    /// avec `server.log_icmp_errors`)
+    pub icmp_errors: u64,
+
+    /// Associations symétriques passives actives (`server.allow_symmetric`)
+    pub symmetric_peers: usize,
+
     /// Nombre de clients actifs (IPs uniques dans les 60 dernières secondes)
     pub active_clients: usize,
 
     /// Dernière activité TX (millisecondes depuis)
     pub last_tx_ms: u64,
+
+    /// Latence d'émission logicielle : de l'appel à `send_to` à l'horodatage
+    /// noyau du datagramme (nanosecondes, moyenne glissante). Absente sans
+    /// `server.tx_timestamping` ou hors Linux
+    pub tx_software_latency_ns: Option<u64>,
+
+    /// Latence de traitement serveur (T3 - T2)
+    pub processing_latency: ProcessingLatency,
+
+    /// Requêtes rejetées, par règle de validation
+    pub validation_errors: ValidationErrorCounts,
+}
+
+/// Compteurs de requêtes rejetées par règle de validation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ValidationErrorCounts {
+    /// Version NTP hors de 1-4
+    pub invalid_version: u64,
+
+    /// Mode différent de client (3)
+    pub invalid_mode: u64,
+
+    /// Transmit timestamp nul
+    pub zero_transmit_timestamp: u64,
+
+    /// Stratum du client >= 16
+    pub invalid_stratum: u64,
+
+    /// Paquet illisible (taille invalide)
+    pub malformed: u64,
+}
+
+/// Statistiques de latence de traitement (intervalle réception → transmission)
+///
+/// Sans référence externe, l'écart T3 - T2 mesuré sur chaque réponse donne
+/// une estimation de la qualité du serveur lui-même.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessingLatency {
+    /// Nombre de mesures accumulées
+    pub samples: u64,
+
+    /// Dernière latence mesurée (microsecondes)
+    pub last_us: f64,
+
+    /// Latence moyenne (microsecondes)
+    pub mean_us: f64,
+
+    /// Latence maximale (microsecondes)
+    pub max_us: f64,
+
+    /// Gigue : moyenne glissante des écarts entre mesures successives (microsecondes)
+    pub jitter_us: f64,
+}
+
+/// Latence de traitement agrégée sans verrou sur le chemin de réponse,
+/// publiée périodiquement dans `ProcessingLatency`
+#[derive(Debug, Default)]
+pub struct LatencyAccumulator {
+    samples: AtomicU64,
+    sum_ns: AtomicU64,
+    max_ns: AtomicU64,
+    last_ns: AtomicU64,
+
+    /// Gigue en nanosecondes (bits d'un f64)
+    jitter_ns: AtomicU64,
+}
+
+impl LatencyAccumulator {
+    /// Ajoute une mesure de latence (en microsecondes)
+    pub fn record(&self, latency_us: f64) {
+        let latency_ns = (latency_us.max(0.0) * 1000.0) as u64;
+
+        let previous_ns = self.last_ns.swap(latency_ns, Ordering::Relaxed);
+        if self.samples.fetch_add(1, Ordering::Relaxed) > 0 {
+            // Gigue façon RFC 3550 : J += (|D| - J) / 16
+            let delta = latency_ns.abs_diff(previous_ns) as f64;
+            let _ = self.jitter_ns.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                let jitter = f64::from_bits(bits);
+                Some((jitter + (delta - jitter) / 16.0).to_bits())
+            });
+        }
+        self.sum_ns.fetch_add(latency_ns, Ordering::Relaxed);
+        self.max_ns.fetch_max(latency_ns, Ordering::Relaxed);
+    }
+
+    /// Statistiques agrégées à publier
+    pub fn snapshot(&self) -> ProcessingLatency {
+        let samples = self.samples.load(Ordering::Relaxed);
+        let mean_ns = match samples {
+            0 => 0.0,
+            n => self.sum_ns.load(Ordering::Relaxed) as f64 / n as f64,
+        };
+
+        ProcessingLatency {
+            samples,
+            last_us: self.last_ns.load(Ordering::Relaxed) as f64 / 1000.0,
+            mean_us: mean_ns / 1000.0,
+            max_us: self.max_ns.load(Ordering::Relaxed) as f64 / 1000.0,
+            jitter_us: f64::from_bits(self.jitter_ns.load(Ordering::Relaxed)) / 1000.0,
+        }
+    }
+}
+
+/// Moyenne exponentielle d'un débit échantillonné chaque seconde
+///
+/// La constante de temps `window` fixe le poids de chaque échantillon
+/// (1 - e^(-1/window)) : une rafale d'une seconde ne pèse qu'environ 18 %
+/// avec la fenêtre de 5 s par défaut.
+#[derive(Debug, Clone)]
+pub struct RateSmoother {
+    alpha: f64,
+    value: Option<f64>,
+}
+
+impl RateSmoother {
+    pub fn new(window_secs: u64) -> Self {
+        RateSmoother {
+            alpha: 1.0 - (-1.0 / window_secs.max(1) as f64).exp(),
+            value: None,
+        }
+    }
+
+    /// Ajoute l'échantillon de la dernière seconde et retourne le débit lissé
+    pub fn update(&mut self, sample: f64) -> f64 {
+        let value = match self.value {
+            Some(value) => value + self.alpha * (sample - value),
+            None => sample,
+        };
+        self.value = Some(value);
+        value
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +333,20 @@ pub struct ClockInfo {
 
     /// Partie fractionnaire (en nanosecondes)
     pub current_fraction_ns: u32,
+
+    /// Décalage GPS - UTC en secondes selon la table des secondes intercalaires
+    /// (absent sans `clock.leap_seconds_file`)
+    pub leap_offset_seconds: Option<i32>,
+
+    /// Prochaine seconde intercalaire annoncée (ISO 8601 UTC)
+    pub next_leap_second: Option<String>,
+
+    /// Source active de la liste de priorité `clock.sources` (absente pour une source unique)
+    pub active_source: Option<String>,
+
+    /// Temps GPS et horloge système en désaccord au-delà de `clock.max_gps_system_divergence_ms`
+    #[serde(default)]
+    pub divergence_alarm: bool,
 }
 
 /// Gestionnaire de statistiques partagé via Arc<RwLock>
@@ -108,8 +358,11 @@ impl StatsManager {
     pub fn new() -> Self {
         let stats = ServerStats {
             gps: GpsStats {
+                state: GpsState::Disconnected,
                 connected: false,
-                satellites: 0,
+                acquiring: false,
+                satellites_used: 0,
+                satellites_in_view: 0,
                 signal_quality: 0,
                 last_sync_secs: None,
                 nmea_sentences: 0,
@@ -117,12 +370,31 @@ impl StatsManager {
                 pps_count: 0,
                 last_rx_ms: 0,
                 pps_offset: None,
+                watchdog_restarts: 0,
+                malformed_satellites: 0,
+                detected_baud_rate: None,
+                time_accuracy_ns: None,
+                speed_kmh: None,
+                course_deg: None,
+                latitude_deg: None,
+                longitude_deg: None,
             },
             ntp: NtpStats {
                 requests_total: 0,
                 requests_per_second: 0,
+                requests_per_second_smoothed: 0.0,
+                rejected_per_second: 0,
+                duplicate_requests: 0,
+                poll_anomalies: 0,
+                source_port_123_rejected: 0,
+                auth_failures: 0,
+                icmp_errors: 0,
+                symmetric_peers: 0,
                 active_clients: 0,
                 last_tx_ms: 0,
+                tx_software_latency_ns: None,
+                processing_latency: ProcessingLatency::default(),
+                validation_errors: ValidationErrorCounts::default(),
             },
             clock: ClockInfo {
                 stratum: 16,
@@ -130,8 +402,15 @@ impl StatsManager {
                 precision: -20,
                 current_timestamp: 0,
                 current_fraction_ns: 0,
+                leap_offset_seconds: None,
+                next_leap_second: None,
+                active_source: None,
+                divergence_alarm: false,
             },
             satellites: Vec::new(),
+            subsystem_panics: 0,
+            last_panic: None,
+            threads: Vec::new(),
         };
 
         StatsManager {
@@ -156,7 +435,7 @@ impl StatsManager {
     where
         F: FnOnce(&mut GpsStats),
     {
-        if let Ok(mut stats) = self.stats.write() {
+        if let Ok(mut stats) = write_stats(&self.stats) {
             f(&mut stats.gps);
         }
     }
@@ -167,7 +446,7 @@ impl StatsManager {
     where
         F: FnOnce(&mut NtpStats),
     {
-        if let Ok(mut stats) = self.stats.write() {
+        if let Ok(mut stats) = write_stats(&self.stats) {
             f(&mut stats.ntp);
         }
     }
@@ -177,7 +456,7 @@ impl StatsManager {
     where
         F: FnOnce(&mut ClockInfo),
     {
-        if let Ok(mut stats) = self.stats.write() {
+        if let Ok(mut stats) = write_stats(&self.stats) {
             f(&mut stats.clock);
         }
     }
@@ -185,7 +464,7 @@ impl StatsManager {
     /// Met à jour la liste des satellites
     #[allow(dead_code)]
     pub fn update_satellites(&self, satellites: Vec<SatelliteInfo>) {
-        if let Ok(mut stats) = self.stats.write() {
+        if let Ok(mut stats) = write_stats(&self.stats) {
             stats.satellites = satellites;
         }
     }
@@ -196,3 +475,133 @@ impl Default for StatsManager {
         Self::new()
     }
 }
+
+/// Mesure de la contention d'un verrou en écriture
+///
+/// Désactivée par défaut : l'écriture passe directement par `RwLock::write`.
+/// Activée, un `try_write` est tenté d'abord ; s'il échoue (verrou déjà pris),
+/// l'acquisition est comptée comme contendue et son attente chronométrée.
+pub struct LockContention {
+    enabled: AtomicBool,
+    contended: AtomicU64,
+    wait_ns: AtomicU64,
+}
+
+impl LockContention {
+    pub const fn new() -> Self {
+        LockContention {
+            enabled: AtomicBool::new(false),
+            contended: AtomicU64::new(0),
+            wait_ns: AtomicU64::new(0),
+        }
+    }
+
+    /// Active la mesure (surcoût d'un `try_write` par écriture)
+    pub fn enable(&self) {
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    /// Acquiert le verrou en écriture, en mesurant l'attente s'il est déjà pris
+    pub fn write<'a, T>(&self, lock: &'a RwLock<T>) -> LockResult<RwLockWriteGuard<'a, T>> {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return lock.write();
+        }
+
+        match lock.try_write() {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::Poisoned(poisoned)) => Err(poisoned),
+            Err(TryLockError::WouldBlock) => {
+                let start = Instant::now();
+                let result = lock.write();
+                let waited = start.elapsed().as_nanos().min(u64::MAX as u128) as u64;
+                self.contended.fetch_add(1, Ordering::Relaxed);
+                self.wait_ns.fetch_add(waited, Ordering::Relaxed);
+                result
+            }
+        }
+    }
+
+    /// (acquisitions contendues, attente cumulée en ns), `None` si la mesure est désactivée
+    pub fn snapshot(&self) -> Option<(u64, u64)> {
+        self.enabled.load(Ordering::Relaxed).then(|| {
+            (self.contended.load(Ordering::Relaxed), self.wait_ns.load(Ordering::Relaxed))
+        })
+    }
+}
+
+impl Default for LockContention {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Contention du verrou des statistiques partagées (`webserver.stats_lock_metrics`)
+pub static STATS_LOCK_CONTENTION: LockContention = LockContention::new();
+
+/// Acquiert en écriture les statistiques partagées (contention mesurée si activée)
+pub fn write_stats(stats: &RwLock<ServerStats>) -> LockResult<RwLockWriteGuard<'_, ServerStats>> {
+    STATS_LOCK_CONTENTION.write(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_contention_counted() {
+        let contention = LockContention::new();
+        let lock = Arc::new(RwLock::new(0u32));
+
+        // Désactivée : rien n'est mesuré
+        *contention.write(&lock).unwrap() += 1;
+        assert_eq!(contention.snapshot(), None);
+
+        contention.enable();
+        *contention.write(&lock).unwrap() += 1;
+        assert_eq!(contention.snapshot(), Some((0, 0)));
+
+        // Un autre thread tient le verrou pendant 50 ms : acquisition contendue
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let holder = {
+            let lock = Arc::clone(&lock);
+            std::thread::spawn(move || {
+                let _guard = lock.write().unwrap();
+                locked_tx.send(()).unwrap();
+                std::thread::sleep(std::time::Duration::from_millis(50));
+            })
+        };
+        locked_rx.recv().unwrap();
+        *contention.write(&lock).unwrap() += 1;
+        holder.join().unwrap();
+
+        let (contended, wait_ns) = contention.snapshot().unwrap();
+        assert_eq!(contended, 1);
+        assert!(wait_ns >= 10_000_000, "wait_ns = {}", wait_ns);
+        assert_eq!(*lock.read().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_rate_smoother_damps_bursts() {
+        // Débit régulier : valeur lissée stable
+        let mut smoother = RateSmoother::new(5);
+        for _ in 0..30 {
+            assert!((smoother.update(100.0) - 100.0).abs() < 1e-9);
+        }
+
+        // Rafale d'une seconde à 1000 req/s : environ 18 % de l'écart seulement
+        let burst = smoother.update(1_000.0);
+        assert!(burst > 150.0 && burst < 300.0, "burst = {}", burst);
+
+        // Retour vers le débit régulier
+        let mut value = burst;
+        for _ in 0..30 {
+            value = smoother.update(100.0);
+        }
+        assert!((value - 100.0).abs() < 1.0, "value = {}", value);
+
+        // Fenêtre d'une seconde : lissage faible
+        let mut fast = RateSmoother::new(1);
+        fast.update(0.0);
+        assert!(fast.update(100.0) > 60.0);
+    }
+}
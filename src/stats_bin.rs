@@ -0,0 +1,173 @@
+/*!
+Statistiques au format binaire compact (`GET /api/stats.bin`)
+
+Pour les collecteurs de flotte qui interrogent des centaines de serveurs :
+48 octets de taille fixe, entiers little-endian, sans parsing JSON.
+
+```text
+Offset  Taille  Type   Champ
+0       4       [u8]   Magique "PNDL"
+4       2       u16    Version du format (1)
+6       1       u8     Stratum annoncé
+7       1       u8     Satellites utilisés
+8       1       u8     Drapeaux : bit 0 GPS connecté, bit 1 PPS actif,
+                       bit 2 offset PPS disponible
+9       3       -      Réservé (zéro)
+12      8       u64    Pulses PPS reçus
+20      8       i64    Offset PPS (nanosecondes, 0 si indisponible : voir bit 2)
+28      8       u64    Requêtes NTP traitées (requests_total)
+36      4       u32    Requêtes NTP de la dernière seconde
+40      8       u64    Uptime du serveur (secondes)
+```
+
+Toute évolution du format incrémente la version ; les champs existants ne
+sont jamais déplacés.
+*/
+
+use crate::stats::ServerStats;
+
+/// Magique en tête de chaque enregistrement
+const MAGIC: [u8; 4] = *b"PNDL";
+
+/// Version du format décrit ci-dessus
+const VERSION: u16 = 1;
+
+/// Taille d'un enregistrement (octets)
+pub const STATS_BIN_SIZE: usize = 48;
+
+const FLAG_GPS_CONNECTED: u8 = 1 << 0;
+const FLAG_PPS_ACTIVE: u8 = 1 << 1;
+const FLAG_PPS_OFFSET: u8 = 1 << 2;
+
+/// Champs numériques clés des statistiques
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BinaryStats {
+    pub stratum: u8,
+    pub satellites: u8,
+    pub gps_connected: bool,
+    pub pps_active: bool,
+    pub pps_count: u64,
+
+    /// Offset PPS en nanosecondes (virgule fixe), `None` si indisponible
+    pub pps_offset_ns: Option<i64>,
+    pub requests_total: u64,
+    pub requests_per_second: u32,
+    pub uptime_secs: u64,
+}
+
+impl BinaryStats {
+    /// Extrait les champs clés des statistiques partagées
+    pub fn from_stats(stats: &ServerStats, uptime_secs: u64) -> Self {
+        BinaryStats {
+            stratum: stats.clock.stratum,
+            satellites: stats.gps.satellites_used,
+            gps_connected: stats.gps.connected,
+            pps_active: stats.gps.pps_active,
+            pps_count: stats.gps.pps_count,
+            pps_offset_ns: stats.gps.pps_offset.map(|offset| (offset * 1e9).round() as i64),
+            requests_total: stats.ntp.requests_total,
+            requests_per_second: stats.ntp.requests_per_second,
+            uptime_secs,
+        }
+    }
+
+    /// Encode l'enregistrement au format décrit dans la documentation du module
+    pub fn encode(&self) -> [u8; STATS_BIN_SIZE] {
+        let mut flags = 0;
+        if self.gps_connected {
+            flags |= FLAG_GPS_CONNECTED;
+        }
+        if self.pps_active {
+            flags |= FLAG_PPS_ACTIVE;
+        }
+        if self.pps_offset_ns.is_some() {
+            flags |= FLAG_PPS_OFFSET;
+        }
+
+        let mut bytes = [0u8; STATS_BIN_SIZE];
+        bytes[0..4].copy_from_slice(&MAGIC);
+        bytes[4..6].copy_from_slice(&VERSION.to_le_bytes());
+        bytes[6] = self.stratum;
+        bytes[7] = self.satellites;
+        bytes[8] = flags;
+        bytes[12..20].copy_from_slice(&self.pps_count.to_le_bytes());
+        bytes[20..28].copy_from_slice(&self.pps_offset_ns.unwrap_or(0).to_le_bytes());
+        bytes[28..36].copy_from_slice(&self.requests_total.to_le_bytes());
+        bytes[36..40].copy_from_slice(&self.requests_per_second.to_le_bytes());
+        bytes[40..48].copy_from_slice(&self.uptime_secs.to_le_bytes());
+        bytes
+    }
+
+    /// Décode un enregistrement (`None` si la magique, la version ou la taille diffèrent)
+    #[allow(dead_code)]
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != STATS_BIN_SIZE || bytes[0..4] != MAGIC {
+            return None;
+        }
+        if u16::from_le_bytes(bytes[4..6].try_into().ok()?) != VERSION {
+            return None;
+        }
+
+        let u64_at = |offset: usize| bytes[offset..offset + 8].try_into().ok().map(u64::from_le_bytes);
+        let flags = bytes[8];
+
+        Some(BinaryStats {
+            stratum: bytes[6],
+            satellites: bytes[7],
+            gps_connected: flags & FLAG_GPS_CONNECTED != 0,
+            pps_active: flags & FLAG_PPS_ACTIVE != 0,
+            pps_count: u64_at(12)?,
+            pps_offset_ns: (flags & FLAG_PPS_OFFSET != 0).then(|| u64_at(20).map(|raw| raw as i64)).flatten(),
+            requests_total: u64_at(28)?,
+            requests_per_second: u32::from_le_bytes(bytes[36..40].try_into().ok()?),
+            uptime_secs: u64_at(40)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::StatsManager;
+
+    #[test]
+    fn test_binary_stats_round_trip() {
+        let mut stats = StatsManager::new().clone_arc().read().unwrap().clone();
+        stats.clock.stratum = 1;
+        stats.gps.satellites_used = 11;
+        stats.gps.connected = true;
+        stats.gps.pps_active = true;
+        stats.gps.pps_count = 86_400;
+        stats.gps.pps_offset = Some(-0.000_001_234);
+        stats.ntp.requests_total = 5_000_000_000;
+        stats.ntp.requests_per_second = 1_250;
+
+        let record = BinaryStats::from_stats(&stats, 3_600);
+        let bytes = record.encode();
+
+        // Disposition fixe little-endian
+        assert_eq!(&bytes[0..4], b"PNDL");
+        assert_eq!(bytes[4..6], [1, 0]);
+        assert_eq!((bytes[6], bytes[7], bytes[8]), (1, 11, 0b111));
+        assert_eq!(bytes[12..20], 86_400u64.to_le_bytes());
+        assert_eq!(bytes[20..28], (-1_234i64).to_le_bytes());
+        assert_eq!(bytes[40..48], 3_600u64.to_le_bytes());
+
+        let decoded = BinaryStats::decode(&bytes).unwrap();
+        assert_eq!(decoded, record);
+        assert_eq!(decoded.pps_offset_ns, Some(-1_234));
+        assert_eq!(decoded.requests_total, 5_000_000_000);
+
+        // Sans offset PPS : drapeau à zéro, champ ignoré au décodage
+        stats.gps.pps_offset = None;
+        let bytes = BinaryStats::from_stats(&stats, 0).encode();
+        assert_eq!(bytes[8] & FLAG_PPS_OFFSET, 0);
+        assert_eq!(BinaryStats::decode(&bytes).unwrap().pps_offset_ns, None);
+
+        // Enregistrement tronqué ou d'une autre version : rejeté
+        assert_eq!(BinaryStats::decode(&bytes[..40]), None);
+        let mut other_version = bytes;
+        other_version[4] = 2;
+        assert_eq!(BinaryStats::decode(&other_version), None);
+    }
+}
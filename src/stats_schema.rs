@@ -0,0 +1,206 @@
+/*!
+Schéma des statistiques (`GET /api/stats/schema`)
+
+Décrit chaque champ de `/api/stats` (chemin, type JSON, unité) pour que les
+outils de supervision génèrent leurs jauges sans connaître le serveur :
+
+```text
+"gps.pps_offset": { "type": "number", "unit": "seconds", "nullable": true }
+"satellites[].snr": { "type": "integer", "unit": "dB-Hz" }
+```
+
+Les chemins sont pointés, `[]` désignant les éléments d'un tableau. La table
+est maintenue à la main à côté de `stats::ServerStats` : un test vérifie que
+chaque champ sérialisé y figure.
+*/
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Version du schéma, incrémentée à chaque renommage ou suppression de champ
+const SCHEMA_VERSION: u32 = 1;
+
+/// Description d'un champ des statistiques
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FieldSchema {
+    /// Type JSON : "integer", "number", "string", "boolean", "object" ou "array"
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+
+    /// Unité de la valeur (absente pour les objets, tableaux et textes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<&'static str>,
+
+    /// Valeur `null` possible (champ `Option`)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub nullable: bool,
+}
+
+/// Schéma complet des statistiques
+#[derive(Debug, Serialize)]
+pub struct StatsSchema {
+    pub version: u32,
+    pub fields: BTreeMap<&'static str, FieldSchema>,
+}
+
+const fn field(kind: &'static str, unit: Option<&'static str>, nullable: bool) -> FieldSchema {
+    FieldSchema { kind, unit, nullable }
+}
+
+const OBJECT: FieldSchema = field("object", None, false);
+const ARRAY: FieldSchema = field("array", None, false);
+const TEXT: FieldSchema = field("string", None, false);
+const OPTIONAL_TEXT: FieldSchema = field("string", None, true);
+const FLAG: FieldSchema = field("boolean", None, false);
+const COUNT: FieldSchema = field("integer", Some("count"), false);
+
+/// Champs de `stats::ServerStats`, dans l'ordre de la structure
+const FIELDS: &[(&str, FieldSchema)] = &[
+    ("gps", OBJECT),
+    ("gps.state", TEXT),
+    ("gps.connected", FLAG),
+    ("gps.acquiring", FLAG),
+    ("gps.satellites_used", COUNT),
+    ("gps.satellites_in_view", COUNT),
+    ("gps.signal_quality", field("integer", Some("0-10"), false)),
+    ("gps.last_sync_secs", field("integer", Some("seconds"), true)),
+    ("gps.nmea_sentences", COUNT),
+    ("gps.pps_active", FLAG),
+    ("gps.pps_count", COUNT),
+    ("gps.last_rx_ms", field("integer", Some("milliseconds"), false)),
+    ("gps.pps_offset", field("number", Some("seconds"), true)),
+    ("gps.watchdog_restarts", COUNT),
+    ("gps.malformed_satellites", COUNT),
+    ("gps.detected_baud_rate", field("integer", Some("baud"), true)),
+    ("gps.time_accuracy_ns", field("integer", Some("nanoseconds"), true)),
+    ("gps.speed_kmh", field("number", Some("km/h"), true)),
+    ("gps.course_deg", field("number", Some("degrees"), true)),
+    ("gps.latitude_deg", field("number", Some("degrees"), true)),
+    ("gps.longitude_deg", field("number", Some("degrees"), true)),
+    ("ntp", OBJECT),
+    ("ntp.requests_total", COUNT),
+    ("ntp.requests_per_second", field("integer", Some("requests/s"), false)),
+    ("ntp.requests_per_second_smoothed", field("number", Some("requests/s"), false)),
+    ("ntp.rejected_per_second", field("integer", Some("requests/s"), false)),
+    ("ntp.duplicate_requests", COUNT),
+    ("ntp.poll_anomalies", COUNT),
+    ("ntp.source_port_123_rejected", COUNT),
+    ("ntp.auth_failures", COUNT),
+    ("ntp.icmp_errors", COUNT),
+    ("ntp.symmetric_peers", COUNT),
+    ("ntp.active_clients", COUNT),
+    ("ntp.last_tx_ms", field("integer", Some("milliseconds"), false)),
+    ("ntp.tx_software_latency_ns", field("integer", Some("nanoseconds"), true)),
+    ("ntp.processing_latency", OBJECT),
+    ("ntp.processing_latency.samples", COUNT),
+    ("ntp.processing_latency.last_us", field("number", Some("microseconds"), false)),
+    ("ntp.processing_latency.mean_us", field("number", Some("microseconds"), false)),
+    ("ntp.processing_latency.max_us", field("number", Some("microseconds"), false)),
+    ("ntp.processing_latency.jitter_us", field("number", Some("microseconds"), false)),
+    ("ntp.validation_errors", OBJECT),
+    ("ntp.validation_errors.invalid_version", COUNT),
+    ("ntp.validation_errors.invalid_mode", COUNT),
+    ("ntp.validation_errors.zero_transmit_timestamp", COUNT),
+    ("ntp.validation_errors.invalid_stratum", COUNT),
+    ("ntp.validation_errors.malformed", COUNT),
+    ("clock", OBJECT),
+    ("clock.stratum", field("integer", None, false)),
+    ("clock.reference_id", TEXT),
+    ("clock.precision", field("integer", Some("log2 seconds"), false)),
+    ("clock.current_timestamp", field("integer", Some("NTP seconds"), false)),
+    ("clock.current_fraction_ns", field("integer", Some("nanoseconds"), false)),
+    ("clock.leap_offset_seconds", field("integer", Some("seconds"), true)),
+    ("clock.next_leap_second", OPTIONAL_TEXT),
+    ("clock.active_source", OPTIONAL_TEXT),
+    ("clock.divergence_alarm", FLAG),
+    ("satellites", ARRAY),
+    ("satellites[].prn", field("integer", None, false)),
+    ("satellites[].elevation", field("integer", Some("degrees"), false)),
+    ("satellites[].azimuth", field("integer", Some("degrees"), false)),
+    ("satellites[].snr", field("integer", Some("dB-Hz"), false)),
+    ("satellites[].constellation", TEXT),
+    ("subsystem_panics", COUNT),
+    ("last_panic", OPTIONAL_TEXT),
+    ("threads", ARRAY),
+    ("threads[].name", TEXT),
+    ("threads[].state", TEXT),
+    ("threads[].last_progress_age_ms", field("integer", Some("milliseconds"), true)),
+    ("threads[].stale_after_ms", field("integer", Some("milliseconds"), false)),
+];
+
+/// Schéma des statistiques exposé par l'API
+pub fn stats_schema() -> StatsSchema {
+    StatsSchema {
+        version: SCHEMA_VERSION,
+        fields: FIELDS.iter().copied().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heartbeat::{ThreadHealth, ThreadState};
+    use crate::stats::{SatelliteInfo, StatsManager};
+    use serde_json::Value;
+
+    /// Chemins de tous les champs d'une valeur sérialisée
+    fn collect_paths(value: &Value, prefix: &str, paths: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                    paths.push(path.clone());
+                    collect_paths(child, &path, paths);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    collect_paths(item, &format!("{}[]", prefix), paths);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_stats_schema_covers_fields() {
+        let schema = stats_schema();
+        for (path, kind) in [
+            ("gps", "object"),
+            ("ntp", "object"),
+            ("clock", "object"),
+            ("satellites", "array"),
+            ("subsystem_panics", "integer"),
+            ("last_panic", "string"),
+            ("threads", "array"),
+        ] {
+            assert_eq!(schema.fields[path].kind, kind, "{}", path);
+        }
+        assert_eq!(schema.fields["gps.pps_offset"].unit, Some("seconds"));
+        assert!(schema.fields["gps.pps_offset"].nullable);
+        assert_eq!(schema.fields["ntp.last_tx_ms"].unit, Some("milliseconds"));
+
+        // Tout champ sérialisé (tableaux non vides) est décrit
+        let mut stats = StatsManager::new().get();
+        stats.satellites.push(SatelliteInfo {
+            prn: 12,
+            elevation: 40,
+            azimuth: 83,
+            snr: 46,
+            constellation: "GPS".to_string(),
+        });
+        stats.threads.push(ThreadHealth {
+            name: "gps_reader".to_string(),
+            state: ThreadState::Running,
+            last_progress_age_ms: Some(10),
+            stale_after_ms: 5_000,
+        });
+
+        let mut paths = Vec::new();
+        collect_paths(&serde_json::to_value(&stats).unwrap(), "", &mut paths);
+        for path in &paths {
+            assert!(schema.fields.contains_key(path.as_str()), "{} missing from schema", path);
+        }
+        assert_eq!(paths.len(), schema.fields.len());
+    }
+}
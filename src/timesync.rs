@@ -0,0 +1,91 @@
+/*!
+Calculs de synchronisation NTP du point de vue client (RFC 5905)
+
+À partir des quatre timestamps d'un échange :
+- T1 : émission de la requête (horloge client)
+- T2 : réception de la requête (horloge serveur)
+- T3 : émission de la réponse (horloge serveur)
+- T4 : réception de la réponse (horloge client)
+
+on obtient l'offset `((T2 - T1) + (T3 - T4)) / 2` et le délai aller-retour
+`(T4 - T1) - (T3 - T2)`.
+
+Les différences sont calculées en virgule fixe 32.32 modulo 2^64, ce qui
+reste correct au passage d'ère NTP (2036) tant que les timestamps d'un même
+échange sont à moins de 68 ans les uns des autres.
+*/
+
+use crate::packet::NtpTimestamp;
+
+/// Nombre d'unités de fraction NTP par seconde (2^32)
+const FRACTION_PER_SECOND: f64 = 4_294_967_296.0;
+
+/// Différence signée `a - b` en unités 32.32
+fn diff(a: NtpTimestamp, b: NtpTimestamp) -> i128 {
+    a.0.wrapping_sub(b.0) as i64 as i128
+}
+
+/// Calcule l'offset et le délai d'un échange NTP, en secondes
+///
+/// Retourne `(offset, delay)` : l'offset est positif si l'horloge serveur
+/// est en avance sur l'horloge client.
+#[allow(dead_code)]
+pub fn ntp_offset_delay(
+    t1: NtpTimestamp,
+    t2: NtpTimestamp,
+    t3: NtpTimestamp,
+    t4: NtpTimestamp,
+) -> (f64, f64) {
+    let offset = (diff(t2, t1) + diff(t3, t4)) as f64 / 2.0 / FRACTION_PER_SECOND;
+    let delay = (diff(t4, t1) - diff(t3, t2)) as f64 / FRACTION_PER_SECOND;
+
+    (offset, delay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(seconds: u64, nanos: u32) -> NtpTimestamp {
+        NtpTimestamp::from_seconds_and_nanos(seconds, nanos)
+    }
+
+    #[test]
+    fn test_symmetric_exchange() {
+        // Serveur en avance de 0.5 s, 10 ms de trajet dans chaque sens
+        let t1 = ts(3_900_000_000, 0);
+        let t2 = ts(3_900_000_000, 510_000_000);
+        let t3 = ts(3_900_000_000, 520_000_000);
+        let t4 = ts(3_900_000_000, 30_000_000);
+
+        let (offset, delay) = ntp_offset_delay(t1, t2, t3, t4);
+        assert!((offset - 0.5).abs() < 1e-6, "offset = {}", offset);
+        assert!((delay - 0.020).abs() < 1e-6, "delay = {}", delay);
+    }
+
+    #[test]
+    fn test_straddling_second_boundary() {
+        // Client en avance de 2 ms, l'échange traverse une frontière de seconde
+        let t1 = ts(3_900_000_000, 999_000_000);
+        let t2 = ts(3_900_000_000, 999_000_000);
+        let t3 = ts(3_900_000_001, 500_000);
+        let t4 = ts(3_900_000_001, 4_500_000);
+
+        let (offset, delay) = ntp_offset_delay(t1, t2, t3, t4);
+        assert!((offset - (-0.002)).abs() < 1e-6, "offset = {}", offset);
+        assert!((delay - 0.004).abs() < 1e-6, "delay = {}", delay);
+    }
+
+    #[test]
+    fn test_era_rollover() {
+        // Le client est juste avant la fin de l'ère 0, le serveur juste après
+        let t1 = ts(u32::MAX as u64, 900_000_000);
+        let t2 = ts(0, 100_000_000);
+        let t3 = ts(0, 100_000_000);
+        let t4 = ts(u32::MAX as u64, 900_000_000);
+
+        let (offset, delay) = ntp_offset_delay(t1, t2, t3, t4);
+        assert!((offset - 0.2).abs() < 1e-6, "offset = {}", offset);
+        assert!(delay.abs() < 1e-6, "delay = {}", delay);
+    }
+}
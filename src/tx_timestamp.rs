@@ -0,0 +1,135 @@
+/*!
+Horodatage d'émission par le noyau (Linux `SO_TIMESTAMPING`)
+
+Le transmit timestamp (T3) est lu juste avant `send_to`, mais le paquet ne
+quitte la pile réseau qu'un peu plus tard. Avec `SOF_TIMESTAMPING_TX_SOFTWARE`,
+le noyau horodate chaque datagramme au moment où il est remis au pilote et
+renvoie cet instant sur la file d'erreurs du socket (`MSG_ERRQUEUE`).
+
+Avec `SOF_TIMESTAMPING_OPT_ID`, chaque horodatage porte le rang du datagramme
+émis sur le socket (0, 1, 2...) : la file est vidée sans attendre après chaque
+réponse (`socket_options::read_error_queue`, qui lit aussi les erreurs ICMP),
+et un horodatage arrivé en retard est rapproché de son propre envoi plutôt que
+du suivant.
+
+L'écart entre l'horloge système lue avant l'envoi et cet instant mesure la
+latence d'émission logicielle, exposée dans `ntp.tx_software_latency_ns`.
+L'horodatage noyau est en temps système (`CLOCK_REALTIME`), pas en temps GPS.
+
+Disponible uniquement sous Linux ; ailleurs, l'activation échoue avec
+`ErrorKind::Unsupported`.
+*/
+
+use std::io;
+use std::net::UdpSocket;
+use std::time::Duration;
+
+/// Horodatage d'émission d'un datagramme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxTimestamp {
+    /// Rang du datagramme sur le socket depuis l'activation (`OPT_ID`)
+    pub id: u32,
+
+    /// Temps système de l'émission (depuis l'epoch Unix)
+    pub time: Duration,
+}
+
+/// Active l'horodatage d'émission logiciel, numéroté, sur le socket
+pub fn enable(socket: &UdpSocket) -> io::Result<()> {
+    imp::enable(socket)
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::io;
+    use std::mem::size_of;
+    use std::net::UdpSocket;
+    use std::os::fd::AsRawFd;
+
+    pub fn enable(socket: &UdpSocket) -> io::Result<()> {
+        let flags: libc::c_uint = libc::SOF_TIMESTAMPING_TX_SOFTWARE
+            | libc::SOF_TIMESTAMPING_SOFTWARE
+            | libc::SOF_TIMESTAMPING_OPT_ID
+            | libc::SOF_TIMESTAMPING_OPT_TSONLY;
+
+        // SAFETY: pointeur et taille désignent `flags`, valide pendant l'appel
+        let result = unsafe {
+            libc::setsockopt(
+                socket.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_TIMESTAMPING,
+                &flags as *const libc::c_uint as *const libc::c_void,
+                size_of::<libc::c_uint>() as libc::socklen_t,
+            )
+        };
+
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use std::io;
+    use std::net::UdpSocket;
+
+    pub fn enable(_socket: &UdpSocket) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "TX timestamping is only available on Linux",
+        ))
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::socket_options::{read_error_queue, ErrorQueueMessage};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// Horodatages d'émission en attente dans la file d'erreurs
+    fn read_pending(socket: &UdpSocket) -> Vec<TxTimestamp> {
+        read_error_queue(socket)
+            .unwrap()
+            .into_iter()
+            .filter_map(|message| match message {
+                ErrorQueueMessage::TxTimestamp(timestamp) => Some(timestamp),
+                ErrorQueueMessage::Icmp(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    #[ignore = "requires kernel SO_TIMESTAMPING support (absent from some sandboxes)"]
+    fn test_tx_timestamp_after_send() {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        enable(&socket).unwrap();
+
+        let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        socket.send_to(&[0u8; 48], socket.local_addr().unwrap()).unwrap();
+        socket.send_to(&[0u8; 48], socket.local_addr().unwrap()).unwrap();
+
+        let mut timestamps = Vec::new();
+        for _ in 0..100 {
+            timestamps.extend(read_pending(&socket));
+            if timestamps.len() == 2 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        let after = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+
+        // Un horodatage par datagramme, numérotés dans l'ordre d'émission, entre
+        // la lecture avant l'envoi et la lecture de la file d'erreurs
+        assert_eq!(timestamps.iter().map(|ts| ts.id).collect::<Vec<_>>(), vec![0, 1]);
+        for ts in &timestamps {
+            assert!(ts.time >= before && ts.time <= after, "{:?} <= {:?} <= {:?}", before, ts.time, after);
+        }
+
+        // File d'erreurs vidée : plus d'horodatage en attente
+        assert!(read_pending(&socket).is_empty());
+    }
+}
@@ -6,31 +6,248 @@ Fournit :
 - API REST pour les statistiques
 - WebSocket pour mises à jour temps-réel
 - Indicateurs GPS/PPS/USB RX/TX
+- Compression gzip/deflate et ETag sur l'API de statistiques
+- Favicon et fichiers statiques (CSS/JS) embarqués dans le binaire
+- Export CSV des satellites en vue (diagnostic d'antenne)
+- Métriques au format texte Prometheus (/metrics)
+- Fuseau horaire d'affichage optionnel (dashboard et /api/time uniquement :
+  le protocole NTP sert toujours l'UTC)
+- Démarrage optionnellement différé jusqu'à la première synchronisation
+- Lecture et ajustement à chaud du rate limit (/api/ratelimit, protégé par jeton)
+- Rapport de suivi de l'horloge à la manière de `chronyc tracking` (/api/tracking)
 */
 
-use crate::clock::ClockSource;
-use crate::stats::ServerStats;
+use crate::clock::{
+    root_dispersion_ns, ClockSource, DisciplineState, HistogramSnapshot, ManualClock, OffsetHistory, OffsetSample,
+};
+use crate::config_reload::ConfigReloader;
+use crate::heartbeat;
+use crate::packet::{NtpTimestamp, NTP_UNIX_OFFSET};
+use crate::packet_decode::{ntp_timestamp_to_rfc3339, DecodeReport, DecodeRequest, RequestPolicy};
+use crate::panic_guard::run_guarded;
+use crate::security::RateLimiter;
+use crate::snr_log;
+use crate::stats::{SatelliteInfo, ServerStats};
+use crate::stats_bin::BinaryStats;
+use crate::stats_schema::{stats_schema, StatsSchema};
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        State,
+        Path, State,
     },
-    response::Html,
-    routing::get,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{Html, IntoResponse, Response},
+    routing::{get, post, put},
     Json, Router,
 };
-use serde::Serialize;
+use chrono::{DateTime, FixedOffset, Offset, SecondsFormat, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::time::{sleep, Duration};
-use tracing::{error, info};
+use tokio::sync::broadcast;
+use tokio::time::Duration;
+use tower_http::compression::CompressionLayer;
+use tracing::{debug, error, info, warn};
+
+/// Page d'accueil : dashboard complet si `web/` était présent au build (voir build.rs),
+/// sinon dashboard minimal intégré
+#[cfg(embedded_dashboard)]
+const INDEX_HTML: &str = include_str!("../web/index.html");
+#[cfg(not(embedded_dashboard))]
+const INDEX_HTML: &str = FALLBACK_INDEX_HTML;
+
+/// Favicon : (type MIME, contenu)
+#[cfg(embedded_dashboard)]
+const FAVICON: (&str, &[u8]) = ("image/x-icon", include_bytes!("../web/favicon.ico"));
+#[cfg(not(embedded_dashboard))]
+const FAVICON: (&str, &[u8]) = ("image/svg+xml", FALLBACK_FAVICON_SVG.as_bytes());
+
+/// Fichiers statiques embarqués, servis sous /static/ : (nom, type MIME, contenu)
+#[cfg(embedded_dashboard)]
+const STATIC_ASSETS: &[(&str, &str, &[u8])] = &[
+    ("style.css", "text/css; charset=utf-8", include_bytes!("../web/static/style.css")),
+    ("app.js", "text/javascript; charset=utf-8", include_bytes!("../web/static/app.js")),
+];
+#[cfg(not(embedded_dashboard))]
+const STATIC_ASSETS: &[(&str, &str, &[u8])] = &[];
+
+/// Dashboard minimal, toujours compilé : utilisé quand `web/` est absent au build
+#[cfg_attr(embedded_dashboard, allow(dead_code))]
+const FALLBACK_INDEX_HTML: &str = r#"<!DOCTYPE html>
+<html lang="fr">
+<head>
+    <meta charset="UTF-8">
+    <title>Pendulum - Serveur NTP</title>
+</head>
+<body style="font-family: monospace; background: #333; color: #fff; padding: 20px;">
+    <h1>Pendulum Serveur NTP</h1>
+    <p>Dashboard minimal (fichiers web/ absents lors de la compilation)</p>
+    <pre id="stats">Chargement...</pre>
+    <script>
+        async function refresh() {
+            try {
+                const response = await fetch('/api/stats');
+                const stats = await response.json();
+                document.getElementById('stats').textContent = JSON.stringify(stats, null, 2);
+            } catch (e) {
+                document.getElementById('stats').textContent = 'Erreur : ' + e;
+            }
+        }
+        refresh();
+        setInterval(refresh, 1000);
+    </script>
+</body>
+</html>
+"#;
+
+/// Favicon minimal du dashboard intégré
+#[cfg_attr(embedded_dashboard, allow(dead_code))]
+const FALLBACK_FAVICON_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><circle cx="8" cy="8" r="7.5" fill="#333"/><circle cx="10" cy="10.5" r="2.2" fill="#4caf50"/></svg>"##;
+
+/// Durée de cache des fichiers statiques (non versionnés : durée courte)
+const STATIC_CACHE_CONTROL: &str = "public, max-age=3600";
+
+/// Fuseau horaire d'affichage du dashboard (n'affecte jamais les timestamps NTP)
+#[derive(Debug, Clone, Copy)]
+pub enum DisplayTimezone {
+    /// Décalage fixe (ex: "+02:00")
+    Fixed(FixedOffset),
+
+    /// Fuseau IANA, heure d'été comprise (ex: "Europe/Paris")
+    Named(Tz),
+}
+
+impl DisplayTimezone {
+    /// Parse un nom IANA ou un décalage fixe
+    pub fn parse(s: &str) -> anyhow::Result<Self> {
+        if let Ok(tz) = s.parse::<Tz>() {
+            return Ok(DisplayTimezone::Named(tz));
+        }
+
+        s.parse::<FixedOffset>().map(DisplayTimezone::Fixed).map_err(|_| {
+            anyhow::anyhow!(
+                "Invalid display timezone '{}': expected an IANA name (ex: Europe/Paris) or a fixed offset (ex: +02:00)",
+                s
+            )
+        })
+    }
+
+    /// Nom affiché du fuseau
+    fn name(&self) -> String {
+        match self {
+            DisplayTimezone::Fixed(offset) => offset.to_string(),
+            DisplayTimezone::Named(tz) => tz.name().to_string(),
+        }
+    }
+
+    /// Convertit un instant UTC en heure locale de ce fuseau
+    fn local_time(&self, utc: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self {
+            DisplayTimezone::Fixed(offset) => utc.with_timezone(offset),
+            DisplayTimezone::Named(tz) => {
+                let local = utc.with_timezone(tz);
+                local.with_timezone(&local.offset().fix())
+            }
+        }
+    }
+}
 
 /// État partagé du serveur web
 #[derive(Clone)]
 pub struct WebServerState {
     stats: Arc<std::sync::RwLock<ServerStats>>,
     clock: Arc<dyn ClockSource>,
+    offset_history: Option<Arc<OffsetHistory>>,
+    display_timezone: Option<DisplayTimezone>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    admin_token: Option<String>,
+
+    /// Horloge réglable par PUT /api/clock/set (`clock.source = "manual"`)
+    manual_clock: Option<Arc<ManualClock>>,
+
+    /// Rechargement du fichier de configuration par POST /api/config/reload
+    config_reloader: Option<Arc<ConfigReloader>>,
+
+    /// Options d'acceptation des requêtes du serveur NTP (POST /api/decode)
+    request_policy: Arc<RequestPolicy>,
+
+    /// Démarrage du serveur (uptime de /api/stats.bin)
+    started_at: std::time::Instant,
+
+    /// Trames temps-réel diffusées aux clients WebSocket (JSON sérialisé une fois par tick)
+    realtime: broadcast::Sender<Arc<str>>,
+
+    /// Retards consécutifs avant déconnexion d'un client WebSocket (0 = jamais)
+    ws_max_consecutive_lags: u32,
+
+    /// Connexions WebSocket ouvertes et plafond
+    ws_connections: WsConnections,
+
+    /// Ajoute `server_health` à /api/time et aux trames WebSocket
+    server_health: bool,
+
+    /// Masque d'élévation de /api/skyplot (degrés)
+    skyplot_elevation_mask_deg: u8,
+
+    /// Root delay fixe annoncé par le serveur NTP (`server.static_root_delay_us`)
+    static_root_delay_us: u32,
+}
+
+/// Intervalle entre deux trames temps-réel (20 FPS)
+const REALTIME_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Trames conservées par défaut pour les clients WebSocket lents
+const DEFAULT_WS_CHANNEL_CAPACITY: usize = 16;
+
+/// Retards consécutifs par défaut avant déconnexion d'un client WebSocket
+const DEFAULT_WS_MAX_CONSECUTIVE_LAGS: u32 = 5;
+
+/// Connexions WebSocket simultanées par défaut (0 = illimité)
+const DEFAULT_MAX_WS_CONNECTIONS: usize = 100;
+
+/// Compteur des connexions WebSocket ouvertes, plafonné
+#[derive(Clone)]
+struct WsConnections {
+    active: Arc<AtomicUsize>,
+
+    /// 0 = illimité
+    max: usize,
+}
+
+/// Place occupée par une connexion WebSocket, libérée à la fermeture
+struct WsConnectionSlot(Arc<AtomicUsize>);
+
+impl WsConnections {
+    fn new(max: usize) -> Self {
+        WsConnections {
+            active: Arc::new(AtomicUsize::new(0)),
+            max,
+        }
+    }
+
+    /// Réserve une place, `None` si le plafond est atteint
+    fn try_acquire(&self) -> Option<WsConnectionSlot> {
+        self.active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                (self.max == 0 || active < self.max).then_some(active + 1)
+            })
+            .ok()
+            .map(|_| WsConnectionSlot(Arc::clone(&self.active)))
+    }
+}
+
+impl Drop for WsConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
 }
 
+/// Masque d'élévation par défaut de /api/skyplot (degrés)
+const DEFAULT_SKYPLOT_ELEVATION_MASK_DEG: u8 = 10;
+
 /// Informations temps-réel pour WebSocket
 #[derive(Debug, Clone, Serialize)]
 struct RealtimeData {
@@ -51,12 +268,106 @@ struct RealtimeData {
 
     /// Timestamp Unix (pour JavaScript Date)
     unix_timestamp_ms: u64,
+
+    /// Timestamp Unix en nanosecondes (résolution complète de l'horloge)
+    /// Dépasse la précision des nombres JavaScript : côté navigateur, utiliser `nanos`
+    unix_timestamp_ns: u64,
+
+    /// Heure locale ISO 8601 dans le fuseau d'affichage (UTC si non configuré)
+    local_time_iso: String,
+
+    /// Décalage du fuseau d'affichage par rapport à l'UTC (secondes)
+    utc_offset_seconds: i32,
+
+    /// Fuseau d'affichage configuré (absent = heure locale du navigateur)
+    display_timezone: Option<String>,
+
+    /// Santé du serveur NTP (`webserver.realtime_server_health`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    server_health: Option<ServerHealth>,
+}
+
+/// Santé instantanée du serveur NTP pour un panneau d'exploitation
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ServerHealth {
+    requests_per_second: u32,
+    rejected_per_second: u32,
+    active_clients: usize,
+
+    /// Latence de traitement T3 - T2 (microsecondes)
+    processing_latency_mean_us: f64,
+    processing_latency_max_us: f64,
+    processing_jitter_us: f64,
+
+    /// Contention du verrou des statistiques (`webserver.stats_lock_metrics`) :
+    /// acquisitions contendues et attente cumulée (nanosecondes)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats_lock_contended: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats_lock_wait_ns: Option<u64>,
+}
+
+impl ServerHealth {
+    fn from_stats(stats: &ServerStats) -> Self {
+        let lock = crate::stats::STATS_LOCK_CONTENTION.snapshot();
+        ServerHealth {
+            requests_per_second: stats.ntp.requests_per_second,
+            rejected_per_second: stats.ntp.rejected_per_second,
+            active_clients: stats.ntp.active_clients,
+            processing_latency_mean_us: stats.ntp.processing_latency.mean_us,
+            processing_latency_max_us: stats.ntp.processing_latency.max_us,
+            processing_jitter_us: stats.ntp.processing_latency.jitter_us,
+            stats_lock_contended: lock.map(|(contended, _)| contended),
+            stats_lock_wait_ns: lock.map(|(_, wait_ns)| wait_ns),
+        }
+    }
 }
 
 pub struct WebServer {
     bind_addr: String,
     stats: Arc<std::sync::RwLock<ServerStats>>,
     clock: Arc<dyn ClockSource>,
+    offset_history: Option<Arc<OffsetHistory>>,
+    display_timezone: Option<DisplayTimezone>,
+    start_after_sync: Option<std::time::Duration>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    admin_token: Option<String>,
+    manual_clock: Option<Arc<ManualClock>>,
+    config_reloader: Option<Arc<ConfigReloader>>,
+    request_policy: Arc<RequestPolicy>,
+    started_at: std::time::Instant,
+    ws_channel_capacity: usize,
+    ws_max_consecutive_lags: u32,
+    max_ws_connections: usize,
+    server_health: bool,
+    skyplot_elevation_mask_deg: u8,
+    static_root_delay_us: u32,
+}
+
+/// Intervalle de vérification de la synchronisation avant démarrage
+const SYNC_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Attend que l'horloge soit synchronisée (stratum < 16 et temps absolu disponible)
+///
+/// Retourne true si la synchronisation est atteinte, false si `timeout` expire avant.
+pub fn wait_for_sync(
+    clock: &dyn ClockSource,
+    timeout: std::time::Duration,
+    poll_interval: std::time::Duration,
+) -> bool {
+    let deadline = std::time::Instant::now() + timeout;
+
+    loop {
+        if clock.stratum() < 16 && clock.has_absolute_time() {
+            return true;
+        }
+
+        let now = std::time::Instant::now();
+        if now >= deadline {
+            return false;
+        }
+        std::thread::sleep(poll_interval.min(deadline - now));
+    }
 }
 
 impl WebServer {
@@ -69,36 +380,165 @@ impl WebServer {
             bind_addr,
             stats,
             clock,
+            offset_history: None,
+            display_timezone: None,
+            start_after_sync: None,
+            rate_limiter: None,
+            admin_token: None,
+            manual_clock: None,
+            config_reloader: None,
+            request_policy: Arc::default(),
+            started_at: std::time::Instant::now(),
+            ws_channel_capacity: DEFAULT_WS_CHANNEL_CAPACITY,
+            ws_max_consecutive_lags: DEFAULT_WS_MAX_CONSECUTIVE_LAGS,
+            max_ws_connections: DEFAULT_MAX_WS_CONNECTIONS,
+            server_health: false,
+            skyplot_elevation_mask_deg: DEFAULT_SKYPLOT_ELEVATION_MASK_DEG,
+            static_root_delay_us: 0,
         }
     }
 
+    /// Connexions WebSocket simultanées au-delà desquelles les nouvelles sont
+    /// refusées en 503 (0 = illimité)
+    pub fn with_max_ws_connections(mut self, max_ws_connections: usize) -> Self {
+        self.max_ws_connections = max_ws_connections;
+        self
+    }
+
+    /// Masque d'élévation de /api/skyplot (degrés au-dessus de l'horizon)
+    pub fn with_skyplot_elevation_mask(mut self, elevation_mask_deg: u8) -> Self {
+        self.skyplot_elevation_mask_deg = elevation_mask_deg;
+        self
+    }
+
+    /// Root delay fixe du serveur NTP, rapporté par /api/tracking
+    pub fn with_static_root_delay_us(mut self, static_root_delay_us: u32) -> Self {
+        self.static_root_delay_us = static_root_delay_us;
+        self
+    }
+
+    /// Ajoute la santé du serveur NTP (requêtes/s, rejets/s, clients actifs,
+    /// latence de traitement) à /api/time et aux trames WebSocket
+    pub fn with_realtime_server_health(mut self) -> Self {
+        self.server_health = true;
+        self
+    }
+
+    /// Trames conservées pour les clients WebSocket lents et retards consécutifs
+    /// tolérés avant déconnexion (0 = jamais)
+    pub fn with_ws_backpressure(mut self, channel_capacity: usize, max_consecutive_lags: u32) -> Self {
+        self.ws_channel_capacity = channel_capacity.max(1);
+        self.ws_max_consecutive_lags = max_consecutive_lags;
+        self
+    }
+
+    /// Expose le rate limiter du serveur NTP sur /api/ratelimit
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Expose le réglage de l'horloge manuelle sur PUT /api/clock/set
+    pub fn with_manual_clock(mut self, clock: Arc<ManualClock>) -> Self {
+        self.manual_clock = Some(clock);
+        self
+    }
+
+    /// Expose le rechargement de la configuration sur POST /api/config/reload
+    pub fn with_config_reloader(mut self, reloader: Arc<ConfigReloader>) -> Self {
+        self.config_reloader = Some(reloader);
+        self
+    }
+
+    /// Options du serveur NTP appliquées par POST /api/decode
+    pub fn with_request_policy(mut self, policy: RequestPolicy) -> Self {
+        self.request_policy = Arc::new(policy);
+        self
+    }
+
+    /// Jeton requis pour les points d'accès d'administration (/api/ratelimit)
+    pub fn with_admin_token(mut self, token: String) -> Self {
+        self.admin_token = Some(token);
+        self
+    }
+
+    /// N'ouvre le port qu'après la première synchronisation de l'horloge (ou `timeout`)
+    pub fn with_start_after_sync(mut self, timeout: std::time::Duration) -> Self {
+        self.start_after_sync = Some(timeout);
+        self
+    }
+
+    /// Affiche l'heure du dashboard et de /api/time dans ce fuseau
+    pub fn with_display_timezone(mut self, timezone: DisplayTimezone) -> Self {
+        self.display_timezone = Some(timezone);
+        self
+    }
+
+    /// Expose l'historique d'offset horloge/système sur /api/offset_history
+    pub fn with_offset_history(mut self, history: Arc<OffsetHistory>) -> Self {
+        self.offset_history = Some(history);
+        self
+    }
+
     /// Démarre le serveur web dans un thread Tokio séparé
     pub fn start(self) -> std::thread::JoinHandle<()> {
         info!("Starting web server on {}", self.bind_addr);
 
+        let stats = Arc::clone(&self.stats);
         std::thread::spawn(move || {
-            let runtime = tokio::runtime::Runtime::new().unwrap();
-            runtime.block_on(async move {
-                if let Err(e) = self.run().await {
-                    error!("Web server error: {:#}", e);
-                }
-            });
+            run_guarded("web_server", &stats, || self.serve_blocking());
         })
     }
 
+    /// Corps du thread du serveur web (attente de synchronisation puis runtime Tokio)
+    fn serve_blocking(self) {
+        if let Some(timeout) = self.start_after_sync {
+            info!("Web server waiting for first clock sync (timeout {:?})", timeout);
+            if wait_for_sync(self.clock.as_ref(), timeout, SYNC_POLL_INTERVAL) {
+                info!("Clock synchronized, starting web server");
+            } else {
+                warn!("Clock not synchronized after {:?}, starting web server anyway", timeout);
+            }
+        }
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async move {
+            if let Err(e) = self.run().await {
+                error!("Web server error: {:#}", e);
+            }
+        });
+    }
+
     async fn run(self) -> anyhow::Result<()> {
+        let (realtime, _) = broadcast::channel(self.ws_channel_capacity);
+        tokio::spawn(realtime_producer(
+            realtime.clone(),
+            Arc::clone(&self.stats),
+            Arc::clone(&self.clock),
+            self.display_timezone,
+            self.server_health,
+        ));
+
         let state = WebServerState {
             stats: self.stats,
             clock: self.clock,
+            offset_history: self.offset_history,
+            display_timezone: self.display_timezone,
+            rate_limiter: self.rate_limiter,
+            admin_token: self.admin_token,
+            manual_clock: self.manual_clock,
+            config_reloader: self.config_reloader,
+            request_policy: self.request_policy,
+            started_at: self.started_at,
+            realtime,
+            ws_max_consecutive_lags: self.ws_max_consecutive_lags,
+            ws_connections: WsConnections::new(self.max_ws_connections),
+            server_health: self.server_health,
+            skyplot_elevation_mask_deg: self.skyplot_elevation_mask_deg,
+            static_root_delay_us: self.static_root_delay_us,
         };
 
-        // Routes
-        let app = Router::new()
-            .route("/", get(index_handler))
-            .route("/api/stats", get(stats_handler))
-            .route("/api/time", get(time_handler))
-            .route("/ws", get(websocket_handler))
-            .with_state(state);
+        let app = build_router(state);
 
         // Bind et écoute
         let listener = tokio::net::TcpListener::bind(&self.bind_addr).await?;
@@ -110,85 +550,1662 @@ impl WebServer {
     }
 }
 
+/// Construit le routeur de l'interface web
+fn build_router(state: WebServerState) -> Router {
+    // Endpoints de polling et fichiers texte : compressés selon Accept-Encoding
+    // (le WebSocket n'est pas concerné)
+    let compressed = Router::new()
+        .route("/api/stats", get(stats_handler))
+        .route("/api/stats.bin", get(stats_bin_handler))
+        .route("/api/stats/schema", get(stats_schema_handler))
+        .route("/api/satellites.csv", get(satellites_csv_handler))
+        .route("/metrics", get(metrics_handler))
+        .route("/static/*path", get(static_handler))
+        .layer(CompressionLayer::new().gzip(true).deflate(true));
+
+    Router::new()
+        .route("/", get(index_handler))
+        .route("/favicon.ico", get(favicon_handler))
+        .route("/api/time", get(time_handler))
+        .route("/api/offset_history", get(offset_history_handler))
+        .route("/api/offset_summary", get(offset_summary_handler))
+        .route("/api/tracking", get(tracking_handler))
+        .route("/api/skyplot", get(skyplot_handler))
+        .route("/api/ratelimit", get(ratelimit_get_handler).put(ratelimit_put_handler))
+        .route("/api/clock/set", put(clock_set_handler))
+        .route("/api/config/reload", post(config_reload_handler))
+        .route("/api/decode", post(decode_handler))
+        .route("/ws", get(websocket_handler))
+        .merge(compressed)
+        .with_state(state)
+}
+
+/// Répond avec un corps JSON accompagné d'un ETag dérivé de `version`,
+/// ou 304 Not Modified si le client possède déjà cette version
+fn json_with_etag(headers: &HeaderMap, body: Vec<u8>, version: impl Hash) -> Response {
+    let mut hasher = DefaultHasher::new();
+    version.hash(&mut hasher);
+    let etag = format!("\"{:016x}\"", hasher.finish());
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"))
+        .unwrap_or(false);
+
+    let etag_value = HeaderValue::from_str(&etag).expect("ETag hexadécimal toujours valide");
+
+    if not_modified {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag_value)]).into_response();
+    }
+
+    (
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static("application/json")),
+            (header::ETAG, etag_value),
+        ],
+        body,
+    )
+        .into_response()
+}
+
 /// Page d'accueil avec dashboard
 async fn index_handler() -> Html<&'static str> {
-    Html(include_str!("../web/index.html"))
+    Html(INDEX_HTML)
+}
+
+/// Favicon du dashboard
+async fn favicon_handler() -> impl IntoResponse {
+    let (content_type, body) = FAVICON;
+    (
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CACHE_CONTROL, "public, max-age=86400"),
+        ],
+        body,
+    )
+}
+
+/// Fichiers statiques embarqués (CSS, JS)
+async fn static_handler(Path(path): Path<String>) -> Response {
+    match STATIC_ASSETS.iter().find(|(name, _, _)| *name == path) {
+        Some((_, content_type, body)) => (
+            [
+                (header::CONTENT_TYPE, *content_type),
+                (header::CACHE_CONTROL, STATIC_CACHE_CONTROL),
+            ],
+            *body,
+        )
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
 }
 
 /// API REST : Statistiques complètes
-async fn stats_handler(State(state): State<WebServerState>) -> Json<ServerStats> {
-    let stats = state.stats.read().unwrap().clone();
-    Json(stats)
+async fn stats_handler(State(state): State<WebServerState>, headers: HeaderMap) -> Response {
+    let mut stats = state.stats.read().unwrap().clone();
+
+    // L'ETag tient compte de l'état des threads mais pas de l'âge de leur
+    // dernier battement, qui change à chaque requête
+    let Ok(snapshot) = serde_json::to_vec(&stats) else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    stats.threads = heartbeat::report();
+    let thread_states: Vec<_> = stats.threads.iter().map(|thread| thread.state).collect();
+
+    match serde_json::to_vec(&stats) {
+        Ok(body) => json_with_etag(&headers, body, (snapshot, thread_states)),
+        Err(e) => {
+            error!("Failed to serialize stats: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
 }
 
-/// API REST : Temps actuel
-async fn time_handler(State(state): State<WebServerState>) -> Json<RealtimeData> {
-    let timestamp = state.clock.now();
+/// Statistiques clés au format binaire compact (voir `stats_bin`)
+async fn stats_bin_handler(State(state): State<WebServerState>) -> Response {
+    let uptime = state.started_at.elapsed().as_secs();
+    let record = BinaryStats::from_stats(&state.stats.read().unwrap(), uptime);
+
+    (
+        [(header::CONTENT_TYPE, "application/octet-stream")],
+        record.encode().to_vec(),
+    )
+        .into_response()
+}
+
+/// Schéma des statistiques : type et unité de chaque champ (voir `stats_schema`)
+async fn stats_schema_handler() -> Json<StatsSchema> {
+    Json(stats_schema())
+}
+
+/// État du rate limiter exposé par /api/ratelimit
+#[derive(Debug, Serialize)]
+struct RateLimitInfo {
+    max_requests_per_second: u32,
+    tracked_ips: usize,
+}
+
+/// Corps de PUT /api/ratelimit
+#[derive(Debug, Deserialize)]
+struct RateLimitUpdate {
+    max_requests_per_second: u32,
+}
+
+fn rate_limit_info(limiter: &RateLimiter) -> RateLimitInfo {
+    RateLimitInfo {
+        max_requests_per_second: limiter.max_requests_per_second(),
+        tracked_ips: limiter.stats().tracked_ips,
+    }
+}
+
+/// Vérifie le jeton d'administration (`Authorization: Bearer <jeton>`)
+/// 403 si aucun jeton n'est configuré, 401 si le jeton est absent ou faux
+fn check_admin_token(state: &WebServerState, headers: &HeaderMap) -> Result<(), StatusCode> {
+    let expected = state.admin_token.as_deref().ok_or(StatusCode::FORBIDDEN)?;
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or("");
+
+    // Comparaison en temps constant (pas de fuite de la longueur du préfixe commun)
+    let matches = provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0;
+
+    if matches {
+        Ok(())
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+/// API REST : Rate limit actuel (jeton d'administration requis, 404 si le rate
+/// limiting est désactivé)
+async fn ratelimit_get_handler(
+    State(state): State<WebServerState>,
+    headers: HeaderMap,
+) -> Result<Json<RateLimitInfo>, StatusCode> {
+    check_admin_token(&state, &headers)?;
+    let limiter = state.rate_limiter.as_ref().ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(rate_limit_info(limiter)))
+}
+
+/// API REST : Modification à chaud du rate limit par défaut (jeton d'administration requis)
+async fn ratelimit_put_handler(
+    State(state): State<WebServerState>,
+    headers: HeaderMap,
+    Json(update): Json<RateLimitUpdate>,
+) -> Result<Json<RateLimitInfo>, StatusCode> {
+    check_admin_token(&state, &headers)?;
+    let limiter = state.rate_limiter.as_ref().ok_or(StatusCode::NOT_FOUND)?;
+
+    if update.max_requests_per_second == 0 {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    info!(
+        "Rate limit changed via API: {} -> {} requests/s",
+        limiter.max_requests_per_second(),
+        update.max_requests_per_second
+    );
+    limiter.set_max_requests_per_second(update.max_requests_per_second);
+
+    Ok(Json(rate_limit_info(limiter)))
+}
+
+/// Corps de PUT /api/clock/set : temps de référence (RFC 3339, ex: "2026-10-16T08:00:00.250Z")
+#[derive(Debug, Deserialize)]
+struct ClockSetRequest {
+    time: String,
+}
+
+/// Convertit un instant RFC 3339 en timestamp NTP (`None` si illisible ou antérieur à 1970)
+fn ntp_timestamp_from_rfc3339(time: &str) -> Option<NtpTimestamp> {
+    let time = DateTime::parse_from_rfc3339(time).ok()?;
+    let unix_seconds = u64::try_from(time.timestamp()).ok()?;
+    Some(NtpTimestamp::from_seconds_and_nanos(
+        unix_seconds + NTP_UNIX_OFFSET,
+        time.timestamp_subsec_nanos(),
+    ))
+}
+
+/// API REST : Fixe le temps de l'horloge manuelle à l'instant de réception
+/// (jeton d'administration requis, 404 sans `clock.source = "manual"`)
+async fn clock_set_handler(
+    State(state): State<WebServerState>,
+    headers: HeaderMap,
+    Json(request): Json<ClockSetRequest>,
+) -> Result<StatusCode, StatusCode> {
+    check_admin_token(&state, &headers)?;
+    let clock = state.manual_clock.as_ref().ok_or(StatusCode::NOT_FOUND)?;
+    let time = ntp_timestamp_from_rfc3339(&request.time).ok_or(StatusCode::UNPROCESSABLE_ENTITY)?;
+
+    clock.set_reference(time);
+    info!("Manual clock reference set via API: {}", request.time);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Métriques au format texte Prometheus
+async fn metrics_handler(State(state): State<WebServerState>) -> impl IntoResponse {
     let stats = state.stats.read().unwrap().clone();
+    let mut body = render_metrics(&stats);
+    if let Some(histogram) = state.clock.pps_offset_histogram() {
+        push_histogram(
+            &mut body,
+            "pendulum_pps_offset_seconds",
+            "Raw PPS offsets (system - GPS) in seconds",
+            &histogram,
+        );
+    }
+
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")], body)
+}
+
+/// Ajoute une métrique (avec ses lignes HELP/TYPE) au format d'exposition Prometheus
+fn push_metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(&str, f64)]) {
+    out.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n", name, help, name, kind));
+    for (labels, value) in samples {
+        out.push_str(&format!("{}{} {}\n", name, labels, value));
+    }
+}
+
+/// Ajoute un histogramme (séries `_bucket`, `_sum` et `_count`) au format Prometheus
+fn push_histogram(out: &mut String, name: &str, help: &str, histogram: &HistogramSnapshot) {
+    out.push_str(&format!("# HELP {} {}\n# TYPE {} histogram\n", name, help, name));
+    for (bound, count) in &histogram.buckets {
+        let le = if bound.is_infinite() { "+Inf".to_string() } else { bound.to_string() };
+        out.push_str(&format!("{}_bucket{{le=\"{}\"}} {}\n", name, le, count));
+    }
+    out.push_str(&format!("{}_sum {}\n{}_count {}\n", name, histogram.sum, name, histogram.count));
+}
+
+/// Rend les statistiques partagées au format texte Prometheus
+fn render_metrics(stats: &ServerStats) -> String {
+    let mut out = String::new();
+    let ntp = &stats.ntp;
+    let errors = &ntp.validation_errors;
+
+    push_metric(
+        &mut out,
+        "pendulum_ntp_requests_total",
+        "counter",
+        "NTP requests answered",
+        &[("", ntp.requests_total as f64)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_ntp_requests_per_second",
+        "gauge",
+        "NTP requests answered during the last second",
+        &[("", ntp.requests_per_second as f64)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_ntp_requests_per_second_smoothed",
+        "gauge",
+        "NTP requests answered per second, exponentially smoothed",
+        &[("", ntp.requests_per_second_smoothed)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_ntp_duplicate_requests_total",
+        "counter",
+        "Retransmitted NTP requests answered but not counted as new requests",
+        &[("", ntp.duplicate_requests as f64)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_ntp_poll_anomalies_total",
+        "counter",
+        "NTP requests with a poll field outside the plausible range",
+        &[("", ntp.poll_anomalies as f64)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_ntp_source_port_123_rejected_total",
+        "counter",
+        "Client requests dropped because they came from source port 123",
+        &[("", ntp.source_port_123_rejected as f64)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_ntp_auth_failures_total",
+        "counter",
+        "Authenticated requests dropped for an unknown key or a bad MAC",
+        &[("", ntp.auth_failures as f64)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_ntp_icmp_errors_total",
+        "counter",
+        "ICMP errors (unreachable clients) reported for sent responses",
+        &[("", ntp.icmp_errors as f64)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_ntp_validation_errors_total",
+        "counter",
+        "NTP requests rejected, by validation rule",
+        &[
+            ("{reason=\"invalid_version\"}", errors.invalid_version as f64),
+            ("{reason=\"invalid_mode\"}", errors.invalid_mode as f64),
+            ("{reason=\"zero_transmit_timestamp\"}", errors.zero_transmit_timestamp as f64),
+            ("{reason=\"invalid_stratum\"}", errors.invalid_stratum as f64),
+            ("{reason=\"malformed\"}", errors.malformed as f64),
+        ],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_ntp_processing_latency_mean_us",
+        "gauge",
+        "Mean server processing latency (T3 - T2) in microseconds",
+        &[("", ntp.processing_latency.mean_us)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_subsystem_panics_total",
+        "counter",
+        "Panics caught in subsystem threads (GPS reader, web server...)",
+        &[("", stats.subsystem_panics as f64)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_clock_stratum",
+        "gauge",
+        "Current clock stratum",
+        &[("", stats.clock.stratum as f64)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_gps_connected",
+        "gauge",
+        "GPS serial port connected (1) or not (0)",
+        &[("", stats.gps.connected as u8 as f64)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_gps_satellites",
+        "gauge",
+        "GPS satellites used",
+        &[("", stats.gps.satellites_used as f64)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_gps_satellites_in_view",
+        "gauge",
+        "GPS satellites in view (GSV)",
+        &[("", stats.gps.satellites_in_view as f64)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_gps_pps_pulses_total",
+        "counter",
+        "PPS pulses received",
+        &[("", stats.gps.pps_count as f64)],
+    );
+    push_metric(
+        &mut out,
+        "pendulum_gps_watchdog_restarts_total",
+        "counter",
+        "GPS reader restarts by the watchdog",
+        &[("", stats.gps.watchdog_restarts as f64)],
+    );
+
+    if let Some((contended, wait_ns)) = crate::stats::STATS_LOCK_CONTENTION.snapshot() {
+        push_metric(
+            &mut out,
+            "pendulum_stats_lock_contention_count",
+            "counter",
+            "Contended write acquisitions of the shared stats lock",
+            &[("", contended as f64)],
+        );
+        push_metric(
+            &mut out,
+            "pendulum_stats_lock_contention_wait_ns",
+            "counter",
+            "Cumulative time spent waiting for the shared stats write lock in nanoseconds",
+            &[("", wait_ns as f64)],
+        );
+    }
+
+    out
+}
+
+/// API REST : Satellites en vue au format CSV
+async fn satellites_csv_handler(State(state): State<WebServerState>) -> impl IntoResponse {
+    let satellites = state.stats.read().unwrap().satellites.clone();
+
+    let mut body = String::from(snr_log::CSV_HEADER);
+    body.push_str(&snr_log::csv_rows(chrono::Utc::now(), &satellites));
+
+    ([(header::CONTENT_TYPE, "text/csv; charset=utf-8")], body)
+}
+
+/// Position de l'antenne (degrés décimaux)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+struct AntennaPosition {
+    latitude_deg: f64,
+    longitude_deg: f64,
+}
+
+/// Satellite du skyplot, signalé s'il est sous le masque d'élévation
+#[derive(Debug, Clone, Serialize)]
+struct SkyplotSatellite {
+    #[serde(flatten)]
+    satellite: SatelliteInfo,
+    below_mask: bool,
+}
+
+/// Données du skyplot : satellites en vue, position de l'antenne et masque d'élévation
+#[derive(Debug, Clone, Serialize)]
+struct Skyplot {
+    /// Absente tant qu'aucune trame GGA avec fix n'a été reçue
+    antenna: Option<AntennaPosition>,
+    elevation_mask_deg: u8,
+    satellites: Vec<SkyplotSatellite>,
+}
+
+/// Construit le skyplot à partir des statistiques
+fn skyplot(stats: &ServerStats, elevation_mask_deg: u8) -> Skyplot {
+    let antenna = match (stats.gps.latitude_deg, stats.gps.longitude_deg) {
+        (Some(latitude_deg), Some(longitude_deg)) => Some(AntennaPosition { latitude_deg, longitude_deg }),
+        _ => None,
+    };
 
+    let satellites = stats
+        .satellites
+        .iter()
+        .map(|satellite| SkyplotSatellite {
+            below_mask: satellite.elevation < elevation_mask_deg,
+            satellite: satellite.clone(),
+        })
+        .collect();
+
+    Skyplot {
+        antenna,
+        elevation_mask_deg,
+        satellites,
+    }
+}
+
+/// API REST : Skyplot avec masque d'élévation (diagnostic du placement de l'antenne)
+async fn skyplot_handler(State(state): State<WebServerState>) -> Json<Skyplot> {
+    let stats = state.stats.read().unwrap();
+    Json(skyplot(&stats, state.skyplot_elevation_mask_deg))
+}
+
+/// Construit les données temps-réel pour un timestamp NTP donné
+///
+/// Les champs NTP et Unix restent toujours en UTC ; seul `local_time_iso`
+/// dépend du fuseau d'affichage. `server_health` ajoute la santé du serveur NTP.
+fn realtime_data(
+    timestamp: NtpTimestamp,
+    stats: ServerStats,
+    display_timezone: Option<&DisplayTimezone>,
+    server_health: bool,
+) -> RealtimeData {
     let seconds = timestamp.seconds();
     let fraction = timestamp.fraction();
 
     // Convertir fraction en nanosecondes
     let nanos = ((fraction as u64 * 1_000_000_000) >> 32) as u32;
 
-    // Convertir en timestamp Unix pour JavaScript
-    const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
-    let unix_timestamp_ms = ((seconds as u64 - NTP_UNIX_OFFSET) * 1000)
-        + (nanos as u64 / 1_000_000);
+    // Convertir en timestamp Unix (ns complet, ms dérivé pour JavaScript Date)
+    // Un temps antérieur à 1970 (RTC non réglée, timestamp nul) donne 0 au lieu de déborder
+    let unix_timestamp_ns = (seconds as u64)
+        .checked_sub(NTP_UNIX_OFFSET)
+        .map(|unix_seconds| unix_seconds * 1_000_000_000 + nanos as u64)
+        .unwrap_or(0);
+    let unix_timestamp_ms = unix_timestamp_ns / 1_000_000;
+
+    let utc = DateTime::from_timestamp_millis(unix_timestamp_ms as i64).unwrap_or_default();
+    let local = match display_timezone {
+        Some(tz) => tz.local_time(utc),
+        None => utc.fixed_offset(),
+    };
 
-    Json(RealtimeData {
+    RealtimeData {
         timestamp: timestamp.0,
         seconds,
         fraction,
         nanos,
+        server_health: server_health.then(|| ServerHealth::from_stats(&stats)),
         stats,
         unix_timestamp_ms,
+        unix_timestamp_ns,
+        local_time_iso: local.to_rfc3339_opts(SecondsFormat::Millis, true),
+        utc_offset_seconds: local.offset().local_minus_utc(),
+        display_timezone: display_timezone.map(DisplayTimezone::name),
+    }
+}
+
+/// API REST : Temps actuel
+async fn time_handler(State(state): State<WebServerState>) -> Json<RealtimeData> {
+    let timestamp = state.clock.now();
+    let stats = state.stats.read().unwrap().clone();
+
+    Json(realtime_data(timestamp, stats, state.display_timezone.as_ref(), state.server_health))
+}
+
+/// API REST : Historique de l'offset brut horloge/système
+/// (404 si `clock.record_offset_history` est désactivé)
+async fn offset_history_handler(
+    State(state): State<WebServerState>,
+) -> Result<Json<Vec<OffsetSample>>, StatusCode> {
+    match state.offset_history {
+        Some(ref history) => Ok(Json(history.snapshot())),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+/// Pente (secondes par seconde) en deçà de laquelle l'offset est considéré stable : 1 ns/s
+const OFFSET_TREND_STABLE_SLOPE: f64 = 1e-9;
+
+/// Statistiques de l'offset sur une fenêtre glissante
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct OffsetWindowStats {
+    pub samples: usize,
+    pub rms_seconds: f64,
+    pub min_seconds: f64,
+    pub max_seconds: f64,
+}
+
+impl OffsetWindowStats {
+    fn add(&mut self, offset: f64) {
+        if self.samples == 0 {
+            self.min_seconds = offset;
+            self.max_seconds = offset;
+        } else {
+            self.min_seconds = self.min_seconds.min(offset);
+            self.max_seconds = self.max_seconds.max(offset);
+        }
+        // Somme des carrés, convertie en RMS une fois la fenêtre parcourue
+        self.rms_seconds += offset * offset;
+        self.samples += 1;
+    }
+
+    fn finish(mut self) -> Self {
+        if self.samples > 0 {
+            self.rms_seconds = (self.rms_seconds / self.samples as f64).sqrt();
+        }
+        self
+    }
+}
+
+/// Résumé de l'historique d'offset pour le widget sparkline du dashboard
+#[derive(Debug, Clone, Serialize)]
+pub struct OffsetSummary {
+    /// Dernier offset mesuré (secondes)
+    pub current_offset_seconds: f64,
+
+    /// Instant de la dernière mesure (millisecondes Unix)
+    pub unix_timestamp_ms: u64,
+
+    pub last_minute: OffsetWindowStats,
+    pub last_10_minutes: OffsetWindowStats,
+
+    /// Pente de l'offset sur la dernière minute (nanosecondes par seconde)
+    pub slope_ns_per_second: f64,
+
+    /// "rising", "falling" ou "stable" (pente inférieure à 1 ns/s)
+    pub trend: &'static str,
+}
+
+/// Calcule le résumé des fenêtres d'une et dix minutes précédant le dernier
+/// échantillon (parcours unique depuis la fin, `None` si l'historique est vide)
+fn offset_summary(samples: &[OffsetSample]) -> Option<OffsetSummary> {
+    let latest = *samples.last()?;
+    let mut last_minute = OffsetWindowStats::default();
+    let mut last_10_minutes = OffsetWindowStats::default();
+
+    // Régression linéaire sur la dernière minute (abscisse relative au dernier échantillon)
+    let (mut sum_x, mut sum_y, mut sum_xy, mut sum_xx) = (0.0, 0.0, 0.0, 0.0);
+
+    for sample in samples.iter().rev() {
+        let age_ms = latest.unix_timestamp_ms.saturating_sub(sample.unix_timestamp_ms);
+        if age_ms > 600_000 {
+            break;
+        }
+        last_10_minutes.add(sample.offset_seconds);
+
+        if age_ms <= 60_000 {
+            last_minute.add(sample.offset_seconds);
+            let x = -(age_ms as f64) / 1000.0;
+            sum_x += x;
+            sum_y += sample.offset_seconds;
+            sum_xy += x * sample.offset_seconds;
+            sum_xx += x * x;
+        }
+    }
+
+    let n = last_minute.samples as f64;
+    let denominator = n * sum_xx - sum_x * sum_x;
+    let slope = if denominator > 0.0 {
+        (n * sum_xy - sum_x * sum_y) / denominator
+    } else {
+        0.0
+    };
+    let trend = if slope > OFFSET_TREND_STABLE_SLOPE {
+        "rising"
+    } else if slope < -OFFSET_TREND_STABLE_SLOPE {
+        "falling"
+    } else {
+        "stable"
+    };
+
+    Some(OffsetSummary {
+        current_offset_seconds: latest.offset_seconds,
+        unix_timestamp_ms: latest.unix_timestamp_ms,
+        last_minute: last_minute.finish(),
+        last_10_minutes: last_10_minutes.finish(),
+        slope_ns_per_second: slope * 1e9,
+        trend,
     })
 }
 
+/// API REST : Résumé de l'historique d'offset (dernier offset, RMS, min/max, tendance)
+/// (404 si `clock.record_offset_history` est désactivé ou l'historique vide)
+async fn offset_summary_handler(
+    State(state): State<WebServerState>,
+) -> Result<Json<OffsetSummary>, StatusCode> {
+    state
+        .offset_history
+        .as_ref()
+        .and_then(|history| offset_summary(&history.snapshot()))
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Rapport de suivi de l'horloge, champs calqués sur `chronyc tracking`
+///
+/// Les champs de discipline valent `null` tant que l'horloge n'est disciplinée
+/// par aucune référence (horloge système seule, GPS jamais reçu).
+#[derive(Debug, Serialize)]
+pub struct TrackingReport {
+    pub reference_id: String,
+    pub stratum: u8,
+
+    /// Temps de référence de la dernière synchronisation (ISO 8601 UTC)
+    pub ref_time: Option<String>,
+
+    /// Offset courant de l'horloge système (filtré, secondes)
+    pub system_time_offset: Option<f64>,
+    pub last_offset: Option<f64>,
+    pub rms_offset: Option<f64>,
+    pub frequency_ppm: Option<f64>,
+    pub residual_freq_ppm: Option<f64>,
+    pub skew_ppm: Option<f64>,
+
+    /// Root delay annoncé (secondes) : `server.static_root_delay_us`, nul par défaut
+    pub root_delay: f64,
+
+    /// Root dispersion annoncée hors mode dégradé (secondes) : incertitude de la
+    /// source (0 si inconnue), élargie à l'écart GPS/système en cas de divergence
+    pub root_dispersion: f64,
+}
+
+/// Construit le rapport de suivi à partir de l'état de l'horloge, avec les
+/// root delay et dispersion des réponses NTP
+fn tracking_report(clock: &dyn ClockSource, static_root_delay_us: u32) -> TrackingReport {
+    let discipline = clock.discipline();
+    let measured = discipline.filter(|state| state.sample_count > 0);
+    let field = |get: fn(&DisciplineState) -> f64| measured.as_ref().map(get);
+
+    let ref_time = discipline.and_then(|state| state.ref_time).and_then(ntp_timestamp_to_rfc3339);
+
+    TrackingReport {
+        reference_id: String::from_utf8_lossy(&clock.reference_id())
+            .trim_end_matches('\0')
+            .to_string(),
+        stratum: clock.stratum(),
+        ref_time,
+        system_time_offset: field(|state| state.offset_seconds),
+        last_offset: field(|state| state.last_offset_seconds),
+        rms_offset: field(|state| state.rms_offset_seconds),
+        frequency_ppm: field(|state| state.frequency_ppm),
+        residual_freq_ppm: field(|state| state.residual_frequency_ppm),
+        skew_ppm: field(|state| state.skew_ppm),
+        root_delay: static_root_delay_us as f64 * 1e-6,
+        root_dispersion: root_dispersion_ns(clock) as f64 * 1e-9,
+    }
+}
+
+/// API REST : Rapport de suivi de l'horloge (type `chronyc tracking`)
+async fn tracking_handler(State(state): State<WebServerState>) -> Json<TrackingReport> {
+    Json(tracking_report(state.clock.as_ref(), state.static_root_delay_us))
+}
+
+/// API REST : Relit le fichier de configuration et applique les options modifiables
+/// à chaud (jeton d'administration requis, 422 si le fichier est invalide)
+async fn config_reload_handler(State(state): State<WebServerState>, headers: HeaderMap) -> Response {
+    if let Err(status) = check_admin_token(&state, &headers) {
+        return status.into_response();
+    }
+    let Some(reloader) = state.config_reloader.clone() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    match tokio::task::spawn_blocking(move || reloader.reload()).await {
+        Ok(Ok(report)) => Json(report).into_response(),
+        Ok(Err(e)) => {
+            warn!("Config reload refused: {:#}", e);
+            (StatusCode::UNPROCESSABLE_ENTITY, format!("{:#}", e)).into_response()
+        }
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+/// API REST : Décode un paquet NTP capturé et indique pourquoi le serveur le rejetterait
+/// (jeton d'administration requis, 422 si l'encodage est invalide)
+async fn decode_handler(
+    State(state): State<WebServerState>,
+    headers: HeaderMap,
+    Json(request): Json<DecodeRequest>,
+) -> Result<Json<DecodeReport>, (StatusCode, String)> {
+    check_admin_token(&state, &headers).map_err(|status| (status, String::new()))?;
+    let bytes = request.bytes().map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e))?;
+    Ok(Json(crate::packet_decode::decode(&bytes, &state.request_policy, request.source)))
+}
+
 /// WebSocket pour mises à jour temps-réel
 #[axum::debug_handler]
 async fn websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<WebServerState>,
 ) -> axum::response::Response {
-    ws.on_upgrade(|socket| websocket_task(socket, state))
+    // Plafond atteint : refus avant l'upgrade (tâche et abonnement jamais créés)
+    let Some(slot) = state.ws_connections.try_acquire() else {
+        debug!("WebSocket connection refused: {} connections open", state.ws_connections.max);
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    };
+
+    ws.on_upgrade(|socket| async move {
+        websocket_task(socket, state).await;
+        drop(slot);
+    })
+}
+
+/// Produit les trames temps-réel toutes les 50 ms, sérialisées une seule fois
+/// pour tous les clients WebSocket (rien n'est calculé sans client)
+async fn realtime_producer(
+    realtime: broadcast::Sender<Arc<str>>,
+    stats: Arc<std::sync::RwLock<ServerStats>>,
+    clock: Arc<dyn ClockSource>,
+    display_timezone: Option<DisplayTimezone>,
+    server_health: bool,
+) {
+    let mut interval = tokio::time::interval(REALTIME_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+        if realtime.receiver_count() == 0 {
+            continue;
+        }
+
+        let timestamp = clock.now();
+        let snapshot = stats.read().unwrap().clone();
+        let data = realtime_data(timestamp, snapshot, display_timezone.as_ref(), server_health);
+
+        match serde_json::to_string(&data) {
+            Ok(json) => {
+                let _ = realtime.send(Arc::from(json));
+            }
+            Err(e) => error!("Failed to serialize realtime data: {}", e),
+        }
+    }
+}
+
+/// Issue de l'attente de la prochaine trame temps-réel d'un client
+#[derive(Debug, PartialEq)]
+enum NextFrame {
+    Frame(Arc<str>),
+
+    /// Trop de retards consécutifs : client à déconnecter
+    TooSlow,
+
+    /// Producteur arrêté
+    Closed,
+}
+
+/// Prochaine trame à envoyer à un client
+///
+/// Un client en retard (trames écrasées dans le canal) saute directement à la
+/// trame la plus récente au lieu d'être déconnecté ; seuls les retards répétés
+/// sans réception à jour entre eux (`max_consecutive_lags`) le déconnectent.
+async fn next_frame(
+    receiver: &mut broadcast::Receiver<Arc<str>>,
+    consecutive_lags: &mut u32,
+    max_consecutive_lags: u32,
+) -> NextFrame {
+    match receiver.recv().await {
+        Ok(frame) => {
+            *consecutive_lags = 0;
+            NextFrame::Frame(frame)
+        }
+        Err(broadcast::error::RecvError::Closed) => NextFrame::Closed,
+        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+            *consecutive_lags += 1;
+            debug!("WebSocket client lagging: {} frames skipped", skipped);
+            if max_consecutive_lags > 0 && *consecutive_lags >= max_consecutive_lags {
+                return NextFrame::TooSlow;
+            }
+
+            // Vider le canal jusqu'à la trame la plus récente
+            let mut latest = None;
+            loop {
+                match receiver.try_recv() {
+                    Ok(frame) => latest = Some(frame),
+                    Err(broadcast::error::TryRecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::TryRecvError::Closed) => return NextFrame::Closed,
+                    Err(broadcast::error::TryRecvError::Empty) => break,
+                }
+            }
+
+            match latest {
+                Some(frame) => NextFrame::Frame(frame),
+                None => match receiver.recv().await {
+                    Ok(frame) => NextFrame::Frame(frame),
+                    Err(_) => NextFrame::Closed,
+                },
+            }
+        }
+    }
 }
 
-/// Tâche WebSocket : envoie les mises à jour toutes les 50ms
+/// Tâche WebSocket : relaie les trames temps-réel diffusées toutes les 50 ms
 async fn websocket_task(mut socket: WebSocket, state: WebServerState) {
+    let mut receiver = state.realtime.subscribe();
+    let mut consecutive_lags = 0;
+
     loop {
-        let timestamp = state.clock.now();
-        let stats = state.stats.read().unwrap().clone();
-
-        let seconds = timestamp.seconds();
-        let fraction = timestamp.fraction();
-        let nanos = ((fraction as u64 * 1_000_000_000) >> 32) as u32;
-
-        const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
-        let unix_timestamp_ms = ((seconds as u64 - NTP_UNIX_OFFSET) * 1000)
-            + (nanos as u64 / 1_000_000);
-
-        let data = RealtimeData {
-            timestamp: timestamp.0,
-            seconds,
-            fraction,
-            nanos,
-            stats,
-            unix_timestamp_ms,
+        let frame = match next_frame(&mut receiver, &mut consecutive_lags, state.ws_max_consecutive_lags).await {
+            NextFrame::Frame(frame) => frame,
+            NextFrame::TooSlow => {
+                warn!("Disconnecting WebSocket client: {} consecutive lags", consecutive_lags);
+                let _ = socket.send(Message::Close(None)).await;
+                break;
+            }
+            NextFrame::Closed => break,
         };
 
-        let json = match serde_json::to_string(&data) {
-            Ok(j) => j,
-            Err(_) => break,
+        if socket.send(Message::Text(frame.to_string())).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SystemClock;
+    use crate::config::Config;
+    use crate::packet::{NtpMode, NtpPacket};
+    use crate::stats::StatsManager;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn test_state() -> WebServerState {
+        WebServerState {
+            stats: StatsManager::new().clone_arc(),
+            clock: Arc::new(SystemClock::new()),
+            offset_history: None,
+            display_timezone: None,
+            rate_limiter: None,
+            admin_token: None,
+            manual_clock: None,
+            config_reloader: None,
+            request_policy: Arc::default(),
+            started_at: std::time::Instant::now(),
+            realtime: broadcast::channel(DEFAULT_WS_CHANNEL_CAPACITY).0,
+            ws_max_consecutive_lags: DEFAULT_WS_MAX_CONSECUTIVE_LAGS,
+            ws_connections: WsConnections::new(DEFAULT_MAX_WS_CONNECTIONS),
+            server_health: false,
+            skyplot_elevation_mask_deg: DEFAULT_SKYPLOT_ELEVATION_MASK_DEG,
+            static_root_delay_us: 0,
+        }
+    }
+
+    /// Ouvre une connexion `/ws` et retourne le code de statut HTTP de la réponse
+    /// (connexion gardée ouverte tant que le flux n'est pas fermé)
+    async fn open_websocket(addr: std::net::SocketAddr) -> (u16, tokio::net::TcpStream) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(
+                b"GET /ws HTTP/1.1\r\nHost: localhost\r\nConnection: Upgrade\r\nUpgrade: websocket\r\n\
+                  Sec-WebSocket-Version: 13\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+            )
+            .await
+            .unwrap();
+
+        let mut response = [0u8; 12];
+        stream.read_exact(&mut response).await.unwrap();
+        let status = std::str::from_utf8(&response[9..12]).unwrap().parse().unwrap();
+        (status, stream)
+    }
+
+    #[tokio::test]
+    async fn test_ws_connection_cap() {
+        let state = WebServerState {
+            ws_connections: WsConnections::new(2),
+            ..test_state()
         };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, build_router(state)).await });
 
-        if socket.send(Message::Text(json)).await.is_err() {
-            break;
+        // Plafond de 2 : deux upgrades acceptés, le troisième refusé
+        let (first, _first) = open_websocket(addr).await;
+        let (second, _second) = open_websocket(addr).await;
+        let (third, _third) = open_websocket(addr).await;
+        assert_eq!((first, second, third), (101, 101, 503));
+
+        // Place libérée à la fermeture d'une connexion
+        let connections = WsConnections::new(1);
+        let slot = connections.try_acquire().unwrap();
+        assert!(connections.try_acquire().is_none());
+        drop(slot);
+        assert!(connections.try_acquire().is_some());
+        assert!(WsConnections::new(0).try_acquire().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_clock_set_requires_token_and_manual_source() {
+        let clock = Arc::new(ManualClock::new(1));
+        let state = WebServerState {
+            manual_clock: Some(Arc::clone(&clock)),
+            admin_token: Some("secret".to_string()),
+            ..test_state()
+        };
+        let app = build_router(state);
+
+        let put = |token: &str, body: &str| {
+            Request::put("/api/clock/set")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(put("wrong", r#"{"time":"2026-10-16T08:00:00Z"}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = app.clone().oneshot(put("secret", r#"{"time":"yesterday"}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(!clock.has_absolute_time());
+
+        let response = app.oneshot(put("secret", r#"{"time":"2026-10-16T08:00:00.250Z"}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            clock.last_update(),
+            NtpTimestamp::from_seconds_and_nanos(NTP_UNIX_OFFSET + 1_792_137_600, 250_000_000)
+        );
+
+        // Sans horloge manuelle : 404
+        let app = build_router(WebServerState {
+            admin_token: Some("secret".to_string()),
+            ..test_state()
+        });
+        let response = app.oneshot(put("secret", r#"{"time":"2026-10-16T08:00:00Z"}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_config_reload_reports_and_applies_diff() {
+        let path = std::env::temp_dir().join(format!("pendulum-reload-{}.toml", std::process::id()));
+        let mut config = Config::default();
+        config.to_file(&path).unwrap();
+
+        let limiter = Arc::new(RateLimiter::new(config.security.max_requests_per_second));
+        let reloader = ConfigReloader::new(path.clone(), config.clone(), Some(Arc::clone(&limiter)));
+        let app = build_router(WebServerState {
+            config_reloader: Some(Arc::new(reloader)),
+            admin_token: Some("secret".to_string()),
+            ..test_state()
+        });
+        let reload = |token: &str| {
+            Request::post("/api/config/reload")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(reload("wrong")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Limite modifiée (appliquée) et adresse d'écoute modifiée (au redémarrage)
+        let old_limit = config.security.max_requests_per_second;
+        config.security.max_requests_per_second = 7;
+        config.server.bind_address = "127.0.0.1:10123".to_string();
+        config.to_file(&path).unwrap();
+
+        let response = app.clone().oneshot(reload("secret")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(
+            report["applied"],
+            serde_json::json!([{ "field": "security.max_requests_per_second", "old": old_limit, "new": 7 }])
+        );
+        assert_eq!(report["restart_required"][0]["field"], "server.bind_address");
+        assert_eq!(report["restart_required"][0]["new"], "127.0.0.1:10123");
+        assert_eq!(limiter.max_requests_per_second(), 7);
+
+        // Limite changée par PUT /api/ratelimit : le diff part de la valeur en vigueur ;
+        // jeton d'administration modifié : valeur masquée
+        limiter.set_max_requests_per_second(50);
+        config.webserver.admin_token = Some("new-secret".to_string());
+        config.to_file(&path).unwrap();
+
+        let response = app.clone().oneshot(reload("secret")).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            report["applied"],
+            serde_json::json!([{ "field": "security.max_requests_per_second", "old": 50, "new": 7 }])
+        );
+        assert_eq!(limiter.max_requests_per_second(), 7);
+        assert_eq!(
+            report["restart_required"][1],
+            serde_json::json!({ "field": "webserver.admin_token", "old": null, "new": "***" })
+        );
+        assert!(!String::from_utf8_lossy(&body).contains("new-secret"));
+
+        // Fichier invalide : refusé, rien n'est modifié
+        std::fs::write(&path, "[server]\nstratum = 0\n").unwrap();
+        let response = app.oneshot(reload("secret")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(limiter.max_requests_per_second(), 7);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_decode_packets() {
+        let app = build_router(WebServerState {
+            admin_token: Some("secret".to_string()),
+            ..test_state()
+        });
+        let decode = |body: serde_json::Value| {
+            let app = app.clone();
+            async move {
+                let response = app
+                    .oneshot(
+                        Request::post("/api/decode")
+                            .header(header::AUTHORIZATION, "Bearer secret")
+                            .header(header::CONTENT_TYPE, "application/json")
+                            .body(Body::from(body.to_string()))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = response.status();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, serde_json::from_slice::<serde_json::Value>(&body).unwrap_or_default())
+            }
+        };
+
+        // Requête client valide (NTPv4, mode 3), transmit timestamp 2026-10-16T08:00:00.5Z
+        let mut request = NtpPacket::new_server_response();
+        request.mode = NtpMode::Client;
+        request.stratum = 0;
+        request.transmit_timestamp = NtpTimestamp::from_seconds_and_nanos(NTP_UNIX_OFFSET + 1_792_137_600, 500_000_000);
+        let hex: String = request.to_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+
+        let (status, report) = decode(serde_json::json!({ "hex": hex })).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(report["valid"], true);
+        assert_eq!(report["packet"]["mode"], "Client");
+        assert_eq!(report["packet"]["version"], 4);
+        assert_eq!(report["packet"]["transmit_timestamp"]["raw"], "0xee7c580080000000");
+        assert_eq!(report["packet"]["transmit_timestamp"]["utc"], "2026-10-16T08:00:00.500000Z");
+        assert_eq!(report["packet"]["originate_timestamp"]["utc"], serde_json::Value::Null);
+
+        // Réponse serveur soumise comme requête (base64) : rejetée, avec la raison
+        use base64::Engine;
+        request.mode = NtpMode::Server;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(request.to_bytes());
+        let (_, report) = decode(serde_json::json!({ "base64": encoded })).await;
+        assert_eq!(report["valid"], false);
+        assert_eq!(report["reason"], "Invalid NTP mode (expected client)");
+
+        // Paquet tronqué : illisible
+        let (_, report) = decode(serde_json::json!({ "hex": "e3 00 06 ec" })).await;
+        assert_eq!(report["packet"], serde_json::Value::Null);
+        assert_eq!(report["length"], 4);
+        assert_eq!(report["valid"], false);
+
+        let (status, _) = decode(serde_json::json!({ "hex": "zz" })).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        // Politique du serveur : mode 1 refusé par défaut, servi avec
+        // answer_symmetric_as_server ; port 123 refusé avec reject_source_port_123
+        request.mode = NtpMode::SymmetricActive;
+        let symmetric = request.to_bytes();
+        request.mode = NtpMode::Client;
+        let client = request.to_bytes();
+        let from_port_123: std::net::SocketAddr = "192.0.2.1:123".parse().unwrap();
+        assert!(crate::packet_decode::decode(&symmetric, &RequestPolicy::default(), None).reason.is_some());
+
+        let mut config = Config::default();
+        config.security.answer_symmetric_as_server = true;
+        config.security.reject_source_port_123 = true;
+        let policy = RequestPolicy::from_config(&config);
+        assert!(crate::packet_decode::decode(&symmetric, &policy, Some(from_port_123)).valid);
+        assert!(crate::packet_decode::decode(&client, &policy, None).valid);
+        let report = crate::packet_decode::decode(&client, &policy, Some(from_port_123));
+        assert_eq!(
+            report.reason.as_deref(),
+            Some("Client request from source port 123 (security.reject_source_port_123)")
+        );
+
+        // Pairs symétriques autorisés : validation en mode symétrique
+        config.server.allow_symmetric = true;
+        assert!(crate::packet_decode::decode(&symmetric, &RequestPolicy::from_config(&config), None).valid);
+    }
+
+    #[tokio::test]
+    async fn test_ratelimit_put_changes_limit() {
+        let limiter = Arc::new(RateLimiter::new(100));
+        let state = WebServerState {
+            rate_limiter: Some(Arc::clone(&limiter)),
+            admin_token: Some("secret".to_string()),
+            ..test_state()
+        };
+        let app = build_router(state);
+
+        let put = |token: &str, body: &str| {
+            Request::put("/api/ratelimit")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        // Jeton invalide ou valeur nulle : refusés, limite inchangée
+        let response = app.clone().oneshot(put("wrong", r#"{"max_requests_per_second":2}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = app.clone().oneshot(put("secret", r#"{"max_requests_per_second":0}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(limiter.max_requests_per_second(), 100);
+
+        let response = app.clone().oneshot(put("secret", r#"{"max_requests_per_second":2}"#)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // La nouvelle limite s'applique immédiatement
+        let ip: std::net::IpAddr = "192.0.2.1".parse().unwrap();
+        assert!(limiter.check_rate_limit(ip));
+        assert!(limiter.check_rate_limit(ip));
+        assert!(!limiter.check_rate_limit(ip));
+
+        // Lecture également protégée par le jeton
+        let response = app
+            .clone()
+            .oneshot(Request::get("/api/ratelimit").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .oneshot(
+                Request::get("/api/ratelimit")
+                    .header(header::AUTHORIZATION, "Bearer secret")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let info: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(info["max_requests_per_second"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_offset_summary_statistics() {
+        // 10 minutes à 1 échantillon par seconde, offset en dents de scie de ±4 µs,
+        // puis une dernière minute en rampe de +1 µs/s partant de 0
+        let history = Arc::new(OffsetHistory::new(3600));
+        let start_ms = 1_700_000_000_000;
+        for second in 0..600u64 {
+            let offset = if second % 2 == 0 { 4e-6 } else { -4e-6 };
+            history.push(OffsetSample { unix_timestamp_ms: start_ms + second * 1000, offset_seconds: offset });
+        }
+        for second in 0..=60u64 {
+            history.push(OffsetSample {
+                unix_timestamp_ms: start_ms + (600 + second) * 1000,
+                offset_seconds: second as f64 * 1e-6,
+            });
+        }
+
+        let state = WebServerState { offset_history: Some(history), ..test_state() };
+        let response = build_router(state)
+            .oneshot(Request::get("/api/offset_summary").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let summary: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        let approx = |value: &serde_json::Value, expected: f64| (value.as_f64().unwrap() - expected).abs() < 1e-12;
+        assert!(approx(&summary["current_offset_seconds"], 60e-6));
+        assert_eq!(summary["unix_timestamp_ms"], start_ms + 660_000);
+        assert_eq!(summary["trend"], "rising");
+        assert!((summary["slope_ns_per_second"].as_f64().unwrap() - 1000.0).abs() < 1e-3);
+
+        // Dernière minute : rampe 0..=60 µs, RMS = sqrt(Σk² / 61) µs
+        let minute = &summary["last_minute"];
+        assert_eq!(minute["samples"], 61);
+        assert!(approx(&minute["min_seconds"], 0.0));
+        assert!(approx(&minute["max_seconds"], 60e-6));
+        let expected_rms = ((0..=60).map(|k| (k * k) as f64).sum::<f64>() / 61.0).sqrt() * 1e-6;
+        assert!(approx(&minute["rms_seconds"], expected_rms));
+
+        // Dix minutes : 540 échantillons à ±4 µs (secondes 61 à 600) et la rampe
+        let ten = &summary["last_10_minutes"];
+        assert_eq!(ten["samples"], 601);
+        assert!(approx(&ten["min_seconds"], -4e-6));
+        assert!(approx(&ten["max_seconds"], 60e-6));
+        let sum_sq = 540.0 * 16e-12 + (0..=60).map(|k| (k * k) as f64 * 1e-12).sum::<f64>();
+        assert!(approx(&ten["rms_seconds"], (sum_sq / 601.0).sqrt()));
+
+        // Historique désactivé : 404
+        let response = build_router(test_state())
+            .oneshot(Request::get("/api/offset_summary").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_tracking_report_from_clock_state() {
+        struct DisciplinedClock;
+
+        impl ClockSource for DisciplinedClock {
+            fn now(&self) -> NtpTimestamp {
+                NtpTimestamp::from_seconds_and_nanos(3_940_000_000, 0)
+            }
+
+            fn reference_id(&self) -> [u8; 4] {
+                *b"PPS\0"
+            }
+
+            fn stratum(&self) -> u8 {
+                1
+            }
+
+            fn precision(&self) -> i8 {
+                -20
+            }
+
+            fn time_accuracy_ns(&self) -> Option<u64> {
+                Some(2_000)
+            }
+
+            fn divergence_ns(&self) -> Option<u64> {
+                Some(5_000)
+            }
+
+            fn discipline(&self) -> Option<DisciplineState> {
+                Some(DisciplineState {
+                    // 2024-11-11T12:00:00.5Z
+                    ref_time: Some(NtpTimestamp::from_seconds_and_nanos(NTP_UNIX_OFFSET + 1_731_326_400, 500_000_000)),
+                    last_offset_seconds: 0.000_002,
+                    offset_seconds: 0.000_001_5,
+                    rms_offset_seconds: 0.000_003,
+                    frequency_ppm: -12.5,
+                    residual_frequency_ppm: 0.25,
+                    skew_ppm: 0.5,
+                    sample_count: 60,
+                })
+            }
+        }
+
+        let state = WebServerState {
+            clock: Arc::new(DisciplinedClock),
+            static_root_delay_us: 250_000,
+            ..test_state()
+        };
+        let response = build_router(state)
+            .oneshot(Request::get("/api/tracking").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(report["reference_id"], "PPS");
+        assert_eq!(report["stratum"], 1);
+        assert_eq!(report["ref_time"], "2024-11-11T12:00:00.500000Z");
+        assert_eq!(report["system_time_offset"], 0.000_001_5);
+        assert_eq!(report["last_offset"], 0.000_002);
+        assert_eq!(report["rms_offset"], 0.000_003);
+        assert_eq!(report["frequency_ppm"], -12.5);
+        assert_eq!(report["residual_freq_ppm"], 0.25);
+        assert_eq!(report["skew_ppm"], 0.5);
+        // Mêmes root delay et dispersion que les réponses NTP : délai configuré,
+        // dispersion élargie à l'écart GPS/système
+        assert_eq!(report["root_delay"], 0.25);
+        assert_eq!(report["root_dispersion"], 0.000_005);
+
+        // Horloge non disciplinée : champs de discipline nuls
+        let report = tracking_report(&SystemClock::new(), 0);
+        assert_eq!(report.ref_time, None);
+        assert_eq!(report.frequency_ppm, None);
+    }
+
+    #[test]
+    fn test_wait_for_sync() {
+        use crate::clock::GpsNmeaClock;
+
+        let poll = std::time::Duration::from_millis(5);
+
+        // Horloge système seule : jamais synchronisée, le délai expire
+        let clock = SystemClock::new();
+        assert!(!wait_for_sync(&clock, std::time::Duration::from_millis(20), poll));
+
+        // Horloge GPS synchronisée pendant l'attente
+        let clock = Arc::new(GpsNmeaClock::new(30));
+        let syncer = Arc::clone(&clock);
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(30));
+            syncer.update_gps_time(NtpTimestamp::from_seconds_and_nanos(3_900_000_000, 0), Some(8));
+        });
+        assert!(wait_for_sync(clock.as_ref(), std::time::Duration::from_secs(5), poll));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_epoch_conversions_agree() {
+        // 2026-10-16T08:00:00.500Z : 1_792_137_600 s Unix
+        const UNIX_SECONDS: u64 = 1_792_137_600;
+        let expected = NtpTimestamp::from_seconds_and_nanos(UNIX_SECONDS + NTP_UNIX_OFFSET, 500_000_000);
+
+        // Unix -> NTP : API, trames RMC et ZDA
+        assert_eq!(ntp_timestamp_from_rfc3339("2026-10-16T08:00:00.500Z"), Some(expected));
+        let rmc = "$GPRMC,080000.50,A,4916.45,N,12311.12,W,000.5,054.7,161026,020.3,E*00";
+        assert_eq!(crate::gps_reader::parse_gprmc(rmc), Some(expected));
+        assert_eq!(crate::gps_reader::parse_zda("$GPZDA,080000.50,16,10,2026,00,00*00"), Some(expected));
+
+        // NTP -> Unix : temps réel, décodeur de paquets, fichier des secondes intercalaires
+        let realtime = realtime_data(expected, StatsManager::new().get(), None, false);
+        assert_eq!(realtime.unix_timestamp_ms, UNIX_SECONDS * 1000 + 500);
+        assert_eq!(ntp_timestamp_to_rfc3339(expected).as_deref(), Some("2026-10-16T08:00:00.500000Z"));
+        assert_eq!(
+            crate::leap_seconds::ntp_seconds_to_iso(expected.seconds() as u64).as_deref(),
+            Some("2026-10-16T08:00:00Z")
+        );
+
+        // Horloge système : même seconde que SystemTime, à l'exécution du test près
+        let unix_now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+        let ntp_now = SystemClock::new().now().seconds() as u64;
+        assert!(ntp_now.abs_diff(unix_now + NTP_UNIX_OFFSET) <= 1);
+    }
+
+    #[test]
+    fn test_display_timezone_only_affects_local_time() {
+        // 2024-11-11T12:00:00.250Z
+        let timestamp = NtpTimestamp::from_seconds_and_nanos(NTP_UNIX_OFFSET + 1_731_326_400, 250_000_000);
+        let stats = StatsManager::new().get();
+
+        let utc = realtime_data(timestamp, stats.clone(), None, false);
+        assert_eq!(utc.local_time_iso, "2024-11-11T12:00:00.250Z");
+        assert_eq!(utc.utc_offset_seconds, 0);
+        assert_eq!(utc.display_timezone, None);
+
+        let fixed = DisplayTimezone::parse("+02:00").unwrap();
+        let local = realtime_data(timestamp, stats.clone(), Some(&fixed), false);
+        assert_eq!(local.local_time_iso, "2024-11-11T14:00:00.250+02:00");
+        assert_eq!(local.utc_offset_seconds, 7200);
+
+        // Fuseau IANA en heure d'hiver
+        let paris = DisplayTimezone::parse("Europe/Paris").unwrap();
+        let local = realtime_data(timestamp, stats, Some(&paris), false);
+        assert_eq!(local.local_time_iso, "2024-11-11T13:00:00.250+01:00");
+        assert_eq!(local.display_timezone.as_deref(), Some("Europe/Paris"));
+
+        // Les champs NTP/Unix restent en UTC
+        assert_eq!(local.timestamp, utc.timestamp);
+        assert_eq!(local.seconds, utc.seconds);
+        assert_eq!(local.unix_timestamp_ms, utc.unix_timestamp_ms);
+
+        assert!(DisplayTimezone::parse("Mars/Olympus").is_err());
+    }
+
+    #[test]
+    fn test_unix_timestamp_ns_matches_ms() {
+        // 2024-11-11T12:00:00.123456789Z
+        let timestamp = NtpTimestamp::from_seconds_and_nanos(NTP_UNIX_OFFSET + 1_731_326_400, 123_456_789);
+        let data = realtime_data(timestamp, StatsManager::new().get(), None, false);
+
+        assert_eq!(data.unix_timestamp_ns / 1_000_000, data.unix_timestamp_ms);
+        assert_eq!(data.unix_timestamp_ns % 1_000_000_000, data.nanos as u64);
+        assert_eq!(data.unix_timestamp_ms, 1_731_326_400_123);
+        // Conversion fraction NTP → ns à 1 ns près
+        assert!((data.unix_timestamp_ns as i64 - 1_731_326_400_123_456_789).abs() <= 1);
+    }
+
+    #[test]
+    fn test_realtime_server_health_when_enabled() {
+        let timestamp = NtpTimestamp::from_seconds_and_nanos(NTP_UNIX_OFFSET + 1_731_326_400, 0);
+        let mut stats = StatsManager::new().get();
+        stats.ntp.requests_per_second = 120;
+        stats.ntp.rejected_per_second = 3;
+        stats.ntp.active_clients = 7;
+        stats.ntp.processing_latency.mean_us = 18.5;
+
+        // Désactivée par défaut : absente de la trame
+        let data = realtime_data(timestamp, stats.clone(), None, false);
+        let json = serde_json::to_value(&data).unwrap();
+        assert!(json.get("server_health").is_none());
+
+        let data = realtime_data(timestamp, stats, None, true);
+        let json = serde_json::to_value(&data).unwrap();
+        let health = &json["server_health"];
+        assert_eq!(health["requests_per_second"], 120);
+        assert_eq!(health["rejected_per_second"], 3);
+        assert_eq!(health["active_clients"], 7);
+        assert_eq!(health["processing_latency_mean_us"], 18.5);
+    }
+
+    #[test]
+    fn test_skyplot_elevation_mask() {
+        let satellite = |prn: u8, elevation: u8| SatelliteInfo {
+            prn,
+            elevation,
+            azimuth: 0,
+            snr: 40,
+            constellation: "GPS".to_string(),
+        };
+        let mut stats = StatsManager::new().get();
+        stats.satellites = vec![satellite(1, 5), satellite(2, 15), satellite(3, 15), satellite(4, 60)];
+
+        // Sans fix GGA : position inconnue
+        let plot = skyplot(&stats, 15);
+        assert_eq!(plot.antenna, None);
+
+        // Masque de 15° : seul le satellite à 5° est sous le masque (15° exactement reste visible)
+        let below: Vec<u8> = plot.satellites.iter().filter(|s| s.below_mask).map(|s| s.satellite.prn).collect();
+        assert_eq!(below, vec![1]);
+
+        stats.gps.latitude_deg = Some(48.1173);
+        stats.gps.longitude_deg = Some(11.5167);
+        let json = serde_json::to_value(skyplot(&stats, 0)).unwrap();
+        assert_eq!(json["antenna"]["latitude_deg"], 48.1173);
+        assert_eq!(json["elevation_mask_deg"], 0);
+        assert_eq!(json["satellites"][0]["prn"], 1);
+        assert_eq!(json["satellites"][0]["below_mask"], false);
+    }
+
+    #[test]
+    fn test_timestamp_before_unix_epoch() {
+        // Timestamp nul et temps de 1969 : pas de débordement, sentinelle 0
+        for timestamp in [
+            NtpTimestamp::default(),
+            NtpTimestamp::from_seconds_and_nanos(2_208_988_799, 500_000_000),
+        ] {
+            let data = realtime_data(timestamp, StatsManager::new().get(), None, false);
+            assert_eq!(data.unix_timestamp_ns, 0);
+            assert_eq!(data.unix_timestamp_ms, 0);
+            assert_eq!(data.local_time_iso, "1970-01-01T00:00:00.000Z");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stats_etag_not_modified() {
+        let app = build_router(test_state());
+
+        let first = app
+            .clone()
+            .oneshot(Request::get("/api/stats").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = first.headers().get(header::ETAG).unwrap().clone();
+
+        let second = app
+            .oneshot(
+                Request::get("/api/stats")
+                    .header(header::IF_NONE_MATCH, etag.clone())
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(second.headers().get(header::ETAG), Some(&etag));
+    }
+
+    #[tokio::test]
+    async fn test_favicon() {
+        let response = build_router(test_state())
+            .oneshot(Request::get("/favicon.ico").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("image/"));
+    }
+
+    #[test]
+    fn test_fallback_dashboard() {
+        // Le dashboard intégré est compilé dans tous les cas et reste autonome
+        assert!(FALLBACK_INDEX_HTML.starts_with("<!DOCTYPE html>"));
+        assert!(FALLBACK_INDEX_HTML.contains("/api/stats"));
+        assert!(!FALLBACK_INDEX_HTML.contains("/static/"));
+        assert!(FALLBACK_FAVICON_SVG.starts_with("<svg"));
+    }
+
+    #[tokio::test]
+    async fn test_index_served() {
+        let response = build_router(test_state())
+            .oneshot(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[cfg(embedded_dashboard)]
+    #[tokio::test]
+    async fn test_static_assets() {
+        let app = build_router(test_state());
+
+        let css = app
+            .clone()
+            .oneshot(Request::get("/static/style.css").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(css.status(), StatusCode::OK);
+        assert_eq!(css.headers()[header::CONTENT_TYPE], "text/css; charset=utf-8");
+        assert_eq!(css.headers()[header::CACHE_CONTROL], STATIC_CACHE_CONTROL);
+
+        let missing = app
+            .oneshot(Request::get("/static/missing.js").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_validation_errors() {
+        let state = test_state();
+        state.stats.write().unwrap().ntp.validation_errors.invalid_mode = 3;
+
+        let response = build_router(state)
+            .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("pendulum_ntp_validation_errors_total{reason=\"invalid_mode\"} 3\n"));
+        assert!(body.contains("pendulum_ntp_validation_errors_total{reason=\"invalid_version\"} 0\n"));
+    }
+
+    #[tokio::test]
+    async fn test_stats_gzip_encoding() {
+        let app = build_router(test_state());
+
+        let response = app
+            .oneshot(
+                Request::get("/api/stats")
+                    .header(header::ACCEPT_ENCODING, "gzip")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            response.headers().get(header::CONTENT_ENCODING).unwrap(),
+            "gzip"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lagging_websocket_client_skips_to_latest() {
+        let (sender, mut receiver) = broadcast::channel::<Arc<str>>(4);
+        let frame = |n: u32| -> Arc<str> { Arc::from(n.to_string()) };
+        let mut lags = 0;
+
+        // Client à jour
+        sender.send(frame(0)).unwrap();
+        assert_eq!(next_frame(&mut receiver, &mut lags, 2).await, NextFrame::Frame(frame(0)));
+
+        // 10 trames produites pendant que le client est bloqué : il saute à la dernière
+        for n in 1..=10 {
+            sender.send(frame(n)).unwrap();
+        }
+        assert_eq!(next_frame(&mut receiver, &mut lags, 2).await, NextFrame::Frame(frame(10)));
+        assert_eq!(lags, 1);
+
+        // Rattrapé : la trame suivante arrive normalement et le compteur repart à zéro
+        sender.send(frame(11)).unwrap();
+        assert_eq!(next_frame(&mut receiver, &mut lags, 2).await, NextFrame::Frame(frame(11)));
+        assert_eq!(lags, 0);
+
+        // Retards répétés sans rattrapage : déconnexion
+        for n in 12..=20 {
+            sender.send(frame(n)).unwrap();
+        }
+        assert_eq!(next_frame(&mut receiver, &mut lags, 2).await, NextFrame::Frame(frame(20)));
+        for n in 21..=30 {
+            sender.send(frame(n)).unwrap();
         }
+        assert_eq!(next_frame(&mut receiver, &mut lags, 2).await, NextFrame::TooSlow);
 
-        // Mise à jour toutes les 50ms (20 FPS)
-        sleep(Duration::from_millis(50)).await;
+        // Producteur arrêté
+        let mut receiver = sender.subscribe();
+        drop(sender);
+        assert_eq!(next_frame(&mut receiver, &mut 0, 2).await, NextFrame::Closed);
     }
 }
@@ -0,0 +1,279 @@
+let ws = null;
+let lastRxTime = 0;
+let lastTxTime = 0;
+
+function connectWebSocket() {
+    const wsUrl = `ws://${window.location.host}/ws`;
+    ws = new WebSocket(wsUrl);
+
+    ws.onopen = () => {
+        console.log('WebSocket connecté');
+        updateConnectionStatus(true);
+    };
+
+    ws.onmessage = (event) => {
+        const data = JSON.parse(event.data);
+        updateDisplay(data);
+    };
+
+    ws.onerror = (error) => {
+        console.error('WebSocket erreur:', error);
+        updateConnectionStatus(false);
+    };
+
+    ws.onclose = () => {
+        console.log('WebSocket déconnecté, reconnexion dans 3s...');
+        updateConnectionStatus(false);
+        setTimeout(connectWebSocket, 3000);
+    };
+}
+
+function updateConnectionStatus(connected) {
+    const led = document.getElementById('ws-led');
+    const status = document.getElementById('ws-status');
+
+    if (connected) {
+        led.className = 'led active';
+        status.textContent = 'Connecté';
+    } else {
+        led.className = 'led error';
+        status.textContent = 'Déconnecté';
+    }
+}
+
+// Libellé et LED de chaque état du récepteur GPS
+const GPS_STATES = {
+    disconnected: { label: 'Déconnecté', led: 'led inactive' },
+    no_fix: { label: 'Acquisition...', led: 'led inactive' },
+    fix_2d: { label: 'Fix 2D', led: 'led active' },
+    fix_3d: { label: 'Fix 3D', led: 'led active' },
+    pps_locked: { label: 'Verrouillé PPS', led: 'led active' },
+    holdover: { label: 'Holdover', led: 'led error' }
+};
+
+function updateDisplay(data) {
+    // Mise à jour de l'horloge
+    // Fuseau d'affichage configuré côté serveur : décaler l'instant UTC et lire les champs UTC
+    // (sinon heure locale du navigateur)
+    const hasZone = data.display_timezone != null;
+    const date = new Date(data.unix_timestamp_ms + (hasZone ? data.utc_offset_seconds * 1000 : 0));
+    const hours = String(hasZone ? date.getUTCHours() : date.getHours()).padStart(2, '0');
+    const minutes = String(hasZone ? date.getUTCMinutes() : date.getMinutes()).padStart(2, '0');
+    const seconds = String(hasZone ? date.getUTCSeconds() : date.getSeconds()).padStart(2, '0');
+    const millis = String(date.getMilliseconds()).padStart(3, '0');
+    const micros = String(Math.floor((data.nanos % 1000000) / 1000)).padStart(3, '0');
+    const subMicros = String(data.nanos % 1000).padStart(3, '0');
+
+    document.getElementById('time').textContent = `${hours}:${minutes}:${seconds}`;
+    document.getElementById('date').textContent = date.toLocaleDateString('fr-FR', {
+        weekday: 'long',
+        year: 'numeric',
+        month: 'long',
+        day: 'numeric',
+        timeZone: hasZone ? 'UTC' : undefined
+    });
+    document.getElementById('milliseconds').textContent = `${millis},${micros} ${subMicros} ms`;
+
+    // Mise à jour des informations horloge
+    document.getElementById('stratum').textContent = data.stats.clock.stratum;
+    document.getElementById('reference').textContent = data.stats.clock.reference_id;
+    const precisionUs = Math.pow(2, data.stats.clock.precision) * 1_000_000;
+    document.getElementById('precision').textContent = `~${precisionUs.toFixed(1)} µs`;
+
+    // Mise à jour GPS
+    const gpsLed = document.getElementById('gps-led');
+    const gpsState = GPS_STATES[data.stats.gps.state] || GPS_STATES.disconnected;
+    gpsLed.className = gpsState.led;
+    document.getElementById('gps-status').textContent = gpsState.label;
+
+    document.getElementById('satellites').textContent =
+        `${data.stats.gps.satellites_used} / ${data.stats.gps.satellites_in_view}`;
+
+    // Barres de signal
+    const signalQuality = data.stats.gps.signal_quality;
+    const bars = document.querySelectorAll('.signal-bars .bar');
+    bars.forEach((bar, index) => {
+        if (index < signalQuality) {
+            bar.classList.add('active');
+        } else {
+            bar.classList.remove('active');
+        }
+    });
+
+    // Offset PPS
+    if (data.stats.gps.pps_offset !== null) {
+        const offsetMs = (data.stats.gps.pps_offset * 1000).toFixed(3);
+        document.getElementById('pps-offset').textContent = `${offsetMs} ms`;
+    } else {
+        document.getElementById('pps-offset').textContent = '--';
+    }
+
+    // USB RX/TX avec clignotement
+    const now = Date.now();
+    const rxLed = document.getElementById('rx-led');
+    const txLed = document.getElementById('tx-led');
+
+    // RX (GPS)
+    if (data.stats.gps.last_rx_ms < 100) {
+        rxLed.className = 'led active blink';
+        if (data.stats.gps.last_rx_ms !== lastRxTime) {
+            setTimeout(() => rxLed.classList.remove('blink'), 200);
+        }
+        lastRxTime = data.stats.gps.last_rx_ms;
+        document.getElementById('rx-status').textContent = 'Actif';
+    } else {
+        rxLed.className = 'led inactive';
+        document.getElementById('rx-status').textContent = 'Inactif';
+    }
+
+    // TX (NTP)
+    if (data.stats.ntp.last_tx_ms < 100) {
+        txLed.className = 'led active blink';
+        if (data.stats.ntp.last_tx_ms !== lastTxTime) {
+            setTimeout(() => txLed.classList.remove('blink'), 200);
+        }
+        lastTxTime = data.stats.ntp.last_tx_ms;
+        document.getElementById('tx-status').textContent = 'Actif';
+    } else {
+        txLed.className = 'led inactive';
+        document.getElementById('tx-status').textContent = 'Inactif';
+    }
+
+    // PPS
+    const ppsLed = document.getElementById('pps-led');
+    if (data.stats.gps.pps_active) {
+        ppsLed.className = 'led active';
+        document.getElementById('pps-status').textContent = 'Actif';
+    } else {
+        ppsLed.className = 'led inactive';
+        document.getElementById('pps-status').textContent = 'Inactif';
+    }
+
+    // Statistiques
+    document.getElementById('ntp-requests').textContent = data.stats.ntp.requests_total.toLocaleString('fr-FR');
+    document.getElementById('ntp-rate').textContent = Math.round(data.stats.ntp.requests_per_second_smoothed);
+    document.getElementById('nmea-count').textContent = data.stats.gps.nmea_sentences.toLocaleString('fr-FR');
+    document.getElementById('pps-count').textContent = data.stats.gps.pps_count.toLocaleString('fr-FR');
+
+    // Skyplot
+    drawSkyplot(data.stats.satellites || []);
+}
+
+function drawSkyplot(satellites) {
+    const canvas = document.getElementById('skyplot');
+    const ctx = canvas.getContext('2d');
+
+    // Adapter la résolution au device pixel ratio pour netteté
+    const dpr = window.devicePixelRatio || 1;
+    const rect = canvas.getBoundingClientRect();
+    canvas.width = rect.width * dpr;
+    canvas.height = rect.height * dpr;
+    ctx.scale(dpr, dpr);
+
+    const size = rect.width;
+    const centerX = size / 2;
+    const centerY = size / 2;
+    const radius = size / 2 - 40;
+
+    // Fond
+    ctx.fillStyle = 'rgba(0, 0, 0, 0.3)';
+    ctx.fillRect(0, 0, size, size);
+
+    // Cercles de distance (90°, 60°, 30°, 0°)
+    ctx.strokeStyle = 'rgba(255, 255, 255, 0.2)';
+    ctx.lineWidth = 1;
+
+    for (let elev = 90; elev >= 0; elev -= 30) {
+        const r = radius * (1 - elev / 90);
+        ctx.beginPath();
+        ctx.arc(centerX, centerY, r, 0, Math.PI * 2);
+        ctx.stroke();
+
+        // Labels d'élévation
+        ctx.fillStyle = 'rgba(255, 255, 255, 0.5)';
+        ctx.font = '10px monospace';
+        ctx.textAlign = 'center';
+        ctx.fillText(elev + '°', centerX, centerY - r - 5);
+    }
+
+    // Axes cardinaux
+    ctx.strokeStyle = 'rgba(255, 255, 255, 0.3)';
+    ctx.lineWidth = 1;
+
+    // Nord-Sud
+    ctx.beginPath();
+    ctx.moveTo(centerX, centerY - radius);
+    ctx.lineTo(centerX, centerY + radius);
+    ctx.stroke();
+
+    // Est-Ouest
+    ctx.beginPath();
+    ctx.moveTo(centerX - radius, centerY);
+    ctx.lineTo(centerX + radius, centerY);
+    ctx.stroke();
+
+    // Labels cardinaux
+    ctx.fillStyle = 'rgba(255, 255, 255, 0.8)';
+    ctx.font = 'bold 14px monospace';
+    ctx.textAlign = 'center';
+    ctx.fillText('N', centerX, centerY - radius - 10);
+    ctx.fillText('S', centerX, centerY + radius + 20);
+    ctx.fillText('E', centerX + radius + 15, centerY + 5);
+    ctx.fillText('O', centerX - radius - 15, centerY + 5);
+
+    // Dessiner les satellites
+    satellites.forEach(sat => {
+        // Convertir élévation en rayon (90° = centre, 0° = bord)
+        const elevRatio = 1 - (sat.elevation / 90);
+        const r = radius * elevRatio;
+
+        // Convertir azimut en angle (0° = Nord = haut, sens horaire)
+        const angle = (sat.azimuth - 90) * (Math.PI / 180);
+
+        // Position du satellite
+        const x = centerX + r * Math.cos(angle);
+        const y = centerY + r * Math.sin(angle);
+
+        // Couleur selon SNR
+        let color;
+        if (sat.snr === 0) {
+            color = 'rgba(200, 200, 200, 0.5)'; // Gris = pas de signal
+        } else if (sat.snr < 20) {
+            color = 'rgba(255, 100, 100, 0.8)'; // Rouge = faible
+        } else if (sat.snr < 30) {
+            color = 'rgba(255, 200, 50, 0.8)'; // Jaune = moyen
+        } else {
+            color = 'rgba(50, 255, 100, 0.8)'; // Vert = bon
+        }
+
+        // Dessiner le cercle du satellite
+        ctx.beginPath();
+        ctx.arc(x, y, 8, 0, Math.PI * 2);
+        ctx.fillStyle = color;
+        ctx.fill();
+        ctx.strokeStyle = 'rgba(255, 255, 255, 0.6)';
+        ctx.lineWidth = 1.5;
+        ctx.stroke();
+
+        // PRN du satellite
+        ctx.fillStyle = 'rgba(255, 255, 255, 0.9)';
+        ctx.font = 'bold 11px monospace';
+        ctx.textAlign = 'center';
+        ctx.textBaseline = 'middle';
+        ctx.fillText(sat.prn, x, y);
+
+        // Constellation (petite icône)
+        ctx.font = '8px monospace';
+        ctx.fillStyle = 'rgba(255, 255, 255, 0.6)';
+        ctx.fillText(sat.constellation.substring(0, 1), x, y + 15);
+    });
+
+    // Nombre de satellites en vue
+    ctx.fillStyle = 'rgba(255, 255, 255, 0.7)';
+    ctx.font = '12px monospace';
+    ctx.textAlign = 'left';
+    ctx.fillText(`${satellites.length} sat(s) en vue`, 10, 20);
+}
+
+// Connexion au démarrage
+connectWebSocket();